@@ -2,35 +2,211 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::color::Color;
+use crate::path::PathId;
+use common::emote::EmoteId;
 use glam::{Vec2, Vec3, Vec4};
 
 pub struct Animation {
     position: Vec2,
     animation_type: AnimationType,
     start_seconds: f32,
+    /// Whether debris was already thrown.
+    emitted: bool,
 }
 
 pub enum AnimationType {
+    /// Units lost by one side of a fight.
+    Damage {
+        count: usize,
+        attacker: bool,
+    },
+    Emote(EmoteId),
     Emp(Color),
     NuclearExplosion,
+    /// A mutual ally (or the player) pinged a tower.
+    Ping(Color),
     ShellExplosion,
 }
 
+/// Colors of animations, grouped in one place for theming and contrast tuning.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AnimationPalette {
+    /// Units lost by the attacker of a fight.
+    pub damage_attacker: Vec3,
+    /// Units lost by the defender of a fight.
+    pub damage_defender: Vec3,
+    pub emote: Vec3,
+    /// Replaces [`Color::Gray`] (e.g. zombie) EMPs, which don't look good gray.
+    pub emp_gray: Color,
+    pub nuclear_explosion: Vec3,
+    pub nuclear_debris: Vec3,
+    pub shell_explosion: Vec3,
+    pub shell_debris: Vec3,
+}
+
+impl AnimationPalette {
+    pub const DEFAULT: Self = Self {
+        damage_attacker: Vec3::new(0.67, 0.67, 0.67),
+        damage_defender: Vec3::new(1.0, 0.4, 0.4),
+        emote: Vec3::ONE,
+        emp_gray: Color::Red,
+        nuclear_explosion: Vec3::ONE,
+        nuclear_debris: Vec3::new(0.8, 0.8, 0.8),
+        shell_explosion: Vec3::ONE,
+        shell_debris: Vec3::new(0.7, 0.7, 0.7),
+    };
+
+    /// [`Self::DEFAULT`], adjusted for the high contrast and colorblind settings.
+    pub fn new(high_contrast: bool, colorblind: bool) -> Self {
+        let mut palette = Self::DEFAULT;
+        if high_contrast {
+            // Dim grays blend into the background.
+            palette.damage_attacker = Vec3::ONE;
+            palette.nuclear_debris = Vec3::ONE;
+            palette.shell_debris = Vec3::ONE;
+        }
+        if colorblind {
+            // Red is hard to tell apart from gray, unlike orange.
+            palette.damage_defender = Vec3::new(1.0, 0.6, 0.1);
+        }
+        palette
+    }
+
+    /// The main color of `animation_type`.
+    pub fn color(&self, animation_type: &AnimationType) -> Vec3 {
+        match *animation_type {
+            AnimationType::Damage { attacker, .. } => {
+                if attacker {
+                    self.damage_attacker
+                } else {
+                    self.damage_defender
+                }
+            }
+            AnimationType::Emote(_) => self.emote,
+            AnimationType::Emp(color) => Self::stroke(if color == Color::Gray {
+                self.emp_gray
+            } else {
+                color
+            }),
+            AnimationType::NuclearExplosion => self.nuclear_explosion,
+            AnimationType::Ping(color) => Self::stroke(color),
+            AnimationType::ShellExplosion => self.shell_explosion,
+        }
+    }
+
+    /// The color of debris thrown by `animation_type`, if any.
+    pub fn debris(&self, animation_type: &AnimationType) -> Option<Vec3> {
+        match animation_type {
+            AnimationType::Emp(_) => Some(self.color(animation_type)),
+            AnimationType::NuclearExplosion => Some(self.nuclear_debris),
+            AnimationType::ShellExplosion => Some(self.shell_debris),
+            _ => None,
+        }
+    }
+
+    fn stroke(color: Color) -> Vec3 {
+        let (stroke, _) = color.colors(true, true, false);
+        stroke.unwrap() // TODO don't return option that's always Some.
+    }
+}
+
+impl Default for AnimationPalette {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 impl Animation {
     pub fn new(position: Vec2, animation_type: AnimationType, time_seconds: f32) -> Self {
         Self {
             position,
             animation_type,
             start_seconds: time_seconds,
+            emitted: false,
         }
     }
 
-    /// Returns a boolean of whether animation is *not* done.
-    pub fn render<F: FnMut(Vec2, f32, Vec4)>(
-        &self,
-        mut draw_filled_circle: F,
+    /// Like [`Self::new`], but for an event that all viewers see, which happened `seconds_ago`
+    /// per the game clock (see [`TowerState::seconds_since`]). Unlike local time, this basis is
+    /// the same for everyone, so they all see the same phase.
+    ///
+    /// [`TowerState::seconds_since`]: crate::state::TowerState::seconds_since
+    pub fn shared(
+        position: Vec2,
+        animation_type: AnimationType,
         time_seconds: f32,
+        seconds_ago: f32,
+    ) -> Self {
+        Self::new(position, animation_type, time_seconds - seconds_ago)
+    }
+
+    /// How long an emote lasts, in seconds.
+    const EMOTE_SECONDS: f32 = 2.0;
+    /// How long a damage number lasts, in seconds.
+    const DAMAGE_SECONDS: f32 = 1.0;
+    /// How long a ping lasts, in seconds.
+    const PING_SECONDS: f32 = 3.0;
+
+    /// Whether the animation could overlap the axis-aligned rectangle between `min` and `max`
+    /// in world space.
+    pub fn intersects(&self, min: Vec2, max: Vec2) -> bool {
+        let radius = match self.animation_type {
+            // Text rises and is offset to the side.
+            AnimationType::Damage { .. } => 2.5,
+            // Rises above the tower.
+            AnimationType::Emote(_) => 3.5,
+            AnimationType::Emp(_) => 1.0,
+            AnimationType::NuclearExplosion => 1.5,
+            // Pulses outward.
+            AnimationType::Ping(_) => 2.5,
+            AnimationType::ShellExplosion => 0.3,
+        };
+        let closest = self.position.clamp(min, max);
+        closest.distance_squared(self.position) <= radius * radius
+    }
+
+    /// Returns a boolean of whether animation is *not* done. `emit_debris` is called once with
+    /// the center, count, speed, radius and color of debris to throw. Colors come from
+    /// `palette`. `reduced_motion` disables pulsing. If not `visible` (see [`Self::intersects`]),
+    /// nothing is drawn but the animation still ends on schedule.
+    pub fn render<
+        F: FnMut(PathId, Vec2, f32, Vec4),
+        T: FnMut(&str, Vec2, f32, Vec4),
+        D: FnMut(Vec2, usize, f32, f32, Vec3),
+    >(
+        &mut self,
+        mut draw_filled_path: F,
+        mut draw_text: T,
+        mut emit_debris: D,
+        palette: &AnimationPalette,
+        time_seconds: f32,
+        reduced_motion: bool,
+        visible: bool,
     ) -> bool {
+        if !std::mem::replace(&mut self.emitted, true)
+            && let Some(color) = palette.debris(&self.animation_type)
+        {
+            let (count, speed, radius) = match self.animation_type {
+                AnimationType::Emp(_) => (12, 3.0, 0.08),
+                AnimationType::NuclearExplosion => (48, 4.0, 0.3),
+                _ => (6, 2.0, 0.1),
+            };
+            emit_debris(self.position, count, speed, radius, color);
+        }
+
+        let mut draw_filled_path = |path_id: PathId, center: Vec2, scale: f32, color: Vec4| {
+            if visible {
+                draw_filled_path(path_id, center, scale, color)
+            }
+        };
+        let mut draw_text = |text: &str, center: Vec2, scale: f32, color: Vec4| {
+            if visible {
+                draw_text(text, center, scale, color)
+            }
+        };
+        let mut draw_filled_circle = |center: Vec2, radius: f32, color: Vec4| {
+            draw_filled_path(PathId::Explosion, center, radius, color)
+        };
         let mut draw =
             |time_delay: f32, time_scale: f32, max_radius: f32, max_alpha: f32, color: Vec3| {
                 let t = time_seconds - self.start_seconds;
@@ -53,17 +229,206 @@ impl Animation {
                 }
             };
 
-        let white = Vec3::ONE;
+        let color = palette.color(&self.animation_type);
         match self.animation_type {
-            AnimationType::Emp(color) => {
-                let (stroke, _) = color.colors(true, true, false);
-                let color = stroke.unwrap(); // TODO don't return option that's always Some.
-                draw(1.2, -0.5, 1.0, 0.3, color)
+            AnimationType::Damage { count, attacker } => {
+                let t = (time_seconds - self.start_seconds) * (1.0 / Self::DAMAGE_SECONDS);
+                if t >= 1.0 {
+                    return false;
+                }
+                let x = if attacker { -0.6 } else { 0.6 };
+                let position = self.position + Vec2::new(x, 1.0 + t);
+                draw_text(&format!("-{count}"), position, 1.0, color.extend(1.0 - t));
+                true
+            }
+            AnimationType::Emote(emote_id) => {
+                let t = (time_seconds - self.start_seconds) * (1.0 / Self::EMOTE_SECONDS);
+                if t >= 1.0 {
+                    return false;
+                }
+                // Rise while fading out during the second half.
+                let alpha = ((1.0 - t) * 2.0).min(1.0);
+                let position = self.position + Vec2::new(0.0, 1.5 + t * 0.5);
+                draw_filled_path(PathId::Emote(emote_id), position, 1.5, color.extend(alpha));
+                true
+            }
+            AnimationType::Emp(_) => draw(1.2, -0.5, 1.0, 0.3, color),
+            AnimationType::Ping(_) => {
+                let t = (time_seconds - self.start_seconds) * (1.0 / Self::PING_SECONDS);
+                if t >= 1.0 {
+                    return false;
+                }
+                // Pulse a few times while fading out.
+                let pulse = if reduced_motion {
+                    0.0
+                } else {
+                    (t * 3.0).fract()
+                };
+                let alpha = (1.0 - t) * (1.0 - pulse * 0.5);
+                draw_filled_path(
+                    PathId::Marker,
+                    self.position,
+                    1.5 + pulse,
+                    color.extend(alpha),
+                );
+                true
             }
             AnimationType::NuclearExplosion => {
-                draw(0.0, 0.33, 1.5, 0.6, white) | draw(0.0, 1.0, 1.0, 1.0, white)
+                draw(0.0, 0.33, 1.5, 0.6, color) | draw(0.0, 1.0, 1.0, 1.0, color)
+            }
+            AnimationType::ShellExplosion => draw(-0.25, 2.0, 0.3, 0.7, color),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::{Animation, AnimationPalette, AnimationType};
+    use crate::color::Color;
+    use common::emote::EmoteId;
+    use glam::{Vec2, Vec3};
+    use std::cell::Cell;
+
+    /// Renders until done, returning how many frames were drawn and when it was done.
+    fn run(animation_type: AnimationType, visible: bool) -> (usize, f32) {
+        let mut animation = Animation::new(Vec2::ZERO, animation_type, 0.0);
+        let mut draws = 0;
+        for frame in 0..10000 {
+            let time_seconds = frame as f32 * 0.01;
+            let drew = Cell::new(false);
+            let alive = animation.render(
+                |_, _, _, _| drew.set(true),
+                |_, _, _, _| drew.set(true),
+                |_, _, _, _, _| {},
+                &AnimationPalette::DEFAULT,
+                time_seconds,
+                false,
+                visible,
+            );
+            draws += drew.get() as usize;
+            if !alive {
+                return (draws, time_seconds);
             }
-            AnimationType::ShellExplosion => draw(-0.25, 2.0, 0.3, 0.7, white),
         }
+        panic!("never done");
+    }
+
+    #[test]
+    fn culled_animations_expire_on_schedule() {
+        let animation_types: [fn() -> AnimationType; 4] = [
+            || AnimationType::Damage {
+                count: 3,
+                attacker: true,
+            },
+            || AnimationType::NuclearExplosion,
+            || AnimationType::Ping(Color::Purple),
+            || AnimationType::ShellExplosion,
+        ];
+        for animation_type in animation_types {
+            let (visible_draws, visible_done) = run(animation_type(), true);
+            let (culled_draws, culled_done) = run(animation_type(), false);
+            assert!(visible_draws > 0);
+            assert_eq!(culled_draws, 0);
+            assert_eq!(visible_done, culled_done);
+        }
+    }
+
+    #[test]
+    fn shared_animations_agree() {
+        // Two viewers with different local clocks see the same event, which happened 0.3
+        // seconds ago per the game clock.
+        let draws = |time_seconds: f32| {
+            let mut animation = Animation::shared(
+                Vec2::ZERO,
+                AnimationType::NuclearExplosion,
+                time_seconds,
+                0.3,
+            );
+            let mut draws = Vec::new();
+            for frame in 0..50 {
+                animation.render(
+                    |_, center, scale, color| draws.push((frame, center, scale, color)),
+                    |_, _, _, _| {},
+                    |_, _, _, _, _| {},
+                    &AnimationPalette::DEFAULT,
+                    time_seconds + frame as f32 * 0.05,
+                    false,
+                    true,
+                );
+            }
+            draws
+        };
+        let (a, b) = (draws(3.0), draws(1000.0));
+        assert!(!a.is_empty());
+        assert_eq!(a.len(), b.len());
+        for ((frame_a, center_a, scale_a, color_a), (frame_b, center_b, scale_b, color_b)) in
+            a.into_iter().zip(b)
+        {
+            assert_eq!(frame_a, frame_b);
+            assert_eq!(center_a, center_b);
+            assert!((scale_a - scale_b).abs() < 0.001);
+            assert!(color_a.abs_diff_eq(color_b, 0.001));
+        }
+    }
+
+    #[test]
+    fn palette_colors() {
+        assert_eq!(
+            AnimationPalette::new(false, false),
+            AnimationPalette::DEFAULT
+        );
+        let colorblind = AnimationPalette::new(false, true);
+        assert_ne!(colorblind.damage_defender, colorblind.damage_attacker);
+
+        let animation_types = [
+            AnimationType::Damage {
+                count: 3,
+                attacker: true,
+            },
+            AnimationType::Damage {
+                count: 3,
+                attacker: false,
+            },
+            AnimationType::Emote(EmoteId::Heart),
+            AnimationType::Emp(Color::Gray),
+            AnimationType::Emp(Color::Blue),
+            AnimationType::NuclearExplosion,
+            AnimationType::Ping(Color::Purple),
+            AnimationType::ShellExplosion,
+        ];
+        for (high_contrast, colorblind) in
+            [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let palette = AnimationPalette::new(high_contrast, colorblind);
+            for animation_type in &animation_types {
+                let color = palette.color(animation_type);
+                assert!(color.is_finite());
+                assert!(color.cmpge(Vec3::ZERO).all() && color.cmple(Vec3::ONE).all());
+                assert!(color.max_element() > 0.0, "invisible");
+            }
+            // Gray EMPs are replaced.
+            assert_eq!(
+                palette.color(&AnimationType::Emp(Color::Gray)),
+                palette.color(&AnimationType::Emp(palette.emp_gray))
+            );
+            for animation_type in [
+                AnimationType::Emp(Color::Blue),
+                AnimationType::NuclearExplosion,
+                AnimationType::ShellExplosion,
+            ] {
+                assert!(palette.debris(&animation_type).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn intersects() {
+        let (min, max) = (Vec2::ZERO, Vec2::splat(10.0));
+        let animation =
+            |x: f32| Animation::new(Vec2::new(x, 5.0), AnimationType::ShellExplosion, 0.0);
+        assert!(animation(5.0).intersects(min, max));
+        assert!(animation(10.2).intersects(min, max));
+        assert!(!animation(11.0).intersects(min, max));
+        assert!(!animation(-1.0).intersects(min, max));
     }
 }
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+
+/// Slowly tours active parts of the world while on the spawn screen, to give the page life.
+/// Only the rendered camera moves, so the viewport sent to the server stays the same.
+#[derive(Debug, Default)]
+pub struct Attract {
+    /// Seconds without input.
+    idle: f32,
+    /// Center and zoom to render with, if touring.
+    camera: Option<(Vec2, f32)>,
+    /// Where the camera is heading (the default view's center if there was nowhere interesting),
+    /// and for how many more seconds.
+    waypoint: Option<(Vec2, f32)>,
+}
+
+impl Attract {
+    /// How long to wait for input before touring.
+    const IDLE_SECONDS: f32 = 5.0;
+    /// How long to head towards each waypoint.
+    const WAYPOINT_SECONDS: f32 = 12.0;
+    /// Zoom relative to the default view.
+    const ZOOM: f32 = 0.35;
+    /// How quickly the camera approaches its waypoint and zoom, per second.
+    const EASING: f32 = 0.25;
+
+    /// Call on any input, which stops touring.
+    pub fn cancel(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Call every frame with the default view, which the camera stays within. `pick_waypoint`
+    /// is given the bounds of the camera's center and returns an interesting point within, if
+    /// any (otherwise it isn't called again until the next waypoint is due).
+    pub fn update(
+        &mut self,
+        elapsed_seconds: f32,
+        enabled: bool,
+        center: Vec2,
+        zooms: Vec2,
+        pick_waypoint: impl FnOnce(Vec2, Vec2) -> Option<Vec2>,
+    ) {
+        if !enabled {
+            self.cancel();
+            return;
+        }
+        self.idle += elapsed_seconds;
+        if self.idle < Self::IDLE_SECONDS {
+            return;
+        }
+
+        // Keep the camera's view within the default view.
+        let margin = zooms * (1.0 - Self::ZOOM);
+        let (min, max) = (center - margin, center + margin);
+
+        let (camera_center, camera_zoom) = self.camera.get_or_insert((center, zooms.x));
+        let remaining = self.waypoint.as_mut().map(|(_, remaining)| {
+            *remaining -= elapsed_seconds;
+            *remaining
+        });
+        if remaining.map_or(true, |remaining| remaining <= 0.0) {
+            let waypoint = pick_waypoint(min, max).map_or(center, |w| w.clamp(min, max));
+            self.waypoint = Some((waypoint, Self::WAYPOINT_SECONDS));
+        }
+
+        let t = 1.0 - (-elapsed_seconds * Self::EASING).exp();
+        let target = self.waypoint.map_or(center, |(waypoint, _)| waypoint);
+        *camera_center = camera_center.lerp(target, t).clamp(min, max);
+        *camera_zoom += (zooms.x * Self::ZOOM - *camera_zoom) * t;
+    }
+
+    /// Center and zoom to render with instead of the default view, if touring.
+    pub fn camera(&self) -> Option<(Vec2, f32)> {
+        self.camera
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::attract::Attract;
+    use glam::Vec2;
+
+    #[test]
+    fn attract() {
+        let (center, zooms) = (Vec2::splat(100.0), Vec2::new(50.0, 25.0));
+        let waypoint = Vec2::new(500.0, 90.0);
+        let mut attract = Attract::default();
+        let update = |attract: &mut Attract, seconds: f32, enabled: bool| {
+            for _ in 0..(seconds * 10.0) as usize {
+                attract.update(0.1, enabled, center, zooms, |_, _| Some(waypoint));
+            }
+        };
+
+        // Waits for idle.
+        update(&mut attract, Attract::IDLE_SECONDS - 1.0, true);
+        assert_eq!(attract.camera(), None);
+        update(&mut attract, 30.0, true);
+        let (camera_center, camera_zoom) = attract.camera().unwrap();
+
+        // Stays within the default view while heading for the (out of bounds) waypoint.
+        let margin = zooms * (1.0 - Attract::ZOOM);
+        let bounded = waypoint.clamp(center - margin, center + margin);
+        assert!(camera_center.abs_diff_eq(bounded, 1.0));
+        assert!((camera_zoom - zooms.x * Attract::ZOOM).abs() < 1.0);
+
+        // Input stops touring.
+        attract.cancel();
+        assert_eq!(attract.camera(), None);
+        update(&mut attract, 30.0, false);
+        assert_eq!(attract.camera(), None);
+
+        // Nowhere interesting waits for the next waypoint before looking again.
+        let mut picks = 0;
+        for _ in 0..((Attract::IDLE_SECONDS + Attract::WAYPOINT_SECONDS * 1.5) * 10.0) as usize {
+            attract.update(0.1, true, center, zooms, |_, _| {
+                picks += 1;
+                None
+            });
+        }
+        assert_eq!(picks, 2);
+        let (camera_center, _) = attract.camera().unwrap();
+        assert!(camera_center.abs_diff_eq(center, 0.01));
+    }
+}
@@ -0,0 +1,320 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::tower::{TowerArray, TowerType};
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter, Write};
+use std::str::FromStr;
+
+/// A named, saved sequence of upgrades.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blueprint {
+    pub name: String,
+    /// In order, each an upgrade of the previous.
+    pub steps: Vec<TowerType>,
+}
+
+impl Blueprint {
+    /// The upgrades that lead to `tower_type` from its [`TowerType::basis`].
+    pub fn new(name: &str, tower_type: TowerType) -> Self {
+        let mut steps = Vec::new();
+        let mut current = tower_type;
+        while let Some(downgrade) = current.downgrade() {
+            steps.push(current);
+            current = downgrade;
+        }
+        steps.reverse();
+        Self {
+            name: Self::sanitize_name(name),
+            steps,
+        }
+    }
+
+    /// Removes characters reserved by [`Blueprints`]' storage format.
+    fn sanitize_name(name: &str) -> String {
+        name.chars()
+            .filter(|c| !matches!(c, ':' | ';' | ','))
+            .take(Self::MAX_NAME_CHARS)
+            .collect()
+    }
+
+    const MAX_NAME_CHARS: usize = 24;
+
+    /// Steps left to apply to a tower of `tower_type`, or [`None`] if the blueprint doesn't
+    /// apply to it.
+    pub fn remaining(&self, tower_type: TowerType) -> Option<&[TowerType]> {
+        let start = match self.steps.iter().position(|&step| step == tower_type) {
+            Some(i) => i + 1,
+            None if self.steps.first()?.downgrade() == Some(tower_type) => 0,
+            None => return None,
+        };
+        let remaining = &self.steps[start..];
+        (!remaining.is_empty()).then_some(remaining)
+    }
+
+    /// Checks that `steps` can be applied, in order, to a tower of `tower_type` given the player's
+    /// `tower_counts`. Accounts for the tower itself changing type along the way.
+    pub fn validate(
+        steps: &[TowerType],
+        mut tower_type: TowerType,
+        tower_counts: &TowerArray<u8>,
+    ) -> Result<(), &'static str> {
+        let mut counts = *tower_counts;
+        for &step in steps {
+            if !tower_type.can_upgrade_to(step) {
+                return Err("not an upgrade");
+            }
+            if !step.has_prerequisites(&counts) {
+                return Err("missing prerequisites");
+            }
+            counts[tower_type] = counts[tower_type].saturating_sub(1);
+            counts[step] = counts[step].saturating_add(1);
+            tower_type = step;
+        }
+        Ok(())
+    }
+}
+
+/// All of the player's saved [`Blueprint`]s, most recent last.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blueprints(pub Vec<Blueprint>);
+
+impl Blueprints {
+    pub const MAX: usize = 8;
+
+    /// Saves a blueprint, replacing any with the same name, and forgetting the oldest if there
+    /// are too many.
+    pub fn save(&self, blueprint: Blueprint) -> Self {
+        let mut ret = self.clone();
+        ret.0.retain(|b| b.name != blueprint.name);
+        ret.0.push(blueprint);
+        let excess = ret.0.len().saturating_sub(Self::MAX);
+        ret.0.drain(..excess);
+        ret
+    }
+
+    /// Whether an identical blueprint (except for name) is saved.
+    pub fn contains_steps(&self, steps: &[TowerType]) -> bool {
+        self.0.iter().any(|b| b.steps == steps)
+    }
+}
+
+impl Display for Blueprints {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, blueprint) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_char(';')?;
+            }
+            f.write_str(&blueprint.name)?;
+            f.write_char(':')?;
+            for (i, step) in blueprint.steps.iter().enumerate() {
+                if i != 0 {
+                    f.write_char(',')?;
+                }
+                Display::fmt(step, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Blueprints {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(
+            s.split(';')
+                .filter_map(|blueprint| {
+                    let (name, steps) = blueprint.split_once(':')?;
+                    let steps = steps
+                        .split(',')
+                        .map(TowerType::from_str)
+                        .collect::<Result<Vec<_>, _>>()
+                        .ok()?;
+                    // Drop corrupt blueprints instead of applying them.
+                    let valid = !steps.is_empty()
+                        && steps.windows(2).all(|w| w[0].can_upgrade_to(w[1]))
+                        && steps[0].downgrade().is_some();
+                    valid.then(|| Blueprint {
+                        name: Blueprint::sanitize_name(name),
+                        steps,
+                    })
+                })
+                .take(Self::MAX)
+                .collect(),
+        ))
+    }
+}
+
+/// A [`Blueprint`] being applied to a tower, one upgrade at a time.
+#[derive(Debug)]
+pub struct QueuedBlueprint {
+    steps: VecDeque<TowerType>,
+    /// Ticks to wait for the issued first step to take effect before issuing it again, in case
+    /// it was rejected.
+    pending: u8,
+}
+
+/// What to do next about a [`QueuedBlueprint`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlueprintStep {
+    /// Issue this upgrade now.
+    Upgrade(TowerType),
+    /// Wait for an upgrade to take effect or for prerequisites to be met.
+    Wait,
+    /// Finished, or no longer applicable (e.g. the tower was upgraded manually).
+    Done,
+}
+
+impl QueuedBlueprint {
+    const RETRY_TICKS: u8 = 5;
+
+    pub fn new(steps: &[TowerType]) -> Self {
+        Self {
+            steps: steps.iter().copied().collect(),
+            pending: 0,
+        }
+    }
+
+    /// Call once per tick with the current state of the tower.
+    pub fn advance(
+        &mut self,
+        tower_type: TowerType,
+        active: bool,
+        tower_counts: &TowerArray<u8>,
+    ) -> BlueprintStep {
+        while self.steps.front() == Some(&tower_type) {
+            self.steps.pop_front();
+            self.pending = 0;
+        }
+        let Some(&next) = self.steps.front() else {
+            return BlueprintStep::Done;
+        };
+        if !tower_type.can_upgrade_to(next) {
+            return BlueprintStep::Done;
+        }
+        if self.pending > 0 {
+            self.pending -= 1;
+            return BlueprintStep::Wait;
+        }
+        if !active || !next.has_prerequisites(tower_counts) {
+            return BlueprintStep::Wait;
+        }
+        self.pending = Self::RETRY_TICKS;
+        BlueprintStep::Upgrade(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blueprint::{Blueprint, BlueprintStep, Blueprints, QueuedBlueprint};
+    use common::tower::{TowerArray, TowerType};
+    use std::str::FromStr;
+
+    fn counts(counts: &[(TowerType, u8)]) -> TowerArray<u8> {
+        let mut ret = TowerArray::default();
+        for &(tower_type, count) in counts {
+            ret[tower_type] = count;
+        }
+        ret
+    }
+
+    #[test]
+    fn remaining() {
+        let blueprint = Blueprint::new("sat", TowerType::Satellite);
+        assert_eq!(blueprint.steps, [TowerType::Ews, TowerType::Satellite]);
+        assert_eq!(
+            blueprint.remaining(TowerType::Radar),
+            Some([TowerType::Ews, TowerType::Satellite].as_slice())
+        );
+        assert_eq!(
+            blueprint.remaining(TowerType::Ews),
+            Some([TowerType::Satellite].as_slice())
+        );
+        assert_eq!(blueprint.remaining(TowerType::Satellite), None);
+        assert_eq!(blueprint.remaining(TowerType::Barracks), None);
+    }
+
+    #[test]
+    fn validate() {
+        use TowerType::*;
+        let steps = [Town, City];
+
+        // Town requires 3 villages, including this one.
+        let mut enough = counts(&[(Village, 3), (Generator, 1), (Quarry, 2), (Reactor, 1)]);
+        enough[Town] = 2;
+        assert_eq!(Blueprint::validate(&steps, Village, &enough), Ok(()));
+
+        // City requires 3 towns, one of which will be this one.
+        let mut short = enough;
+        short[Town] = 1;
+        assert!(Blueprint::validate(&steps, Village, &short).is_err());
+
+        assert!(Blueprint::validate(&steps, Barracks, &enough).is_err());
+    }
+
+    #[test]
+    fn blueprints_round_trip() {
+        let blueprints = Blueprints::default()
+            .save(Blueprint::new("a:b;c,", TowerType::City))
+            .save(Blueprint::new("sat", TowerType::Satellite));
+        assert_eq!(blueprints.0[0].name, "abc");
+
+        let string = blueprints.to_string();
+        assert_eq!(Blueprints::from_str(&string), Ok(blueprints.clone()));
+
+        // Replaces by name.
+        let replaced = blueprints.save(Blueprint::new("sat", TowerType::Silo));
+        assert_eq!(replaced.0.len(), 2);
+        assert!(replaced.contains_steps(&[TowerType::Quarry, TowerType::Silo]));
+
+        // Ignores garbage.
+        assert_eq!(
+            Blueprints::from_str("x:Satellite;y:Nope;z:"),
+            Ok(Blueprints::default())
+        );
+    }
+
+    #[test]
+    fn queued_blueprint() {
+        use TowerType::*;
+        let tower_counts = counts(&[(Generator, 5), (Rocket, 2)]);
+        let mut queued = QueuedBlueprint::new(&[Ews, Satellite]);
+
+        assert_eq!(
+            queued.advance(Radar, true, &tower_counts),
+            BlueprintStep::Upgrade(Ews)
+        );
+        // Don't issue it again before it takes effect, unless it seems to have been rejected.
+        for _ in 0..QueuedBlueprint::RETRY_TICKS {
+            assert_eq!(
+                queued.advance(Radar, true, &tower_counts),
+                BlueprintStep::Wait
+            );
+        }
+        assert_eq!(
+            queued.advance(Radar, true, &tower_counts),
+            BlueprintStep::Upgrade(Ews)
+        );
+        // Upgrading.
+        assert_eq!(
+            queued.advance(Ews, false, &tower_counts),
+            BlueprintStep::Wait
+        );
+        assert_eq!(
+            queued.advance(Ews, true, &tower_counts),
+            BlueprintStep::Upgrade(Satellite)
+        );
+        assert_eq!(
+            queued.advance(Satellite, false, &tower_counts),
+            BlueprintStep::Done
+        );
+
+        // Diverged.
+        let mut queued = QueuedBlueprint::new(&[Ews, Satellite]);
+        assert_eq!(
+            queued.advance(Mine, true, &tower_counts),
+            BlueprintStep::Done
+        );
+    }
+}
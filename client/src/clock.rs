@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::ticks::Ticks;
+use std::fmt::{self, Display, Formatter};
+
+/// Elapsed game time, accumulated from [`Singleton::tick`][common::singleton::Singleton::tick]
+/// so it doesn't wrap like [`Ticks`] do.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameClock {
+    ticks: u32,
+    /// The server's tick as of the last update.
+    last_tick: Option<Ticks>,
+}
+
+impl GameClock {
+    /// Call whenever the server ticks (or the client reconnects) with the server's tick.
+    pub fn update(&mut self, tick: Ticks) {
+        let forward = self
+            .last_tick
+            .map(|last| tick.0.wrapping_sub(last.0))
+            .filter(|&delta| delta <= u16::MAX / 2);
+        if let Some(delta) = forward {
+            self.ticks = self.ticks.saturating_add(delta as u32);
+        } else {
+            // First update, or the server went back in time (e.g. restarted).
+            self.ticks = tick.0 as u32;
+        }
+        self.last_tick = Some(tick);
+    }
+
+    pub fn whole_secs(&self) -> u32 {
+        self.ticks / Ticks::FREQUENCY_HZ.0 as u32
+    }
+}
+
+/// E.g. `4:05`, or `1:04:05` after an hour.
+impl Display for GameClock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let secs = self.whole_secs();
+        let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if hours > 0 {
+            write!(f, "{hours}:{minutes:02}:{secs:02}")
+        } else {
+            write!(f, "{minutes}:{secs:02}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::GameClock;
+    use common::ticks::Ticks;
+
+    #[test]
+    fn game_clock() {
+        let mut clock = GameClock::default();
+        clock.update(Ticks::from_whole_secs(65));
+        assert_eq!(clock.to_string(), "1:05");
+
+        // Monotonic across wrapping.
+        for tick in [30000, 60000, u16::MAX] {
+            clock.update(Ticks::from_repr(tick));
+        }
+        let before = clock.whole_secs();
+        clock.update(Ticks::from_repr(3));
+        assert_eq!(clock.whole_secs(), before + 1);
+        assert_eq!(clock.to_string(), "4:33:04");
+
+        // Resyncs to a restarted server.
+        clock.update(Ticks::from_repr(20000));
+        clock.update(Ticks::from_whole_secs(2));
+        assert_eq!(clock.to_string(), "0:02");
+    }
+}
@@ -4,7 +4,7 @@
 use crate::game::TowerGame;
 use client_util::context::Context;
 use core_protocol::PlayerId;
-use glam::Vec3;
+use glam::{Vec3, Vec4};
 use renderer::{rgb_hex, rgba_array_to_css};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -108,4 +108,66 @@ impl Color {
 
         (stroke_color, fill_color)
     }
+
+    /// Blends [`Self::colors`] of `from` into those of `self` as `t` goes from 0 to 1. A missing
+    /// fill is treated as transparent.
+    pub fn mix(
+        self,
+        from: Self,
+        t: f32,
+        active: bool,
+        hovered: bool,
+        selected: bool,
+    ) -> (Option<Vec4>, Option<Vec4>) {
+        let t = t.clamp(0.0, 1.0);
+        let (from_stroke, from_fill) = from.colors(active, hovered, selected);
+        let (to_stroke, to_fill) = self.colors(active, hovered, selected);
+        let lerp = |from: Option<Vec3>, to: Option<Vec3>| match (from, to) {
+            (Some(from), Some(to)) => Some(from.lerp(to, t).extend(1.0)),
+            (Some(from), None) => Some(from.extend(1.0 - t)),
+            (None, Some(to)) => Some(to.extend(t)),
+            (None, None) => None,
+        };
+        (lerp(from_stroke, to_stroke), lerp(from_fill, to_fill))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::Color;
+    use glam::Vec3;
+
+    #[test]
+    fn mix() {
+        let opaque = |(stroke, fill): (Option<Vec3>, Option<Vec3>)| {
+            (stroke.map(|c| c.extend(1.0)), fill.map(|c| c.extend(1.0)))
+        };
+        let (red, blue) = (Color::Red, Color::Blue);
+        assert_eq!(
+            blue.mix(red, 0.0, true, false, false),
+            opaque(red.colors(true, false, false))
+        );
+        assert_eq!(
+            blue.mix(red, 1.0, true, false, false),
+            opaque(blue.colors(true, false, false))
+        );
+        // Clamped.
+        assert_eq!(
+            blue.mix(red, 2.0, true, false, false),
+            blue.mix(red, 1.0, true, false, false)
+        );
+
+        let (stroke, fill) = blue.mix(red, 0.5, true, false, false);
+        let (red_stroke, _) = red.colors(true, false, false);
+        let (blue_stroke, _) = blue.colors(true, false, false);
+        let expected = (red_stroke.unwrap() + blue_stroke.unwrap()) * 0.5;
+        assert!(stroke.unwrap().truncate().abs_diff_eq(expected, 1e-5));
+        assert_eq!(fill.unwrap().w, 1.0);
+
+        // Gray has no fill, so it fades in.
+        let (_, fill) = blue.mix(Color::Gray, 0.25, true, false, false);
+        assert_eq!(fill.unwrap().w, 0.25);
+        let (_, fill) = Color::Gray.mix(blue, 0.25, true, false, false);
+        assert_eq!(fill.unwrap().w, 0.75);
+    }
 }
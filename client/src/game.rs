@@ -1,37 +1,55 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::animation::{Animation, AnimationType};
+use crate::animation::{Animation, AnimationPalette, AnimationType};
+use crate::attract::Attract;
 use crate::background::TowerBackgroundLayer;
+use crate::blueprint::{Blueprint, BlueprintStep, QueuedBlueprint};
+use crate::clock::GameClock;
 use crate::color::Color;
 use crate::key_dispenser::KeyDispenser;
-use crate::layout::{force_layout, tower_layout};
+use crate::layout::{force_layout, strength_layout, tower_layout};
+use crate::particle::DebrisLayer;
 use crate::path::*;
+use crate::reconnect::Reconnect;
 use crate::road::RoadLayer;
-use crate::settings::TowerSettings;
+use crate::settings::{EffectsQuality, TowerSettings};
+use crate::shortcut::shortcuts;
+use crate::sprite::SpriteLayer;
 use crate::state::TowerState;
-use crate::territory::Territories;
+use crate::stats::SessionStats;
+use crate::trail::Trails;
 use crate::tutorial::Tutorial;
-use crate::ui::{SelectedTower, TowerUiEvent, TowerUiProps};
+use crate::ui::{NetworkStats, SelectedTower, TowerUiEvent, TowerUiProps};
+use crate::undo::{ClearedSupplyLine, SupplyLineUndo};
 use client_util::context::Context;
 use client_util::game_client::GameClient;
-use client_util::keyboard::Key;
+use client_util::keyboard::{Key, KeyboardEvent};
 use client_util::mouse::{MouseButton, MouseEvent};
 use client_util::pan_zoom::PanZoom;
+use client_util::rate_limiter::{RateLimiter, TokenBucket};
 use client_util::visibility::VisibilityEvent;
+use common::alerts::AlertFlag;
 use common::chunk::ChunkRectangle;
+use common::emote::{EmoteEvent, EmoteId};
 use common::force::{Force, Path};
 use common::info::{GainedTowerReason, Info, InfoEvent};
+use common::ping::PingEvent;
 use common::protocol::{Command, Update};
-use common::tower::{Tower, TowerId, TowerRectangle, TowerType};
+use common::ticks::Ticks;
+use common::tower::{Tower, TowerArray, TowerId, TowerRectangle, TowerType};
 use common::unit::Unit;
 use common::units::Units;
-use common::world::{World, WorldChunks};
+use common::world::WorldChunks;
 use common_util::x_vec2::U16Vec2;
-use core_protocol::id::GameId;
+use core_protocol::id::{GameId, PlayerId};
+use flagset::FlagSet;
 use glam::{IVec2, Vec2, Vec3, Vec4};
+use rand::prelude::IteratorRandom;
 use renderer::{DefaultRender, Layer, RenderChain};
 use renderer2d::{Camera2d, TextLayer};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::f32::consts::PI;
 
 engine_macros::include_audio!("/audio.mp3" "./audio.json");
@@ -43,8 +61,13 @@ pub struct TowerGame {
     /// (start, (current, current time)).
     drag: Option<Drag>,
     selected_tower_id: Option<TowerId>,
+    /// Whether [`Self::selected_tower_id`] was clicked, as opposed to hovered (see
+    /// [`TowerSettings::hover_select`]).
+    pinned: bool,
+    /// Index into [`Self::tower_menu_actions`] of the tower it was chosen for, when navigating
+    /// the tower menu with the keyboard.
+    menu_cursor: Option<(TowerId, usize)>,
     pan_zoom: PanZoom,
-    territories: Territories,
     panning: bool,
     tutorial: Tutorial,
     lock_dialog: Option<TowerType>,
@@ -53,9 +76,221 @@ pub struct TowerGame {
     was_alive: bool,
     tight_viewport: TowerRectangle,
     margin_viewport: TowerRectangle,
+    /// Limits how often the viewport is sent in low bandwidth mode.
+    viewport_rate_limit: RateLimiter,
+    /// Whether the ruler already auto-retreated from the current threat.
+    retreated: bool,
+    /// Alert flags and ruler position when auto-retreat last found no path to safety, so it
+    /// isn't searched for again until either changes.
+    retreat_failed: Option<(FlagSet<AlertFlag>, Option<TowerId>)>,
+    /// Destination and time of the last auto-retreat, for indicating it.
+    last_retreat: Option<(TowerId, f32)>,
+    /// Limits how often all forces can be retreated.
+    retreat_all_rate_limit: RateLimiter,
+    /// Commands to retreat all forces that have yet to be sent.
+    retreat_all_commands: Vec<Command>,
+    /// Limits how fast [`Self::retreat_all_commands`] are sent.
+    retreat_all_command_bucket: TokenBucket,
+    /// Limits emotes to what the server would accept.
+    emote_bucket: TokenBucket,
+    /// Blueprints being applied, one upgrade at a time.
+    blueprint_queue: HashMap<TowerId, QueuedBlueprint>,
+    /// Most recent [`Info`]s, only recorded with cheats (see [`Self::copy_debug_state`]).
+    recent_info: VecDeque<Info>,
+    /// Limits how often debug state can be copied.
+    copy_debug_state_rate_limit: RateLimiter,
+    /// Previous color and time of recent ownership changes of visible towers, to blend from.
+    recolors: HashMap<TowerId, (Color, f32)>,
+    /// Recent positions of drawn forces, if [`TowerSettings::motion_trails`].
+    trails: Trails,
+    /// Elapsed game time.
+    clock: GameClock,
+    /// Resolution multiplier of a screenshot to take next frame.
+    screenshot: Option<u32>,
+    /// Path and percent of the last deployment, for [`TowerSettings::repeat_deploy_key`].
+    last_deployment: Option<(Vec<TowerId>, u8)>,
+    /// Supply lines recently cleared with Shift+R, for [`TowerSettings::undo_key`].
+    supply_line_undo: SupplyLineUndo,
+    /// Ally whose alliance a second click will break, if
+    /// [`TowerSettings::confirm_break_alliance`].
+    pending_break_alliance: Option<PlayerId>,
+    /// Whether the death screen was dismissed, e.g. to change alias or spawn preference.
+    death_screen_dismissed: bool,
+    /// Tours the world on the spawn screen, if [`TowerSettings::attract_mode`].
+    attract: Attract,
+    /// Whether the keyboard shortcuts are shown.
+    show_shortcuts: bool,
+    /// Preserves the selection across reconnecting.
+    reconnect: Reconnect,
+    /// Of the player's current (or last) life. Unlike [`TowerState`], survives reconnecting.
+    stats: SessionStats,
 }
 
 impl TowerGame {
+    /// How long to indicate an auto-retreat.
+    const RETREAT_INDICATION_SECONDS: f32 = 5.0;
+    /// How many towers beyond the edge of the screen to receive updates for.
+    const VIEWPORT_MARGIN: u16 = 2;
+    /// [`Self::VIEWPORT_MARGIN`] in low bandwidth mode.
+    const LOW_BANDWIDTH_VIEWPORT_MARGIN: u16 = 0;
+    /// Minimum seconds between viewport changes in low bandwidth mode.
+    const LOW_BANDWIDTH_VIEWPORT_PERIOD: f32 = 1.0;
+    /// How long music takes to fade in or out.
+    const MUSIC_FADE_SECONDS: f32 = 1.0;
+    /// Avoid flooding the server when retreating many forces: send this many at once...
+    const RETREAT_ALL_COMMAND_BURST: u32 = 16;
+    /// ...and then this many per second.
+    const RETREAT_ALL_COMMANDS_PER_SECOND: f32 = 60.0;
+    /// How many [`Self::recent_info`] to keep.
+    const MAX_RECENT_INFO: usize = 16;
+    /// How long towers take to change color when they change owner.
+    const RECOLOR_SECONDS: f32 = 0.4;
+    /// Bounds [`Self::recolors`], e.g. when an entire empire collapses at once.
+    const MAX_RECOLORS: usize = 128;
+
+    /// Copies a summary of the game state to the clipboard, as JSON, to attach to bug reports.
+    /// Leaves out aliases, chat, and anything else identifying.
+    fn copy_debug_state(&self, context: &Context<Self>) {
+        #[derive(Serialize)]
+        struct DebugState {
+            player_id: Option<u32>,
+            alive: bool,
+            towers: usize,
+            selected_tower_id: Option<[u16; 2]>,
+            camera_center: [f32; 2],
+            camera_zoom: f32,
+            recent_info: Vec<String>,
+        }
+
+        let me = context.player_id();
+        let state = DebugState {
+            player_id: me.map(|me| me.0.get()),
+            alive: context.state.game.alive,
+            towers: me.map_or(0, |me| {
+                context.state.game.world.iter_player_towers(me).count()
+            }),
+            selected_tower_id: self
+                .selected_tower_id
+                .map(|tower_id| [tower_id.x, tower_id.y]),
+            camera_center: self.pan_zoom.get_center().to_array(),
+            camera_zoom: self.pan_zoom.get_zoom(),
+            recent_info: self
+                .recent_info
+                .iter()
+                .map(|info| format!("{info:?}"))
+                .collect(),
+        };
+
+        match serde_json::to_string_pretty(&state) {
+            Ok(json) => js_hooks::copy_to_clipboard(&json),
+            Err(e) => js_hooks::console_log!("couldn't copy debug state: {e}"),
+        }
+    }
+
+    /// Pings the tower under the mouse, if any, for mutual allies to see.
+    fn ping(&self, context: &mut Context<Self>) {
+        if let Some(tower_id) = context
+            .mouse
+            .view_position
+            .and_then(|v| get_closest(self.camera.to_world_position(v), context))
+        {
+            context.send_to_game(Command::Ping(tower_id));
+        }
+    }
+
+    /// Starts retreating all moving forces, unless that was done too recently.
+    fn retreat_all(&mut self, context: &Context<Self>) {
+        if self.retreat_all_rate_limit.ready() {
+            self.retreat_all_commands = retreat_all_commands(context);
+        }
+    }
+
+    /// Deploys from the source of [`Self::last_deployment`] to its destination again, with the
+    /// units the source has now. Plays a quiet sound instead if that's no longer possible.
+    fn repeat_deployment(&mut self, context: &mut Context<Self>) {
+        let Some((path, percent)) = &self.last_deployment else {
+            return;
+        };
+        let (src, dst, percent) = (path[0], *path.last().unwrap(), *percent);
+        let world = &context.state.game.world;
+        let path = context
+            .player_id()
+            .zip(world.chunk.get(src))
+            .filter(|(me, tower)| {
+                tower.player_id == Some(*me) && !tower.force_units_percent(percent).is_empty()
+            })
+            .and_then(|(me, tower)| {
+                // Revalidates the route, which may have become obstructed or out of range.
+                world.find_best_path(
+                    src,
+                    dst,
+                    deploy_max_edge_distance(tower, percent),
+                    me,
+                    |tower_id| is_visible(context, tower_id),
+                )
+            });
+
+        if let Some(path) = path {
+            self.last_deployment = Some((path.clone(), percent));
+            let command = Command::deploy_percent_from_path(path, percent);
+            self.tutorial.observe_command(&command);
+            context.send_to_game(command);
+        } else {
+            context.audio.play_with_volume(Audio::Event, 0.25);
+        }
+    }
+
+    /// Restores supply lines recently cleared with Shift+R, if they're still viable. Plays a quiet
+    /// sound instead if none are.
+    fn undo_clear_supply_lines(&mut self, context: &mut Context<Self>) {
+        let Some(me) = context.player_id() else {
+            return;
+        };
+        let world = &context.state.game.world;
+        let commands: Vec<_> = self
+            .supply_line_undo
+            .take(context.client.time_seconds)
+            .filter(|cleared| cleared.is_viable(world, me))
+            .map(|cleared| Command::SetSupplyLine {
+                tower_id: cleared.tower_id,
+                path: Some(cleared.path),
+                pull: cleared.pull,
+            })
+            .collect();
+
+        if commands.is_empty() {
+            context.audio.play_with_volume(Audio::Event, 0.25);
+        }
+        for command in commands {
+            context.send_to_game(command);
+        }
+    }
+
+    /// Moves the ruler away from danger, at most once per threat, if enabled.
+    fn auto_retreat(&mut self, context: &mut Context<Self>) {
+        let alerts = context.state.game.alerts;
+        if !context.settings.auto_retreat || !alerts.flags().contains(AlertFlag::RulerUnderAttack) {
+            self.retreated = false;
+            self.retreat_failed = None;
+            return;
+        }
+        let state = (alerts.flags(), alerts.ruler_position);
+        if self.retreated || self.retreat_failed == Some(state) {
+            return;
+        }
+        let Some(path) = alerts
+            .ruler_position
+            .and_then(|ruler| path_to_safety(context, ruler, None, true))
+        else {
+            self.retreat_failed = Some(state);
+            return;
+        };
+        self.retreated = true;
+        self.retreat_failed = None;
+        self.last_retreat = Some((*path.last().unwrap(), context.client.time_seconds));
+        context.send_to_game(Command::deploy_force_from_path(path));
+    }
+
     fn move_world_space(&mut self, world_space: Vec2, context: &mut Context<Self>) {
         if let Some(drag) = self.drag.as_mut() {
             if let Some(closest) = get_closest(world_space, context) {
@@ -89,12 +324,23 @@ impl Drag {
 pub struct TowerLayer {
     background: TowerBackgroundLayer,
     roads: RoadLayer,
+    sprites: SpriteLayer,
     paths: PathLayer,
+    debris: DebrisLayer,
     text: TextLayer,
 }
 
 impl TowerGame {
     const RULER_DRAG_DELAY: f32 = 1.2;
+    /// Below this zoom per pixel, individual units are drawn.
+    const UNIT_LAYOUT_ZOOM_PER_PIXEL: f32 = 0.2;
+    /// Below this zoom per pixel (but not drawing units), unit count badges are drawn.
+    const UNIT_BADGE_ZOOM_PER_PIXEL: f32 = 0.4;
+    /// Below this zoom per pixel, upgrade indicators are drawn.
+    const UPGRADE_INDICATOR_ZOOM_PER_PIXEL: f32 = 0.3;
+    /// Above this zoom per pixel, towers are only a few pixels wide so they are drawn as sprites
+    /// (unless [`TowerSettings::full_detail_towers`]).
+    const SPRITE_ZOOM_PER_PIXEL: f32 = 0.5;
 }
 
 impl GameClient for TowerGame {
@@ -115,7 +361,9 @@ impl GameClient for TowerGame {
             TowerLayer {
                 background: TowerBackgroundLayer::new(&*renderer),
                 roads: RoadLayer::new(&*renderer),
+                sprites: SpriteLayer::new(&*renderer),
                 paths: PathLayer::new(&*renderer),
+                debris: DebrisLayer::new(&*renderer),
                 text: TextLayer::new(&*renderer),
             }
         })?;
@@ -126,8 +374,9 @@ impl GameClient for TowerGame {
             animations: Default::default(),
             drag: Default::default(),
             selected_tower_id: Default::default(),
+            pinned: false,
+            menu_cursor: None,
             pan_zoom: Default::default(),
-            territories: Default::default(),
             panning: Default::default(),
             tutorial: Default::default(),
             lock_dialog: None,
@@ -135,11 +384,191 @@ impl GameClient for TowerGame {
             was_alive: Default::default(),
             tight_viewport: Default::default(),
             margin_viewport: Default::default(),
+            viewport_rate_limit: RateLimiter::new(Self::LOW_BANDWIDTH_VIEWPORT_PERIOD),
+            retreated: false,
+            retreat_failed: None,
+            last_retreat: None,
+            retreat_all_rate_limit: RateLimiter::new(1.0),
+            retreat_all_commands: Vec::new(),
+            retreat_all_command_bucket: TokenBucket::new(
+                Self::RETREAT_ALL_COMMAND_BURST,
+                Self::RETREAT_ALL_COMMANDS_PER_SECOND,
+            ),
+            emote_bucket: TokenBucket::new(1, 1.0 / EmoteId::COOLDOWN.to_secs()),
+            blueprint_queue: HashMap::new(),
+            recent_info: VecDeque::new(),
+            copy_debug_state_rate_limit: RateLimiter::new(1.0),
+            recolors: HashMap::new(),
+            trails: Trails::default(),
+            clock: GameClock::default(),
+            screenshot: None,
+            last_deployment: None,
+            supply_line_undo: SupplyLineUndo::default(),
+            pending_break_alliance: None,
+            death_screen_dismissed: false,
+            attract: Attract::default(),
+            show_shortcuts: false,
+            reconnect: Reconnect::default(),
+            stats: SessionStats::default(),
         })
     }
 
+    fn peek_keyboard(&mut self, event: &KeyboardEvent, context: &mut Context<Self>) {
+        self.attract.cancel();
+        if event.down && event.key == context.settings.ping_key && context.state.game.alive {
+            self.ping(context);
+        }
+        // Only on the initial press, not auto-repeat. Shift+R (clear supply lines) is unaffected.
+        if event.down
+            && !event.ctrl
+            && event.key == context.settings.supply_lines_key
+            && context.keyboard.is_up(event.key)
+        {
+            context.settings.set_show_supply_lines(
+                !context.settings.show_supply_lines,
+                &mut context.browser_storages,
+            );
+        }
+        if event.down
+            && event.key == context.settings.screenshot_key
+            && context.keyboard.is_up(event.key)
+        {
+            self.screenshot = Some(if event.shift { 2 } else { 1 });
+        }
+        if event.down
+            && event.key == context.settings.repeat_deploy_key
+            && context.keyboard.is_up(event.key)
+            && context.state.game.alive
+        {
+            self.repeat_deployment(context);
+        }
+        if event.down
+            && event.key == context.settings.undo_key
+            && context.keyboard.is_up(event.key)
+            && context.state.game.alive
+        {
+            self.undo_clear_supply_lines(context);
+        }
+        if event.down && event.key == Key::SlashQuestion && context.keyboard.is_up(event.key) {
+            self.show_shortcuts = !self.show_shortcuts;
+        } else if event.down && event.key == Key::Escape {
+            self.show_shortcuts = false;
+        }
+        if event.down {
+            self.navigate_tower_menu(event.key, context);
+        }
+    }
+
+    /// Selects tower menu actions with number or arrow keys, and performs the selected one with
+    /// Space or Enter.
+    fn navigate_tower_menu(&mut self, key: Key, context: &mut Context<Self>) {
+        let actions = self.tower_menu_actions(context);
+        let Some(tower_id) = self.selected_tower_id.filter(|_| !actions.is_empty()) else {
+            return;
+        };
+        let cursor = self.menu_cursor(context);
+        let last = actions.len() - 1;
+        let next = match key {
+            Key::Down => Some(cursor.map_or(0, |i| if i == last { 0 } else { i + 1 })),
+            Key::Up => Some(cursor.map_or(last, |i| i.checked_sub(1).unwrap_or(last))),
+            Key::Space | Key::Enter => {
+                if let Some(i) = cursor {
+                    let action = actions[i];
+                    let ready = match action {
+                        TowerUiEvent::Upgrade { tower_type, .. } => {
+                            tower_type.has_prerequisites(&context.state.game.tower_counts)
+                        }
+                        _ => true,
+                    };
+                    if ready {
+                        self.menu_cursor = None;
+                        self.ui(action, context);
+                    }
+                }
+                return;
+            }
+            _ => key
+                .digit_with_ten()
+                .map(|digit| digit.get() as usize - 1)
+                .filter(|&i| i <= last),
+        };
+        if let Some(i) = next {
+            self.menu_cursor = Some((tower_id, i));
+        }
+    }
+
+    /// Valid [`Self::menu_cursor`] for the currently open tower menu.
+    fn menu_cursor(&self, context: &Context<Self>) -> Option<usize> {
+        let (tower_id, i) = self.menu_cursor?;
+        (Some(tower_id) == self.selected_tower_id && i < self.tower_menu_actions(context).len())
+            .then_some(i)
+    }
+
+    /// Actions of the open tower menu that can be chosen with the keyboard, in the order they
+    /// are shown (see [`TowerOverlay`][crate::ui::tower_overlay::TowerOverlay]).
+    fn tower_menu_actions(&self, context: &Context<Self>) -> Vec<TowerUiEvent> {
+        // Ui is hidden while dragging.
+        let Some((tower_id, tower)) = self
+            .selected_tower_id
+            .filter(|_| self.drag.is_none())
+            .and_then(|tower_id| Some((tower_id, context.state.game.world.chunk.get(tower_id)?)))
+        else {
+            return Vec::new();
+        };
+        let Some(me) = context.player_id().filter(|_| context.state.game.alive) else {
+            return Vec::new();
+        };
+        let Some(owner) = tower.player_id else {
+            return Vec::new();
+        };
+
+        if owner == me {
+            if !tower.active() {
+                return Vec::new();
+            }
+            let tower_type = tower.tower_type;
+            let basis = tower_type.basis();
+            tower_type
+                .upgrades()
+                .chain((basis != tower_type).then_some(basis))
+                .map(|upgrade| {
+                    if context.client.rewarded_ads
+                        && upgrade.level() > 0
+                        && !context.settings.unlocks.contains(upgrade)
+                    {
+                        TowerUiEvent::LockDialog(Some(upgrade))
+                    } else {
+                        TowerUiEvent::Upgrade {
+                            tower_id,
+                            tower_type: upgrade,
+                        }
+                    }
+                })
+                .collect()
+        } else {
+            // Spectators have no player.
+            let break_alliance = context
+                .state
+                .game
+                .world
+                .player
+                .get(&me)
+                .is_some_and(|p| p.actor.allies.contains(&owner));
+            vec![TowerUiEvent::Alliance {
+                with: owner,
+                break_alliance,
+            }]
+        }
+    }
+
     fn peek_mouse(&mut self, event: &MouseEvent, context: &mut Context<Self>) {
         update_visible(context);
+        self.attract.cancel();
+
+        // Clicking elsewhere cancels breaking an alliance.
+        if matches!(event, MouseEvent::Button { down: true, .. }) {
+            self.pending_break_alliance = None;
+        }
 
         match *event {
             MouseEvent::MoveViewSpace(view_space) => {
@@ -152,6 +581,10 @@ impl GameClient for TowerGame {
                 }
             }
             MouseEvent::Button { button, down, .. } => match button {
+                MouseButton::Middle if context.settings.middle_click_pan => {
+                    self.close_tower_menu();
+                    self.panning = down;
+                }
                 #[cfg(debug_assertions)]
                 MouseButton::Middle => {
                     if down {
@@ -164,7 +597,11 @@ impl GameClient for TowerGame {
                     }
                 }
                 MouseButton::Left => {
-                    if down {
+                    if down && context.keyboard.is_down(Key::Alt) {
+                        if context.state.game.alive {
+                            self.ping(context);
+                        }
+                    } else if down {
                         if self.drag.is_none() && !self.panning {
                             if let Some(drag_start) = context.mouse.view_position.and_then(|v| {
                                 get_closest(self.camera.to_world_position(v), context)
@@ -184,12 +621,16 @@ impl GameClient for TowerGame {
                     } else {
                         if let Some((start, current, current_start_time)) = Drag::zip(self.drag) {
                             if start == current {
-                                if self.selected_tower_id == Some(start) {
+                                if self.selected_tower_id == Some(start)
+                                    && (self.pinned || !context.settings.hover_select)
+                                {
                                     // Double click to deselect.
                                     // TODO don't deselect tower if tried dragging a path.
                                     self.selected_tower_id = None;
                                 } else {
+                                    // Clicking a hovered tower pins it.
                                     self.selected_tower_id = Some(start);
+                                    self.pinned = true;
                                 }
                             } else if let Some((source_tower, _destination_tower)) = context
                                 .state
@@ -203,7 +644,8 @@ impl GameClient for TowerGame {
                                     self.selected_tower_id = None;
                                 }
 
-                                let strength = source_tower.force_units();
+                                let percent = deploy_percent(context);
+                                let strength = source_tower.force_units_percent(percent);
                                 let tower_edge_distance = source_tower.tower_type.ranged_distance();
                                 let strength_edge_distance =
                                     (!strength.is_empty()).then(|| strength.max_edge_distance());
@@ -211,9 +653,10 @@ impl GameClient for TowerGame {
                                     .map_or(tower_edge_distance, |e| e.min(tower_edge_distance));
                                 let shorter_max_edge_distance =
                                     max_edge_distance != tower_edge_distance;
-                                let supply_tower_id = self.selected_tower_id.filter(|_| {
+                                let supply_tower_id = self.pinned_tower_id(context).filter(|_| {
                                     source_tower.generates_mobile_units()
                                         && !shorter_max_edge_distance
+                                        && percent == Tower::FULL_DEPLOY_PERCENT
                                 });
 
                                 let path = context.state.game.world.find_best_path(
@@ -233,20 +676,22 @@ impl GameClient for TowerGame {
                                         || context.client.time_seconds
                                             >= current_start_time + Self::RULER_DRAG_DELAY
                                     {
-                                        context.send_to_game(
-                                            if let Some(tower_id) = supply_tower_id {
-                                                let path = Path::new(path);
-                                                Command::SetSupplyLine {
-                                                    tower_id,
-                                                    // TODO accept any invalid path.
-                                                    path: (source_tower.supply_line.as_ref()
-                                                        != Some(&path))
-                                                    .then_some(path),
-                                                }
-                                            } else {
-                                                Command::deploy_force_from_path(path)
-                                            },
-                                        );
+                                        let command = if let Some(tower_id) = supply_tower_id {
+                                            let path = Path::new(path);
+                                            Command::SetSupplyLine {
+                                                tower_id,
+                                                // TODO accept any invalid path.
+                                                path: (source_tower.supply_line.as_ref()
+                                                    != Some(&path))
+                                                .then_some(path),
+                                                pull: false,
+                                            }
+                                        } else {
+                                            self.last_deployment = Some((path.clone(), percent));
+                                            Command::deploy_percent_from_path(path, percent)
+                                        };
+                                        self.tutorial.observe_command(&command);
+                                        context.send_to_game(command);
                                     }
                                 }
                             } else {
@@ -284,6 +729,7 @@ impl GameClient for TowerGame {
                 // Set this to something invalid, so it will be reset (and resent) on the next update.
                 self.tight_viewport = TowerRectangle::invalid();
                 self.margin_viewport = TowerRectangle::invalid();
+                self.viewport_rate_limit.fast_track();
                 // Stop receiving big updates (to avoid buffered updates causing issues).
                 context.send_to_game(Command::SetViewport(ChunkRectangle::invalid()))
             }
@@ -292,14 +738,29 @@ impl GameClient for TowerGame {
     }
 
     fn render(&mut self, elapsed_seconds: f32, context: &Context<Self>) {
+        // Render the screenshot at a higher resolution, restoring the canvas size afterwards.
+        let screenshot = self.screenshot.take().map(|scale| {
+            let renderer = self.render_chain.renderer();
+            let canvas_size = renderer.canvas_size();
+            let max_scale = renderer.max_canvas_size() / canvas_size.max_element().max(1);
+            renderer.set_canvas_size(canvas_size * scale.min(max_scale).max(1));
+            canvas_size
+        });
+
         let mut frame = self.render_chain.begin(context.client.time_seconds);
         let (renderer, layer) = frame.draw();
 
-        let camera = self.pan_zoom.get_center();
-        let zoom = self.pan_zoom.get_zoom();
+        let (camera, zoom) = self
+            .attract
+            .camera()
+            .unwrap_or((self.pan_zoom.get_center(), self.pan_zoom.get_zoom()));
         let canvas_size = renderer.canvas_size();
         self.camera.update(camera, zoom, canvas_size);
         let zoom_per_pixel = zoom / canvas_size.x as f32;
+        // In world units, so that text is at least the minimum text size on screen.
+        let min_text_px = context.common_settings.text_scale.min_px().unwrap_or(0.0);
+        let min_text_height =
+            min_text_px * js_hooks::window().device_pixel_ratio() as f32 * zoom_per_pixel;
 
         // Make sure this is after `Renderer::set_camera`.
         layer.background.update(camera, zoom, context, renderer);
@@ -321,18 +782,56 @@ impl GameClient for TowerGame {
             .map(|t| t.tower_type);
         let get_visibility = |id| is_visible(context, id).then_some(1.0).unwrap_or_default();
         let me = context.player_id();
+        // Holding the focus key dims towers the selected tower can't deploy to.
+        let focus = self
+            .selected_tower_id
+            .filter(|_| context.keyboard.is_down(context.settings.focus_key))
+            .zip(me)
+            .and_then(|(tower_id, me)| {
+                let tower = context.state.game.world.chunk.get(tower_id)?;
+                (tower.player_id == Some(me)).then(|| {
+                    context.state.game.world.reachable_towers(
+                        tower_id,
+                        deploy_max_edge_distance(tower, deploy_percent(context)),
+                        me,
+                        |id| is_visible(context, id),
+                    )
+                })
+            });
+        // Phase of pulsing indicators, which are static if reduced motion is preferred.
+        let pulse = if context.common_settings.reduced_motion {
+            0.0
+        } else {
+            (renderer.time * PI).sin()
+        };
+        // Like pulse, but per the game clock, so all viewers of a shared spectacle agree.
+        let shared_pulse = if context.common_settings.reduced_motion {
+            0.0
+        } else {
+            (context.state.game.clock_seconds() * PI).sin()
+        };
+        // Which types of our towers can be upgraded right now.
+        let mut upgrade_available = TowerArray::<bool>::default();
+        if context.settings.upgrade_indicators
+            && context.state.game.alive
+            && zoom_per_pixel < Self::UPGRADE_INDICATOR_ZOOM_PER_PIXEL
+        {
+            let unlocks = &context.settings.unlocks;
+            for (tower_type, available) in upgrade_available.iter_mut() {
+                *available = tower_type
+                    .upgrade_targets(&context.state.game.tower_counts)
+                    .any(|upgrade| !context.client.rewarded_ads || unlocks.contains(upgrade));
+            }
+        }
 
+        let sprites =
+            zoom_per_pixel > Self::SPRITE_ZOOM_PER_PIXEL && !context.settings.full_detail_towers;
         for (tower_id, tower) in context
             .state
             .game
             .visible
-            .iter(&context.state.game.world.chunk)
+            .iter_rect(&context.state.game.world.chunk, self.margin_viewport)
         {
-            if !self.margin_viewport.contains(tower_id) {
-                // TODO iter viewport intersection visible and towers.
-                continue;
-            }
-
             let tower_position = tower_id.as_vec2();
             let hovered = hovered_tower_id == Some(tower_id);
             let selected = self.selected_tower_id == Some(tower_id);
@@ -359,7 +858,9 @@ impl GameClient for TowerGame {
                 }
             }
 
-            let show_supply_lines = context.keyboard.is_down(Key::R);
+            // Holding R shows them momentarily.
+            let show_supply_lines =
+                context.settings.show_supply_lines || context.keyboard.is_down(Key::R);
             if show_supply_lines
                 || Some(tower_id) == self.selected_tower_id
                 || Some(tower_id) == hovered_tower_id
@@ -370,7 +871,15 @@ impl GameClient for TowerGame {
 
                 if show_supply_lines || !is_hover || !is_dragging {
                     if let Some(path) = &tower.supply_line {
-                        if tower.player_id.is_some() && tower.player_id == me {
+                        let mine = tower.player_id.is_some() && tower.player_id == me;
+                        // Supply lines are replicated like the rest of the tower, so allies'
+                        // are already known.
+                        let allied = context.settings.ally_supply_lines
+                            && !mine
+                            && me.zip(tower.player_id).map_or(false, |(me, owner)| {
+                                context.state.game.world.have_alliance(me, owner)
+                            });
+                        if mine || allied {
                             let alpha = if is_selected {
                                 if is_dragging {
                                     0.5 // Darken selected while changing it.
@@ -382,14 +891,29 @@ impl GameClient for TowerGame {
                             } else {
                                 0.3
                             };
-
-                            layer.roads.draw_path(
-                                path.iter(),
-                                Some(u32::MAX), // Existing supply lines must be valid.
-                                usize::MAX,
-                                true,
-                                |id| get_visibility(id) * alpha,
-                            );
+                            // Subdue allies' to distinguish them from ours.
+                            let alpha = if allied { alpha * 0.5 } else { alpha };
+
+                            if tower.supply_pull {
+                                // Arrows point the way the units go.
+                                let path: Vec<_> = path.iter().collect();
+                                layer.roads.draw_path_color(
+                                    path.into_iter().rev(),
+                                    Some(u32::MAX),
+                                    usize::MAX,
+                                    true,
+                                    RoadLayer::PULL_COLOR,
+                                    |id| get_visibility(id) * alpha,
+                                );
+                            } else {
+                                layer.roads.draw_path(
+                                    path.iter(),
+                                    Some(u32::MAX), // Existing supply lines must be valid.
+                                    usize::MAX,
+                                    true,
+                                    |id| get_visibility(id) * alpha,
+                                );
+                            }
                         }
                     }
                 }
@@ -427,6 +951,20 @@ impl GameClient for TowerGame {
                     color,
                     selected,
                 );
+
+                // Spawn protected rulers get a pulsing golden ring instead of a shield bubble.
+                if tower.units.has_ruler()
+                    && tower
+                        .player_id
+                        .is_some_and(|p| context.state.game.world.player(p).spawn_protected)
+                {
+                    layer.paths.draw_circle(
+                        tower_position,
+                        shield_radius.max(0.55) * 1.5 + pulse * 0.05,
+                        Some(Vec3::new(1.0, 0.85, 0.3).extend(0.7 + pulse * 0.2)),
+                        None,
+                    );
+                }
             }
 
             let mut nuke = None;
@@ -440,7 +978,7 @@ impl GameClient for TowerGame {
                 }
             }
             if let Some(color) = nuke {
-                let t = (renderer.time * PI).sin();
+                let t = shared_pulse;
                 let angle = (t * 0.075 + 0.25) * PI;
                 let scale = shield_radius.max(0.55) * 3.6 + t * 0.075;
                 let (stroke, _) = color.colors(true, hovered, selected);
@@ -456,22 +994,67 @@ impl GameClient for TowerGame {
                 );
             }
 
-            let active = tower.active();
-            let (stroke_color, fill_color) = color.colors(active, hovered, selected);
-
-            // TODO draw simple sprite above certain zoom_per_pixel.
-            layer.paths.draw_path(
-                PathId::Tower(tower.tower_type),
-                tower_position,
-                0.0,
-                tower_scale,
-                stroke_color,
-                fill_color,
-                active,
+            let dimmed = focus
+                .as_ref()
+                .is_some_and(|reachable| !selected && !reachable.contains(&tower_id));
+            let active = tower.active() && !dimmed;
+            let (stroke_color, fill_color) =
+                if let Some(&(from, time)) = self.recolors.get(&tower_id) {
+                    let t = (context.client.time_seconds - time) * (1.0 / Self::RECOLOR_SECONDS);
+                    color.mix(from, t, active, hovered, selected)
+                } else {
+                    let (stroke_color, fill_color) = color.colors(active, hovered, selected);
+                    (
+                        stroke_color.map(|c| c.extend(1.0)),
+                        fill_color.map(|c| c.extend(1.0)),
+                    )
+                };
+            let alpha = if dimmed { 0.25 } else { 1.0 };
+            let (stroke_color, fill_color) = (
+                stroke_color.map(|c| c.truncate().extend(c.w * alpha)),
+                fill_color.map(|c| c.truncate().extend(c.w * alpha)),
             );
 
+            if sprites {
+                layer.sprites.draw_tower(
+                    tower.tower_type,
+                    tower_position,
+                    tower_scale,
+                    stroke_color,
+                    fill_color,
+                );
+            } else {
+                layer.paths.draw_path_a(
+                    PathId::Tower(tower.tower_type),
+                    tower_position,
+                    0.0,
+                    tower_scale,
+                    stroke_color,
+                    fill_color,
+                    active,
+                );
+            }
+
+            if upgrade_available[tower.tower_type]
+                && tower.active()
+                && tower.player_id.is_some()
+                && tower.player_id == me
+            {
+                let alpha = 0.5 + pulse.abs() * 0.25;
+                let offset = Vec2::splat(tower_scale * 0.45);
+                layer.paths.draw_path_a(
+                    PathId::UpgradeAvailable,
+                    tower_position + offset,
+                    0.0,
+                    0.4,
+                    Some(Vec3::splat(0.9).extend(alpha)),
+                    Some(Vec3::new(0.3, 0.8, 0.4).extend(alpha)),
+                    false,
+                );
+            }
+
             if show_similar_towers == Some(tower.tower_type) {
-                let x = (renderer.time * PI).sin().abs();
+                let x = pulse.abs();
                 let scale = (zoom * 0.025).max(2.0) * 0.75;
                 let offset = Vec2::new(0.0, tower_scale * 0.75 + scale * 0.45 + scale * (x * 0.12));
                 let color = 1.0 - x * 0.1;
@@ -488,7 +1071,7 @@ impl GameClient for TowerGame {
             }
 
             let (stroke_color, fill_color) = color.colors(true, hovered, selected);
-            if zoom_per_pixel < 0.2 {
+            if zoom_per_pixel < Self::UNIT_LAYOUT_ZOOM_PER_PIXEL {
                 for unit_layout in tower_layout(tower, context.client.time_seconds) {
                     layer.paths.draw_path(
                         PathId::Unit(unit_layout.unit),
@@ -500,15 +1083,51 @@ impl GameClient for TowerGame {
                         unit_layout.active,
                     );
                 }
+            } else if zoom_per_pixel < Self::UNIT_BADGE_ZOOM_PER_PIXEL
+                && !tower.units.is_empty()
+                && (context.cheats()
+                    || tower.player_id.zip(me).map_or(false, |(owner, me)| {
+                        owner == me || context.state.game.world.have_alliance(owner, me)
+                    }))
+            {
+                let color = Vec3::splat(0.88).lerp(Vec3::new(1.0, 0.6, 0.3), tower.fullness());
+                layer.text.draw(
+                    &tower.units.len().to_string(),
+                    tower_position - Vec2::Y * (tower_scale * 0.5 + 0.3),
+                    0.9f32.max(min_text_height),
+                    color.extend(1.0).to_array().map(|c| (c * 255.0) as u8),
+                );
             }
 
             let mut draw_force = |force: &Force| {
-                let force_position =
-                    force.interpolated_position(context.state.game.time_since_last_tick);
+                let force_position = force.interpolated_position_ticks(
+                    context
+                        .state
+                        .game
+                        .interpolation_ticks(context.settings.extrapolation),
+                );
 
                 let color = Color::new(context, force.player_id);
                 let (stroke_color, fill_color) = color.colors(true, hovered, selected);
 
+                if context.settings.motion_trails {
+                    let trail_color = fill_color.or(stroke_color).unwrap_or(Vec3::ONE);
+                    let time = context.client.time_seconds;
+                    let mut trail = self.trails.record(force, force_position, time);
+                    let mut newer = trail.next().unwrap();
+                    for older in trail.filter(|older| older.0 != force_position) {
+                        let alpha = |age: f32| (1.0 - age).max(0.0) * 0.5;
+                        layer.roads.draw_road(
+                            newer.0,
+                            older.0,
+                            0.15,
+                            trail_color.extend(alpha(newer.1)),
+                            alpha(older.1),
+                        );
+                        newer = older;
+                    }
+                }
+
                 let (shield_intensity, shield_radius) =
                     shield_intensity_radius(force.units.available(Unit::Shield));
                 draw_shield(
@@ -545,15 +1164,8 @@ impl GameClient for TowerGame {
                     .filter(|f| !is_visible(context, f.current_destination()))
                     .for_each(|force| draw_force(force));
             }
-
-            if !self.tight_viewport.contains(tower_id) {
-                continue;
-            }
-
-            if let Some(player_id) = tower.player_id {
-                self.territories.record(tower_id, player_id);
-            }
         }
+        self.trails.retain_seen();
 
         // Draw keys.
         if context.client.rewarded_ads && let Some((key, opacity)) = self.key_dispenser.key(context.client.time_seconds) && is_visible(context, key) {
@@ -561,25 +1173,54 @@ impl GameClient for TowerGame {
             layer.paths.draw_path_a(PathId::Key, key.as_vec2() + Vec2::new(0.0, 1.5), 0.0, 1.0, stroke.map(|s| s.extend(opacity)), fill.map(|f| f.extend(opacity)), false)
         }
 
-        self.animations.retain(|animation| {
+        let debris = context.settings.effects == EffectsQuality::High;
+        let camera = &self.camera;
+        let view_min = camera.to_world_position(Vec2::NEG_ONE);
+        let view_max = camera.to_world_position(Vec2::ONE);
+        self.animations.retain_mut(|animation| {
+            let visible = animation.intersects(view_min, view_max);
             animation.render(
-                |center: Vec2, radius: f32, color: Vec4| {
-                    layer.paths.draw_path_a(
-                        PathId::Explosion,
+                |path_id: PathId, center: Vec2, scale: f32, color: Vec4| {
+                    layer
+                        .paths
+                        .draw_path_a(path_id, center, 0.0, scale, None, Some(color), false);
+                },
+                |text: &str, center: Vec2, scale: f32, color: Vec4| {
+                    layer.text.draw(
+                        text,
                         center,
-                        0.0,
-                        radius,
-                        None,
-                        Some(color),
-                        false,
+                        scale.max(min_text_height),
+                        color.to_array().map(|c| (c * 255.0) as u8),
                     );
                 },
+                |center: Vec2, count: usize, speed: f32, radius: f32, color: Vec3| {
+                    // Don't waste particles on explosions that can't be seen.
+                    let view_position = camera.to_view_position(center);
+                    if debris && view_position.abs().max_element() < 1.2 {
+                        layer.debris.emit(
+                            center,
+                            count,
+                            speed,
+                            radius,
+                            color,
+                            context.client.time_seconds,
+                        );
+                    }
+                },
+                &AnimationPalette::new(
+                    context.common_settings.high_contrast,
+                    context.settings.colorblind,
+                ),
                 context.client.time_seconds,
+                context.common_settings.reduced_motion,
+                visible,
             )
         });
 
-        self.territories
-            .update(elapsed_seconds, |player_id, center, count| {
+        context.state.game.territories.update(
+            self.tight_viewport,
+            elapsed_seconds,
+            |player_id, center, count| {
                 if let Some(player) = context.state.core.player_or_bot(player_id) {
                     let outgoing_request = me
                         .map(|me| {
@@ -614,7 +1255,7 @@ impl GameClient for TowerGame {
                     if !is_me || zoom > 30.0 {
                         let tower_area = count as f32 * (TowerId::CONVERSION as f32).powi(2);
                         let max_text_height = tower_area.sqrt() * 0.5;
-                        let text_height = (zoom * 0.05).min(max_text_height);
+                        let text_height = (zoom * 0.05).min(max_text_height).max(min_text_height);
                         let center = center + Vec2::Y * (text_height * 0.5 + 1.0);
 
                         layer.text.draw(
@@ -642,17 +1283,25 @@ impl GameClient for TowerGame {
                         }
                     }
                 }
-            });
+            },
+        );
 
         Self::draw_drag_path(
             self.drag,
-            self.selected_tower_id,
+            self.pinned_tower_id(context),
             &get_visibility,
+            min_text_height,
             context,
             layer,
         );
 
         frame.end(&self.camera);
+
+        if let Some(canvas_size) = screenshot {
+            let renderer = self.render_chain.renderer();
+            renderer.download_png("kiomet.png");
+            renderer.set_canvas_size(canvas_size);
+        }
     }
 
     fn ui(&mut self, event: TowerUiEvent, context: &mut Context<Self>) {
@@ -661,10 +1310,26 @@ impl GameClient for TowerGame {
                 with,
                 break_alliance,
             } => {
-                context.send_to_game(Command::Alliance {
-                    with,
-                    break_alliance,
-                });
+                // Breaking a mutual alliance (not cancelling a request) may require confirmation.
+                let confirm = break_alliance
+                    && context.settings.confirm_break_alliance
+                    && context
+                        .player_id()
+                        .is_some_and(|me| context.state.game.world.have_alliance(me, with));
+                if confirm && self.pending_break_alliance != Some(with) {
+                    self.pending_break_alliance = Some(with);
+                } else {
+                    context.send_to_game(Command::Alliance {
+                        with,
+                        break_alliance,
+                    });
+                    self.close_tower_menu();
+                }
+            }
+            TowerUiEvent::ApplyBlueprint { tower_id, index } => {
+                if let Some(queued) = self.queue_blueprint(tower_id, index, context) {
+                    self.blueprint_queue.insert(tower_id, queued);
+                }
                 self.close_tower_menu();
             }
             TowerUiEvent::DismissCaptureTutorial => {
@@ -673,26 +1338,48 @@ impl GameClient for TowerGame {
             TowerUiEvent::DismissUpgradeTutorial => {
                 self.tutorial.dismiss_upgrade();
             }
+            TowerUiEvent::RetreatAll => {
+                self.retreat_all(context);
+            }
+            TowerUiEvent::ReplayTutorial => {
+                self.tutorial.replay();
+            }
+            TowerUiEvent::Emote(emote_id) => {
+                if self.emote_bucket.try_consume(1) {
+                    context.send_to_game(Command::Emote(emote_id));
+                }
+            }
+            TowerUiEvent::GiftTower { tower_id, to } => {
+                context.send_to_game(Command::GiftTower { tower_id, to });
+                self.close_tower_menu();
+            }
+            TowerUiEvent::ToggleSupplyPull(tower_id) => {
+                if let Some(tower) = context.state.game.world.chunk.get(tower_id) {
+                    context.send_to_game(Command::SetSupplyLine {
+                        tower_id,
+                        path: tower.supply_line.clone(),
+                        pull: !tower.supply_pull,
+                    });
+                }
+            }
             TowerUiEvent::Spawn(alias) => {
                 context.send_set_alias(alias);
-                context.send_to_game(Command::Spawn);
+                context.send_to_game(spawn_command(&context.settings));
+            }
+            TowerUiEvent::PlayAgain => {
+                context.send_to_game(spawn_command(&context.settings));
+            }
+            TowerUiEvent::DismissDeathScreen => {
+                self.death_screen_dismissed = true;
             }
             TowerUiEvent::PanTo(tower_id) => {
-                self.pan_zoom.pan_to(tower_id.as_vec2());
+                self.pan_to(tower_id.as_vec2(), context);
             }
             TowerUiEvent::Upgrade {
                 tower_id,
                 tower_type,
             } => {
-                if let Some(unlocks) = context.settings.unlocks.unlock(tower_type) {
-                    context
-                        .settings
-                        .set_unlocks(unlocks, &mut context.browser_storages);
-                }
-                context.send_to_game(Command::Upgrade {
-                    tower_id,
-                    tower_type,
-                });
+                self.upgrade(tower_id, tower_type, context);
                 self.close_tower_menu();
             }
             TowerUiEvent::Unlock(tower_type) => {
@@ -706,6 +1393,9 @@ impl GameClient for TowerGame {
             TowerUiEvent::LockDialog(show) => {
                 self.lock_dialog = show;
             }
+            TowerUiEvent::Shortcuts(show) => {
+                self.show_shortcuts = show;
+            }
         }
     }
 
@@ -724,9 +1414,45 @@ impl GameClient for TowerGame {
             self.move_world_space(world_space, context);
         }
 
+        self.retreat_all_rate_limit.update(elapsed_seconds);
+        if context.keyboard.is_down(context.settings.retreat_all_key) {
+            self.retreat_all(context);
+        }
+        self.retreat_all_command_bucket.update(elapsed_seconds);
+        let count = self
+            .retreat_all_commands
+            .len()
+            .min(self.retreat_all_command_bucket.available() as usize);
+        self.retreat_all_command_bucket.try_consume(count as u32);
+        for command in self.retreat_all_commands.drain(..count) {
+            context.send_to_game(command);
+        }
+        self.emote_bucket.update(elapsed_seconds);
+
+        // Developer only, for capturing context of bugs.
+        self.copy_debug_state_rate_limit.update(elapsed_seconds);
+        if context.cheats()
+            && context.keyboard.is_down(Key::Shift)
+            && context.keyboard.is_down(Key::C)
+            && self.copy_debug_state_rate_limit.ready()
+        {
+            self.copy_debug_state(context);
+        }
+
         let ticked = std::mem::take(&mut context.state.game.ticked);
         if ticked {
+            self.clock.update(context.state.game.world.singleton().tick);
+            let me = me.filter(|_| context.state.game.alive);
+            let world = &context.state.game.world;
+            self.stats.tick(
+                self.clock.whole_secs(),
+                me.into_iter()
+                    .flat_map(|me| world.iter_player_towers(me))
+                    .map(|(tower_id, _)| tower_id),
+            );
             self.tutorial.update(context);
+            self.auto_retreat(context);
+            self.advance_blueprints(context);
             if context.client.rewarded_ads && self.key_dispenser.update(context) {
                 context.settings.set_unlocks(
                     context.settings.unlocks.add_key(),
@@ -735,14 +1461,25 @@ impl GameClient for TowerGame {
             }
         }
 
+        self.hover_select(context);
+
         if context.keyboard.is_down(Key::R) && context.keyboard.is_down(Key::Shift) {
             if let Some(tower_id) = self.selected_tower_id {
                 // Clear supply line of selected tower.
                 if let Some(tower) = context.state.game.world.chunk.get(tower_id) {
-                    if tower.supply_line.is_some() {
+                    if let Some(path) = tower.supply_line.clone() {
+                        self.supply_line_undo.push(
+                            ClearedSupplyLine {
+                                tower_id,
+                                path,
+                                pull: tower.supply_pull,
+                            },
+                            context.client.time_seconds,
+                        );
                         context.send_to_game(Command::SetSupplyLine {
                             tower_id,
                             path: None,
+                            pull: false,
                         })
                     }
                 }
@@ -752,19 +1489,24 @@ impl GameClient for TowerGame {
                     .state
                     .game
                     .visible
-                    .iter(&context.state.game.world.chunk)
-                    .filter(|&(id, t)| {
-                        self.margin_viewport.contains(id)
-                            && t.supply_line.is_some()
-                            && t.player_id.is_some()
-                            && t.player_id == me
-                    })
-                    .next();
-                if let Some((tower_id, _)) = tower {
+                    .iter_rect(&context.state.game.world.chunk, self.margin_viewport)
+                    .find(|&(_, t)| {
+                        t.supply_line.is_some() && t.player_id.is_some() && t.player_id == me
+                    });
+                if let Some((tower_id, tower)) = tower {
+                    self.supply_line_undo.push(
+                        ClearedSupplyLine {
+                            tower_id,
+                            path: tower.supply_line.clone().unwrap(),
+                            pull: tower.supply_pull,
+                        },
+                        context.client.time_seconds,
+                    );
                     // TODO iter viewport intersection visible and towers.
                     context.send_to_game(Command::SetSupplyLine {
                         tower_id,
                         path: None,
+                        pull: false,
                     });
                 }
             }
@@ -772,6 +1514,14 @@ impl GameClient for TowerGame {
 
         self.pan_zoom
             .set_aspect_ratio(self.render_chain.renderer().aspect_ratio());
+        self.pan_zoom.set_zoom_limit(
+            context
+                .state
+                .game
+                .max_view_radius
+                .filter(|_| !context.cheats())
+                .map(|radius| radius as f32 * TowerId::CONVERSION as f32),
+        );
 
         if context.cheats() && context.keyboard.is_down(Key::B) {
             self.pan_zoom.set_bounds(
@@ -790,21 +1540,62 @@ impl GameClient for TowerGame {
                 context.cheats() && context.keyboard.is_down(Key::N),
             );
         }
+        self.pan_zoom.set_inertia(
+            context
+                .settings
+                .pan_inertia
+                .then_some(context.settings.pan_deceleration),
+        );
+        if self.panning {
+            self.pan_zoom.hold();
+        }
+        // After bounds, so animation and inertia are clamped to them.
+        self.pan_zoom.update(elapsed_seconds);
 
         context.audio.set_muted_by_game(!context.state.game.alive);
+        context
+            .audio
+            .set_music_volume_setting(context.settings.music_volume.multiplier());
 
         if context.state.game.alive {
             if !context.audio.is_playing(Audio::Music) {
-                context.audio.play(Audio::Music);
+                context
+                    .audio
+                    .play_with_fade(Audio::Music, 1.0, Self::MUSIC_FADE_SECONDS);
             }
 
             if !self.was_alive {
-                self.pan_zoom.reset_center();
-                self.pan_zoom.reset_zoom()
+                let me = context.player_id();
+                let world = &context.state.game.world;
+                if let Some((selected_tower_id, pinned)) = self.reconnect.restore(|tower_id| {
+                    world.chunk.get(tower_id).is_some_and(|t| t.player_id == me)
+                }) {
+                    // Reconnected, so pick up where we left off.
+                    self.selected_tower_id = selected_tower_id;
+                    self.pinned = pinned;
+                } else {
+                    // Start afresh, e.g. after playing again.
+                    self.pan_zoom.reset_center();
+                    self.pan_zoom.reset_zoom();
+                    self.stats = Default::default();
+                    self.death_screen_dismissed = false;
+                    self.last_deployment = None;
+                    self.last_retreat = None;
+                    self.retreated = false;
+                    self.retreat_failed = None;
+                    self.menu_cursor = None;
+                    self.pending_break_alliance = None;
+                }
+            }
+            if context.socket.is_reconnecting() {
+                self.reconnect.save(self.selected_tower_id, self.pinned);
             }
+            self.attract.cancel();
 
             let mut pan = Vec2::ZERO;
             let mut any = false;
+            // Up and down arrows are for the tower menu while navigating it.
+            let menu_cursor = self.menu_cursor(context).is_some();
 
             if context
                 .keyboard
@@ -824,20 +1615,14 @@ impl GameClient for TowerGame {
                 pan.x -= 1.0;
                 any = true;
             }
-            if context
-                .keyboard
-                .state(Key::Down)
-                .combined(context.keyboard.state(Key::S))
-                .is_down()
+            if context.keyboard.is_down(Key::S)
+                || (!menu_cursor && context.keyboard.is_down(Key::Down))
             {
                 pan.y += 1.0;
                 any = true;
             }
-            if context
-                .keyboard
-                .state(Key::Up)
-                .combined(context.keyboard.state(Key::W))
-                .is_down()
+            if context.keyboard.is_down(Key::W)
+                || (!menu_cursor && context.keyboard.is_down(Key::Up))
             {
                 pan.y -= 1.0;
                 any = true;
@@ -847,7 +1632,27 @@ impl GameClient for TowerGame {
 
             if context.keyboard.is_down(Key::H) {
                 if let Some(king) = context.state.game.alerts.ruler_position {
-                    self.pan_zoom.pan_to(king.as_vec2());
+                    self.pan_to(king.as_vec2(), context);
+                }
+            }
+
+            if context.keyboard.is_down(context.settings.fit_territory_key) {
+                if let Some(me) = context.player_id() {
+                    let territory = TowerRectangle::bounding(
+                        context
+                            .state
+                            .game
+                            .world
+                            .iter_player_towers(me)
+                            .map(|(tower_id, _)| tower_id),
+                    );
+                    // Minimum zoom prevents zooming in too far on a single tower.
+                    if territory.is_valid() {
+                        self.pan_zoom.fit(
+                            territory.bottom_left.floor_position(),
+                            territory.top_right.ceil_position(),
+                        );
+                    }
                 }
             }
 
@@ -868,63 +1673,202 @@ impl GameClient for TowerGame {
                 self.close_tower_menu();
             }
         } else {
-            context.audio.stop_playing(Audio::Music);
-            self.selected_tower_id = None;
+            context
+                .audio
+                .stop_playing_with_fade(Audio::Music, Self::MUSIC_FADE_SECONDS);
             self.drag = None;
-            self.pan_zoom.reset_center();
-            self.pan_zoom.reset_zoom();
+            self.reconnect
+                .update(elapsed_seconds, context.socket.is_open());
+            if !self.reconnect.is_pending() {
+                self.selected_tower_id = None;
+                self.pan_zoom.reset_center();
+                self.pan_zoom.reset_zoom();
+            }
+
+            let game = &context.state.game;
+            self.attract.update(
+                elapsed_seconds,
+                context.settings.attract_mode
+                    && !context.common_settings.reduced_motion
+                    && !self.reconnect.is_pending(),
+                self.pan_zoom.get_center(),
+                self.pan_zoom.get_zooms(),
+                |min, max| {
+                    // Prefer following bots, which are always active.
+                    let owned = |bot: bool| {
+                        game.visible
+                            .iter(&game.world.chunk)
+                            .filter(|(_, tower)| tower.player_id.map(PlayerId::is_bot) == Some(bot))
+                            .map(|(tower_id, _)| tower_id.as_vec2())
+                            .filter(|&p| p.cmpge(min).all() && p.cmple(max).all())
+                            .choose(&mut rand::thread_rng())
+                    };
+                    owned(true).or_else(|| owned(false))
+                },
+            );
         }
 
         // Time passed.
-        context.state.game.time_since_last_tick += elapsed_seconds;
+        context
+            .state
+            .game
+            .advance_time(elapsed_seconds, context.settings.interpolation_smoothing);
+
+        let now = context.client.time_seconds;
+        self.recolors
+            .retain(|_, &mut (_, time)| now - time < Self::RECOLOR_SECONDS);
+
+        let alert_verbosity = context.settings.alert_verbosity;
+        for InfoEvent {
+            position,
+            info,
+            tick,
+        } in std::mem::take(&mut context.state.game.info_events)
+        {
+            // All viewers of the event agree on how long ago it happened.
+            let seconds_ago = context.state.game.seconds_since(tick);
+
+            if context.cheats() {
+                if self.recent_info.len() >= Self::MAX_RECENT_INFO {
+                    self.recent_info.pop_front();
+                }
+                self.recent_info.push_back(info);
+            }
 
-        for InfoEvent { position, info } in std::mem::take(&mut context.state.game.info_events) {
             let volume = 1.0 / (1.0 + position.distance(self.pan_zoom.get_center()));
 
             let animation_type = match info {
-                Info::Emp(player_id) => {
-                    let color = Color::new(context, player_id);
-                    Some(AnimationType::Emp(color.make_gray_red()))
-                }
+                Info::Emp(player_id) => Some(AnimationType::Emp(Color::new(context, player_id))),
                 Info::NuclearExplosion => Some(AnimationType::NuclearExplosion),
                 Info::ShellExplosion => Some(AnimationType::ShellExplosion),
                 _ => None,
             };
 
             if let Some(animation_type) = animation_type {
-                self.animations.push(Animation::new(
+                self.animations.push(Animation::shared(
                     position,
                     animation_type,
                     context.client.time_seconds,
+                    seconds_ago,
                 ));
             }
 
+            let previous_owner = match info {
+                Info::GainedTower {
+                    tower_id, reason, ..
+                } => Some((
+                    tower_id,
+                    match reason {
+                        GainedTowerReason::CapturedFrom(player_id) => player_id,
+                        GainedTowerReason::GiftedBy(player_id) => Some(player_id),
+                        GainedTowerReason::Explored | GainedTowerReason::Spawned => None,
+                    },
+                )),
+                Info::LostTower {
+                    tower_id,
+                    player_id,
+                    ..
+                } => Some((tower_id, Some(player_id))),
+                _ => None,
+            };
+            if let Some((tower_id, previous_owner)) = previous_owner
+                && self.recolors.len() < Self::MAX_RECOLORS
+                && is_visible(context, tower_id)
+            {
+                // Both events happen when a tower is captured, and agree on the previous owner.
+                self.recolors
+                    .entry(tower_id)
+                    .or_insert((Color::new(context, previous_owner), now));
+            }
+
+            // Fights at towers out of sight must not be revealed.
+            if let Info::Combat {
+                tower_id,
+                attacker_lost,
+                defender_lost,
+            } = info
+                && context.settings.damage_numbers
+                && is_visible(context, tower_id)
+            {
+                for (count, attacker) in [(attacker_lost, true), (defender_lost, false)] {
+                    if count > 0 {
+                        self.animations.push(Animation::shared(
+                            position,
+                            AnimationType::Damage { count, attacker },
+                            context.client.time_seconds,
+                            seconds_ago,
+                        ));
+                    }
+                }
+            }
+
+            if let Some(me) = me
+                && context.state.game.alive
+            {
+                self.stats.on_info(&info, me);
+            }
+
             match info {
                 Info::GainedTower {
                     player_id, reason, ..
                 } if Some(player_id) == me
-                    && matches!(reason, GainedTowerReason::CapturedFrom(_)) =>
+                    && matches!(reason, GainedTowerReason::CapturedFrom(_))
+                    && alert_verbosity.important() =>
                 {
                     context.audio.play_with_volume(Audio::Success, volume);
                 }
-                Info::LostTower { player_id, .. } if Some(player_id) == me => {
+                Info::LostTower { player_id, .. }
+                    if Some(player_id) == me && alert_verbosity.important() =>
+                {
                     context.audio.play_with_volume(Audio::Loss, volume);
                 }
-                Info::LostForce(player_id) if Some(player_id) == me => {
+                Info::LostForce(player_id) if Some(player_id) == me && alert_verbosity.all() => {
                     context.audio.play_with_volume(Audio::Pain, volume);
                 }
                 _ => {}
             }
         }
 
+        for EmoteEvent {
+            tower_id, emote_id, ..
+        } in std::mem::take(&mut context.state.game.emotes)
+        {
+            if Self::emotes(context) {
+                self.animations.push(Animation::new(
+                    tower_id.as_vec2(),
+                    AnimationType::Emote(emote_id),
+                    context.client.time_seconds,
+                ));
+            }
+        }
+
+        for PingEvent {
+            player_id,
+            tower_id,
+        } in std::mem::take(&mut context.state.game.pings)
+        {
+            let color = Color::new(context, Some(player_id));
+            self.animations.push(Animation::new(
+                tower_id.as_vec2(),
+                AnimationType::Ping(color),
+                context.client.time_seconds,
+            ));
+        }
+
         let center = self.pan_zoom.get_center();
         let bottom_left = center - self.pan_zoom.get_zooms();
         let top_right = center + self.pan_zoom.get_zooms();
         let tight_viewport =
             TowerRectangle::new(TowerId::floor(bottom_left), TowerId::ceil(top_right));
 
+        self.viewport_rate_limit.update(elapsed_seconds);
         if tight_viewport != self.tight_viewport {
-            let viewport_margin = U16Vec2::splat(2);
+            let low_bandwidth = context.settings.low_bandwidth;
+            let viewport_margin = U16Vec2::splat(if low_bandwidth {
+                Self::LOW_BANDWIDTH_VIEWPORT_MARGIN
+            } else {
+                Self::VIEWPORT_MARGIN
+            });
             let margin_viewport = TowerRectangle::new(
                 TowerId::from(tight_viewport.bottom_left.saturating_sub(viewport_margin)),
                 TowerId::from(tight_viewport.top_right.saturating_add(viewport_margin)),
@@ -932,17 +1876,27 @@ impl GameClient for TowerGame {
 
             let viewport_chunks: ChunkRectangle = margin_viewport.into();
             let old_viewport_chunks: ChunkRectangle = self.margin_viewport.into();
-            if viewport_chunks != old_viewport_chunks {
-                context.send_to_game(Command::SetViewport(viewport_chunks));
+            let changed = viewport_chunks != old_viewport_chunks;
+            // If rate limited, leave the viewports alone so the change is retried next frame.
+            if !changed || !low_bandwidth || self.viewport_rate_limit.ready() {
+                if changed {
+                    context.send_to_game(Command::SetViewport(viewport_chunks));
+                }
+                self.tight_viewport = tight_viewport;
+                self.margin_viewport = margin_viewport;
             }
-            self.tight_viewport = tight_viewport;
-            self.margin_viewport = margin_viewport;
         }
 
         context.set_ui_props(TowerUiProps {
             lock_dialog: self.lock_dialog,
             alive: context.state.game.alive,
             death_reason: context.state.game.death_reason.into(),
+            death_stats: (!context.state.game.alive
+                && context.settings.death_screen
+                && !self.death_screen_dismissed
+                && context.state.game.death_reason.0.is_some())
+            .then(|| self.stats.summary()),
+            spawn_queue: context.state.game.spawn_queue,
             selected_tower: self.selected_tower_id.and_then(|tower_id| {
                 // Don't obstruct drag.
                 if self.drag.is_some() {
@@ -963,24 +1917,213 @@ impl GameClient for TowerGame {
                             .core
                             .player_id
                             .zip(tower.player_id)
-                            .map(|(us, them)| {
-                                context.state.game.world.player(us).allies.contains(&them)
+                            .and_then(|(us, them)| {
+                                let us = context.state.game.world.player.get(&us)?;
+                                Some(us.actor.allies.contains(&them))
                             })
                             .unwrap_or(false),
+                        allies: context
+                            .player_id()
+                            .filter(|&me| tower.player_id == Some(me) && !tower.units.has_ruler())
+                            .map(|me| {
+                                let world = &context.state.game.world;
+                                world
+                                    .player(me)
+                                    .allies
+                                    .iter()
+                                    .copied()
+                                    .filter(|&ally| world.have_alliance(me, ally))
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
                         tower,
                         tower_id,
+                        menu_cursor: self.menu_cursor(context),
+                        confirm_break_alliance: self.pending_break_alliance.is_some()
+                            && self.pending_break_alliance == tower.player_id,
                     })
             }),
             tower_counts: context.state.game.tower_counts,
-            alerts: context.state.game.alerts,
+            alerts: context
+                .settings
+                .alert_verbosity
+                .filter(context.state.game.alerts),
+            filling: filling_tower(context)
+                .filter(|_| context.settings.alert_verbosity.important()),
+            auto_retreat: self
+                .last_retreat
+                .filter(|&(_, time)| {
+                    context.client.time_seconds < time + Self::RETREAT_INDICATION_SECONDS
+                })
+                .map(|(tower_id, _)| tower_id),
             tutorial_alert: self.tutorial.alert(),
             unlocks: context.settings.unlocks.clone(),
+            emotes: Self::emotes(context),
+            network: context.settings.show_network.then(|| NetworkStats {
+                rtt: context.state.core.rtt,
+                jitter: context
+                    .client
+                    .jitter_monitor
+                    .jitter()
+                    .map(|jitter| (jitter * 1000.0) as u16),
+            }),
+            clock: context.settings.show_clock.then_some(self.clock),
+            world_event: context
+                .state
+                .game
+                .world
+                .world_event()
+                .map(|(event, remaining)| (event, remaining.to_whole_secs())),
+            paused: context.state.game.paused,
+            desync: context
+                .state
+                .game
+                .desync
+                .clone()
+                .filter(|_| context.cheats())
+                .map(Into::into),
+            shortcuts: self
+                .show_shortcuts
+                .then(|| shortcuts(&context.settings, context.cheats())),
         });
 
+        if self.was_alive != context.state.game.alive {
+            // Towers of a previous life are no longer ours.
+            self.supply_line_undo.clear();
+        }
         self.was_alive = context.state.game.alive;
     }
 }
 
+/// Returns the player's tower that will soon be full, if any, so they can be warned in advance.
+fn filling_tower(context: &Context<TowerGame>) -> Option<TowerId> {
+    const WARNING: Ticks = Ticks::from_whole_secs(10);
+
+    let me = context.player_id()?;
+    context
+        .state
+        .game
+        .world
+        .iter_player_towers(me)
+        .filter(|(_, tower)| tower.push_supply_line().is_none())
+        .filter_map(|(tower_id, tower)| {
+            tower
+                .will_overflow_in()
+                .filter(|&ticks| ticks != Ticks::ZERO && ticks <= WARNING)
+                .map(|ticks| (ticks, tower_id))
+        })
+        .min()
+        .map(|(_, tower_id)| tower_id)
+}
+
+/// Returns the shortest path, through only the player's own towers, from `src` to the nearest
+/// tower that is not under attack (and, if `interior`, is surrounded by the player's territory).
+fn path_to_safety(
+    context: &Context<TowerGame>,
+    src: TowerId,
+    max_edge_distance: Option<u32>,
+    interior: bool,
+) -> Option<Vec<TowerId>> {
+    /// Limit pathfinding work per attempt.
+    const MAX_CANDIDATES: usize = 8;
+
+    let me = context.player_id()?;
+    let chunk = &context.state.game.world.chunk;
+    let is_interior = |tower_id: TowerId| {
+        tower_id.neighbors().all(|neighbor_id| {
+            chunk
+                .get(neighbor_id)
+                .map_or(true, |t| t.player_id == Some(me))
+        })
+    };
+
+    let mut candidates: Vec<TowerId> = chunk
+        .iter_towers()
+        .map(|(tower_id, _)| tower_id)
+        .filter(|&tower_id| {
+            tower_id != src && is_safe(context, tower_id) && (!interior || is_interior(tower_id))
+        })
+        .collect();
+    candidates.sort_by_key(|&tower_id| src.distance_squared(tower_id));
+
+    candidates
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .find_map(|destination| {
+            // Never pass through towers that aren't ours or are also under attack.
+            context.state.game.world.find_best_path(
+                src,
+                destination,
+                max_edge_distance,
+                me,
+                |tower_id| is_visible(context, tower_id) && is_safe(context, tower_id),
+            )
+        })
+}
+
+/// Returns commands that stop or redirect the player's moving forces to the nearest of their
+/// towers that aren't under attack. Forces with no such destination are left alone.
+fn retreat_all_commands(context: &Context<TowerGame>) -> Vec<Command> {
+    let Some(me) = context.player_id() else {
+        return Vec::new();
+    };
+    let mut redirected = HashSet::new();
+    let mut commands = Vec::new();
+    for (tower_id, tower) in context
+        .state
+        .game
+        .visible
+        .iter(&context.state.game.world.chunk)
+    {
+        for force in tower
+            .inbound_forces
+            .iter()
+            .filter(|f| f.player_id == Some(me))
+        {
+            let source = force.current_source();
+            if !redirected.insert((tower_id, source)) {
+                // Same command would apply to this force too.
+                continue;
+            }
+            let path = if is_safe(context, tower_id) {
+                if force.path().iter().count() <= 2 {
+                    // Already stopping there.
+                    continue;
+                }
+                None
+            } else if let Some(path) =
+                path_to_safety(context, tower_id, force.units.max_edge_distance(), false)
+            {
+                Some(Path::new(path))
+            } else {
+                continue;
+            };
+            commands.push(Command::RedirectForce {
+                tower_id,
+                source,
+                path,
+            });
+        }
+    }
+    commands
+}
+
+/// Is the tower the player's and not under attack?
+fn is_safe(context: &Context<TowerGame>, tower_id: TowerId) -> bool {
+    let me = context.player_id();
+    context
+        .state
+        .game
+        .world
+        .chunk
+        .get(tower_id)
+        .map_or(false, |tower| {
+            me.is_some()
+                && tower.player_id == me
+                && tower.inbound_forces.iter().all(|f| f.player_id == me)
+        })
+}
+
 /// Should attempts to send the player's ruler through this tower be warned against?
 fn is_perilous(context: &Context<TowerGame>, tower_id: TowerId) -> bool {
     context
@@ -997,17 +2140,124 @@ fn is_perilous(context: &Context<TowerGame>, tower_id: TowerId) -> bool {
 }
 
 impl TowerGame {
+    /// How long [`Self::pan_to`] takes if [`TowerSettings::smooth_camera`] is enabled.
+    const PAN_TO_SECONDS: f32 = 0.25;
+
+    fn upgrade(&mut self, tower_id: TowerId, tower_type: TowerType, context: &mut Context<Self>) {
+        if let Some(unlocks) = context.settings.unlocks.unlock(tower_type) {
+            context
+                .settings
+                .set_unlocks(unlocks, &mut context.browser_storages);
+        }
+        let command = Command::Upgrade {
+            tower_id,
+            tower_type,
+        };
+        self.tutorial.observe_command(&command);
+        context.send_to_game(command);
+    }
+
+    /// Returns [`None`] if the blueprint doesn't (yet) apply to the tower.
+    fn queue_blueprint(
+        &self,
+        tower_id: TowerId,
+        index: usize,
+        context: &Context<Self>,
+    ) -> Option<QueuedBlueprint> {
+        let blueprint = context.settings.blueprints.0.get(index)?;
+        let tower = context.state.game.world.chunk.get(tower_id)?;
+        if tower.player_id.is_none() || tower.player_id != context.player_id() {
+            return None;
+        }
+        let steps = blueprint.remaining(tower.tower_type)?;
+        // Locked towers must be unlocked one at a time, via the lock dialog.
+        let unlocks = &context.settings.unlocks;
+        if context.client.rewarded_ads && !steps.iter().all(|&step| unlocks.contains(step)) {
+            return None;
+        }
+        Blueprint::validate(steps, tower.tower_type, &context.state.game.tower_counts).ok()?;
+        Some(QueuedBlueprint::new(steps))
+    }
+
+    /// Issues the next upgrade of each queued blueprint. Call once per tick.
+    fn advance_blueprints(&mut self, context: &mut Context<Self>) {
+        let me = context.player_id();
+        let mut upgrades = Vec::new();
+        self.blueprint_queue.retain(|&tower_id, queued| {
+            let Some(tower) = context.state.game.world.chunk.get(tower_id) else {
+                return false;
+            };
+            if tower.player_id.is_none() || tower.player_id != me {
+                return false;
+            }
+            match queued.advance(
+                tower.tower_type,
+                tower.active(),
+                &context.state.game.tower_counts,
+            ) {
+                BlueprintStep::Upgrade(tower_type) => upgrades.push((tower_id, tower_type)),
+                BlueprintStep::Wait => {}
+                BlueprintStep::Done => return false,
+            }
+            true
+        });
+        for (tower_id, tower_type) in upgrades {
+            self.upgrade(tower_id, tower_type, context);
+        }
+    }
+
+    /// Whether emotes are enabled by both the player and the server (see
+    /// [`FeatureFlags`][`core_protocol::dto::FeatureFlags`]).
+    fn emotes(context: &Context<Self>) -> bool {
+        context.settings.emotes && context.state.core.features.enabled("emotes", true)
+    }
+
+    /// Selects the tower under the mouse, if enabled and no selection is pinned. Leaves
+    /// selection alone while dragging or panning.
+    fn hover_select(&mut self, context: &Context<Self>) {
+        self.pinned &= self.selected_tower_id.is_some();
+        if !context.settings.hover_select
+            || self.pinned
+            || self.drag.is_some()
+            || self.panning
+            || !context.state.game.alive
+        {
+            return;
+        }
+        self.selected_tower_id = context
+            .mouse
+            .view_position
+            .and_then(|v| get_closest(self.camera.to_world_position(v), context));
+    }
+
+    /// [`Self::selected_tower_id`], unless it was only hovered. A hovered selection isn't
+    /// deliberate enough to, for example, set a supply line.
+    fn pinned_tower_id(&self, context: &Context<Self>) -> Option<TowerId> {
+        self.selected_tower_id
+            .filter(|_| self.pinned || !context.settings.hover_select)
+    }
+
     fn close_tower_menu(&mut self) {
+        self.pending_break_alliance = None;
         // Ui is already hidden while dragging.
         if self.drag.is_none() {
             self.selected_tower_id = None;
         }
     }
 
+    fn pan_to(&mut self, target: Vec2, context: &Context<Self>) {
+        if context.settings.smooth_camera {
+            self.pan_zoom.pan_to_animated(target, Self::PAN_TO_SECONDS);
+        } else {
+            self.pan_zoom.pan_to(target);
+        }
+    }
+
     fn draw_drag_path(
         drag: Option<Drag>,
         selected_tower_id: Option<TowerId>,
         get_visibility: &impl Fn(TowerId) -> f32,
+        min_text_height: f32,
         context: &Context<TowerGame>,
         layer: &mut TowerLayer,
     ) {
@@ -1020,7 +2270,8 @@ impl TowerGame {
             }
 
             // TODO don't duplicate this code with find best incomplete path.
-            let strength = source_tower.force_units();
+            let percent = deploy_percent(context);
+            let strength = source_tower.force_units_percent(percent);
             let tower_edge_distance = source_tower.tower_type.ranged_distance();
             let strength_edge_distance =
                 (!strength.is_empty()).then(|| strength.max_edge_distance());
@@ -1030,7 +2281,8 @@ impl TowerGame {
 
             let do_supply_line = selected_tower_id.is_some()
                 && source_tower.generates_mobile_units()
-                && !shorter_max_edge_distance;
+                && !shorter_max_edge_distance
+                && percent == Tower::FULL_DEPLOY_PERCENT;
 
             // Can drag supply lines even without units.
             if strength.is_empty() && !do_supply_line {
@@ -1055,11 +2307,47 @@ impl TowerGame {
                     .chain(std::iter::once(current))
                     .inspect(|&tower_id| perilous |= is_perilous(context, tower_id)),
                 max_edge_distance,
-                World::MAX_PATH_ROADS,
+                context.state.game.world.max_path_roads(),
                 do_supply_line,
                 get_visibility,
             );
 
+            if viable && !strength.is_empty() {
+                // Show how many units will actually be sent, and of what kind.
+                let position = current.as_vec2() + Vec2::Y * 2.5;
+                let label = if percent == Tower::FULL_DEPLOY_PERCENT {
+                    strength.len().to_string()
+                } else {
+                    format!("{} ({percent}%)", strength.len())
+                };
+                layer.text.draw(
+                    &label,
+                    position,
+                    1.2f32.max(min_text_height),
+                    [255, 255, 255, 220],
+                );
+
+                let (stroke, fill) = Color::Blue.colors(false, true, false);
+                for (unit_layout, count) in strength_layout(&strength) {
+                    let center = position + Vec2::Y * 1.5 + unit_layout.relative_position;
+                    layer.paths.draw_path(
+                        PathId::Unit(unit_layout.unit),
+                        center,
+                        unit_layout.angle,
+                        unit_layout.scale,
+                        stroke,
+                        fill,
+                        unit_layout.active,
+                    );
+                    layer.text.draw(
+                        &count.to_string(),
+                        center + Vec2::Y * 1.0,
+                        0.7f32.max(min_text_height),
+                        [255, 255, 255, 200],
+                    );
+                }
+            }
+
             if viable && perilous && strength.contains(Unit::Ruler) {
                 let progress = (context.client.time_seconds - current_start_time)
                     * (1.0 / Self::RULER_DRAG_DELAY);
@@ -1081,6 +2369,33 @@ impl TowerGame {
     }
 }
 
+/// How far a deployment of `percent` of `tower`'s units can go in one road, or [`None`] if it
+/// has to follow roads.
+fn deploy_max_edge_distance(tower: &Tower, percent: u8) -> Option<u32> {
+    let strength = tower.force_units_percent(percent);
+    let tower_edge_distance = tower.tower_type.ranged_distance();
+    let strength_edge_distance = (!strength.is_empty()).then(|| strength.max_edge_distance());
+    strength_edge_distance.map_or(tower_edge_distance, |e| e.min(tower_edge_distance))
+}
+
+/// Spawns per [`TowerSettings::spawn_preference`], waiting for room if
+/// [`TowerSettings::queue_spawn`].
+fn spawn_command(settings: &TowerSettings) -> Command {
+    match settings.spawn_preference {
+        Some(preference) if settings.queue_spawn => Command::QueueSpawn(preference),
+        preference => Command::Spawn(preference),
+    }
+}
+
+/// How much of a tower's units to deploy, depending on whether Ctrl is held.
+fn deploy_percent(context: &Context<TowerGame>) -> u8 {
+    if context.keyboard.is_down(Key::Ctrl) {
+        context.settings.deploy_fraction.percent()
+    } else {
+        Tower::FULL_DEPLOY_PERCENT
+    }
+}
+
 pub fn exists(context: &Context<TowerGame>, tower_id: TowerId) -> bool {
     context.state.game.world.chunk.get(tower_id).is_some()
 }
@@ -1097,11 +2412,12 @@ fn update_visible(context: &mut Context<TowerGame>) {
 
     let all_visible =
         !context.state.game.alive || (context.cheats() && context.keyboard.is_down(Key::B));
+    let ally_vision = context.state.game.ally_vision;
     context
         .state
         .game
         .visible
-        .update(&context.state.game.world, me, all_visible)
+        .update(&context.state.game.world, me, ally_vision, all_visible)
 }
 
 fn get_closest(point: Vec2, context: &Context<TowerGame>) -> Option<TowerId> {
@@ -68,6 +68,22 @@ pub fn force_layout(force: &Force) -> impl Iterator<Item = UnitLayout> + '_ {
     swarm_layout(&force.units, delta.y.atan2(delta.x))
 }
 
+/// A centered row with one icon per kind of unit in `strength`, along with how many there are.
+pub fn strength_layout(strength: &Units) -> impl Iterator<Item = (UnitLayout, usize)> + '_ {
+    const SPACING: f32 = 1.6;
+    let offset = (strength.iter().count().max(1) - 1) as f32 * SPACING * -0.5;
+    strength.iter().enumerate().map(move |(i, (unit, count))| {
+        let layout = UnitLayout {
+            unit,
+            relative_position: Vec2::X * (offset + i as f32 * SPACING),
+            angle: 0.0,
+            scale: unit_scale(unit) * 3.6,
+            active: false,
+        };
+        (layout, count)
+    })
+}
+
 fn swarm_layout(units: &Units, direction: f32) -> impl Iterator<Item = UnitLayout> + '_ {
     let mut i = 0;
     let unit_formations: &'static [Vec2] = bytemuck::cast_slice(&UNIT_FORMATION_BYTES.0);
@@ -251,8 +267,9 @@ impl Iterator for UnitTypeGridLayout {
 
 #[cfg(test)]
 mod tests {
-    use crate::layout::unit_angle;
+    use crate::layout::{strength_layout, unit_angle};
     use common::unit::Unit;
+    use common::units::Units;
     use std::f32::consts::TAU;
 
     #[test]
@@ -262,4 +279,24 @@ mod tests {
         assert!(unit_angle(Unit::Tank, 0.1) < 0.0);
         assert!(unit_angle(Unit::Tank, 0.1 + TAU) < 0.0);
     }
+
+    #[test]
+    fn test_strength_layout() {
+        assert_eq!(strength_layout(&Units::default()).count(), 0);
+
+        let mut strength = Units::default();
+        strength.add(Unit::Soldier, 5);
+        strength.add(Unit::Tank, 2);
+        strength.add(Unit::Ruler, 1);
+        let layouts: Vec<_> = strength_layout(&strength).collect();
+        assert_eq!(
+            layouts
+                .iter()
+                .map(|(l, c)| (l.unit, *c))
+                .collect::<Vec<_>>(),
+            strength.iter().collect::<Vec<_>>()
+        );
+        let sum: f32 = layouts.iter().map(|(l, _)| l.relative_position.x).sum();
+        assert!(sum.abs() < 0.001);
+    }
 }
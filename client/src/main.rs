@@ -14,20 +14,30 @@ use game::TowerGame;
 use ui::TowerUi;
 
 mod animation;
+mod attract;
 mod background;
+mod blueprint;
+mod clock;
 mod color;
 mod finite_index;
 mod game;
 mod key_dispenser;
 mod layout;
+mod particle;
 mod path;
+mod reconnect;
 mod road;
 mod settings;
+mod shortcut;
+mod sprite;
 mod state;
+mod stats;
 mod territory;
+mod trail;
 mod translation;
 mod tutorial;
 mod ui;
+mod undo;
 mod visible;
 
 fn main() {
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::{Vec2, Vec3, Vec4};
+use rand::Rng;
+use renderer::{derive_vertex, DefaultRender, Layer, RenderLayer, Renderer, Shader};
+use renderer2d::{Camera2d, Particle, ParticleLayer};
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+derive_vertex!(
+    struct Debris {
+        position: Vec2,
+        velocity: Vec2,
+        color: Vec4,
+        radius: f32,
+    }
+);
+
+impl Particle for Debris {
+    const LIFESPAN: f32 = 1.2;
+}
+
+/// Debris and smoke thrown out by explosions.
+#[derive(Layer)]
+pub struct DebrisLayer {
+    #[layer]
+    particles: ParticleLayer<Debris>,
+    shader: Shader,
+    /// Creation times of particles that may still be alive, oldest first.
+    created: VecDeque<f32>,
+}
+
+impl RenderLayer<&Camera2d> for DebrisLayer {
+    fn render(&mut self, renderer: &Renderer, camera: &Camera2d) {
+        if let Some(binding) = self.shader.bind(renderer) {
+            camera.prepare(&binding);
+            binding.uniform("uTime", renderer.time);
+            binding.uniform("uLifespan", Debris::LIFESPAN);
+            binding.uniform("uPixelsPerUnit", camera.pixels_per_unit());
+            self.particles.render(renderer, &binding);
+        }
+    }
+}
+
+impl DefaultRender for DebrisLayer {
+    fn new(renderer: &Renderer) -> Self {
+        Self {
+            particles: ParticleLayer::new(renderer),
+            shader: renderer.create_shader(
+                include_str!("shader/debris.vert"),
+                include_str!("shader/debris.frag"),
+            ),
+            created: Default::default(),
+        }
+    }
+}
+
+impl DebrisLayer {
+    /// Most particles alive at once, to bound the cost of big battles.
+    const MAX_PARTICLES: usize = 512;
+
+    /// Throws up to `count` particles of `radius` outward from `center` at up to `speed` units
+    /// per second, fewer if there are already too many.
+    pub fn emit(
+        &mut self,
+        center: Vec2,
+        count: usize,
+        speed: f32,
+        radius: f32,
+        color: Vec3,
+        time_seconds: f32,
+    ) {
+        let expiry = time_seconds - Debris::LIFESPAN;
+        while let Some(&created) = self.created.front() && created < expiry {
+            self.created.pop_front();
+        }
+
+        let count = count.min(Self::MAX_PARTICLES - self.created.len());
+        let mut rng = rand::thread_rng();
+        for _ in 0..count {
+            let (sin, cos) = rng.gen_range(0.0..TAU).sin_cos();
+            let velocity = Vec2::new(cos, sin) * rng.gen_range(0.2..1.0);
+            let shade = rng.gen_range(0.6..1.0);
+            self.particles.add(Debris {
+                position: center,
+                velocity: velocity * speed,
+                color: (color * shade).extend(rng.gen_range(0.5..0.9)),
+                radius: radius * rng.gen_range(0.5..1.0),
+            });
+            self.created.push_back(time_seconds);
+        }
+    }
+}
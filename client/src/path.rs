@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::color::Color;
+use common::emote::EmoteId;
 use common::tower::TowerType;
 use common::unit::Unit;
 use fxhash::FxHashMap;
@@ -23,7 +24,7 @@ use renderer::{
 use renderer2d::Camera2d;
 use std::cell::RefCell;
 
-const STROKE_WIDTH: f32 = 0.05;
+pub const STROKE_WIDTH: f32 = 0.05;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum PathId {
@@ -42,10 +43,14 @@ pub enum PathId {
     BreakAlliance,
     /// Request alliance with another player.
     RequestAlliance,
+    /// Emote rendered above a player's ruler.
+    Emote(EmoteId),
     /// Cursor renders on top of everything.
     Cursor,
     /// Key is HUD-only.
     Key,
+    /// One of our towers can be upgraded.
+    UpgradeAvailable,
 }
 
 impl PathId {
@@ -55,6 +60,7 @@ impl PathId {
             PathId::BreakAlliance => break_alliance(),
             PathId::Circle(radius) => circle(radius as f32),
             PathId::Cursor => cursor(),
+            PathId::Emote(emote_id) => emote(emote_id),
             PathId::Explosion => circle(1.0),
             PathId::Key => key(),
             PathId::Marker => marker(),
@@ -62,6 +68,7 @@ impl PathId {
             PathId::Target => target(),
             PathId::Tower(tower_type) => tower(tower_type),
             PathId::Unit(u) => unit(u),
+            PathId::UpgradeAvailable => upgrade_available(),
         }
     }
 }
@@ -271,17 +278,47 @@ struct SvgEntry {
     path: &'static str,
     /// Base 64 encoded complete SVGs. They're [`None`] until requested.
     colored: [Option<&'static str>; std::mem::variant_count::<Color>()],
+    /// Base 64 encoded [`SvgCache::get_mask`]. It's [`None`] until requested.
+    mask: Option<&'static str>,
 }
 
 impl SvgCache {
+    /// Pixels per side of [`Self::get_mask`].
+    const MASK_RESOLUTION: u32 = 64;
+
     pub fn get(path_id: PathId, color: Color) -> &'static str {
+        Self::with(|s| s.get_inner(path_id, color))
+    }
+
+    /// Like [`Self::get`], but filled red and stroked green at a fixed resolution, so that a
+    /// texture of it can be colored like a path (see [`SpriteLayer`][crate::sprite::SpriteLayer]).
+    pub fn get_mask(path_id: PathId) -> &'static str {
+        Self::with(|s| s.get_mask_inner(path_id))
+    }
+
+    fn with(f: impl FnOnce(&mut Self) -> &'static str) -> &'static str {
         thread_local! {
              static S: RefCell<Option<SvgCache>> = RefCell::new(None);
         }
-        S.with(|s: &RefCell<Option<SvgCache>>| {
-            s.borrow_mut()
-                .get_or_insert_default()
-                .get_inner(path_id, color)
+        S.with(|s: &RefCell<Option<SvgCache>>| f(s.borrow_mut().get_or_insert_default()))
+    }
+
+    fn get_mask_inner(&mut self, path_id: PathId) -> &'static str {
+        let entry = self.get_svg_entry(path_id);
+        entry.mask.get_or_insert_with(|| {
+            let svg = format!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}" fill="#ff0000" stroke="#00ff00" stroke-width="{}"><path d="{}"/></svg>"##,
+                Self::MASK_RESOLUTION,
+                Self::MASK_RESOLUTION,
+                -0.5 - STROKE_WIDTH * 0.5,
+                -0.5 - STROKE_WIDTH * 0.5,
+                1.0 + STROKE_WIDTH,
+                1.0 + STROKE_WIDTH,
+                STROKE_WIDTH,
+                entry.path,
+            );
+
+            format!("data:image/svg+xml;base64,{}", base64::encode(svg)).leak()
         })
     }
 
@@ -391,6 +428,7 @@ impl SvgCache {
             SvgEntry {
                 path: svg_builder.build().leak(),
                 colored: Default::default(),
+                mask: None,
             }
         })
     }
@@ -1374,6 +1412,45 @@ fn break_alliance() -> Path {
     p.build()
 }
 
+fn emote(emote_id: EmoteId) -> Path {
+    let mut p = Path::builder();
+    match emote_id {
+        EmoteId::Smile | EmoteId::Frown => {
+            p.add_circle(pt(0.5, 0.5), 0.4, Winding::Positive);
+            p.add_circle(pt(0.35, 0.6), 0.06, Winding::Negative);
+            p.add_circle(pt(0.65, 0.6), 0.06, Winding::Negative);
+
+            // Mouth curves up or down.
+            let (corner, outer, inner) = if emote_id == EmoteId::Smile {
+                (0.4, 0.15, 0.3)
+            } else {
+                (0.25, 0.45, 0.35)
+            };
+            p.begin(pt(0.3, corner));
+            p.quadratic_bezier_to(pt(0.5, outer), pt(0.7, corner));
+            p.quadratic_bezier_to(pt(0.5, inner), pt(0.3, corner));
+            p.close();
+        }
+        EmoteId::Heart => {
+            p.begin(pt(0.5, 0.1));
+            p.cubic_bezier_to(pt(0.2, 0.35), pt(0.0, 0.6), pt(0.25, 0.85));
+            p.quadratic_bezier_to(pt(0.4, 0.95), pt(0.5, 0.75));
+            p.quadratic_bezier_to(pt(0.6, 0.95), pt(0.75, 0.85));
+            p.cubic_bezier_to(pt(1.0, 0.6), pt(0.8, 0.35), pt(0.5, 0.1));
+            p.close();
+        }
+        EmoteId::Exclamation => {
+            p.begin(pt(0.4, 0.9));
+            p.line_to(pt(0.6, 0.9));
+            p.line_to(pt(0.55, 0.35));
+            p.line_to(pt(0.45, 0.35));
+            p.close();
+            p.add_circle(pt(0.5, 0.18), 0.08, Winding::Positive);
+        }
+    }
+    p.build()
+}
+
 fn cursor() -> Path {
     let mut p = Path::builder();
     p.begin(pt(0.7, 0.05));
@@ -1406,6 +1483,19 @@ fn key() -> Path {
     p.build()
 }
 
+fn upgrade_available() -> Path {
+    let mut p = Path::builder();
+    p.begin(pt(0.5, 0.9));
+    p.line_to(pt(0.85, 0.5));
+    p.line_to(pt(0.65, 0.5));
+    p.line_to(pt(0.65, 0.1));
+    p.line_to(pt(0.35, 0.1));
+    p.line_to(pt(0.35, 0.5));
+    p.line_to(pt(0.15, 0.5));
+    p.close();
+    p.build()
+}
+
 fn request_alliance() -> Path {
     let pt = offset_pt(1.3, Vec2::new(0.0, 0.085));
     let mut p = Path::builder();
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::tower::TowerId;
+
+/// Remembers the selection when the connection drops, to restore it after reconnecting (as
+/// opposed to respawning), because reconnecting resets the game state (and thus `alive`).
+#[derive(Debug, Default)]
+pub struct Reconnect {
+    /// Selected tower and whether it was pinned, as of when the connection dropped.
+    saved: Option<(Option<TowerId>, bool)>,
+    /// Seconds spent connected but not alive since the connection dropped.
+    waiting: f32,
+}
+
+impl Reconnect {
+    /// How long after reconnecting to wait to be alive again before assuming the player died
+    /// in the meantime.
+    const TIMEOUT_SECONDS: f32 = 5.0;
+
+    /// Call every frame while alive but reconnecting.
+    pub fn save(&mut self, selected_tower_id: Option<TowerId>, pinned: bool) {
+        self.saved = Some((selected_tower_id, pinned));
+        self.waiting = 0.0;
+    }
+
+    /// Whether a reconnection is in progress, during which the selection and camera shouldn't
+    /// be reset.
+    pub fn is_pending(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    /// Call every frame while not alive.
+    pub fn update(&mut self, elapsed_seconds: f32, connected: bool) {
+        if !self.is_pending() || !connected {
+            return;
+        }
+        self.waiting += elapsed_seconds;
+        if self.waiting > Self::TIMEOUT_SECONDS {
+            *self = Self::default();
+        }
+    }
+
+    /// Call upon becoming alive. Returns [`None`] unless reconnecting, otherwise the selection
+    /// to restore, provided `is_mine` still holds for the selected tower in the fresh world.
+    pub fn restore(
+        &mut self,
+        is_mine: impl FnOnce(TowerId) -> bool,
+    ) -> Option<(Option<TowerId>, bool)> {
+        let (selected_tower_id, pinned) = std::mem::take(self).saved?;
+        let selected_tower_id = selected_tower_id.filter(|&tower_id| is_mine(tower_id));
+        Some((selected_tower_id, pinned && selected_tower_id.is_some()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reconnect::Reconnect;
+    use common::tower::TowerId;
+
+    #[test]
+    fn reconnect() {
+        let tower_id = TowerId::new(5, 7);
+        let mut reconnect = Reconnect::default();
+
+        // Respawning.
+        assert!(!reconnect.is_pending());
+        reconnect.update(10.0, true);
+        assert_eq!(reconnect.restore(|_| true), None);
+
+        // Reconnecting.
+        reconnect.save(Some(tower_id), true);
+        reconnect.update(1.0, false);
+        reconnect.update(1.0, true);
+        assert!(reconnect.is_pending());
+        assert_eq!(reconnect.restore(|_| true), Some((Some(tower_id), true)));
+        assert!(!reconnect.is_pending());
+
+        // Stale selection.
+        reconnect.save(Some(tower_id), true);
+        assert_eq!(reconnect.restore(|_| false), Some((None, false)));
+
+        // Died while disconnected.
+        reconnect.save(Some(tower_id), false);
+        reconnect.update(60.0, false);
+        assert!(reconnect.is_pending());
+        reconnect.update(Reconnect::TIMEOUT_SECONDS + 1.0, true);
+        assert!(!reconnect.is_pending());
+        assert_eq!(reconnect.restore(|_| true), None);
+    }
+}
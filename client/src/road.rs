@@ -50,6 +50,11 @@ impl RoadLayer {
         }
     }
 
+    /// Color of viable paths.
+    const COLOR: Vec4 = Vec4::splat(0.9);
+    /// Color of pulling supply lines (see [`common::tower::Tower::supply_pull`]).
+    pub const PULL_COLOR: Vec4 = Vec4::new(0.55, 0.85, 1.0, 0.9);
+
     /// Returns true iff the path is viable (non-hypothetical).
     pub fn draw_path(
         &mut self,
@@ -57,6 +62,26 @@ impl RoadLayer {
         max_edge_distance: Option<u32>,
         max_edges: usize,
         supply: bool,
+        get_visibility: impl FnMut(TowerId) -> f32,
+    ) -> bool {
+        self.draw_path_color(
+            iter,
+            max_edge_distance,
+            max_edges,
+            supply,
+            Self::COLOR,
+            get_visibility,
+        )
+    }
+
+    /// Like [`Self::draw_path`], but with a different `color` for the viable part.
+    pub fn draw_path_color(
+        &mut self,
+        iter: impl Iterator<Item = TowerId>,
+        max_edge_distance: Option<u32>,
+        max_edges: usize,
+        supply: bool,
+        viable_color: Vec4,
         mut get_visibility: impl FnMut(TowerId) -> f32,
     ) -> bool {
         let max_edge_distance = max_edge_distance.unwrap_or(World::MAX_ROAD_LENGTH);
@@ -89,7 +114,7 @@ impl RoadLayer {
                     hypothetical = true;
                     Vec4::new(0.8, 0.4, 0.2, 0.5)
                 } else {
-                    Vec4::splat(0.9)
+                    viable_color
                 };
 
             self.draw_road_uv(
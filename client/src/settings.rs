@@ -1,16 +1,313 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::blueprint::Blueprints;
 use client_util::browser_storage::BrowserStorages;
+use client_util::keyboard::Key;
 use client_util::setting::{SettingCategory, Settings};
+use common::alerts::{AlertFlag, Alerts};
+use common::protocol::SpawnPreference;
 use common::tower::TowerType;
 use std::collections::HashSet;
 use std::fmt::{self, Display, Formatter, Write};
 use std::str::FromStr;
+use strum::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
-#[derive(Clone, Default, PartialEq, Settings)]
+#[derive(Clone, PartialEq, Settings)]
 pub struct TowerSettings {
     pub(crate) unlocks: Unlocks,
+    /// Saved upgrade orders.
+    pub(crate) blueprints: Blueprints,
+    /// Whether to send and display emotes.
+    #[setting(checkbox = "Emotes")]
+    pub(crate) emotes: bool,
+    /// How much to low-pass the interpolation clock (0 is disabled, must be less than 1).
+    #[setting(range = "0.0..0.95", finite)]
+    pub(crate) interpolation_smoothing: f32,
+    /// Whether to keep moving forces past the last tick when updates are late.
+    #[setting(checkbox = "Graphics/Extrapolate movement")]
+    pub(crate) extrapolation: bool,
+    /// Whether to show units lost in fights.
+    #[setting(checkbox = "Graphics/Damage numbers")]
+    pub(crate) damage_numbers: bool,
+    /// Whether to show all supply lines, as if holding R (toggled by [`Self::supply_lines_key`]).
+    #[setting(checkbox = "Graphics/Show supply lines")]
+    pub(crate) show_supply_lines: bool,
+    /// Whether to show mutual allies' supply lines (subdued).
+    #[setting(checkbox = "Graphics/Ally supply lines")]
+    pub(crate) ally_supply_lines: bool,
+    /// Whether to mark our towers that can be upgraded right now.
+    #[setting(checkbox = "Graphics/Upgrade indicators")]
+    pub(crate) upgrade_indicators: bool,
+    /// Whether to draw towers in full detail even when zoomed far out, instead of as sprites.
+    #[setting(checkbox = "Graphics/Full detail towers")]
+    pub(crate) full_detail_towers: bool,
+    /// How elaborate visual effects such as explosions are.
+    #[setting(dropdown = "Graphics/Effects")]
+    pub(crate) effects: EffectsQuality,
+    /// Whether effects avoid colors that are hard to tell apart with color blindness.
+    #[setting(checkbox = "Graphics/Colorblind effects")]
+    pub(crate) colorblind: bool,
+    /// Whether moving forces leave short, fading trails.
+    #[setting(checkbox = "Graphics/Motion trails")]
+    pub(crate) motion_trails: bool,
+    /// Whether jumping to a tower (e.g. alerts, ruler hotkey) pans smoothly instead of instantly.
+    #[setting(checkbox = "Graphics/Smooth camera")]
+    pub(crate) smooth_camera: bool,
+    /// Whether the camera keeps gliding briefly after panning.
+    #[setting(checkbox = "Graphics/Pan inertia")]
+    pub(crate) pan_inertia: bool,
+    /// Whether dragging with the middle mouse button pans, like the right mouse button.
+    #[setting(checkbox = "Middle-click pan")]
+    pub(crate) middle_click_pan: bool,
+    /// How quickly pan inertia slows down, per second.
+    #[setting(range = "1.0..20.0", finite)]
+    pub(crate) pan_deceleration: f32,
+    /// How loud music is relative to sound effects.
+    #[setting(dropdown = "Audio/Music volume")]
+    pub(crate) music_volume: MusicVolume,
+    /// Whether the camera tours the world when idle on the spawn screen.
+    #[setting(checkbox = "Graphics/Menu camera tour")]
+    pub(crate) attract_mode: bool,
+    /// How much of a tower's units to deploy when dragging while holding Ctrl.
+    #[setting(dropdown = "Partial deploy (Ctrl)")]
+    pub(crate) deploy_fraction: DeployFraction,
+    /// Which alerts to show and play sounds for.
+    #[setting(dropdown = "Alerts")]
+    pub(crate) alert_verbosity: AlertVerbosity,
+    /// Whether to automatically move the ruler away from attacks.
+    #[setting(checkbox = "Auto-retreat ruler")]
+    pub(crate) auto_retreat: bool,
+    /// Whether breaking a mutual alliance requires clicking twice.
+    #[setting(checkbox = "Confirm breaking alliances")]
+    pub(crate) confirm_break_alliance: bool,
+    /// Whether hovering over a tower selects it, until a click pins the selection.
+    #[setting(checkbox = "Hover select")]
+    pub(crate) hover_select: bool,
+    /// Whether to save bandwidth by only receiving updates for towers on screen, and telling
+    /// the server about panning less often. Towers near the edge of the screen may briefly
+    /// appear stale or missing while panning.
+    #[setting(checkbox = "Low bandwidth")]
+    pub(crate) low_bandwidth: bool,
+    /// Whether to show round trip time and jitter.
+    #[setting(checkbox = "Show network")]
+    pub(crate) show_network: bool,
+    /// Whether to show elapsed game time.
+    #[setting(checkbox = "Show game clock")]
+    pub(crate) show_clock: bool,
+    /// Whether to show statistics and a "play again" button after dying.
+    #[setting(checkbox = "Death screen")]
+    pub(crate) death_screen: bool,
+    /// Where to spawn relative to other players, or anywhere if [`None`].
+    #[setting(optional)]
+    pub(crate) spawn_preference: Option<SpawnPreference>,
+    /// Whether to wait for room in the [`Self::spawn_preference`] region if it is full,
+    /// instead of spawning elsewhere.
+    #[setting(checkbox = "Wait for spawn region")]
+    pub(crate) queue_spawn: bool,
+    /// Key that zooms to fit all owned towers.
+    pub(crate) fit_territory_key: Key,
+    /// Key that retreats all moving forces to safety.
+    pub(crate) retreat_all_key: Key,
+    /// Key that pings the tower under the mouse for mutual allies.
+    pub(crate) ping_key: Key,
+    /// Key that toggles [`Self::show_supply_lines`].
+    pub(crate) supply_lines_key: Key,
+    /// Key that, while held, dims towers the selected tower can't deploy to.
+    pub(crate) focus_key: Key,
+    /// Key that repeats the last deployment, with the units its source has now.
+    pub(crate) repeat_deploy_key: Key,
+    /// Key that downloads a screenshot of the game, at double resolution if Shift is held.
+    pub(crate) screenshot_key: Key,
+    /// Key that restores supply lines that were just cleared (with Shift+R).
+    pub(crate) undo_key: Key,
+}
+
+impl Default for TowerSettings {
+    fn default() -> Self {
+        Self {
+            unlocks: Unlocks::default(),
+            blueprints: Blueprints::default(),
+            emotes: true,
+            interpolation_smoothing: 0.0,
+            extrapolation: true,
+            damage_numbers: false,
+            show_supply_lines: false,
+            ally_supply_lines: false,
+            upgrade_indicators: true,
+            full_detail_towers: false,
+            effects: EffectsQuality::default(),
+            colorblind: false,
+            motion_trails: false,
+            smooth_camera: true,
+            pan_inertia: false,
+            pan_deceleration: 6.0,
+            middle_click_pan: true,
+            music_volume: MusicVolume::default(),
+            attract_mode: true,
+            deploy_fraction: DeployFraction::default(),
+            alert_verbosity: AlertVerbosity::default(),
+            auto_retreat: false,
+            confirm_break_alliance: true,
+            hover_select: false,
+            low_bandwidth: false,
+            show_network: false,
+            show_clock: false,
+            death_screen: true,
+            spawn_preference: None,
+            queue_spawn: false,
+            fit_territory_key: Key::F,
+            retreat_all_key: Key::X,
+            ping_key: Key::G,
+            supply_lines_key: Key::L,
+            focus_key: Key::V,
+            repeat_deploy_key: Key::Z,
+            screenshot_key: Key::P,
+            undo_key: Key::U,
+        }
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    EnumIter,
+    EnumMessage,
+    EnumString,
+    IntoStaticStr,
+    strum::Display,
+)]
+pub enum EffectsQuality {
+    /// Explosions are just circles.
+    #[strum(message = "Low")]
+    Low,
+    /// Explosions also throw debris.
+    #[default]
+    #[strum(message = "High")]
+    High,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    EnumIter,
+    EnumMessage,
+    EnumString,
+    IntoStaticStr,
+    strum::Display,
+)]
+pub enum MusicVolume {
+    #[strum(message = "Low")]
+    Low,
+    #[strum(message = "Medium")]
+    Medium,
+    #[default]
+    #[strum(message = "High")]
+    High,
+}
+
+impl MusicVolume {
+    /// Multiplier of the overall volume.
+    pub fn multiplier(self) -> f32 {
+        match self {
+            Self::Low => 0.25,
+            Self::Medium => 0.5,
+            Self::High => 1.0,
+        }
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    EnumIter,
+    EnumMessage,
+    EnumString,
+    IntoStaticStr,
+    strum::Display,
+)]
+pub enum DeployFraction {
+    #[strum(message = "Quarter")]
+    Quarter,
+    #[default]
+    #[strum(message = "Half")]
+    Half,
+    #[strum(message = "Three quarters")]
+    ThreeQuarters,
+}
+
+impl DeployFraction {
+    /// See [`Tower::force_units_percent`][`common::tower::Tower::force_units_percent`].
+    pub fn percent(self) -> u8 {
+        match self {
+            Self::Quarter => 25,
+            Self::Half => 50,
+            Self::ThreeQuarters => 75,
+        }
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    EnumIter,
+    EnumMessage,
+    EnumString,
+    IntoStaticStr,
+    strum::Display,
+)]
+pub enum AlertVerbosity {
+    /// Every alert.
+    #[default]
+    #[strum(message = "All")]
+    All,
+    /// Also captures and full or overflowing towers.
+    #[strum(message = "Important")]
+    Important,
+    /// Only the ruler being under attack (and being kicked for inactivity).
+    #[strum(message = "Critical")]
+    Critical,
+}
+
+impl AlertVerbosity {
+    /// Whether to show alerts and play sounds about captures and full or overflowing towers.
+    pub fn important(self) -> bool {
+        self != Self::Critical
+    }
+
+    /// Whether to show alerts and play sounds about everything else that isn't critical.
+    pub fn all(self) -> bool {
+        self == Self::All
+    }
+
+    /// Removes alerts that shouldn't be shown.
+    pub fn filter(self, mut alerts: Alerts) -> Alerts {
+        if !self.important() {
+            alerts.full = None;
+            alerts.overflowing = None;
+        }
+        if !self.all() {
+            alerts.zombies = None;
+            alerts.set_flags(alerts.flags() - AlertFlag::RulerNotSafe);
+        }
+        alerts
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,9 @@
+precision mediump float;
+
+varying vec4 vColor;
+
+void main() {
+    // Round points.
+    vec2 d = gl_PointCoord * 2.0 - 1.0;
+    gl_FragColor = vColor * smoothstep(1.0, 0.8, dot(d, d));
+}
@@ -0,0 +1,25 @@
+attribute vec2 position;
+attribute vec2 velocity;
+attribute vec4 color;
+attribute float radius;
+attribute float created;
+uniform mat3 uView;
+uniform float uTime;
+uniform float uLifespan;
+uniform float uPixelsPerUnit;
+varying vec4 vColor;
+
+void main() {
+    float t = clamp((uTime - created) / uLifespan, 0.0, 1.0);
+
+    // Decelerate to a stop at the end of the lifespan, as if by drag.
+    vec2 vPosition = position + velocity * uLifespan * (t - 0.5 * t * t);
+    gl_Position = vec4(uView * vec3(vPosition, 1.0), 1.0);
+
+    // Grow (like smoke) while fading out.
+    gl_PointSize = radius * 2.0 * uPixelsPerUnit * (1.0 + t);
+    vColor = vec4(color.rgb, color.a * (1.0 - t));
+
+    // Premultiply alpha.
+    vColor.rgb *= vColor.a;
+}
@@ -0,0 +1,12 @@
+precision mediump float;
+
+varying vec2 vUv;
+varying vec4 vStroke;
+varying vec4 vFill;
+uniform sampler2D uSampler;
+
+void main() {
+    // Red is fill and green is stroke (see SvgCache::get_mask).
+    vec4 mask = texture2D(uSampler, vUv);
+    gl_FragColor = mask.r * vFill + mask.g * vStroke;
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+attribute vec2 position;
+attribute vec2 center;
+attribute float scale;
+attribute vec4 stroke;
+attribute vec4 fill;
+uniform mat3 uView;
+varying vec2 vUv;
+varying vec4 vStroke;
+varying vec4 vFill;
+
+void main() {
+    gl_Position = vec4(uView * vec3(position * scale + center, 1.0), 1.0);
+    vUv = vec2(position.x + 0.5, 0.5 - position.y);
+    vStroke = stroke;
+    vFill = fill;
+}
\ No newline at end of file
@@ -0,0 +1,150 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::settings::TowerSettings;
+use client_util::keyboard::Key;
+use strum::EnumIter;
+
+/// What a keyboard shortcut does.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
+pub enum ShortcutAction {
+    Pan,
+    Zoom,
+    PanToRuler,
+    FitTerritory,
+    ShowSupplyLines,
+    ToggleSupplyLines,
+    ClearSupplyLine,
+    UndoClearSupplyLines,
+    SimilarTowers,
+    Focus,
+    PartialDeploy,
+    RepeatDeploy,
+    RetreatAll,
+    Ping,
+    TowerMenu,
+    Screenshot,
+    Shortcuts,
+    // Cheats.
+    CopyDebugState,
+    RevealAll,
+    DebugBounds,
+}
+
+impl ShortcutAction {
+    /// Whether the shortcut only works with cheats.
+    pub fn is_cheat(self) -> bool {
+        matches!(
+            self,
+            Self::CopyDebugState | Self::RevealAll | Self::DebugBounds
+        )
+    }
+}
+
+/// A keyboard shortcut, for the shortcuts overlay.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shortcut {
+    /// Human readable, e.g. `Shift+R`.
+    pub keys: String,
+    pub action: ShortcutAction,
+}
+
+/// Lists keyboard shortcuts, as remapped by `settings`, omitting cheat-only ones unless
+/// `cheats`.
+pub fn shortcuts(settings: &TowerSettings, cheats: bool) -> Vec<Shortcut> {
+    use ShortcutAction::*;
+    let key = key_name;
+    let shift = |k| format!("{}+{}", key(Key::Shift), key(k));
+    let arrows = [Key::Left, Key::Up, Key::Right, Key::Down]
+        .map(key)
+        .join(" ");
+    [
+        (Pan, format!("W A S D, {arrows}")),
+        (Zoom, format!("{} {}", key(Key::Q), key(Key::E))),
+        (PanToRuler, key(Key::H)),
+        (FitTerritory, key(settings.fit_territory_key)),
+        (ShowSupplyLines, key(Key::R)),
+        (ToggleSupplyLines, key(settings.supply_lines_key)),
+        (ClearSupplyLine, shift(Key::R)),
+        (UndoClearSupplyLines, key(settings.undo_key)),
+        (SimilarTowers, key(Key::T)),
+        (Focus, key(settings.focus_key)),
+        (PartialDeploy, key(Key::Ctrl)),
+        (RepeatDeploy, key(settings.repeat_deploy_key)),
+        (RetreatAll, key(settings.retreat_all_key)),
+        (Ping, key(settings.ping_key)),
+        (
+            TowerMenu,
+            format!(
+                "{}-{}, {} {}, {}",
+                key(Key::One),
+                key(Key::Zero),
+                key(Key::Up),
+                key(Key::Down),
+                key(Key::Enter)
+            ),
+        ),
+        (Screenshot, key(settings.screenshot_key)),
+        (Shortcuts, key(Key::SlashQuestion)),
+        (CopyDebugState, shift(Key::C)),
+        (RevealAll, key(Key::B)),
+        (DebugBounds, key(Key::N)),
+    ]
+    .into_iter()
+    .filter(|(action, _)| cheats || !action.is_cheat())
+    .map(|(action, keys)| Shortcut { keys, action })
+    .collect()
+}
+
+/// How to show `key` to the player.
+pub fn key_name(key: Key) -> String {
+    match key {
+        Key::Left => "←".to_owned(),
+        Key::Up => "↑".to_owned(),
+        Key::Right => "→".to_owned(),
+        Key::Down => "↓".to_owned(),
+        Key::MinusUnderscore => "-".to_owned(),
+        Key::EqualsPlus => "=".to_owned(),
+        Key::SlashQuestion => "?".to_owned(),
+        _ => key
+            .digit()
+            .map_or_else(|| key.to_string(), |digit| digit.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::TowerSettings;
+    use crate::shortcut::{shortcuts, ShortcutAction};
+    use client_util::keyboard::Key;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn shortcuts_list_every_action() {
+        let mut settings = TowerSettings::default();
+        let actions = |settings: &TowerSettings, cheats: bool| {
+            shortcuts(settings, cheats)
+                .into_iter()
+                .map(|s| s.action)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            actions(&settings, true),
+            ShortcutAction::iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            actions(&settings, false),
+            ShortcutAction::iter()
+                .filter(|a| !a.is_cheat())
+                .collect::<Vec<_>>()
+        );
+
+        // Remapped.
+        settings.ping_key = Key::K;
+        let ping = shortcuts(&settings, false)
+            .into_iter()
+            .find(|s| s.action == ShortcutAction::Ping)
+            .unwrap();
+        assert_eq!(ping.keys, "K");
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::path::{PathId, SvgCache, STROKE_WIDTH};
+use common::tower::TowerType;
+use fxhash::FxHashMap;
+use glam::{vec2, Vec2, Vec4};
+use renderer::{
+    derive_vertex, DefaultRender, InstanceBuffer, Layer, RenderLayer, Renderer, Shader, Texture,
+    TextureFormat, TriangleBuffer,
+};
+use renderer2d::Camera2d;
+
+derive_vertex!(
+    struct Instance {
+        center: Vec2,
+        scale: f32,
+        stroke: Vec4,
+        fill: Vec4,
+    }
+);
+
+#[derive(Default)]
+struct Sprite {
+    instances: Vec<Instance>,
+    /// Created in [`Layer::pre_render`], to avoid stalling the pipeline during rendering.
+    buffers: Option<(Texture, InstanceBuffer<Instance>)>,
+}
+
+/// Draws towers as single textured quads, which is much cheaper than tessellated paths when
+/// there are many towers, each only a few pixels wide.
+pub struct SpriteLayer {
+    sprites: FxHashMap<TowerType, Sprite>,
+    /// Same for all sprites.
+    quad: TriangleBuffer<Vec2>,
+    shader: Shader,
+}
+
+impl DefaultRender for SpriteLayer {
+    fn new(renderer: &Renderer) -> Self {
+        let mut quad = TriangleBuffer::new(renderer);
+        quad.buffer(
+            renderer,
+            &[
+                vec2(-0.5, -0.5),
+                vec2(0.5, -0.5),
+                vec2(0.5, 0.5),
+                vec2(-0.5, 0.5),
+            ],
+            &[0, 1, 2, 2, 3, 0],
+        );
+
+        Self {
+            sprites: Default::default(),
+            quad,
+            shader: renderer.create_shader(
+                include_str!("shader/sprite.vert"),
+                include_str!("shader/sprite.frag"),
+            ),
+        }
+    }
+}
+
+impl SpriteLayer {
+    /// Approximates [`PathLayer::draw_path_a`][crate::path::PathLayer::draw_path_a] of a
+    /// [`PathId::Tower`] (without rotation).
+    pub fn draw_tower(
+        &mut self,
+        tower_type: TowerType,
+        center: Vec2,
+        scale: f32,
+        stroke: Option<Vec4>,
+        fill: Option<Vec4>,
+    ) {
+        // Premultiply alpha, like the path shader.
+        let premultiply =
+            |c: Option<Vec4>| c.map_or(Vec4::ZERO, |c| (c.truncate() * c.w).extend(c.w));
+        self.sprites
+            .entry(tower_type)
+            .or_default()
+            .instances
+            .push(Instance {
+                center,
+                // The mask includes the stroke beyond the path.
+                scale: scale * (1.0 + STROKE_WIDTH),
+                stroke: premultiply(stroke),
+                fill: premultiply(fill),
+            });
+    }
+}
+
+impl Layer for SpriteLayer {
+    const ALPHA: bool = true;
+
+    fn pre_render(&mut self, renderer: &Renderer) {
+        for (&tower_type, sprite) in &mut self.sprites {
+            let (_, buffer) = sprite.buffers.get_or_insert_with(|| {
+                let texture = Texture::load(
+                    renderer,
+                    SvgCache::get_mask(PathId::Tower(tower_type)),
+                    TextureFormat::Rgba { premultiply: true },
+                    None,
+                    false,
+                );
+                (texture, InstanceBuffer::new(renderer))
+            });
+            buffer.buffer(renderer, &sprite.instances);
+            sprite.instances.clear();
+        }
+    }
+}
+
+impl RenderLayer<&Camera2d> for SpriteLayer {
+    fn render(&mut self, renderer: &Renderer, camera: &Camera2d) {
+        if let Some(binding) = self.shader.bind(renderer) {
+            camera.prepare(&binding);
+            for (texture, buffer) in self.sprites.values().filter_map(|s| s.buffers.as_ref()) {
+                if buffer.is_empty() {
+                    continue;
+                }
+                binding.uniform("uSampler", texture);
+                buffer.bind(renderer, &self.quad).draw();
+            }
+        }
+    }
+}
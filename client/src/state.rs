@@ -1,9 +1,12 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::territory::Territories;
 use crate::visible::Visible;
 use client_util::apply::Apply;
-use common::info::InfoEvent;
+use common::emote::EmoteEvent;
+use common::info::{InfoEvent, OnInfo};
+use common::ping::PingEvent;
 use common::protocol::{Diff, NonActor, Update};
 use common::ticks::Ticks;
 use common::world::{ApplyOwned, World};
@@ -14,9 +17,21 @@ pub struct TowerState {
     non_actor: NonActor,
     pub world: World,
     pub visible: Visible,
+    pub territories: Territories,
+    /// Consumed in update.
     pub info_events: Vec<InfoEvent>,
+    /// [`Checksum::diff`] of the first desync, if any. Only checked in debug/cheats mode.
+    ///
+    /// [`Checksum::diff`]: common_util::actor2::Checksum::diff
+    pub desync: Option<String>,
+    /// Consumed in update.
+    pub emotes: Vec<EmoteEvent>,
+    /// Consumed in update.
+    pub pings: Vec<PingEvent>,
     /// In seconds; for interpolation.
     pub time_since_last_tick: f32,
+    /// Low-passed [`Self::time_since_last_tick`], which is what is actually rendered.
+    smooth_time_since_last_tick: f32,
     pub ticked: bool, // Consumed in update.
 }
 
@@ -28,24 +43,87 @@ impl Deref for TowerState {
     }
 }
 
+impl TowerState {
+    /// Server tick interval, which interpolation is normalized by. Assumes the client's until
+    /// the server sends its own.
+    pub fn tick_period_secs(&self) -> f32 {
+        if self.non_actor.tick_period_secs > 0.0 {
+            self.non_actor.tick_period_secs
+        } else {
+            Ticks::PERIOD_SECS
+        }
+    }
+
+    /// Advances the interpolation clock, low-passing it by `smoothing` (0 is disabled).
+    pub fn advance_time(&mut self, elapsed_seconds: f32, smoothing: f32) {
+        self.time_since_last_tick += elapsed_seconds;
+        self.smooth_time_since_last_tick = smooth(
+            self.smooth_time_since_last_tick + elapsed_seconds,
+            self.time_since_last_tick,
+            smoothing,
+            elapsed_seconds,
+        );
+    }
+
+    /// Seconds since `tick`, per the game clock which (unlike local time) all viewers agree on.
+    /// Animations of shared events start this long ago so that their phases match.
+    pub fn seconds_since(&self, tick: Ticks) -> f32 {
+        Ticks::from_repr(self.world.singleton().tick.0.wrapping_sub(tick.0)).to_secs()
+    }
+
+    /// Seconds per the game clock, for the phase of shared pulsing indicators. Wraps along with
+    /// [`Ticks`].
+    pub fn clock_seconds(&self) -> f32 {
+        self.world.singleton().tick.to_secs() + self.smooth_time_since_last_tick
+    }
+
+    /// Ticks since the last tick, for [`Force::interpolated_position_ticks`]. If not
+    /// `extrapolate`, caps at one tick to avoid overshoot when updates are late.
+    ///
+    /// [`Force::interpolated_position_ticks`]: common::force::Force::interpolated_position_ticks
+    pub fn interpolation_ticks(&self, extrapolate: bool) -> f32 {
+        let ticks = self.smooth_time_since_last_tick * (1.0 / self.tick_period_secs());
+        if extrapolate {
+            ticks
+        } else {
+            ticks.min(1.0)
+        }
+    }
+}
+
+/// Exponentially approaches `target` from `current`. `smoothing` is the fraction of the
+/// difference remaining after 1/60th of a second, so 0 snaps to `target` immediately.
+fn smooth(current: f32, target: f32, smoothing: f32, elapsed_seconds: f32) -> f32 {
+    if smoothing <= 0.0 {
+        return target;
+    }
+    let remaining = smoothing.min(0.99).powf(elapsed_seconds * 60.0);
+    target + (current - target) * remaining
+}
+
 impl Apply<Update> for TowerState {
     fn apply(&mut self, update: Update) {
         self.non_actor.apply(&update.non_actor_diff);
+        self.emotes.extend(update.emotes);
+        self.pings.extend(update.pings);
 
-        let mut on_info_event = |info_event| {
-            if self.info_events.len() < 128 {
-                self.info_events.push(info_event);
-            }
+        let mut on_info = OnUpdateInfo {
+            territories: &mut self.territories,
+            info_events: &mut self.info_events,
+            desync: &mut self.desync,
         };
 
         // js_hooks::console_log!("{:?}", update);
-        self.world
-            .apply_owned(update.actor_update, &mut on_info_event);
+        self.world.apply_owned(update.actor_update, &mut on_info);
+        self.territories.sync_chunks(&self.world);
 
         // Last tick is now.
         // Could set to zero, but this will more gradually account for jitter.
-        self.time_since_last_tick =
-            (self.time_since_last_tick - Ticks::PERIOD_SECS).clamp(-1.0, 1.0) * 0.6;
+        let period = self.tick_period_secs();
+        self.time_since_last_tick = (self.time_since_last_tick - period).clamp(-1.0, 1.0) * 0.6;
+        // Shift by the same amount to stay continuous, letting smoothing absorb the jitter.
+        self.smooth_time_since_last_tick =
+            (self.smooth_time_since_last_tick - period).clamp(-1.0, 1.0);
 
         // Invalidate visible cache.
         self.visible.ticked();
@@ -54,3 +132,61 @@ impl Apply<Update> for TowerState {
         self.ticked = true;
     }
 }
+
+struct OnUpdateInfo<'a> {
+    territories: &'a mut Territories,
+    info_events: &'a mut Vec<InfoEvent>,
+    desync: &'a mut Option<String>,
+}
+
+impl OnInfo for OnUpdateInfo<'_> {
+    fn on_info(&mut self, info_event: InfoEvent) {
+        // Before dropping excess events, which would desync territories.
+        self.territories.on_info(&info_event.info);
+        if self.info_events.len() < 128 {
+            self.info_events.push(info_event);
+        }
+    }
+
+    fn on_desync(&mut self, diff: String) {
+        // Subsequent diffs are likely just fallout from the first.
+        self.desync.get_or_insert(diff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::smooth;
+
+    #[test]
+    fn smooth_disabled() {
+        assert_eq!(smooth(0.0, 1.0, 0.0, 1.0 / 60.0), 1.0);
+    }
+
+    #[test]
+    fn smooth_step_response() {
+        let dt = 1.0 / 60.0;
+        let mut value = 0.0;
+        let mut previous = value;
+        for _ in 0..300 {
+            value = smooth(value, 1.0, 0.8, dt);
+            // Monotonic, no overshoot.
+            assert!(value >= previous && value <= 1.0, "{value}");
+            previous = value;
+        }
+        assert!((value - 1.0).abs() < 0.001, "{value}");
+
+        // One frame removes 20% of the error.
+        assert!((smooth(0.0, 1.0, 0.8, dt) - 0.2).abs() < 0.0001);
+    }
+
+    #[test]
+    fn smooth_frame_rate_independent() {
+        let mut fast = 0.0;
+        for _ in 0..4 {
+            fast = smooth(fast, 1.0, 0.8, 1.0 / 120.0);
+        }
+        let slow = smooth(0.0, 1.0, 0.8, 1.0 / 30.0);
+        assert!((fast - slow).abs() < 0.0001, "{fast} {slow}");
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::info::{Info, LostRulerReason};
+use common::tower::TowerId;
+use core_protocol::id::PlayerId;
+use std::collections::HashMap;
+
+/// Statistics of the player's current life, for the death screen.
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    /// Elapsed game seconds as of the last [`Self::tick`].
+    now: u32,
+    /// When each of the player's towers was acquired.
+    held_since: HashMap<TowerId, u32>,
+    /// Longest any tower was held before being lost, in seconds.
+    longest_lost: u32,
+    peak_towers: usize,
+    rulers_killed: u32,
+}
+
+/// A summary of [`SessionStats`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeathStats {
+    /// Most towers owned at once.
+    pub peak_towers: usize,
+    /// Longest any single tower was held, in seconds.
+    pub longest_held_secs: u32,
+    /// Other players' rulers killed by the player's units.
+    pub rulers_killed: u32,
+}
+
+impl SessionStats {
+    /// Call every tick with elapsed game seconds and the player's towers (none if dead).
+    pub fn tick(&mut self, now: u32, towers: impl Iterator<Item = TowerId>) {
+        self.now = now;
+        let mut count = 0;
+        let mut held_since = std::mem::take(&mut self.held_since);
+        for tower_id in towers {
+            let since = held_since.remove(&tower_id).unwrap_or(now);
+            self.held_since.insert(tower_id, since);
+            count += 1;
+        }
+        // Whatever remains was lost.
+        for since in held_since.into_values() {
+            self.longest_lost = self.longest_lost.max(now.saturating_sub(since));
+        }
+        self.peak_towers = self.peak_towers.max(count);
+    }
+
+    /// Call with every [`Info`] while alive.
+    pub fn on_info(&mut self, info: &Info, me: PlayerId) {
+        if let &Info::LostRuler {
+            player_id,
+            reason: LostRulerReason::KilledBy(Some(killer), _),
+        } = info
+            && killer == me
+            && player_id != me
+        {
+            self.rulers_killed += 1;
+        }
+    }
+
+    pub fn summary(&self) -> DeathStats {
+        let longest_held = self
+            .held_since
+            .values()
+            .map(|&since| self.now.saturating_sub(since))
+            .fold(self.longest_lost, u32::max);
+        DeathStats {
+            peak_towers: self.peak_towers,
+            longest_held_secs: longest_held,
+            rulers_killed: self.rulers_killed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{DeathStats, SessionStats};
+    use common::info::{Info, LostRulerReason};
+    use common::tower::TowerId;
+    use common::unit::Unit;
+    use core_protocol::id::PlayerId;
+
+    #[test]
+    fn session_stats() {
+        let me = PlayerId::SOLO_OFFLINE;
+        let other = PlayerId::nth_bot(0).unwrap();
+        let towers = |xs: &'static [u16]| xs.iter().map(|&x| TowerId::new(x, 0));
+
+        let mut stats = SessionStats::default();
+        stats.tick(0, towers(&[1]));
+        stats.tick(10, towers(&[1, 2, 3]));
+        stats.tick(20, towers(&[2, 3]));
+        stats.tick(50, towers(&[2]));
+        for (player_id, killer) in [(other, me), (me, other), (other, other)] {
+            stats.on_info(
+                &Info::LostRuler {
+                    player_id,
+                    reason: LostRulerReason::KilledBy(Some(killer), Unit::Soldier),
+                },
+                me,
+            );
+        }
+        assert_eq!(
+            stats.summary(),
+            DeathStats {
+                peak_towers: 3,
+                longest_held_secs: 40,
+                rulers_killed: 1,
+            }
+        );
+
+        // Dying loses the rest.
+        stats.tick(100, towers(&[]));
+        stats.tick(200, towers(&[]));
+        assert_eq!(stats.summary().longest_held_secs, 90);
+    }
+}
@@ -1,60 +1,124 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use common::tower::TowerId;
+use common::chunk::ChunkId;
+use common::info::Info;
+use common::tower::{TowerId, TowerRectangle};
+use common::world::World;
 use core_protocol::PlayerId;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use glam::Vec2;
-use std::collections::hash_map::Entry;
 
+/// Where to label the visible part of each player's territory. Ownership is kept up to date
+/// incrementally, from [`Info`]s and chunks coming and going, and labels are only recomputed
+/// when it or the viewport changes, instead of rescanning every tower every frame.
 #[derive(Default)]
 pub struct Territories {
     inner: FxHashMap<PlayerId, Territory>,
+    /// Owner of every known owned tower.
+    owners: FxHashMap<TowerId, PlayerId>,
+    /// Chunks accounted for in [`Self::owners`].
+    chunks: FxHashSet<ChunkId>,
+    /// As of the last [`Self::update`].
+    viewport: TowerRectangle,
 }
 
 impl Territories {
-    /// Record that a visible tower at `tower_id` has a `player_id`.
-    #[inline]
-    pub fn record(&mut self, tower_id: TowerId, player_id: PlayerId) {
-        let t = match self.inner.entry(player_id) {
-            Entry::Occupied(e) => e.into_mut(),
-            Entry::Vacant(e) => (#[cold]
-            || e.insert(Default::default()))(),
-        };
-        let pos = tower_id.as_vec2();
-        t.sum += pos;
-        t.count += 1;
+    /// Applies an ownership change. Call for every [`Info`], even ones that are otherwise
+    /// dropped.
+    pub fn on_info(&mut self, info: &Info) {
+        match *info {
+            Info::GainedTower {
+                tower_id,
+                player_id,
+                ..
+            } => self.set_owner(tower_id, Some(player_id)),
+            Info::LostTower {
+                tower_id,
+                player_id,
+                ..
+            } if self.owners.get(&tower_id) == Some(&player_id) => self.set_owner(tower_id, None),
+            _ => {}
+        }
+    }
 
-        if let Some(center_of_mass) = t.center_of_mass {
-            let new = (tower_id, pos.distance_squared(center_of_mass));
-            let best = t.best_tower_id.get_or_insert(new);
-            if new.1 < best.1 {
-                *best = new;
+    /// Accounts for chunks that were added or removed without [`Info`]s. Call after applying
+    /// each update. Only scans towers of chunks that changed.
+    pub fn sync_chunks(&mut self, world: &World) {
+        let mut removed = std::mem::take(&mut self.chunks);
+        for (chunk_id, chunk) in world.chunk.iter() {
+            if !removed.remove(&chunk_id) {
+                for (tower_id, tower) in chunk.actor.iter(chunk_id) {
+                    self.set_owner(tower_id, tower.player_id);
+                }
+            }
+            self.chunks.insert(chunk_id);
+        }
+
+        if !removed.is_empty() {
+            let forgotten: Vec<_> = self
+                .owners
+                .keys()
+                .copied()
+                .filter(|&tower_id| removed.contains(&ChunkId::from(tower_id)))
+                .collect();
+            for tower_id in forgotten {
+                self.set_owner(tower_id, None);
             }
         }
     }
 
-    /// Call each frame after recording all the visible towers. Calls a function for rendering each
-    /// territory given it's player, center, and tower count.
-    pub fn update(&mut self, elapsed_seconds: f32, mut f: impl FnMut(PlayerId, Vec2, usize)) {
+    fn set_owner(&mut self, tower_id: TowerId, player_id: Option<PlayerId>) {
+        let previous = if let Some(player_id) = player_id {
+            self.owners.insert(tower_id, player_id)
+        } else {
+            self.owners.remove(&tower_id)
+        };
+        if previous == player_id {
+            return;
+        }
+
+        if let Some(previous) = previous
+            && let Some(t) = self.inner.get_mut(&previous)
+        {
+            t.towers.remove(&tower_id);
+            t.dirty = true;
+        }
+        if let Some(player_id) = player_id {
+            let t = self.inner.entry(player_id).or_default();
+            t.towers.insert(tower_id);
+            t.dirty = true;
+        }
+    }
+
+    /// Call each frame. Calls a function for rendering each territory given it's player, center,
+    /// and tower count, only counting towers within `viewport`.
+    pub fn update(
+        &mut self,
+        viewport: TowerRectangle,
+        elapsed_seconds: f32,
+        mut f: impl FnMut(PlayerId, Vec2, usize),
+    ) {
+        let moved = std::mem::replace(&mut self.viewport, viewport) != viewport;
         self.inner.retain(|&player_id, t| {
-            // Take data that needs to be recalculated every frame.
-            let count = std::mem::take(&mut t.count);
-            let sum = std::mem::take(&mut t.sum);
-            let best_tower_id = std::mem::take(&mut t.best_tower_id);
-            if count == 0 {
+            if t.towers.is_empty() {
                 return false;
             }
-            t.center_of_mass = Some(sum * (1.0 / count as f32));
+            if std::mem::take(&mut t.dirty) || moved {
+                t.aggregate(viewport);
+            }
 
-            if let Some((tower_id, _)) = best_tower_id {
+            if let Some(tower_id) = t.best_tower_id {
                 let new_pos = tower_id.as_vec2();
                 let pos = t.pos.get_or_insert(new_pos);
                 let delta = new_pos - *pos;
                 *pos += delta.clamp_length_max(elapsed_seconds * (3.0 + delta.length()));
 
                 // Can only render once we have a pos.
-                f(player_id, *pos, count);
+                f(player_id, *pos, t.count);
+            } else {
+                // Not visible, so don't animate from the old pos once it is again.
+                t.pos = None;
             }
             true
         })
@@ -63,9 +127,157 @@ impl Territories {
 
 #[derive(Debug, Default)]
 struct Territory {
-    best_tower_id: Option<(TowerId, f32)>,
-    center_of_mass: Option<Vec2>,
+    /// Visible tower closest to the center of mass of visible towers, as of the last
+    /// [`Self::aggregate`].
+    best_tower_id: Option<TowerId>,
+    /// Visible towers, as of the last [`Self::aggregate`].
     count: usize,
+    /// [`Self::towers`] changed since the last [`Self::aggregate`].
+    dirty: bool,
     pos: Option<Vec2>,
-    sum: Vec2,
+    towers: FxHashSet<TowerId>,
+}
+
+impl Territory {
+    /// Recomputes [`Self::count`] and [`Self::best_tower_id`] from the towers within `viewport`.
+    fn aggregate(&mut self, viewport: TowerRectangle) {
+        let visible = || {
+            self.towers
+                .iter()
+                .copied()
+                .filter(move |&tower_id| viewport.contains(tower_id))
+        };
+        let (sum, count) = visible().fold((Vec2::ZERO, 0), |(sum, count), tower_id| {
+            (sum + tower_id.as_vec2(), count + 1)
+        });
+        let best_tower_id = (count > 0)
+            .then(|| {
+                let center_of_mass = sum * (1.0 / count as f32);
+                visible().min_by(|a, b| {
+                    let a = a.as_vec2().distance_squared(center_of_mass);
+                    let b = b.as_vec2().distance_squared(center_of_mass);
+                    a.total_cmp(&b)
+                })
+            })
+            .flatten();
+        self.count = count;
+        self.best_tower_id = best_tower_id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::territory::Territories;
+    use common::info::{GainedTowerReason, Info, LostTowerReason};
+    use common::tower::{TowerId, TowerRectangle};
+    use core_protocol::PlayerId;
+    use fxhash::FxHashMap;
+    use glam::Vec2;
+
+    /// Dense map where every tower in a `size` by `size` square is owned, in 16 by 16 blocks.
+    fn dense(size: u16) -> Vec<(TowerId, PlayerId)> {
+        (0..size)
+            .flat_map(|x| (0..size).map(move |y| TowerId::new(x, y)))
+            .map(|tower_id| {
+                let player = tower_id.x / 16 + tower_id.y / 16 * (size / 16);
+                (tower_id, PlayerId::nth_bot(player as usize).unwrap())
+            })
+            .collect()
+    }
+
+    /// What [`Territories`] used to do every frame.
+    fn full_recompute(
+        towers: &[(TowerId, PlayerId)],
+        viewport: TowerRectangle,
+    ) -> FxHashMap<PlayerId, (Vec2, usize)> {
+        let visible = || {
+            towers
+                .iter()
+                .copied()
+                .filter(|&(tower_id, _)| viewport.contains(tower_id))
+        };
+
+        let mut sums = FxHashMap::<PlayerId, (Vec2, usize)>::default();
+        for (tower_id, player_id) in visible() {
+            let (sum, count) = sums.entry(player_id).or_default();
+            *sum += tower_id.as_vec2();
+            *count += 1;
+        }
+
+        let mut best = FxHashMap::<PlayerId, (Vec2, f32)>::default();
+        for (tower_id, player_id) in visible() {
+            let (sum, count) = sums[&player_id];
+            let pos = tower_id.as_vec2();
+            let new = (pos, pos.distance_squared(sum * (1.0 / count as f32)));
+            let best = best.entry(player_id).or_insert(new);
+            if new.1 < best.1 {
+                *best = new;
+            }
+        }
+
+        best.into_iter()
+            .map(|(player_id, (pos, _))| (player_id, (pos, sums[&player_id].1)))
+            .collect()
+    }
+
+    fn assert_matches(
+        territories: &mut Territories,
+        towers: &[(TowerId, PlayerId)],
+        viewport: TowerRectangle,
+    ) {
+        let expected = full_recompute(towers, viewport);
+        let mut labels = FxHashMap::default();
+        // Large elapsed time skips smoothing.
+        territories.update(viewport, 1000.0, |player_id, pos, count| {
+            labels.insert(player_id, (pos, count));
+        });
+        assert_eq!(labels.len(), expected.len());
+        for (player_id, (pos, count)) in labels {
+            let (expected_pos, expected_count) = expected[&player_id];
+            assert_eq!(count, expected_count);
+            // Ties may be broken differently.
+            assert!(pos.distance(expected_pos) < 1.5 * TowerId::CONVERSION as f32);
+        }
+    }
+
+    #[test]
+    fn incremental_matches_full() {
+        let mut towers = dense(64);
+        let everything = TowerRectangle::new(TowerId::new(0, 0), TowerId::new(63, 63));
+        let part = TowerRectangle::new(TowerId::new(10, 5), TowerId::new(40, 30));
+        let mut territories = Territories::default();
+        for &(tower_id, player_id) in &towers {
+            territories.set_owner(tower_id, Some(player_id));
+        }
+        assert_matches(&mut territories, &towers, everything);
+        assert_matches(&mut territories, &towers, part);
+
+        // Capture a strip and lose a few towers outright.
+        let capturer = PlayerId::nth_bot(0).unwrap();
+        towers.retain_mut(|(tower_id, player_id)| {
+            if tower_id.y == 20 && tower_id.x < 40 {
+                territories.on_info(&Info::LostTower {
+                    tower_id: *tower_id,
+                    player_id: *player_id,
+                    reason: LostTowerReason::CapturedBy(Some(capturer)),
+                });
+                territories.on_info(&Info::GainedTower {
+                    tower_id: *tower_id,
+                    player_id: capturer,
+                    reason: GainedTowerReason::CapturedFrom(Some(*player_id)),
+                });
+                *player_id = capturer;
+            } else if tower_id.x == 30 {
+                territories.on_info(&Info::LostTower {
+                    tower_id: *tower_id,
+                    player_id: *player_id,
+                    reason: LostTowerReason::DestroyedBy(None),
+                });
+                return false;
+            }
+            true
+        });
+        assert_matches(&mut territories, &towers, part);
+        assert_matches(&mut territories, &towers, everything);
+    }
 }
@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::force::Force;
+use common::tower::TowerId;
+use core_protocol::id::PlayerId;
+use glam::Vec2;
+use std::collections::{HashMap, VecDeque};
+
+/// Identifies a force between frames, as well as possible without an id. Forces start a new
+/// trail whenever they pass through a tower, which is where their trail would bend anyway.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+struct TrailKey {
+    player_id: Option<PlayerId>,
+    source: TowerId,
+    destination: TowerId,
+    /// Disambiguates forces with otherwise equal keys, in drawing order.
+    ordinal: u8,
+}
+
+#[derive(Debug, Default)]
+struct Trail {
+    /// Recent positions and when they were recorded, newest first.
+    points: VecDeque<(Vec2, f32)>,
+    /// Whether the force was drawn this frame.
+    seen: bool,
+}
+
+/// Recent positions of drawn forces, for drawing motion trails.
+#[derive(Debug, Default)]
+pub struct Trails {
+    trails: HashMap<TrailKey, Trail>,
+    /// How many forces with each key (minus ordinal) were drawn this frame.
+    ordinals: HashMap<TrailKey, u8>,
+}
+
+impl Trails {
+    /// How far back trails go, so their length is proportional to speed.
+    pub const SECONDS: f32 = 0.3;
+    /// Per trail.
+    const MAX_POINTS: usize = 8;
+    /// Stop starting new trails beyond this many.
+    const MAX_TRAILS: usize = 512;
+
+    /// Records the `position` of a `force` being drawn at `time`, and returns its trail, newest
+    /// first, as positions and their age (as a fraction of [`Self::SECONDS`]).
+    pub fn record(
+        &mut self,
+        force: &Force,
+        position: Vec2,
+        time: f32,
+    ) -> impl Iterator<Item = (Vec2, f32)> + '_ {
+        let mut key = TrailKey {
+            player_id: force.player_id,
+            source: force.current_source(),
+            destination: force.current_destination(),
+            ordinal: 0,
+        };
+        let ordinal = self.ordinals.entry(key).or_default();
+        key.ordinal = *ordinal;
+        *ordinal = ordinal.saturating_add(1);
+
+        let trail = if self.trails.len() < Self::MAX_TRAILS {
+            Some(self.trails.entry(key).or_default())
+        } else {
+            self.trails.get_mut(&key)
+        };
+
+        let points = trail.map(|trail| {
+            trail.seen = true;
+            let points = &mut trail.points;
+            if points.front().map_or(true, |&(_, t)| {
+                time - t >= Self::SECONDS / Self::MAX_POINTS as f32
+            }) {
+                points.push_front((position, time));
+                points.truncate(Self::MAX_POINTS);
+            }
+            while points
+                .back()
+                .is_some_and(|&(_, t)| time - t > Self::SECONDS)
+            {
+                points.pop_back();
+            }
+            &*points
+        });
+
+        std::iter::once((position, time))
+            .chain(points.into_iter().flatten().copied())
+            .map(move |(p, t)| (p, (time - t) * (1.0 / Self::SECONDS)))
+    }
+
+    /// Call after drawing a frame to forget trails of forces that weren't drawn.
+    pub fn retain_seen(&mut self) {
+        self.ordinals.clear();
+        self.trails
+            .retain(|_, trail| std::mem::take(&mut trail.seen));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trail::Trails;
+    use common::force::{Force, Path};
+    use common::tower::TowerId;
+    use common::units::Units;
+    use core_protocol::id::PlayerId;
+    use glam::Vec2;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn trails() {
+        let player_id = PlayerId(NonZeroU32::new(1).unwrap());
+        let path = Path::new(vec![TowerId::new(10, 10), TowerId::new(11, 10)]);
+        let force = Force::new(player_id, Units::default(), path);
+        let mut trails = Trails::default();
+
+        let mut positions = Vec::new();
+        for i in 0..20 {
+            let time = i as f32 * 0.05;
+            let position = Vec2::new(i as f32 * 0.1, 0.0);
+            positions = trails.record(&force, position, time).collect::<Vec<_>>();
+            trails.retain_seen();
+            // Newest first.
+            assert_eq!(positions[0], (position, 0.0));
+        }
+        // Bounded by age.
+        assert!(positions.len() > 2);
+        assert!(positions.iter().all(|&(_, age)| age < 1.01));
+
+        // Forgotten if not drawn.
+        trails.retain_seen();
+        let positions = trails.record(&force, Vec2::ZERO, 2.0).count();
+        assert_eq!(positions, 2);
+    }
+}
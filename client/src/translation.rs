@@ -1,7 +1,11 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::shortcut::ShortcutAction;
 use common::death_reason::DeathReason;
+use common::emote::EmoteId;
+use common::protocol::SpawnPreference;
+use common::singleton::WorldEvent;
 use common::tower::TowerType;
 use common::unit::Unit;
 use core_protocol::id::LanguageId;
@@ -78,6 +82,8 @@ pub trait TowerTranslation: Copy + Sized {
     s!(silo_label);
     s!(town_label);
     s!(village_label);
+    s!(tower_search_placeholder);
+    s!(tower_search_no_results);
 
     fn unit_label(self, unit: Unit) -> &'static str {
         use Unit::*;
@@ -109,6 +115,7 @@ pub trait TowerTranslation: Copy + Sized {
 
     fn death_reason(self, death_reason: DeathReason) -> String {
         match death_reason {
+            DeathReason::Afk => self.afk_kicked_message().to_owned(),
             DeathReason::RulerKilled { alias, unit } => self.ruler_killed(
                 alias,
                 // TODO don't use to_lowercase as it adds 32.6 kb to the binary.
@@ -118,12 +125,45 @@ pub trait TowerTranslation: Copy + Sized {
     }
 
     fn ruler_killed(self, alias: Option<PlayerAlias>, lower_unit_label: &str) -> String;
+    s!(afk_kicked_message);
 
     // Tower menu actions.
     s!(demolish_hint);
     s!(request_alliance_hint);
     s!(cancel_alliance_hint);
     s!(break_alliance_hint);
+    s!(confirm_break_alliance_hint);
+    s!(previously_held_by_label);
+    s!(gift_to_label);
+    s!(build_cost_label);
+    fn upgrade_cost_clause(self, cost: u32) -> String;
+    s!(save_blueprint_label);
+    s!(apply_blueprint_label);
+    s!(supply_pull_label);
+    s!(supply_push_label);
+
+    // World events
+    fn world_event_label(self, world_event: WorldEvent) -> &'static str {
+        match world_event {
+            WorldEvent::DoubleGeneration => self.double_generation_label(),
+        }
+    }
+    s!(double_generation_label);
+    s!(paused_label);
+
+    // Emotes
+    fn emote_label(self, emote_id: EmoteId) -> &'static str {
+        match emote_id {
+            EmoteId::Smile => self.smile_label(),
+            EmoteId::Frown => self.frown_label(),
+            EmoteId::Heart => self.heart_label(),
+            EmoteId::Exclamation => self.exclamation_label(),
+        }
+    }
+    s!(smile_label);
+    s!(frown_label);
+    s!(heart_label);
+    s!(exclamation_label);
 
     // Alerts
     s!(alert_capture_instruction);
@@ -134,17 +174,161 @@ pub trait TowerTranslation: Copy + Sized {
     s!(alert_ruler_unsafe_hint);
     fn alert_ruler_under_attack_warning(self) -> String;
     s!(alert_ruler_under_attack_hint);
+    s!(alert_auto_retreat_warning);
+    s!(alert_auto_retreat_hint);
     s!(alert_zombies_warning);
     s!(alert_zombies_hint);
-    s!(alert_full_warning);
+    fn alert_full_warning(self, count: usize) -> String;
     s!(alert_full_hint);
-    s!(alert_overflowing_warning);
+    s!(alert_filling_warning);
+    fn alert_overflowing_warning(self, count: usize) -> String;
     s!(alert_overflowing_hint);
+    s!(alert_afk_warning);
+    s!(alert_afk_hint);
+    s!(alert_alliance_limit_warning);
+    s!(alert_alliance_limit_hint);
+
+    // Forces.
+    s!(retreat_all_label);
+
+    // Spawning.
+    fn spawn_preference_label(self, preference: Option<SpawnPreference>) -> &'static str {
+        match preference {
+            None => self.spawn_anywhere_label(),
+            Some(SpawnPreference::Contested) => self.spawn_contested_label(),
+            Some(SpawnPreference::Quiet) => self.spawn_quiet_label(),
+        }
+    }
+    s!(spawn_anywhere_label);
+    s!(spawn_contested_label);
+    s!(spawn_quiet_label);
+    s!(spawn_queue_label);
+
+    // Death screen.
+    s!(peak_towers_label);
+    s!(longest_held_label);
+    s!(rulers_killed_label);
+    s!(play_again_label);
+    s!(menu_label);
+
+    // Keyboard shortcuts.
+    s!(shortcuts_title);
+    fn shortcut_label(self, action: ShortcutAction) -> &'static str {
+        use ShortcutAction::*;
+        match action {
+            Pan => self.pan_shortcut_label(),
+            Zoom => self.zoom_shortcut_label(),
+            PanToRuler => self.pan_to_ruler_shortcut_label(),
+            FitTerritory => self.fit_territory_shortcut_label(),
+            ShowSupplyLines => self.show_supply_lines_shortcut_label(),
+            ToggleSupplyLines => self.toggle_supply_lines_shortcut_label(),
+            ClearSupplyLine => self.clear_supply_line_shortcut_label(),
+            UndoClearSupplyLines => self.undo_clear_supply_lines_shortcut_label(),
+            SimilarTowers => self.similar_towers_shortcut_label(),
+            Focus => self.focus_shortcut_label(),
+            PartialDeploy => self.partial_deploy_shortcut_label(),
+            RepeatDeploy => self.repeat_deploy_shortcut_label(),
+            RetreatAll => self.retreat_all_label(),
+            Ping => self.ping_shortcut_label(),
+            TowerMenu => self.tower_menu_shortcut_label(),
+            Screenshot => self.screenshot_shortcut_label(),
+            Shortcuts => self.shortcuts_title(),
+            CopyDebugState => self.copy_debug_state_shortcut_label(),
+            RevealAll => self.reveal_all_shortcut_label(),
+            DebugBounds => self.debug_bounds_shortcut_label(),
+        }
+    }
+    s!(pan_shortcut_label);
+    s!(zoom_shortcut_label);
+    s!(pan_to_ruler_shortcut_label);
+    s!(fit_territory_shortcut_label);
+    s!(show_supply_lines_shortcut_label);
+    s!(toggle_supply_lines_shortcut_label);
+    s!(clear_supply_line_shortcut_label);
+    s!(undo_clear_supply_lines_shortcut_label);
+    s!(similar_towers_shortcut_label);
+    s!(focus_shortcut_label);
+    s!(partial_deploy_shortcut_label);
+    s!(repeat_deploy_shortcut_label);
+    s!(ping_shortcut_label);
+    s!(tower_menu_shortcut_label);
+    s!(screenshot_shortcut_label);
+    s!(copy_debug_state_shortcut_label);
+    s!(reveal_all_shortcut_label);
+    s!(debug_bounds_shortcut_label);
+}
+
+/// [CLDR plural category](https://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html)
+/// of a count, for messages that depend on it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Plural {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl Plural {
+    fn of(language_id: LanguageId, n: usize) -> Self {
+        match language_id {
+            SimplifiedChinese | Japanese | Vietnamese => Self::Other,
+            French | Hindi if n <= 1 => Self::One,
+            Russian => match (n % 10, n % 100) {
+                (1, m) if m != 11 => Self::One,
+                (2..=4, m) if !(12..=14).contains(&m) => Self::Few,
+                _ => Self::Many,
+            },
+            Arabic => match (n, n % 100) {
+                (0, _) => Self::Zero,
+                (1, _) => Self::One,
+                (2, _) => Self::Two,
+                (_, 3..=10) => Self::Few,
+                (_, 11..=99) => Self::Many,
+                _ => Self::Other,
+            },
+            _ if n == 1 => Self::One,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Returns the English translation if `language_id`'s is missing (empty), logging the first
+/// occurrence of each missing translation in debug builds.
+fn fallback(language_id: LanguageId, translate: fn(LanguageId) -> &'static str) -> &'static str {
+    let translation = translate(language_id);
+    if !translation.is_empty() {
+        return translation;
+    }
+    let english = translate(English);
+    #[cfg(debug_assertions)]
+    log_missing(language_id, english);
+    english
+}
+
+/// `english` identifies the translation.
+#[cfg(debug_assertions)]
+fn log_missing(language_id: LanguageId, english: &'static str) {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LOGGED: RefCell<Vec<(LanguageId, &'static str)>> = RefCell::default();
+    }
+
+    LOGGED.with(|logged| {
+        let mut logged = logged.borrow_mut();
+        if !logged.contains(&(language_id, english)) {
+            logged.push((language_id, english));
+            #[cfg(target_arch = "wasm32")]
+            js_hooks::console_log!("missing {language_id:?} translation of {english:?}");
+        }
+    });
 }
 
 impl TowerTranslation for LanguageId {
     fn tower_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Tower",
             Spanish => "Torre",
             French => "La tour",
@@ -157,11 +341,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "タワー",
             Vietnamese => "Tòa tháp",
             Bork => "Bork",
-        }
+        })
     }
 
     fn airfield_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Airfield",
             Spanish => "Aeródromo",
             French => "Aérodrome",
@@ -174,11 +358,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "机场",
             Vietnamese => "Sân bay",
             Bork => "Airbork",
-        }
+        })
     }
 
     fn armory_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Armory",
             Spanish => "Arsenal",
             French => "Arsenal",
@@ -191,11 +375,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "坦克工厂",
             Vietnamese => "Kho vũ khí",
             Bork => "Armorbork",
-        }
+        })
     }
 
     fn artillery_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Artillery",
             Spanish => "Artillería",
             French => "Artillerie",
@@ -208,11 +392,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "炮兵",
             Vietnamese => "pháo binh",
             Bork => "Borktillery",
-        }
+        })
     }
 
     fn barracks_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Barracks",
             Spanish => "Cuartel",
             French => "Caserne",
@@ -225,11 +409,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "兵营",
             Vietnamese => "Doanh trại",
             Bork => "Borracks",
-        }
+        })
     }
 
     fn bunker_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Bunker",
             Spanish => "Búnker",
             French => "Bunker",
@@ -242,11 +426,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "掩体",
             Vietnamese => "Pháo đài",
             Bork => "Borker",
-        }
+        })
     }
 
     fn centrifuge_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Centrifuge",
             Spanish => "Centrífuga",
             French => "Centrifugeuse",
@@ -259,11 +443,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "离心机",
             Vietnamese => "Máy ly tâm",
             Bork => "Borktrifuge",
-        }
+        })
     }
 
     fn city_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "City",
             Spanish => "Ciudad",
             French => "Cité",
@@ -276,11 +460,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "城市",
             Vietnamese => "Thành phố",
             Bork => "Borkty",
-        }
+        })
     }
 
     fn cliff_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Cliff",
             Spanish => "Acantilado",
             French => "Falaise",
@@ -293,11 +477,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "悬崖",
             Vietnamese => "Vách đá",
             Bork => "Borff",
-        }
+        })
     }
 
     fn generator_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Generator",
             Spanish => "Generador",
             French => "Générateur",
@@ -310,11 +494,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "发电站",
             Vietnamese => "Generator",
             Bork => "Borkerator",
-        }
+        })
     }
 
     fn headquarters_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Headquarters",
             Spanish => "Sede",
             French => "Quartier général",
@@ -327,11 +511,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "总部",
             Vietnamese => "Trụ sở",
             Bork => "Command Bork",
-        }
+        })
     }
 
     fn helipad_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Helipad",
             Spanish => "Helipuerto",
             French => "Héliport",
@@ -344,11 +528,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "直升机停机坪",
             Vietnamese => "Sân bay trực thăng",
             Bork => "Helibork",
-        }
+        })
     }
 
     fn ews_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "EWS",
             Spanish => "Sistema de Alerta Temprana",
             French => "Système d'alerte précoce",
@@ -361,11 +545,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "预警系统",
             Vietnamese => "Hệ thống cảnh báo sớm",
             Bork => "EBS",
-        }
+        })
     }
 
     fn factory_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Factory",
             Spanish => "Fábrica",
             French => "Usine",
@@ -378,11 +562,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "工場",
             Vietnamese => "Nhà máy",
             Bork => "Borktory",
-        }
+        })
     }
 
     fn launcher_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Launcher",
             Spanish => "Lanzacohetes",
             French => "Lance-roquettes",
@@ -395,11 +579,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "ロケット発射筒",
             Vietnamese => "Phóng tên lửa",
             Bork => "Launcherk",
-        }
+        })
     }
 
     fn mine_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Mine",
             Spanish => "Mina",
             French => "Mine",
@@ -412,11 +596,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "機雷",
             Vietnamese => "Mỏ",
             Bork => "Bork Mine",
-        }
+        })
     }
 
     fn projector_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Projector",
             Spanish => "Proyector",
             French => "Projecteur",
@@ -429,11 +613,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "プロジェクター",
             Vietnamese => "Máy chiếu",
             Bork => "Porjector",
-        }
+        })
     }
 
     fn rampart_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Rampart",
             Spanish => "Muralla",
             French => "Rempart",
@@ -446,11 +630,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "堅塁",
             Vietnamese => "bờ lủy",
             Bork => "Rambork",
-        }
+        })
     }
 
     fn reactor_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Reactor",
             Spanish => "Reactor",
             French => "Réacteur",
@@ -463,11 +647,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "原子炉",
             Vietnamese => "Lò phản ứng",
             Bork => "Reactbork",
-        }
+        })
     }
 
     fn refinery_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Refinery",
             Spanish => "Refinería",
             French => "Raffinerie",
@@ -480,11 +664,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "炼油厂",
             Vietnamese => "Nhà máy lọc dầu",
             Bork => "Reborkery",
-        }
+        })
     }
 
     fn radar_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Radar",
             Spanish => "Radar",
             French => "Radar",
@@ -497,11 +681,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "レーダー",
             Vietnamese => "Radar",
             Bork => "Radar Bork",
-        }
+        })
     }
 
     fn ruler_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "King",
             Spanish => "Gobernante",
             French => "Souverain",
@@ -514,11 +698,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "皇帝",
             Vietnamese => "Chủ tịch",
             Bork => "BORK",
-        }
+        })
     }
 
     fn rocket_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Rocket",
             Spanish => "Cohete",
             French => "Fusée",
@@ -531,12 +715,12 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "火箭",
             Vietnamese => "Tên lửa",
             Bork => "Borket",
-        }
+        })
     }
 
     fn runway_label(self) -> &'static str {
         // May translate as "Airstrip" instead.
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Runway",
             Spanish => "Pista",
             French => "Piste",
@@ -549,11 +733,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "跑道",
             Vietnamese => "Đường băng",
             Bork => "Borkway",
-        }
+        })
     }
 
     fn quarry_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Quarry",
             Spanish => "Cantera",
             French => "Carrière",
@@ -566,11 +750,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "切り出す",
             Vietnamese => "Mỏ đá",
             Bork => "Borrky",
-        }
+        })
     }
 
     fn satellite_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Satellite",
             Spanish => "Satélite",
             French => "Satellite",
@@ -583,11 +767,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "衛生",
             Vietnamese => "Vệ tinh",
             Bork => "Borkellite",
-        }
+        })
     }
 
     fn silo_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Silo",
             Spanish => "Silo de misiles",
             French => "Silo à missiles",
@@ -600,11 +784,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "ミサイルサイロ",
             Vietnamese => "Bệ phóng tên lửa",
             Bork => "Bork Silo",
-        }
+        })
     }
 
     fn town_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Town",
             Spanish => "Municipio",
             French => "Commune",
@@ -617,11 +801,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "郷",
             Vietnamese => "Xã",
             Bork => "Borktown",
-        }
+        })
     }
 
     fn village_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Village",
             Spanish => "Pueblo",
             French => "Village",
@@ -634,11 +818,45 @@ impl TowerTranslation for LanguageId {
             Japanese => "村",
             Vietnamese => "Làng",
             Bork => "Borkville",
-        }
+        })
+    }
+
+    fn tower_search_placeholder(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Search towers",
+            Spanish => "Buscar torres",
+            French => "Rechercher des tours",
+            German => "Türme suchen",
+            Italian => "Cerca torri",
+            Russian => "Поиск башен",
+            Arabic => "ابحث عن الأبراج",
+            Hindi => "टावर खोजें",
+            SimplifiedChinese => "搜索塔",
+            Japanese => "タワーを検索",
+            Vietnamese => "Tìm tháp",
+            Bork => "Sniff towers",
+        })
+    }
+
+    fn tower_search_no_results(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "No towers match.",
+            Spanish => "Ninguna torre coincide.",
+            French => "Aucune tour ne correspond.",
+            German => "Keine passenden Türme.",
+            Italian => "Nessuna torre corrisponde.",
+            Russian => "Подходящих башен нет.",
+            Arabic => "لا توجد أبراج مطابقة.",
+            Hindi => "कोई टावर मेल नहीं खाता।",
+            SimplifiedChinese => "没有匹配的塔。",
+            Japanese => "一致するタワーはありません。",
+            Vietnamese => "Không có tháp nào phù hợp.",
+            Bork => "No borks found.",
+        })
     }
 
     fn emp_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "EMP",
             Spanish => "EMP",
             French => "PEM",
@@ -651,11 +869,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "電磁パルス",
             Vietnamese => "Xung điện từ",
             Bork => "EMB",
-        }
+        })
     }
 
     fn shell_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Shell",
             Spanish => "Proyectil",
             French => "Obus",
@@ -668,11 +886,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "弾",
             Vietnamese => "đạn trái phá",
             Bork => "Borkk",
-        }
+        })
     }
 
     fn shield_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Shield",
             Spanish => "Escudo",
             French => "Bouclier",
@@ -685,11 +903,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "シールド",
             Vietnamese => "Khiên",
             Bork => "Bork Field",
-        }
+        })
     }
 
     fn soldier_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Soldier",
             Spanish => "Soldado",
             French => "Soldat",
@@ -702,11 +920,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "兵士",
             Vietnamese => "Quân nhân",
             Bork => "Borker",
-        }
+        })
     }
 
     fn tank_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Tank",
             Spanish => "Tanque",
             French => "Tank",
@@ -719,11 +937,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "戦車",
             Vietnamese => "Xe tăng",
             Bork => "Bork",
-        }
+        })
     }
 
     fn fighter_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Fighter",
             Spanish => "Avión de combate",
             French => "Avion de chasse",
@@ -736,11 +954,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "战斗机",
             Vietnamese => "Máy bay chiến đấu",
             Bork => "Smol Borker",
-        }
+        })
     }
 
     fn bomber_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Bomber",
             Spanish => "Bombardero",
             French => "Bombardier",
@@ -753,11 +971,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "爆撃機",
             Vietnamese => "Máy bay ném bom",
             Bork => "Borker",
-        }
+        })
     }
 
     fn chopper_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Chopper",
             Spanish => "Helicóptero",
             French => "Hélicoptère",
@@ -770,11 +988,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "直升机",
             Vietnamese => "Trực thăng",
             Bork => "Choppy Bork",
-        }
+        })
     }
 
     fn nuke_label(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Nuke",
             Spanish => "Nuke",
             French => "Micro-onde",
@@ -787,11 +1005,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "核兵器",
             Vietnamese => "Nuke",
             Bork => "Borke",
-        }
+        })
     }
 
     fn demolish_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Demolish",
             Spanish => "Demolerlo",
             French => "Démolissez-le",
@@ -804,11 +1022,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "取り壊す",
             Vietnamese => "Phá hủy",
             Bork => "",
-        }
+        })
     }
 
     fn request_alliance_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Request alliance",
             Spanish => "Solicitar alianza",
             French => "Demande d'alliance",
@@ -821,11 +1039,11 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "请求联盟",
             Vietnamese => "yêu cầu liên minh",
             Bork => "Bork?",
-        }
+        })
     }
 
     fn cancel_alliance_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Cancel request",
             Spanish => "Cancelar petición",
             French => "Demande d'annulation",
@@ -838,11 +1056,249 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "取消请求",
             Vietnamese => "Hủy yêu cầu",
             Bork => "Krob",
+        })
+    }
+
+    fn gift_to_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Gift to",
+            Spanish => "Regalar a",
+            French => "Offrir à",
+            German => "Schenken an",
+            Italian => "Regala a",
+            Japanese => "贈る相手",
+            Russian => "Подарить",
+            Arabic => "إهداء إلى",
+            Hindi => "उपहार दें",
+            SimplifiedChinese => "赠送给",
+            Vietnamese => "Tặng cho",
+            Bork => "Bork to",
+        })
+    }
+
+    fn supply_pull_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Pull units",
+            Spanish => "Atraer unidades",
+            French => "Attirer des unités",
+            German => "Einheiten anfordern",
+            Italian => "Richiama unità",
+            Japanese => "ユニットを引き寄せる",
+            Russian => "Притягивать войска",
+            Arabic => "سحب الوحدات",
+            Hindi => "इकाइयाँ खींचें",
+            SimplifiedChinese => "拉取单位",
+            Vietnamese => "Kéo quân",
+            Bork => "Pull borks",
+        })
+    }
+
+    fn supply_push_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Push units",
+            Spanish => "Enviar unidades",
+            French => "Envoyer des unités",
+            German => "Einheiten senden",
+            Italian => "Invia unità",
+            Japanese => "ユニットを送り出す",
+            Russian => "Отправлять войска",
+            Arabic => "دفع الوحدات",
+            Hindi => "इकाइयाँ भेजें",
+            SimplifiedChinese => "推送单位",
+            Vietnamese => "Đẩy quân",
+            Bork => "Push borks",
+        })
+    }
+
+    fn double_generation_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Double generation",
+            Spanish => "Generación doble",
+            French => "Génération doublée",
+            German => "Doppelte Produktion",
+            Italian => "Generazione doppia",
+            Japanese => "生産2倍",
+            Russian => "Двойное производство",
+            Arabic => "إنتاج مضاعف",
+            Hindi => "दोगुना उत्पादन",
+            SimplifiedChinese => "双倍生产",
+            Vietnamese => "Sản xuất gấp đôi",
+            Bork => "Double bork",
+        })
+    }
+
+    fn paused_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Paused",
+            Spanish => "En pausa",
+            French => "En pause",
+            German => "Pausiert",
+            Italian => "In pausa",
+            Japanese => "一時停止中",
+            Russian => "Пауза",
+            Arabic => "متوقف مؤقتًا",
+            Hindi => "रुका हुआ",
+            SimplifiedChinese => "已暂停",
+            Vietnamese => "Tạm dừng",
+            Bork => "Borkn't",
+        })
+    }
+
+    fn smile_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Smile",
+            Spanish => "Sonrisa",
+            French => "Sourire",
+            German => "Lächeln",
+            Italian => "Sorriso",
+            Japanese => "笑顔",
+            Russian => "Улыбка",
+            Arabic => "ابتسامة",
+            Hindi => "मुस्कान",
+            SimplifiedChinese => "微笑",
+            Vietnamese => "Cười",
+            Bork => "Bork bork",
+        })
+    }
+
+    fn frown_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Frown",
+            Spanish => "Ceño fruncido",
+            French => "Froncement",
+            German => "Stirnrunzeln",
+            Italian => "Broncio",
+            Japanese => "しかめっ面",
+            Russian => "Хмурый взгляд",
+            Arabic => "عبوس",
+            Hindi => "नाराज़गी",
+            SimplifiedChinese => "皱眉",
+            Vietnamese => "Cau mày",
+            Bork => "Grr",
+        })
+    }
+
+    fn heart_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Heart",
+            Spanish => "Corazón",
+            French => "Cœur",
+            German => "Herz",
+            Italian => "Cuore",
+            Japanese => "ハート",
+            Russian => "Сердце",
+            Arabic => "قلب",
+            Hindi => "दिल",
+            SimplifiedChinese => "爱心",
+            Vietnamese => "Trái tim",
+            Bork => "Bork love",
+        })
+    }
+
+    fn exclamation_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Exclamation",
+            Spanish => "Exclamación",
+            French => "Exclamation",
+            German => "Ausruf",
+            Italian => "Esclamazione",
+            Japanese => "感嘆符",
+            Russian => "Восклицание",
+            Arabic => "تعجب",
+            Hindi => "विस्मयादिबोधक",
+            SimplifiedChinese => "感叹号",
+            Vietnamese => "Cảm thán",
+            Bork => "Bork!",
+        })
+    }
+
+    fn build_cost_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Cost:",
+            Spanish => "Coste:",
+            French => "Coût :",
+            German => "Kosten:",
+            Italian => "Costo:",
+            Japanese => "コスト:",
+            Russian => "Стоимость:",
+            Arabic => "التكلفة:",
+            Hindi => "लागत:",
+            SimplifiedChinese => "成本:",
+            Vietnamese => "Chi phí:",
+            Bork => "Bork:",
+        })
+    }
+
+    fn upgrade_cost_clause(self, cost: u32) -> String {
+        match self {
+            English => format!(", and costs {cost}."),
+            Spanish => format!(", y cuesta {cost}."),
+            French => format!(", et coûte {cost}."),
+            German => format!(", und kostet {cost}."),
+            Italian => format!(", e costa {cost}."),
+            Japanese => format!("、コストは{cost}です。"),
+            Russian => format!(", и стоит {cost}."),
+            Arabic => format!("، وتكلفته {cost}."),
+            Hindi => format!(", और लागत {cost} है।"),
+            SimplifiedChinese => format!("，花费{cost}。"),
+            Vietnamese => format!(", và tốn {cost}."),
+            Bork => format!(", bork {cost}."),
         }
     }
 
+    fn save_blueprint_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Save blueprint",
+            Spanish => "Guardar plano",
+            French => "Enregistrer le plan",
+            German => "Bauplan speichern",
+            Italian => "Salva progetto",
+            Japanese => "設計図を保存",
+            Russian => "Сохранить чертёж",
+            Arabic => "حفظ المخطط",
+            Hindi => "ब्लूप्रिंट सहेजें",
+            SimplifiedChinese => "保存蓝图",
+            Vietnamese => "Lưu bản thiết kế",
+            Bork => "Save bork",
+        })
+    }
+
+    fn apply_blueprint_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Apply blueprint:",
+            Spanish => "Aplicar plano:",
+            French => "Appliquer le plan :",
+            German => "Bauplan anwenden:",
+            Italian => "Applica progetto:",
+            Japanese => "設計図を適用:",
+            Russian => "Применить чертёж:",
+            Arabic => "تطبيق المخطط:",
+            Hindi => "ब्लूप्रिंट लागू करें:",
+            SimplifiedChinese => "应用蓝图:",
+            Vietnamese => "Áp dụng bản thiết kế:",
+            Bork => "Bork bork:",
+        })
+    }
+
+    fn previously_held_by_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Previously held by",
+            Spanish => "Antes en manos de",
+            French => "Auparavant détenue par",
+            German => "Zuvor gehalten von",
+            Italian => "In precedenza di",
+            Japanese => "以前の所有者",
+            Russian => "Ранее принадлежала",
+            Arabic => "كان يملكه سابقًا",
+            Hindi => "पहले इनके पास था",
+            SimplifiedChinese => "先前的占领者",
+            Vietnamese => "Trước đây thuộc về",
+            Bork => "Borked by",
+        })
+    }
+
     fn break_alliance_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Break alliance",
             Spanish => "Romper alianza",
             French => "Rompre l'alliance",
@@ -855,11 +1311,28 @@ impl TowerTranslation for LanguageId {
             SimplifiedChinese => "打破联盟",
             Vietnamese => "Phá vỡ liên minh",
             Bork => "Krob",
-        }
+        })
+    }
+
+    fn confirm_break_alliance_hint(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Click again to break alliance",
+            Spanish => "Haz clic de nuevo para romper la alianza",
+            French => "Cliquez à nouveau pour rompre l'alliance",
+            German => "Erneut klicken, um das Bündnis zu brechen",
+            Italian => "Clicca di nuovo per rompere l'alleanza",
+            Japanese => "もう一度クリックすると同盟を破ります",
+            Russian => "Нажмите ещё раз, чтобы разорвать союз",
+            Arabic => "انقر مرة أخرى لكسر التحالف",
+            Hindi => "गठबंधन तोड़ने के लिए फिर से क्लिक करें",
+            SimplifiedChinese => "再次点击以打破联盟",
+            Vietnamese => "Nhấp lần nữa để phá vỡ liên minh",
+            Bork => "Krob krob?",
+        })
     }
 
     fn alert_capture_instruction(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Capture more towers",
             Spanish => "Captura más torres",
             French => "Capturez plus de tours",
@@ -872,11 +1345,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "より多くの塔を占領",
             Vietnamese => "Chụp nhiều tháp hơn",
             Bork => "Bork more borks",
-        }
+        })
     }
 
     fn alert_capture_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Drag units from your towers to outside your borders",
             Spanish => "Arrastra unidades desde tus torres hasta fuera de tus fronteras",
             French => "Faites glisser des unités de vos tours vers l'extérieur de vos frontières",
@@ -889,11 +1362,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "ユニットをタワーから国境の外にドラッグします",
             Vietnamese => "Kéo các đơn vị từ tháp của bạn ra bên ngoài biên giới của bạn",
             Bork => "Drag borks from your borks to outside your borkders",
-        }
+        })
     }
 
     fn alert_upgrade_instruction(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Upgrade a tower",
             Spanish => "Mejora una torre",
             French => "Améliorer une tour",
@@ -906,11 +1379,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "タワーをアップグレードする",
             Vietnamese => "Nâng cấp tháp",
             Bork => "Upgrade a bork",
-        }
+        })
     }
 
     fn alert_upgrade_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Click a tower to show upgrade options",
             Spanish => "Haga clic en una torre para mostrar las opciones de actualización",
             French => "Cliquez sur une tour pour afficher les options de mise à niveau",
@@ -923,7 +1396,7 @@ impl TowerTranslation for LanguageId {
             Japanese => "タワーをクリックしてアップグレード オプションを表示します",
             Vietnamese => "Nhấp vào tháp để hiển thị các tùy chọn nâng cấp",
             Bork => "Click a bork to show upgrade options",
-        }
+        })
     }
 
     fn alert_ruler_unsafe_instruction(self) -> String {
@@ -945,7 +1418,8 @@ impl TowerTranslation for LanguageId {
 
     fn alert_ruler_unsafe_hint(self) -> &'static str {
         // FIXME: Redundant tower names?
-        match self {
+        fallback(self, |language_id| {
+            match language_id {
             English => "Shielded Headquarters or Bunkers near the center of your territory provide the most protection",
             Spanish => "Los cuarteles generales o búnkeres blindados cerca del centro de su territorio brindan la mayor protección",
             French => "Les quartiers généraux blindés ou les bunkers situés près du centre de votre territoire offrent la meilleure protection",
@@ -959,6 +1433,7 @@ impl TowerTranslation for LanguageId {
             Vietnamese => "Trụ sở được che chắn hoặc các boongke gần trung tâm lãnh thổ của bạn cung cấp khả năng bảo vệ tối đa",
             Bork => "Shielded Command Bork or Borkers near the center of your territory provide the most protection",
         }
+        })
     }
 
     fn alert_ruler_under_attack_warning(self) -> String {
@@ -980,7 +1455,7 @@ impl TowerTranslation for LanguageId {
     }
 
     fn alert_ruler_under_attack_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "If they die, you lose the game",
             Spanish => "Si muere, pierdes el juego.",
             French => "Si meurt, vous perdez la partie",
@@ -993,11 +1468,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "死亡した場合、ゲームに負けます",
             Vietnamese => "Nếu chết, bạn sẽ thua trò chơi",
             Bork => "If they are borked, the game is borked.",
-        }
+        })
     }
 
     fn alert_zombies_warning(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Zombies sighted",
             Spanish => "Zombis avistados",
             French => "Zombies aperçus",
@@ -1010,11 +1485,11 @@ impl TowerTranslation for LanguageId {
             Japanese => "目撃されたゾンビ",
             Vietnamese => "Thây ma nhìn thấy",
             Bork => "Zomborks sighted",
-        }
+        })
     }
 
     fn alert_zombies_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Escape them by moving in the opposite direction",
             Spanish => "Escapa de ellos moviéndote en la dirección opuesta.",
             French => "Échappez-leur en vous déplaçant dans la direction opposée",
@@ -1027,28 +1502,84 @@ impl TowerTranslation for LanguageId {
             Japanese => "反対方向に移動して逃げる",
             Vietnamese => "Thoát khỏi chúng bằng cách di chuyển theo hướng ngược lại",
             Bork => "Escape them by borking in the opposite direction",
-        }
+        })
     }
 
-    fn alert_full_warning(self) -> &'static str {
-        match self {
-            English => "A tower is full",
-            Spanish => "Una torre está llena",
-            French => "Une tour est pleine",
-            German => "Ein Turm ist voll",
-            Italian => "Una torre è piena",
-            Russian => "Башня полна",
-            Arabic => "برج ممتلئ",
-            Hindi => "एक टावर भरा हुआ है",
-            SimplifiedChinese => "一座塔已满",
-            Japanese => "タワーがいっぱいです",
-            Vietnamese => "Một tòa tháp đã đầy",
-            Bork => "A bork is borked",
+    fn alert_auto_retreat_warning(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Your ruler retreated",
+            Spanish => "Tu gobernante se retiró",
+            French => "Votre souverain a battu en retraite",
+            German => "Ihr Herrscher hat sich zurückgezogen",
+            Italian => "Il tuo sovrano si è ritirato",
+            Russian => "Ваш правитель отступил",
+            Arabic => "تراجع حاكمك",
+            Hindi => "आपका शासक पीछे हट गया",
+            SimplifiedChinese => "你的统治者已撤退",
+            Japanese => "支配者が撤退しました",
+            Vietnamese => "Người cai trị của bạn đã rút lui",
+            Bork => "Your borker borked away",
+        })
+    }
+
+    fn alert_auto_retreat_hint(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Auto-retreat can be disabled in settings",
+            Spanish => "La retirada automática se puede desactivar en la configuración",
+            French => "La retraite automatique peut être désactivée dans les paramètres",
+            German => "Automatischer Rückzug kann in den Einstellungen deaktiviert werden",
+            Italian => "La ritirata automatica può essere disattivata nelle impostazioni",
+            Russian => "Автоотступление можно отключить в настройках",
+            Arabic => "يمكن تعطيل التراجع التلقائي في الإعدادات",
+            Hindi => "स्वतः पीछे हटना सेटिंग्स में बंद किया जा सकता है",
+            SimplifiedChinese => "可以在设置中关闭自动撤退",
+            Japanese => "自動撤退は設定で無効にできます",
+            Vietnamese => "Có thể tắt tự động rút lui trong cài đặt",
+            Bork => "Auto-bork can be unborked in settings",
+        })
+    }
+
+    fn alert_full_warning(self, count: usize) -> String {
+        use Plural::*;
+        let n = count;
+        match (self, Plural::of(self, n)) {
+            (English, One) => "A tower is full".into(),
+            (English, _) => format!("{n} towers are full"),
+            (Spanish, One) => "Una torre está llena".into(),
+            (Spanish, _) => format!("{n} torres están llenas"),
+            (French, One) if n == 1 => "Une tour est pleine".into(),
+            (French, One) => format!("{n} tour est pleine"),
+            (French, _) => format!("{n} tours sont pleines"),
+            (German, One) => "Ein Turm ist voll".into(),
+            (German, _) => format!("{n} Türme sind voll"),
+            (Italian, One) => "Una torre è piena".into(),
+            (Italian, _) => format!("{n} torri sono piene"),
+            (Russian, One) if n == 1 => "Башня полна".into(),
+            (Russian, One) => format!("{n} башня полна"),
+            (Russian, Few) => format!("{n} башни полны"),
+            (Russian, _) => format!("{n} башен полны"),
+            (Arabic, Zero) => "لا توجد أبراج ممتلئة".into(),
+            (Arabic, One) => "برج ممتلئ".into(),
+            (Arabic, Two) => "برجان ممتلئان".into(),
+            (Arabic, Few) => format!("{n} أبراج ممتلئة"),
+            (Arabic, Many) => format!("{n} برجًا ممتلئًا"),
+            (Arabic, _) => format!("{n} برج ممتلئ"),
+            (Hindi, One) if n == 1 => "एक टावर भरा हुआ है".into(),
+            (Hindi, One) => format!("{n} टावर भरा हुआ है"),
+            (Hindi, _) => format!("{n} टावर भरे हुए हैं"),
+            (SimplifiedChinese, _) if n == 1 => "一座塔已满".into(),
+            (SimplifiedChinese, _) => format!("{n} 座塔已满"),
+            (Japanese, _) if n == 1 => "タワーがいっぱいです".into(),
+            (Japanese, _) => format!("{n} 基のタワーがいっぱいです"),
+            (Vietnamese, _) if n == 1 => "Một tòa tháp đã đầy".into(),
+            (Vietnamese, _) => format!("{n} tòa tháp đã đầy"),
+            (Bork, One) => "A bork is borked".into(),
+            (Bork, _) => format!("{n} borks are borked"),
         }
     }
 
     fn alert_full_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Drag away units to make room for more",
             Spanish => "Arrastra unidades para hacer espacio para más",
             French => "Faites glisser les unités pour faire de la place pour plus",
@@ -1061,28 +1592,67 @@ impl TowerTranslation for LanguageId {
             Japanese => "ユニットを引き離してスペースを空けます",
             Vietnamese => "Kéo đơn vị ra xa để có thêm chỗ",
             Bork => "Drag borks away to make room for more borks",
-        }
+        })
     }
 
-    fn alert_overflowing_warning(self) -> &'static str {
-        match self {
-            English => "A tower is overflowing",
-            Spanish => "Una torre se desborda",
-            French => "Une tour déborde",
-            German => "Ein Turm quillt über",
-            Italian => "Una torre trabocca",
-            Russian => "Башня переполнена",
-            Arabic => "البرج يفيض",
-            Hindi => "एक टावर ओवरफ्लो हो रहा है",
-            SimplifiedChinese => "一座塔溢出来",
-            Japanese => "タワーがあふれています",
-            Vietnamese => "Một tòa tháp đang tràn",
-            Bork => "A bork is borking",
+    fn alert_filling_warning(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "A tower will soon be full",
+            Spanish => "Una torre pronto estará llena",
+            French => "Une tour sera bientôt pleine",
+            German => "Ein Turm ist bald voll",
+            Italian => "Una torre sarà presto piena",
+            Russian => "Башня скоро заполнится",
+            Arabic => "سيمتلئ برج قريبًا",
+            Hindi => "एक टावर जल्द ही भर जाएगा",
+            SimplifiedChinese => "一座塔即将满了",
+            Japanese => "タワーがもうすぐいっぱいになります",
+            Vietnamese => "Một tòa tháp sắp đầy",
+            Bork => "A bork will soon be borked",
+        })
+    }
+
+    fn alert_overflowing_warning(self, count: usize) -> String {
+        use Plural::*;
+        let n = count;
+        match (self, Plural::of(self, n)) {
+            (English, One) => "A tower is overflowing".into(),
+            (English, _) => format!("{n} towers are overflowing"),
+            (Spanish, One) => "Una torre se desborda".into(),
+            (Spanish, _) => format!("{n} torres se desbordan"),
+            (French, One) if n == 1 => "Une tour déborde".into(),
+            (French, One) => format!("{n} tour déborde"),
+            (French, _) => format!("{n} tours débordent"),
+            (German, One) => "Ein Turm quillt über".into(),
+            (German, _) => format!("{n} Türme quellen über"),
+            (Italian, One) => "Una torre trabocca".into(),
+            (Italian, _) => format!("{n} torri traboccano"),
+            (Russian, One) if n == 1 => "Башня переполнена".into(),
+            (Russian, One) => format!("{n} башня переполнена"),
+            (Russian, Few) => format!("{n} башни переполнены"),
+            (Russian, _) => format!("{n} башен переполнены"),
+            (Arabic, Zero) => "لا توجد أبراج تفيض".into(),
+            (Arabic, One) => "البرج يفيض".into(),
+            (Arabic, Two) => "برجان يفيضان".into(),
+            (Arabic, Few) => format!("{n} أبراج تفيض"),
+            (Arabic, Many) => format!("{n} برجًا يفيض"),
+            (Arabic, _) => format!("{n} برج يفيض"),
+            (Hindi, One) if n == 1 => "एक टावर ओवरफ्लो हो रहा है".into(),
+            (Hindi, One) => format!("{n} टावर ओवरफ्लो हो रहा है"),
+            (Hindi, _) => format!("{n} टावर ओवरफ्लो हो रहे हैं"),
+            (SimplifiedChinese, _) if n == 1 => "一座塔溢出来".into(),
+            (SimplifiedChinese, _) => format!("{n} 座塔溢出来"),
+            (Japanese, _) if n == 1 => "タワーがあふれています".into(),
+            (Japanese, _) => format!("{n} 基のタワーがあふれています"),
+            (Vietnamese, _) if n == 1 => "Một tòa tháp đang tràn".into(),
+            (Vietnamese, _) => format!("{n} tòa tháp đang tràn"),
+            (Bork, One) => "A bork is borking".into(),
+            (Bork, _) => format!("{n} borks are borking"),
         }
     }
 
     fn alert_overflowing_hint(self) -> &'static str {
-        match self {
+        fallback(self, |language_id| match language_id {
             English => "Drag away units to stop them from disappearing",
             Spanish => "Arrastra unidades para evitar que desaparezcan",
             French => "Faites glisser les unités pour les empêcher de disparaître",
@@ -1095,7 +1665,585 @@ impl TowerTranslation for LanguageId {
             Japanese => "ユニットをドラッグして、ユニットが消えるのを防ぎます",
             Vietnamese => "Kéo các đơn vị đi để ngăn chúng biến mất",
             Bork => "Drag borks away to stop them from borking",
-        }
+        })
+    }
+
+    fn alert_afk_warning(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "You will be kicked for inactivity",
+            Spanish => "Serás expulsado por inactividad",
+            French => "Vous serez expulsé pour inactivité",
+            German => "Du wirst wegen Inaktivität gekickt",
+            Italian => "Verrai espulso per inattività",
+            Russian => "Вы будете исключены за бездействие",
+            Arabic => "سيتم طردك بسبب عدم النشاط",
+            Hindi => "निष्क्रियता के कारण आपको निकाल दिया जाएगा",
+            SimplifiedChinese => "您将因不活跃而被踢出",
+            Japanese => "操作がないため、まもなくキックされます",
+            Vietnamese => "Bạn sẽ bị đuổi vì không hoạt động",
+            Bork => "You will be borked for inactivity",
+        })
+    }
+
+    fn alert_afk_hint(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Deploy units or upgrade towers to stay in the game",
+            Spanish => "Despliega unidades o mejora torres para seguir en el juego",
+            French => "Déployez des unités ou améliorez des tours pour rester dans la partie",
+            German => "Entsende Einheiten oder verbessere Türme, um im Spiel zu bleiben",
+            Italian => "Schiera unità o migliora torri per restare in gioco",
+            Russian => "Отправляйте юнитов или улучшайте башни, чтобы остаться в игре",
+            Arabic => "انشر الوحدات أو قم بترقية الأبراج للبقاء في اللعبة",
+            Hindi => "खेल में बने रहने के लिए इकाइयाँ भेजें या टावर अपग्रेड करें",
+            SimplifiedChinese => "部署单位或升级塔楼以留在游戏中",
+            Japanese => "ゲームに残るには、ユニットを展開するかタワーをアップグレードしてください",
+            Vietnamese => "Triển khai đơn vị hoặc nâng cấp tháp để ở lại trò chơi",
+            Bork => "Bork something to stay in the game",
+        })
+    }
+
+    fn alert_alliance_limit_warning(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Alliance limit reached",
+            Spanish => "Límite de alianzas alcanzado",
+            French => "Limite d'alliances atteinte",
+            German => "Bündnislimit erreicht",
+            Italian => "Limite di alleanze raggiunto",
+            Russian => "Достигнут лимит союзов",
+            Arabic => "تم الوصول إلى حد التحالفات",
+            Hindi => "गठबंधन की सीमा पूरी हो गई",
+            SimplifiedChinese => "已达到联盟上限",
+            Japanese => "同盟の上限に達しました",
+            Vietnamese => "Đã đạt giới hạn liên minh",
+            Bork => "Too many borks",
+        })
+    }
+
+    fn alert_alliance_limit_hint(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Break an alliance to make room for a new one",
+            Spanish => "Rompe una alianza para dejar sitio a una nueva",
+            French => "Rompez une alliance pour en former une nouvelle",
+            German => "Löse ein Bündnis, um Platz für ein neues zu schaffen",
+            Italian => "Rompi un'alleanza per fare spazio a una nuova",
+            Russian => "Разорвите союз, чтобы заключить новый",
+            Arabic => "افسخ تحالفًا لإفساح المجال لتحالف جديد",
+            Hindi => "नए गठबंधन के लिए किसी गठबंधन को तोड़ें",
+            SimplifiedChinese => "解除一个联盟以腾出空间",
+            Japanese => "新しい同盟を結ぶには、既存の同盟を解消してください",
+            Vietnamese => "Phá vỡ một liên minh để tạo chỗ cho liên minh mới",
+            Bork => "Unbork someone first",
+        })
+    }
+
+    fn afk_kicked_message(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Kicked for inactivity",
+            Spanish => "Expulsado por inactividad",
+            French => "Expulsé pour inactivité",
+            German => "Wegen Inaktivität gekickt",
+            Italian => "Espulso per inattività",
+            Russian => "Исключен за бездействие",
+            Arabic => "تم الطرد بسبب عدم النشاط",
+            Hindi => "निष्क्रियता के कारण निकाला गया",
+            SimplifiedChinese => "因不活跃被踢出",
+            Japanese => "操作がないためキックされました",
+            Vietnamese => "Bị đuổi vì không hoạt động",
+            Bork => "Borked for inactivity",
+        })
+    }
+
+    fn retreat_all_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Retreat all",
+            Spanish => "Retirar todo",
+            French => "Tout replier",
+            German => "Alle zurückziehen",
+            Italian => "Ritirata generale",
+            Russian => "Отступить всем",
+            Arabic => "انسحاب الجميع",
+            Hindi => "सभी पीछे हटें",
+            SimplifiedChinese => "全部撤退",
+            Japanese => "全軍撤退",
+            Vietnamese => "Rút lui tất cả",
+            Bork => "Bork away all",
+        })
+    }
+
+    fn spawn_anywhere_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Anywhere",
+            Spanish => "Cualquier lugar",
+            French => "N'importe où",
+            German => "Überall",
+            Italian => "Ovunque",
+            Russian => "Где угодно",
+            Arabic => "في أي مكان",
+            Hindi => "कहीं भी",
+            SimplifiedChinese => "任何地方",
+            Japanese => "どこでも",
+            Vietnamese => "Bất cứ đâu",
+            Bork => "Anybork",
+        })
+    }
+
+    fn spawn_contested_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Contested",
+            Spanish => "Disputado",
+            French => "Disputé",
+            German => "Umkämpft",
+            Italian => "Conteso",
+            Russian => "Спорная зона",
+            Arabic => "متنازع عليه",
+            Hindi => "विवादित",
+            SimplifiedChinese => "争夺区",
+            Japanese => "激戦区",
+            Vietnamese => "Tranh chấp",
+            Bork => "Borkfest",
+        })
+    }
+
+    fn spawn_quiet_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Quiet edge",
+            Spanish => "Borde tranquilo",
+            French => "Bordure calme",
+            German => "Ruhiger Rand",
+            Italian => "Bordo tranquillo",
+            Russian => "Тихая окраина",
+            Arabic => "حافة هادئة",
+            Hindi => "शांत किनारा",
+            SimplifiedChinese => "安静边缘",
+            Japanese => "静かな外縁",
+            Vietnamese => "Rìa yên tĩnh",
+            Bork => "Quiet bork",
+        })
+    }
+
+    fn spawn_queue_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Waiting for room",
+            Spanish => "Esperando espacio",
+            French => "En attente de place",
+            German => "Warte auf Platz",
+            Italian => "In attesa di spazio",
+            Russian => "Ожидание места",
+            Arabic => "في انتظار مكان",
+            Hindi => "जगह की प्रतीक्षा",
+            SimplifiedChinese => "等待空位",
+            Japanese => "空きを待っています",
+            Vietnamese => "Đang chờ chỗ trống",
+            Bork => "Waiting for bork",
+        })
+    }
+
+    fn peak_towers_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Most towers",
+            Spanish => "Máximo de torres",
+            French => "Tours au maximum",
+            German => "Meiste Türme",
+            Italian => "Massimo di torri",
+            Russian => "Максимум башен",
+            Arabic => "أكبر عدد من الأبراج",
+            Hindi => "सर्वाधिक टावर",
+            SimplifiedChinese => "最多塔数",
+            Japanese => "最大タワー数",
+            Vietnamese => "Nhiều tháp nhất",
+            Bork => "Most borks",
+        })
+    }
+
+    fn longest_held_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Longest held tower",
+            Spanish => "Torre retenida más tiempo",
+            French => "Tour tenue le plus longtemps",
+            German => "Am längsten gehaltener Turm",
+            Italian => "Torre tenuta più a lungo",
+            Russian => "Дольше всего удержана башня",
+            Arabic => "أطول برج محتفظ به",
+            Hindi => "सबसे लंबे समय तक रखा टावर",
+            SimplifiedChinese => "坚守最久的塔",
+            Japanese => "最長保持タワー",
+            Vietnamese => "Tháp giữ lâu nhất",
+            Bork => "Longest bork",
+        })
+    }
+
+    fn rulers_killed_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Rulers killed",
+            Spanish => "Gobernantes eliminados",
+            French => "Dirigeants tués",
+            German => "Getötete Herrscher",
+            Italian => "Sovrani uccisi",
+            Russian => "Убито правителей",
+            Arabic => "الحكام المقتولون",
+            Hindi => "मारे गए शासक",
+            SimplifiedChinese => "击杀统治者",
+            Japanese => "倒した支配者",
+            Vietnamese => "Kẻ thống trị bị tiêu diệt",
+            Bork => "Rulers borked",
+        })
+    }
+
+    fn play_again_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Play again",
+            Spanish => "Jugar de nuevo",
+            French => "Rejouer",
+            German => "Nochmal spielen",
+            Italian => "Gioca ancora",
+            Russian => "Играть снова",
+            Arabic => "العب مجددًا",
+            Hindi => "फिर से खेलें",
+            SimplifiedChinese => "再玩一次",
+            Japanese => "もう一度プレイ",
+            Vietnamese => "Chơi lại",
+            Bork => "Bork again",
+        })
+    }
+
+    fn menu_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Menu",
+            Spanish => "Menú",
+            French => "Menu",
+            German => "Menü",
+            Italian => "Menu",
+            Russian => "Меню",
+            Arabic => "القائمة",
+            Hindi => "मेनू",
+            SimplifiedChinese => "菜单",
+            Japanese => "メニュー",
+            Vietnamese => "Menu",
+            Bork => "Bork menu",
+        })
+    }
+
+    fn shortcuts_title(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Keyboard shortcuts",
+            Spanish => "Atajos de teclado",
+            French => "Raccourcis clavier",
+            German => "Tastenkürzel",
+            Italian => "Scorciatoie da tastiera",
+            Russian => "Горячие клавиши",
+            Arabic => "اختصارات لوحة المفاتيح",
+            Hindi => "कीबोर्ड शॉर्टकट",
+            SimplifiedChinese => "键盘快捷键",
+            Japanese => "キーボードショートカット",
+            Vietnamese => "Phím tắt",
+            Bork => "Bork shortcuts",
+        })
+    }
+
+    fn pan_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Move the camera",
+            Spanish => "Mover la cámara",
+            French => "Déplacer la caméra",
+            German => "Kamera bewegen",
+            Italian => "Sposta la visuale",
+            Russian => "Двигать камеру",
+            Arabic => "تحريك الكاميرا",
+            Hindi => "कैमरा घुमाएँ",
+            SimplifiedChinese => "移动视角",
+            Japanese => "カメラを移動",
+            Vietnamese => "Di chuyển camera",
+            Bork => "Bork around",
+        })
+    }
+
+    fn zoom_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Zoom out and in",
+            Spanish => "Alejar y acercar",
+            French => "Dézoomer et zoomer",
+            German => "Heraus- und hineinzoomen",
+            Italian => "Allontana e avvicina",
+            Russian => "Отдалить и приблизить",
+            Arabic => "تصغير وتكبير",
+            Hindi => "ज़ूम आउट और इन",
+            SimplifiedChinese => "缩小和放大",
+            Japanese => "ズームアウト・イン",
+            Vietnamese => "Thu nhỏ và phóng to",
+            Bork => "Bork zoom",
+        })
+    }
+
+    fn pan_to_ruler_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Go to king",
+            Spanish => "Ir al gobernante",
+            French => "Aller au souverain",
+            German => "Zum Herrscher",
+            Italian => "Vai al capo",
+            Russian => "К царю",
+            Arabic => "الانتقال إلى الملك",
+            Hindi => "राजा पर जाएँ",
+            SimplifiedChinese => "前往皇帝",
+            Japanese => "天皇へ移動",
+            Vietnamese => "Đến chỗ chủ tịch",
+            Bork => "Bork to BORK",
+        })
+    }
+
+    fn fit_territory_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Show all your territory",
+            Spanish => "Ver todo tu territorio",
+            French => "Voir tout votre territoire",
+            German => "Ganzes Gebiet zeigen",
+            Italian => "Mostra tutto il territorio",
+            Russian => "Показать всю территорию",
+            Arabic => "عرض كل أراضيك",
+            Hindi => "अपना पूरा क्षेत्र दिखाएँ",
+            SimplifiedChinese => "显示全部领土",
+            Japanese => "全領土を表示",
+            Vietnamese => "Xem toàn bộ lãnh thổ",
+            Bork => "Bork all territory",
+        })
+    }
+
+    fn show_supply_lines_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Show supply lines (hold)",
+            Spanish => "Mostrar líneas de suministro (mantener)",
+            French => "Afficher les lignes de ravitaillement (maintenir)",
+            German => "Versorgungslinien zeigen (halten)",
+            Italian => "Mostra le linee di rifornimento (tieni premuto)",
+            Russian => "Показать линии снабжения (удерживать)",
+            Arabic => "إظهار خطوط الإمداد (اضغط مطولًا)",
+            Hindi => "आपूर्ति लाइनें दिखाएँ (दबाए रखें)",
+            SimplifiedChinese => "显示补给线（按住）",
+            Japanese => "補給線を表示（長押し）",
+            Vietnamese => "Hiện đường tiếp tế (giữ)",
+            Bork => "Bork supply lines (hold)",
+        })
+    }
+
+    fn toggle_supply_lines_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Always show supply lines",
+            Spanish => "Mostrar siempre líneas de suministro",
+            French => "Toujours afficher les lignes de ravitaillement",
+            German => "Versorgungslinien immer zeigen",
+            Italian => "Mostra sempre le linee di rifornimento",
+            Russian => "Всегда показывать линии снабжения",
+            Arabic => "إظهار خطوط الإمداد دائمًا",
+            Hindi => "आपूर्ति लाइनें हमेशा दिखाएँ",
+            SimplifiedChinese => "始终显示补给线",
+            Japanese => "補給線を常に表示",
+            Vietnamese => "Luôn hiện đường tiếp tế",
+            Bork => "Bork supply lines always",
+        })
+    }
+
+    fn clear_supply_line_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Clear supply line",
+            Spanish => "Quitar línea de suministro",
+            French => "Supprimer la ligne de ravitaillement",
+            German => "Versorgungslinie entfernen",
+            Italian => "Rimuovi linea di rifornimento",
+            Russian => "Убрать линию снабжения",
+            Arabic => "إزالة خط الإمداد",
+            Hindi => "आपूर्ति लाइन हटाएँ",
+            SimplifiedChinese => "清除补给线",
+            Japanese => "補給線を解除",
+            Vietnamese => "Xóa đường tiếp tế",
+            Bork => "Unbork supply line",
+        })
+    }
+
+    fn undo_clear_supply_lines_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Restore cleared supply lines",
+            Spanish => "Restaurar líneas de suministro quitadas",
+            French => "Restaurer les lignes de ravitaillement supprimées",
+            German => "Entfernte Versorgungslinien wiederherstellen",
+            Italian => "Ripristina le linee di rifornimento rimosse",
+            Russian => "Вернуть убранные линии снабжения",
+            Arabic => "استعادة خطوط الإمداد المزالة",
+            Hindi => "हटाई गई आपूर्ति लाइनें बहाल करें",
+            SimplifiedChinese => "恢复已清除的补给线",
+            Japanese => "解除した補給線を戻す",
+            Vietnamese => "Khôi phục đường tiếp tế đã xóa",
+            Bork => "Rebork supply lines",
+        })
+    }
+
+    fn similar_towers_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Highlight similar towers (hold)",
+            Spanish => "Resaltar torres similares (mantener)",
+            French => "Surligner les tours similaires (maintenir)",
+            German => "Ähnliche Türme hervorheben (halten)",
+            Italian => "Evidenzia torri simili (tieni premuto)",
+            Russian => "Выделить похожие башни (удерживать)",
+            Arabic => "تمييز الأبراج المماثلة (اضغط مطولًا)",
+            Hindi => "समान मीनारें दिखाएँ (दबाए रखें)",
+            SimplifiedChinese => "高亮相同的塔（按住）",
+            Japanese => "同種の塔を強調（長押し）",
+            Vietnamese => "Làm nổi bật tháp tương tự (giữ)",
+            Bork => "Bork similar towers (hold)",
+        })
+    }
+
+    fn focus_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Show where units can go (hold)",
+            Spanish => "Mostrar adónde pueden ir las unidades (mantener)",
+            French => "Montrer où les unités peuvent aller (maintenir)",
+            German => "Erreichbare Ziele zeigen (halten)",
+            Italian => "Mostra dove possono andare le unità (tieni premuto)",
+            Russian => "Показать, куда могут идти войска (удерживать)",
+            Arabic => "إظهار أين يمكن للوحدات الذهاب (اضغط مطولًا)",
+            Hindi => "दिखाएँ कि इकाइयाँ कहाँ जा सकती हैं (दबाए रखें)",
+            SimplifiedChinese => "显示单位可到达之处（按住）",
+            Japanese => "部隊の到達範囲を表示（長押し）",
+            Vietnamese => "Hiện nơi quân có thể đến (giữ)",
+            Bork => "Bork reach (hold)",
+        })
+    }
+
+    fn partial_deploy_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Send some units (hold while dragging)",
+            Spanish => "Enviar algunas unidades (mantener al arrastrar)",
+            French => "Envoyer une partie des unités (maintenir en glissant)",
+            German => "Teil der Einheiten senden (beim Ziehen halten)",
+            Italian => "Invia parte delle unità (tieni premuto trascinando)",
+            Russian => "Отправить часть войск (удерживать при перетаскивании)",
+            Arabic => "إرسال بعض الوحدات (اضغط مطولًا أثناء السحب)",
+            Hindi => "कुछ इकाइयाँ भेजें (खींचते समय दबाए रखें)",
+            SimplifiedChinese => "派出部分单位（拖动时按住）",
+            Japanese => "一部の部隊を送る（ドラッグ中に長押し）",
+            Vietnamese => "Gửi một phần quân (giữ khi kéo)",
+            Bork => "Bork some units (hold while dragging)",
+        })
+    }
+
+    fn repeat_deploy_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Repeat last deployment",
+            Spanish => "Repetir último despliegue",
+            French => "Répéter le dernier déploiement",
+            German => "Letzten Einsatz wiederholen",
+            Italian => "Ripeti l'ultimo invio",
+            Russian => "Повторить последнюю отправку",
+            Arabic => "تكرار آخر نشر",
+            Hindi => "पिछली तैनाती दोहराएँ",
+            SimplifiedChinese => "重复上次部署",
+            Japanese => "前回の派遣を繰り返す",
+            Vietnamese => "Lặp lại lần triển khai trước",
+            Bork => "Bork again",
+        })
+    }
+
+    fn ping_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Ping tower",
+            Spanish => "Señalar torre",
+            French => "Signaler la tour",
+            German => "Turm markieren",
+            Italian => "Segnala torre",
+            Russian => "Отметить башню",
+            Arabic => "الإشارة إلى البرج",
+            Hindi => "मीनार चिह्नित करें",
+            SimplifiedChinese => "标记塔",
+            Japanese => "塔をピン",
+            Vietnamese => "Đánh dấu tháp",
+            Bork => "Bork tower",
+        })
+    }
+
+    fn tower_menu_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Choose tower menu action",
+            Spanish => "Elegir acción del menú de torre",
+            French => "Choisir une action du menu de la tour",
+            German => "Aktion im Turmmenü wählen",
+            Italian => "Scegli un'azione del menu della torre",
+            Russian => "Выбрать действие в меню башни",
+            Arabic => "اختيار إجراء من قائمة البرج",
+            Hindi => "मीनार मेनू से कार्य चुनें",
+            SimplifiedChinese => "选择塔菜单操作",
+            Japanese => "塔メニューの操作を選択",
+            Vietnamese => "Chọn hành động trong menu tháp",
+            Bork => "Bork tower menu",
+        })
+    }
+
+    fn screenshot_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Screenshot",
+            Spanish => "Captura de pantalla",
+            French => "Capture d'écran",
+            German => "Bildschirmfoto",
+            Italian => "Screenshot",
+            Russian => "Снимок экрана",
+            Arabic => "لقطة شاشة",
+            Hindi => "स्क्रीनशॉट",
+            SimplifiedChinese => "截图",
+            Japanese => "スクリーンショット",
+            Vietnamese => "Chụp màn hình",
+            Bork => "Bork picture",
+        })
+    }
+
+    fn copy_debug_state_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Copy debug state",
+            Spanish => "Copiar estado de depuración",
+            French => "Copier l'état de débogage",
+            German => "Debug-Zustand kopieren",
+            Italian => "Copia stato di debug",
+            Russian => "Скопировать отладочное состояние",
+            Arabic => "نسخ حالة التصحيح",
+            Hindi => "डीबग स्थिति कॉपी करें",
+            SimplifiedChinese => "复制调试状态",
+            Japanese => "デバッグ状態をコピー",
+            Vietnamese => "Sao chép trạng thái gỡ lỗi",
+            Bork => "Copy bork state",
+        })
+    }
+
+    fn reveal_all_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Reveal all (hold)",
+            Spanish => "Revelar todo (mantener)",
+            French => "Tout révéler (maintenir)",
+            German => "Alles aufdecken (halten)",
+            Italian => "Rivela tutto (tieni premuto)",
+            Russian => "Показать всё (удерживать)",
+            Arabic => "كشف الكل (اضغط مطولًا)",
+            Hindi => "सब दिखाएँ (दबाए रखें)",
+            SimplifiedChinese => "显示全部（按住）",
+            Japanese => "すべて表示（長押し）",
+            Vietnamese => "Hiện tất cả (giữ)",
+            Bork => "Bork all (hold)",
+        })
+    }
+
+    fn debug_bounds_shortcut_label(self) -> &'static str {
+        fallback(self, |language_id| match language_id {
+            English => "Debug camera bounds (hold)",
+            Spanish => "Depurar límites de cámara (mantener)",
+            French => "Déboguer les limites de la caméra (maintenir)",
+            German => "Kameragrenzen debuggen (halten)",
+            Italian => "Debug limiti della visuale (tieni premuto)",
+            Russian => "Отладка границ камеры (удерживать)",
+            Arabic => "تصحيح حدود الكاميرا (اضغط مطولًا)",
+            Hindi => "कैमरा सीमा डीबग करें (दबाए रखें)",
+            SimplifiedChinese => "调试视角边界（按住）",
+            Japanese => "カメラ境界をデバッグ（長押し）",
+            Vietnamese => "Gỡ lỗi giới hạn camera (giữ)",
+            Bork => "Bork camera bounds (hold)",
+        })
     }
 
     fn ruler_killed(self, alias: Option<PlayerAlias>, unit: &str) -> String {
@@ -1153,11 +2301,15 @@ impl TowerTranslation for LanguageId {
 
 #[cfg(test)]
 mod tests {
-    use crate::translation::TowerTranslation;
+    use crate::shortcut::ShortcutAction;
+    use crate::translation::Plural::*;
+    use crate::translation::{Plural, TowerTranslation};
     use common::death_reason::DeathReason;
+    use common::tower::TowerType;
     use common::unit::Unit;
     use core_protocol::id::LanguageId;
     use core_protocol::name::PlayerAlias;
+    use strum::IntoEnumIterator;
 
     #[test]
     fn test_death_reason() {
@@ -1176,5 +2328,62 @@ mod tests {
         for id in LanguageId::iter() {
             println!("{}", id.death_reason(reason))
         }
+
+        for id in LanguageId::iter() {
+            println!("{}", id.death_reason(DeathReason::Afk))
+        }
+    }
+
+    #[test]
+    fn test_fallback() {
+        for id in LanguageId::iter() {
+            for tower_type in TowerType::iter() {
+                assert!(
+                    !id.tower_type_label(tower_type).is_empty(),
+                    "{id:?} {tower_type:?}"
+                );
+            }
+            for unit in Unit::iter() {
+                assert!(!id.unit_label(unit).is_empty(), "{id:?} {unit:?}");
+            }
+            assert!(!id.demolish_hint().is_empty(), "{id:?}");
+            for action in ShortcutAction::iter() {
+                assert!(!id.shortcut_label(action).is_empty(), "{id:?} {action:?}");
+            }
+        }
+        assert_eq!(
+            LanguageId::SimplifiedChinese.unit_label(Unit::Fighter),
+            LanguageId::English.unit_label(Unit::Fighter)
+        );
+    }
+
+    #[test]
+    fn test_plural() {
+        for id in LanguageId::iter() {
+            for n in [0, 1, 2, 5, 21] {
+                for message in [id.alert_full_warning(n), id.alert_overflowing_warning(n)] {
+                    assert!(!message.is_empty(), "{id:?} {n}");
+                    if n >= 3 {
+                        assert!(message.contains(&n.to_string()), "{id:?} {message}");
+                    }
+                }
+            }
+        }
+
+        use LanguageId::*;
+        let plurals = |id| [0, 1, 2, 5, 11, 21, 22].map(|n| Plural::of(id, n));
+        assert_eq!(
+            plurals(English),
+            [Other, One, Other, Other, Other, Other, Other]
+        );
+        assert_eq!(
+            plurals(French),
+            [One, One, Other, Other, Other, Other, Other]
+        );
+        assert_eq!(plurals(Russian), [Many, One, Few, Many, Many, One, Few]);
+        assert_eq!(plurals(Arabic), [Zero, One, Two, Few, Many, Many, Many]);
+        assert_eq!(plurals(Japanese), [Other; 7]);
+        assert_eq!(English.alert_full_warning(2), "2 towers are full");
+        assert_eq!(Russian.alert_full_warning(5), "5 башен полны");
     }
 }
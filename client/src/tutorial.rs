@@ -7,14 +7,24 @@ use crate::TowerGame;
 use client_util::context::Context;
 use common::alerts::AlertFlag;
 use common::force::Path;
+use common::protocol::Command;
 use common::tower::{Tower, TowerId, TowerType};
 use core_protocol::id::PlayerId;
 use deployment::{best_deployment, still_is_deployment};
+use flagset::FlagSet;
 use glam::{Vec2, Vec3};
 use std::f32::consts::PI;
 use upgrade::{best_upgrade, still_is_upgrade};
 
-pub enum Tutorial {
+#[derive(Default)]
+pub struct Tutorial {
+    step: Step,
+    /// Progress since [`Tutorial::replay`], used instead of the server's [`AlertFlag`]s which
+    /// would otherwise skip the tutorial.
+    replay_flags: Option<FlagSet<AlertFlag>>,
+}
+
+enum Step {
     /// Waiting for path to suggest.
     WaitingToDeploy,
     Deploying {
@@ -29,7 +39,7 @@ pub enum Tutorial {
     Done,
 }
 
-impl Default for Tutorial {
+impl Default for Step {
     fn default() -> Self {
         Self::WaitingToDeploy
     }
@@ -45,77 +55,103 @@ pub enum TutorialAlert {
 
 impl Tutorial {
     pub fn alert(&self) -> Option<TutorialAlert> {
-        Some(match self {
-            Self::Deploying { path, .. } => TutorialAlert::Capture(path.iter().last().unwrap()),
-            Self::Upgrading { tower_id, .. } => TutorialAlert::Upgrade(*tower_id),
-            Self::Done => return None,
+        Some(match &self.step {
+            Step::Deploying { path, .. } => TutorialAlert::Capture(path.iter().last().unwrap()),
+            Step::Upgrading { tower_id, .. } => TutorialAlert::Upgrade(*tower_id),
+            Step::Done => return None,
             _ => TutorialAlert::Pending,
         })
     }
 
+    /// Shows the tutorial again, even if it was already completed or dismissed.
+    pub fn replay(&mut self) {
+        *self = Self {
+            step: Step::default(),
+            replay_flags: Some(FlagSet::default()),
+        };
+    }
+
+    /// Tracks progress while replaying, since the server's [`AlertFlag`]s are already set.
+    pub fn observe_command(&mut self, command: &Command) {
+        if let Some(flags) = &mut self.replay_flags {
+            match command {
+                Command::DeployForce { .. } => *flags |= AlertFlag::DeployedAnyForce,
+                Command::Upgrade { .. } => *flags |= AlertFlag::UpgradedAnyTower,
+                _ => {}
+            }
+        }
+    }
+
     pub fn dismiss_capture(&mut self) {
-        if matches!(self, Self::WaitingToDeploy { .. } | Self::Deploying { .. }) {
-            *self = Self::WaitingToUpgrade
+        if matches!(
+            self.step,
+            Step::WaitingToDeploy { .. } | Step::Deploying { .. }
+        ) {
+            self.step = Step::WaitingToUpgrade
         }
     }
 
     pub fn dismiss_upgrade(&mut self) {
-        *self = Self::Done;
+        self.step = Step::Done;
     }
 
     /// Only checks context's game state for changes.
     pub fn update(&mut self, context: &Context<TowerGame>) {
         if context.state.game.alive {
-            if context.state.game.bounding_rectangle.area() > 18u32.pow(2) {
+            if self.replay_flags.is_none()
+                && context.state.game.bounding_rectangle.area() > 18u32.pow(2)
+            {
                 // Don't gank the performance by pathfinding too much.
-                *self = Self::Done;
+                self.step = Step::Done;
             }
 
-            let flags = context.state.game.alerts.flags();
-            match self {
-                &mut Self::WaitingToDeploy => {
+            let flags = self
+                .replay_flags
+                .unwrap_or_else(|| context.state.game.alerts.flags());
+            match &mut self.step {
+                Step::WaitingToDeploy => {
                     if flags.contains(AlertFlag::DeployedAnyForce | AlertFlag::UpgradedAnyTower) {
                         // Probably refreshed page into an existing base, skip the tutorial.
-                        *self = Self::Done;
+                        self.step = Step::Done;
                     } else if let Some(tower_id) = best_upgrade(context)
                         .filter(|_| flags.contains(AlertFlag::DeployedAnyForce))
                     {
-                        *self = Self::Upgrading {
+                        self.step = Step::Upgrading {
                             tower_id,
                             start: context.client.time_seconds,
                         };
                     } else if let Some(path) = best_deployment(context) {
-                        *self = Self::Deploying {
+                        self.step = Step::Deploying {
                             path,
                             start: context.client.time_seconds,
                         }
                     }
                 }
-                Self::Deploying { path, .. } => {
+                Step::Deploying { path, .. } => {
                     if !still_is_deployment(context, path) {
-                        *self = Self::WaitingToDeploy;
+                        self.step = Step::WaitingToDeploy;
                         self.update(context); // Update right away.
                     }
                 }
-                Self::WaitingToUpgrade => {
+                Step::WaitingToUpgrade => {
                     if flags.contains(AlertFlag::UpgradedAnyTower) {
-                        *self = Self::Done;
+                        self.step = Step::Done;
                     } else if let Some(tower_id) = best_upgrade(context) {
-                        *self = Self::Upgrading {
+                        self.step = Step::Upgrading {
                             tower_id,
                             start: context.client.time_seconds,
                         };
                     }
                 }
-                Self::Upgrading { tower_id, .. } => {
+                Step::Upgrading { tower_id, .. } => {
                     if flags.contains(AlertFlag::UpgradedAnyTower) {
-                        *self = Self::Done;
+                        self.step = Step::Done;
                     } else if !still_is_upgrade(context, *tower_id) {
-                        *self = Self::WaitingToUpgrade;
+                        self.step = Step::WaitingToUpgrade;
                         self.update(context); // Update right away.
                     }
                 }
-                Self::Done => {}
+                Step::Done => {}
             }
         } else {
             *self = Self::default();
@@ -169,8 +205,8 @@ impl Tutorial {
             }
         };
 
-        match self {
-            Self::Deploying { path, start, .. } => {
+        match &self.step {
+            Step::Deploying { path, start, .. } => {
                 const FADE: f32 = 1.0;
                 const MOVE: f32 = 2.5;
                 const PERIOD: f32 = FADE * 2.0 + MOVE;
@@ -198,7 +234,7 @@ impl Tutorial {
                     draw_cursor(path.destination().as_vec2(), t, true);
                 }
             }
-            Self::Upgrading { tower_id, start } if selected_tower_id != Some(*tower_id) => {
+            Step::Upgrading { tower_id, start } if selected_tower_id != Some(*tower_id) => {
                 let t = ((time - start) * PI * 0.45).sin().abs();
                 draw_cursor(tower_id.as_vec2(), t, false);
             }
@@ -5,27 +5,45 @@ mod about_dialog;
 mod alert_overlay;
 mod button;
 mod changelog_dialog;
+mod connection_indicator;
+mod death_screen;
+mod desync_overlay;
+mod emote_overlay;
 mod help_dialog;
 mod lock_dialog;
+mod shortcuts_dialog;
+mod spawn_preference_picker;
 mod tower_icon;
 mod tower_overlay;
 mod towers_dialog;
 mod unit_icon;
 mod units_dialog;
 
+use crate::clock::GameClock;
 use crate::color::Color;
 use crate::path::{PathId, SvgCache};
 use crate::settings::Unlocks;
+use crate::shortcut::Shortcut;
+use crate::stats::DeathStats;
 use crate::translation::TowerTranslation;
 use crate::tutorial::TutorialAlert;
 use crate::ui::about_dialog::AboutDialog;
 use crate::ui::alert_overlay::AlertOverlay;
+use crate::ui::button::Button;
 use crate::ui::changelog_dialog::ChangelogDialog;
+use crate::ui::connection_indicator::ConnectionIndicator;
+use crate::ui::death_screen::DeathScreen;
+use crate::ui::desync_overlay::DesyncOverlay;
+use crate::ui::emote_overlay::EmoteOverlay;
 use crate::ui::help_dialog::HelpDialog;
+use crate::ui::shortcuts_dialog::ShortcutsDialog;
+use crate::ui::spawn_preference_picker::SpawnPreferencePicker;
 use crate::ui::towers_dialog::TowersDialog;
 use crate::TowerGame;
 use common::alerts::Alerts;
 use common::death_reason::DeathReason;
+use common::emote::EmoteId;
+use common::singleton::WorldEvent;
 use common::tower::{Tower, TowerArray, TowerId, TowerType};
 use common::unit::Unit;
 use core_protocol::name::PlayerAlias;
@@ -43,6 +61,7 @@ use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 use yew_frontend::component::account_menu::AccountMenu;
 use yew_frontend::component::discord_icon::DiscordIcon;
+use yew_frontend::component::invitation_icon::InvitationIcon;
 use yew_frontend::component::invitation_link::InvitationLink;
 use yew_frontend::component::language_menu::LanguageMenu;
 use yew_frontend::component::positioner::{Align, Flex, Position, Positioner};
@@ -60,16 +79,39 @@ use yew_frontend::overlay::spawn::SpawnOverlay;
 use yew_frontend::translation::{use_translation, Translation};
 use yew_router::prelude::*;
 
+pub use connection_indicator::NetworkStats;
+
 #[derive(Copy, Clone)]
 pub enum TowerUiEvent {
     Alliance {
         with: PlayerId,
         break_alliance: bool,
     },
+    /// Index into [`TowerSettings::blueprints`][`crate::settings::TowerSettings::blueprints`].
+    ApplyBlueprint {
+        tower_id: TowerId,
+        index: usize,
+    },
     DismissCaptureTutorial,
+    /// Returns from the death screen to the spawn overlay.
+    DismissDeathScreen,
     DismissUpgradeTutorial,
+    Emote(EmoteId),
+    GiftTower {
+        tower_id: TowerId,
+        to: PlayerId,
+    },
     PanTo(TowerId),
+    /// Spawns again with the same alias.
+    PlayAgain,
+    ReplayTutorial,
+    RetreatAll,
+    /// Shows or hides the keyboard shortcuts.
+    Shortcuts(bool),
     Spawn(PlayerAlias),
+    /// Reverses the direction of a tower's supply line (see
+    /// [`Tower::supply_pull`][common::tower::Tower::supply_pull]).
+    ToggleSupplyPull(TowerId),
     Upgrade {
         tower_id: TowerId,
         tower_type: TowerType,
@@ -82,12 +124,34 @@ pub enum TowerUiEvent {
 pub struct TowerUiProps {
     pub alive: bool,
     pub death_reason: Option<DeathReason>,
+    /// Statistics of the last life, if the death screen should be shown.
+    pub death_stats: Option<DeathStats>,
     pub selected_tower: Option<SelectedTower>,
     pub tower_counts: TowerArray<u8>,
     pub alerts: Alerts,
+    /// A tower that will soon be full.
+    pub filling: Option<TowerId>,
+    /// Where the ruler recently auto-retreated to.
+    pub auto_retreat: Option<TowerId>,
     pub tutorial_alert: Option<TutorialAlert>,
     pub unlocks: Unlocks,
     pub lock_dialog: Option<TowerType>,
+    /// Whether to show emote buttons.
+    pub emotes: bool,
+    /// Connection statistics, if they should be shown.
+    pub network: Option<NetworkStats>,
+    /// Elapsed game time, if it should be shown.
+    pub clock: Option<GameClock>,
+    /// The current world event and how many seconds it has left.
+    pub world_event: Option<(WorldEvent, u16)>,
+    /// Whether the arena is paused.
+    pub paused: bool,
+    /// Checksum diff of a desync (debug/cheats only).
+    pub desync: Option<AttrValue>,
+    /// Keyboard shortcuts, if they should be shown.
+    pub shortcuts: Option<Vec<Shortcut>>,
+    /// Position in line for room in the preferred spawn region, if waiting.
+    pub spawn_queue: Option<u16>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -102,12 +166,19 @@ pub struct SelectedTower {
     pub tower_id: TowerId,
     /// If we are trying to ally with them or already allied with them.
     pub outgoing_alliance: bool,
+    /// Mutual allies the tower could be gifted to, if it is ours.
+    pub allies: Vec<PlayerId>,
+    /// Which action is selected by keyboard navigation, if any.
+    pub menu_cursor: Option<usize>,
+    /// Whether clicking break alliance again will break it.
+    pub confirm_break_alliance: bool,
 }
 
 #[styled_component(TowerUi)]
 pub fn tower_ui(props: &PropertiesWrapper<TowerUiProps>) -> Html {
     let ui_event_callback = use_ui_event_callback::<TowerGame>();
     let on_play = ui_event_callback.reform(TowerUiEvent::Spawn);
+    let on_retreat_all = ui_event_callback.reform(|_| TowerUiEvent::RetreatAll);
 
     let header_css = css!(
         r#"
@@ -142,6 +213,28 @@ pub fn tower_ui(props: &PropertiesWrapper<TowerUiProps>) -> Html {
     "#
     );
 
+    let world_event_css = css!(
+        r#"
+        background-color: #00000080;
+        border-radius: 0.5rem;
+        color: #ffd700;
+        font-weight: bold;
+        margin: 0;
+        padding: 0.25rem 0.75rem;
+        "#
+    );
+
+    let clock_css = css!(
+        r#"
+        color: white;
+        font-family: monospace;
+        font-size: 0.9rem;
+        margin: 0;
+        text-align: center;
+        user-select: none;
+        "#
+    );
+
     const HINTS: &[(&str, &[&str])] = &[
         ("Drag units from towers to expand your territory. Click towers to open the upgrade menu.", &["how", "play"]),
         ("Each Mine produces 1 point every second.", &["how", "earn"]),
@@ -165,30 +258,73 @@ pub fn tower_ui(props: &PropertiesWrapper<TowerUiProps>) -> Html {
                     <ZoomIcon amount={4}/>
                     <VolumeIcon/>
                     <LanguageMenu/>
+                    if multi_server {
+                        <InvitationIcon/>
+                    }
                 </Positioner>
                 <LeaderboardOverlay position={Position::TopRight{margin: MARGIN}} style="max-width: 25%;"/>
-                if let Some(SelectedTower{client_position, color, tower, tower_id, outgoing_alliance}) = props.selected_tower.clone() {
+                if let Some(SelectedTower{client_position, color, tower, tower_id, outgoing_alliance, allies, menu_cursor, confirm_break_alliance}) = props.selected_tower.clone() {
                     <TowerOverlay
                         {client_position}
                         {color}
                         {tower}
                         {tower_id}
                         {outgoing_alliance}
+                        {allies}
+                        {menu_cursor}
+                        {confirm_break_alliance}
                         tower_counts={props.tower_counts}
                         tutorial_alert={props.tutorial_alert}
                         unlocks={props.unlocks.clone()}
                     />
                 }
-                <Positioner position={Position::BottomRight{margin: MARGIN}}>
+                <Positioner position={Position::BottomRight{margin: MARGIN}} flex={Flex::Column}>
+                    <Button onclick={on_retreat_all}>{t.retreat_all_label()}</Button>
                     <RouteLink<TowerRoute> route={TowerRoute::Help}>{t.help_hint()}</RouteLink<TowerRoute>>
                 </Positioner>
+                if props.paused || props.network.is_some() || props.clock.is_some() || props.world_event.is_some() || props.desync.is_some() {
+                    <Positioner position={Position::TopMiddle{margin: MARGIN}} flex={Flex::Column}>
+                        if props.paused {
+                            <p class={world_event_css.clone()}>{t.paused_label()}</p>
+                        }
+                        if let Some((world_event, seconds)) = props.world_event {
+                            <p class={world_event_css}>
+                                {format!("{} ({}:{:02})", t.world_event_label(world_event), seconds / 60, seconds % 60)}
+                            </p>
+                        }
+                        if let Some(stats) = props.network {
+                            <ConnectionIndicator {stats}/>
+                        }
+                        if let Some(clock) = props.clock {
+                            <p class={clock_css}>{clock.to_string()}</p>
+                        }
+                        if let Some(diff) = props.desync.clone() {
+                            <DesyncOverlay {diff}/>
+                        }
+                    </Positioner>
+                }
+                if props.emotes {
+                    <Positioner position={Position::BottomMiddle{margin: MARGIN}}>
+                        <EmoteOverlay/>
+                    </Positioner>
+                }
                 <Positioner position={Position::TopLeft{margin: MARGIN}} align={Align::Left} max_width="25%">
-                    <AlertOverlay alerts={props.alerts} tutorial_alert={props.tutorial_alert}/>
+                    <AlertOverlay
+                        alerts={props.alerts}
+                        filling={props.filling}
+                        auto_retreat={props.auto_retreat}
+                        tutorial_alert={props.tutorial_alert}
+                    />
                 </Positioner>
                 <ChatOverlay position={Position::BottomLeft{margin: MARGIN}} style="max-width: 25%;" hints={HINTS}/>
                 if let Some(tower_type) = props.lock_dialog {
                     <LockDialog keys={props.unlocks.keys} {tower_type}/>
                 }
+                if let Some(shortcuts) = props.shortcuts.clone() {
+                    <ShortcutsDialog {shortcuts}/>
+                }
+            } else if let Some(stats) = props.death_stats {
+                <DeathScreen death_reason={props.death_reason} {stats} spawn_queue={props.spawn_queue}/>
             } else {
                 <SpawnOverlay {on_play}>
                     <p class={header_css}>
@@ -203,6 +339,7 @@ pub fn tower_ui(props: &PropertiesWrapper<TowerUiProps>) -> Html {
                     if let Some(death_reason) = props.death_reason {
                         <p class={death_reason_css}>{t.death_reason(death_reason)}</p>
                     }
+                    <SpawnPreferencePicker spawn_queue={props.spawn_queue}/>
                 </SpawnOverlay>
                 if multi_server {
                     <Positioner position={Position::TopLeft{margin: MARGIN}}>
@@ -14,6 +14,10 @@ use yew_icons::{Icon, IconId};
 #[derive(PartialEq, Properties)]
 pub struct AlertOverlayProps {
     pub alerts: Alerts,
+    /// A tower that will soon be full (see [`Alerts::full`]).
+    pub filling: Option<TowerId>,
+    /// Where the ruler recently auto-retreated to.
+    pub auto_retreat: Option<TowerId>,
     pub tutorial_alert: Option<TutorialAlert>,
 }
 
@@ -48,14 +52,30 @@ pub fn alert_overlay(props: &AlertOverlayProps) -> Html {
 
     let (show_ruler_not_safe, dismiss_ruler_not_safe) = use_dismissible();
     let (show_full, dismiss_full) = use_dismissible();
+    let (show_filling, dismiss_filling) = use_dismissible();
     let (show_overflowing, dismiss_overflowing) = use_dismissible();
     let (show_zombies, dismiss_zombies) = use_dismissible();
+    let (show_alliance_limit, dismiss_alliance_limit) = use_dismissible();
 
     let t = use_translation();
 
     html! {
         <table class={overlay_css}>
-            if props.alerts.flags().contains(AlertFlag::RulerUnderAttack) {
+            if props.alerts.flags().contains(AlertFlag::Afk) {
+                <Alert
+                    instruction={t.alert_afk_warning()}
+                    hint={t.alert_afk_hint()}
+                    icon_id={IconId::FontAwesomeSolidHourglassEnd}
+                />
+            }
+            if let Some(tower_id) = props.auto_retreat {
+                <Alert
+                    instruction={t.alert_auto_retreat_warning()}
+                    hint={t.alert_auto_retreat_hint()}
+                    icon_id={IconId::FontAwesomeSolidPersonWalkingDashedLineArrowRight}
+                    onclick={pan_to_factory(tower_id)}
+                />
+            } else if props.alerts.flags().contains(AlertFlag::RulerUnderAttack) {
                 <Alert
                     instruction={t.alert_ruler_under_attack_warning()}
                     hint={t.alert_ruler_under_attack_hint()}
@@ -91,16 +111,24 @@ pub fn alert_overlay(props: &AlertOverlayProps) -> Html {
             }
             if let Some(tower_id) = props.alerts.full.filter(|_| *show_full) {
                 <Alert
-                    instruction={t.alert_full_warning()}
+                    instruction={t.alert_full_warning(props.alerts.full_count.max(1) as usize)}
                     hint={t.alert_full_hint()}
                     icon_id={IconId::BootstrapExclamationTriangleFill}
                     onclick={pan_to_factory(tower_id)}
                     onclick_dismiss={dismiss_full}
                 />
+            } else if let Some(tower_id) = props.filling.filter(|_| *show_filling) {
+                <Alert
+                    instruction={t.alert_filling_warning()}
+                    hint={t.alert_full_hint()}
+                    icon_id={IconId::BootstrapExclamationTriangleFill}
+                    onclick={pan_to_factory(tower_id)}
+                    onclick_dismiss={dismiss_filling}
+                />
             }
             if let Some(tower_id) = props.alerts.overflowing.filter(|_| *show_overflowing) {
                 <Alert
-                    instruction={t.alert_overflowing_warning()}
+                    instruction={t.alert_overflowing_warning(props.alerts.overflowing_count.max(1) as usize)}
                     hint={t.alert_overflowing_hint()}
                     icon_id={IconId::FontAwesomeSolidCircleInfo}
                     onclick={pan_to_factory(tower_id)}
@@ -116,6 +144,14 @@ pub fn alert_overlay(props: &AlertOverlayProps) -> Html {
                     onclick_dismiss={dismiss_zombies}
                 />
             }
+            if *show_alliance_limit && props.alerts.flags().contains(AlertFlag::AllianceLimitReached) {
+                <Alert
+                    instruction={t.alert_alliance_limit_warning()}
+                    hint={t.alert_alliance_limit_hint()}
+                    icon_id={IconId::FontAwesomeSolidHandshakeSlash}
+                    onclick_dismiss={dismiss_alliance_limit}
+                />
+            }
         </table>
     }
 }
@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use stylist::yew::styled_component;
+use yew::virtual_dom::AttrValue;
+use yew::{html, html_nested, Html, Properties};
+
+/// Network statistics, in milliseconds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NetworkStats {
+    pub rtt: Option<u16>,
+    pub jitter: Option<u16>,
+}
+
+impl NetworkStats {
+    /// Returns how many bars (out of [`Self::MAX_BARS`]) to fill, or [`None`] if unknown.
+    fn bars(&self) -> Option<u8> {
+        let rtt = self.rtt?;
+        // Jitter forces a longer un-jittering delay, so penalize it.
+        let effective = rtt.saturating_add(self.jitter.unwrap_or(0).saturating_mul(2));
+        Some(match effective {
+            0..=150 => 3,
+            151..=300 => 2,
+            _ => 1,
+        })
+    }
+
+    const MAX_BARS: u8 = 3;
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ConnectionIndicatorProps {
+    pub stats: NetworkStats,
+}
+
+#[styled_component(ConnectionIndicator)]
+pub fn connection_indicator(props: &ConnectionIndicatorProps) -> Html {
+    let div_css = css!(
+        r#"
+        display: flex;
+        flex-direction: row;
+        align-items: flex-end;
+        gap: 0.15rem;
+        color: white;
+        font-size: 0.8rem;
+        user-select: none;
+        "#
+    );
+
+    let bar_css = css!(
+        r#"
+        width: 0.3rem;
+        "#
+    );
+
+    let text_css = css!(
+        r#"
+        margin-left: 0.35rem;
+        "#
+    );
+
+    let bars = props.stats.bars();
+    let color = match bars {
+        Some(3) => "#4caf50",
+        Some(2) => "#ffc107",
+        Some(_) => "#f44336",
+        None => "#888888",
+    };
+
+    html! {
+        <div class={div_css}>
+            {(1..=NetworkStats::MAX_BARS).map(|bar| {
+                let background = if bars.map_or(false, |bars| bar <= bars) {
+                    color
+                } else {
+                    "#ffffff40"
+                };
+                html_nested! {
+                    <div
+                        class={bar_css.clone()}
+                        style={AttrValue::Owned(format!("height: {}rem; background: {background};", 0.3 * bar as f32))}
+                    />
+                }
+            }).collect::<Html>()}
+            <span class={text_css}>
+                if let Some(rtt) = props.stats.rtt {
+                    {format!("{rtt}ms")}
+                    if let Some(jitter) = props.stats.jitter {
+                        {format!(" ±{jitter}ms")}
+                    }
+                } else {
+                    {"-"}
+                }
+            </span>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::connection_indicator::NetworkStats;
+
+    #[test]
+    fn bars() {
+        let stats = |rtt, jitter| NetworkStats { rtt, jitter }.bars();
+        assert_eq!(stats(None, Some(5)), None);
+        assert_eq!(stats(Some(40), None), Some(3));
+        assert_eq!(stats(Some(40), Some(60)), Some(2));
+        assert_eq!(stats(Some(200), None), Some(2));
+        assert_eq!(stats(Some(500), Some(5)), Some(1));
+    }
+}
@@ -0,0 +1,139 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game::TowerGame;
+use crate::stats::DeathStats;
+use crate::translation::TowerTranslation;
+use crate::ui::TowerUiEvent;
+use common::death_reason::DeathReason;
+use stylist::yew::styled_component;
+use yew::{html, Html, MouseEvent, Properties};
+use yew_frontend::component::positioner::{Flex, Position, Positioner};
+use yew_frontend::frontend::use_ui_event_callback;
+use yew_frontend::translation::use_translation;
+
+#[derive(PartialEq, Properties)]
+pub struct DeathScreenProps {
+    pub death_reason: Option<DeathReason>,
+    pub stats: DeathStats,
+    /// Position in line for room in the preferred spawn region, if waiting.
+    pub spawn_queue: Option<u16>,
+}
+
+/// Shown instead of the spawn overlay right after dying.
+#[styled_component(DeathScreen)]
+pub fn death_screen(props: &DeathScreenProps) -> Html {
+    let container_css = css!(
+        r#"
+        align-items: center;
+        color: white;
+        display: flex;
+        flex-direction: column;
+        row-gap: 1.5rem;
+        user-select: none;
+        animation: fadein 1s;
+
+        @keyframes fadein {
+            from { opacity: 0; }
+            to   { opacity: 1; }
+        }
+        "#
+    );
+
+    let death_reason_css = css!(
+        r#"
+        font-size: 1.5rem;
+        font-style: italic;
+        margin: 0;
+        text-align: center;
+        "#
+    );
+
+    let stats_css = css!(
+        r#"
+        border-collapse: collapse;
+        font-size: 1.2rem;
+
+        td {
+            padding: 0.25rem 0.75rem;
+        }
+
+        td:last-child {
+            font-weight: bold;
+            text-align: right;
+        }
+        "#
+    );
+
+    let play_again_css = css!(
+        r#"
+        background-color: #549f57;
+        border-radius: 1rem;
+        border: 1px solid #61b365;
+        color: white;
+        cursor: pointer;
+        font-size: 3.25rem;
+        padding: 0.5rem 2rem 0.7rem 2rem;
+        white-space: nowrap;
+
+        :hover {
+            filter: brightness(0.95);
+        }
+
+        :active {
+            filter: brightness(0.9);
+        }
+        "#
+    );
+
+    let menu_css = css!(
+        r#"
+        background: none;
+        border: none;
+        color: #ffffffb0;
+        cursor: pointer;
+        font-size: 1.1rem;
+        text-decoration: underline;
+        "#
+    );
+
+    let t = use_translation();
+    let ui_event_callback = use_ui_event_callback::<TowerGame>();
+    let on_play_again = ui_event_callback.reform(|_: MouseEvent| TowerUiEvent::PlayAgain);
+    let on_menu = ui_event_callback.reform(|_: MouseEvent| TowerUiEvent::DismissDeathScreen);
+
+    let DeathStats {
+        peak_towers,
+        longest_held_secs,
+        rulers_killed,
+    } = props.stats;
+
+    html! {
+        <Positioner position={Position::Center} flex={Flex::Column}>
+            <div class={container_css}>
+                if let Some(death_reason) = props.death_reason {
+                    <p class={death_reason_css}>{t.death_reason(death_reason)}</p>
+                }
+                <table class={stats_css}>
+                    <tr>
+                        <td>{t.peak_towers_label()}</td>
+                        <td>{peak_towers}</td>
+                    </tr>
+                    <tr>
+                        <td>{t.longest_held_label()}</td>
+                        <td>{format!("{}:{:02}", longest_held_secs / 60, longest_held_secs % 60)}</td>
+                    </tr>
+                    <tr>
+                        <td>{t.rulers_killed_label()}</td>
+                        <td>{rulers_killed}</td>
+                    </tr>
+                </table>
+                <button class={play_again_css} onclick={on_play_again}>{t.play_again_label()}</button>
+                if let Some(position) = props.spawn_queue {
+                    <p style="font-style: italic; margin: 0;">{format!("{} (#{position})", t.spawn_queue_label())}</p>
+                }
+                <button class={menu_css} onclick={on_menu}>{t.menu_label()}</button>
+            </div>
+        </Positioner>
+    }
+}
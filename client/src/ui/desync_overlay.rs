@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use stylist::yew::styled_component;
+use yew::virtual_dom::AttrValue;
+use yew::{html, Html, Properties};
+
+#[derive(PartialEq, Properties)]
+pub struct DesyncOverlayProps {
+    /// [`Checksum::diff`][common_util::actor2::Checksum::diff] of the desync.
+    pub diff: AttrValue,
+}
+
+/// Debug-only diagnostics for a client/server desync, selectable for bug reports.
+#[styled_component(DesyncOverlay)]
+pub fn desync_overlay(props: &DesyncOverlayProps) -> Html {
+    let div_css = css!(
+        r#"
+        background-color: #a02020d0;
+        border-radius: 0.5rem;
+        color: white;
+        padding: 0.5rem;
+        user-select: text;
+        "#
+    );
+
+    let pre_css = css!(
+        r#"
+        font-size: 0.8rem;
+        margin: 0.25rem 0 0 0;
+        max-height: 10rem;
+        overflow: auto;
+        white-space: pre-wrap;
+        "#
+    );
+
+    html! {
+        <div class={div_css}>
+            <b>{"Desync detected"}</b>
+            <pre class={pre_css}>{props.diff.clone()}</pre>
+        </div>
+    }
+}
@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::color::Color;
+use crate::path::{PathId, SvgCache};
+use crate::translation::TowerTranslation;
+use crate::ui::button::Button;
+use crate::ui::TowerUiEvent;
+use crate::TowerGame;
+use common::emote::EmoteId;
+use stylist::yew::styled_component;
+use yew::virtual_dom::AttrValue;
+use yew::{html, html_nested, Html, MouseEvent};
+use yew_frontend::frontend::use_ui_event_callback;
+use yew_frontend::translation::use_translation;
+
+#[styled_component(EmoteOverlay)]
+pub fn emote_overlay() -> Html {
+    let div_css = css!(
+        r#"
+        display: flex;
+        flex-direction: row;
+        gap: 0.5rem;
+        "#
+    );
+
+    let img_css = css!(
+        r#"
+        width: 1.75rem;
+        height: 1.75rem;
+        vertical-align: bottom;
+        user-drag: none;
+        -webkit-user-drag: none;
+        "#
+    );
+
+    let t = use_translation();
+    let ui_event_callback = use_ui_event_callback::<TowerGame>();
+
+    html! {
+        <div class={div_css}>
+            {EmoteId::iter().map(|emote_id| {
+                let onclick = ui_event_callback.reform(move |_: MouseEvent| TowerUiEvent::Emote(emote_id));
+                html_nested! {
+                    <Button {onclick} title={t.emote_label(emote_id)}>
+                        <img
+                            alt={"emote"}
+                            src={AttrValue::Static(SvgCache::get(PathId::Emote(emote_id), Color::Blue))}
+                            class={img_css.clone()}
+                        />
+                    </Button>
+                }
+            }).collect::<Html>()}
+        </div>
+    }
+}
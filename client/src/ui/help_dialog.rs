@@ -1,21 +1,32 @@
 use crate::color::Color;
 use crate::path::{PathId, SvgCache};
+use crate::ui::button::Button;
 use crate::ui::tower_icon::TowerIcon;
 use crate::ui::unit_icon::UnitIcon;
-use crate::ui::TowerRoute;
+use crate::ui::{TowerRoute, TowerUiEvent};
+use crate::TowerGame;
 use common::tower::TowerType;
 use common::unit::Unit;
-use yew::{function_component, html, AttrValue, Html};
+use yew::{function_component, html, AttrValue, Callback, Html};
 use yew_frontend::component::route_link::RouteLink;
 use yew_frontend::dialog::dialog::Dialog;
-use yew_frontend::frontend::use_game_id;
+use yew_frontend::frontend::{use_game_id, use_ui_event_callback};
 use yew_frontend::translation::{use_translation, Translation};
+use yew_router::prelude::use_navigator;
 
 #[function_component(HelpDialog)]
 pub fn help_dialog() -> Html {
     let t = use_translation();
     let game_id = use_game_id();
     let game_name = game_id.name();
+    let ui_event_callback = use_ui_event_callback::<TowerGame>();
+    let navigator = use_navigator().unwrap();
+    let on_replay_tutorial = Callback::from(move |_| {
+        ui_event_callback.emit(TowerUiEvent::ReplayTutorial);
+        // Close the dialog so the tutorial is visible.
+        navigator.push(&TowerRoute::Home);
+    });
+
     html! {
         <Dialog title={t.help_title(game_id)}>
             <p>
@@ -26,7 +37,8 @@ pub fn help_dialog() -> Html {
                 {"."}
             </p>
             <h2>{"How to Play"}</h2>
-            <p>{"Drag units to capture towers. To upgrade a tower, click it and then click an available upgrade. Upgrades have their requirements listed next to them."}</p>
+            <p>{"Drag units to capture towers. To upgrade a tower, click it and then click an available upgrade. Upgrades have their requirements listed next to them. Alternatively, once a tower is selected, press 1 through 9 or the up and down arrow keys to choose an option from its menu, and Space or Enter to confirm."}</p>
+            <Button onclick={on_replay_tutorial}>{"Replay tutorial"}</Button>
             <h2>{"How to Win"}</h2>
             <p>
                 {"To earn points, capture more towers and hold them for as long as possible. Protect your "}
@@ -46,7 +58,7 @@ pub fn help_dialog() -> Html {
             {TowerType::iter().filter(TowerType::generates_mobile_units).map(|tower_type| html! {
                  <TowerIcon {tower_type}/>
             }).intersperse_with(|| html!({{" "}})).collect::<Html>()}
-            {" can automatically send units via supply lines. To create a supply line, click a tower to open its menu. Then drag from the tower as normal. If the resulting path has moving arrows, you've succeeded. Hold R to display all your supply lines. To delete a supply line, create the same one again or hold Shift + R."}</p>
+            {" can automatically send units via supply lines. To create a supply line, click a tower to open its menu. Then drag from the tower as normal. If the resulting path has moving arrows, you've succeeded. Hold R to display all your supply lines, or press L to keep displaying them until pressed again. To delete a supply line, create the same one again or hold Shift + R."}</p>
             <h2>{"Alliances"}</h2>
             <p>
                 {"Select an enemy tower and click "}
@@ -55,7 +67,7 @@ pub fn help_dialog() -> Html {
                     style={"width: 1.5rem; height: 1.5rem; vertical-align: bottom;"}
                     alt={"the handshake button"}
                 />
-                {" to request or accept an alliance. Until broken, the alliance will prevent each side from attacking."}</p>
+                {" to request or accept an alliance. Until broken, the alliance will prevent each side from attacking. To point out a tower to your allies, hold Alt and click it, or hover over it and press G."}</p>
             <h2>{"Chat"}</h2>
             <p>{"Use the panel in the bottom left to send messages to other players. Remember to never share personal information in chat!"}</p>
         </Dialog>
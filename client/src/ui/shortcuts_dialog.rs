@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::game::TowerGame;
+use crate::shortcut::Shortcut;
+use crate::translation::TowerTranslation;
+use crate::ui::TowerUiEvent;
+use stylist::yew::styled_component;
+use yew::{html, Html, MouseEvent, Properties};
+use yew_frontend::component::curtain::Curtain;
+use yew_frontend::component::positioner::{Position, Positioner};
+use yew_frontend::frontend::use_ui_event_callback;
+use yew_frontend::translation::use_translation;
+
+#[derive(PartialEq, Properties)]
+pub struct ShortcutsDialogProps {
+    pub shortcuts: Vec<Shortcut>,
+}
+
+/// Lists keyboard shortcuts, toggled with `?`.
+#[styled_component(ShortcutsDialog)]
+pub fn shortcuts_dialog(props: &ShortcutsDialogProps) -> Html {
+    let dialog_css = css!(
+        r#"
+        background-color: #2c3e50;
+        border-radius: 0.5rem;
+        color: white;
+        max-height: 80vh;
+        overflow-y: auto;
+        padding: 1rem;
+        user-select: none;
+        "#
+    );
+
+    let table_css = css!(
+        r#"
+        border-collapse: collapse;
+
+        td {
+            padding: 0.2rem 0.5rem;
+        }
+
+        td:first-child {
+            font-family: monospace;
+            font-weight: bold;
+            text-align: right;
+            white-space: nowrap;
+        }
+        "#
+    );
+
+    let t = use_translation();
+    let ui_event_callback = use_ui_event_callback::<TowerGame>();
+    let on_close = ui_event_callback.reform(|_: MouseEvent| TowerUiEvent::Shortcuts(false));
+
+    html! {
+        <Curtain opacity={127} onclick={on_close}>
+            <Positioner position={Position::Center}>
+                <div class={dialog_css} onclick={|e: MouseEvent| e.stop_propagation()}>
+                    <h2 style="margin: 0 0 0.5rem 0;">{t.shortcuts_title()}</h2>
+                    <table class={table_css}>
+                        {props.shortcuts.iter().map(|Shortcut{keys, action}| html! {
+                            <tr>
+                                <td>{keys.clone()}</td>
+                                <td>{t.shortcut_label(*action)}</td>
+                            </tr>
+                        }).collect::<Html>()}
+                    </table>
+                </div>
+            </Positioner>
+        </Curtain>
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::settings::TowerSettings;
+use crate::translation::TowerTranslation;
+use crate::ui::button::Button;
+use crate::TowerGame;
+use client_util::browser_storage::BrowserStorages;
+use common::protocol::SpawnPreference;
+use stylist::yew::styled_component;
+use yew::{html, html_nested, Html, MouseEvent, Properties};
+use yew_frontend::frontend::use_gctw;
+use yew_frontend::translation::use_translation;
+
+#[derive(PartialEq, Properties)]
+pub struct SpawnPreferencePickerProps {
+    /// Position in line for room in the preferred region, if waiting.
+    pub spawn_queue: Option<u16>,
+}
+
+#[styled_component(SpawnPreferencePicker)]
+pub fn spawn_preference_picker(props: &SpawnPreferencePickerProps) -> Html {
+    let div_css = css!(
+        r#"
+        display: flex;
+        flex-direction: row;
+        justify-content: center;
+        gap: 0.5rem;
+        margin-top: 0.5rem;
+        "#
+    );
+
+    let queue_css = css!(
+        r#"
+        font-style: italic;
+        margin: 0.5rem 0 0 0;
+        text-align: center;
+        "#
+    );
+
+    let gctw = use_gctw::<TowerGame>();
+    let t = use_translation();
+    let selected = gctw.settings_cache.spawn_preference;
+
+    html! {
+        <>
+            <div class={div_css}>
+                {std::iter::once(None).chain(SpawnPreference::iter().map(Some)).map(|preference| {
+                    let onclick = gctw.change_settings_callback.reform(move |_: MouseEvent| {
+                        Box::new(move |settings: &mut TowerSettings, browser_storages: &mut BrowserStorages| {
+                            settings.set_spawn_preference(preference, browser_storages);
+                        })
+                    });
+                    let style = if preference == selected {
+                        "background: #0075ff;"
+                    } else {
+                        "background: #888888;"
+                    };
+                    html_nested! {
+                        <Button {onclick} style={style}>
+                            {t.spawn_preference_label(preference)}
+                        </Button>
+                    }
+                }).collect::<Html>()}
+            </div>
+            if let Some(position) = props.spawn_queue {
+                <p class={queue_css}>{format!("{} (#{position})", t.spawn_queue_label())}</p>
+            }
+        </>
+    }
+}
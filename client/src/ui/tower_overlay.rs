@@ -1,6 +1,7 @@
+use crate::blueprint::Blueprint;
 use crate::color::Color;
 use crate::path::{PathId, SvgCache};
-use crate::settings::Unlocks;
+use crate::settings::{TowerSettings, Unlocks};
 use crate::translation::TowerTranslation;
 use crate::tutorial::TutorialAlert;
 use crate::ui::button::Button;
@@ -8,25 +9,32 @@ use crate::ui::tower_icon::TowerIcon;
 use crate::ui::unit_icon::UnitIcon;
 use crate::ui::TowerUiEvent;
 use crate::TowerGame;
+use client_util::browser_storage::BrowserStorages;
 use common::tower::{Tower, TowerArray, TowerId, TowerType};
+use core_protocol::PlayerId;
 use glam::IVec2;
 use stylist::css;
 use stylist::yew::styled_component;
 use yew::virtual_dom::AttrValue;
 use yew::{classes, html, html_nested, Callback, Html, MouseEvent, Properties};
-use yew_frontend::frontend::{use_core_state, use_rewarded_ad, use_ui_event_callback};
+use yew_frontend::frontend::{use_core_state, use_gctw, use_rewarded_ad, use_ui_event_callback};
 use yew_frontend::translation::{use_translation, Translation};
 
 #[derive(PartialEq, Properties)]
 pub struct TowerOverlayProps {
     pub color: Color,
     pub outgoing_alliance: bool,
+    pub allies: Vec<PlayerId>,
     pub tower_id: TowerId,
     pub tower: Tower,
     pub client_position: IVec2,
     pub tower_counts: TowerArray<u8>,
     pub tutorial_alert: Option<TutorialAlert>,
     pub unlocks: Unlocks,
+    /// Index of the upgrade (or alliance) button selected by keyboard navigation.
+    pub menu_cursor: Option<usize>,
+    /// Whether clicking break alliance again will break it.
+    pub confirm_break_alliance: bool,
 }
 
 #[styled_component(TowerOverlay)]
@@ -114,6 +122,26 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
         }
     };
 
+    let on_apply_blueprint_factory = {
+        let send_ui_event = use_ui_event_callback::<TowerGame>();
+
+        move |index: usize| {
+            send_ui_event
+                .reform(move |_: MouseEvent| TowerUiEvent::ApplyBlueprint { tower_id, index })
+        }
+    };
+
+    let on_toggle_supply_pull = use_ui_event_callback::<TowerGame>()
+        .reform(move |_: MouseEvent| TowerUiEvent::ToggleSupplyPull(tower_id));
+
+    let on_gift_factory = {
+        let send_ui_event = use_ui_event_callback::<TowerGame>();
+
+        move |to: PlayerId| {
+            send_ui_event.reform(move |_: MouseEvent| TowerUiEvent::GiftTower { tower_id, to })
+        }
+    };
+
     let rewarded_ad = use_rewarded_ad();
     let locked = {
         let unlocks = props.unlocks.clone();
@@ -140,6 +168,13 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
         .filter(|_| !is_mine)
         .and_then(|player_id| core_state.player_or_bot(player_id))
         .map(|p| p.alias);
+    let previous_aliases = props
+        .tower
+        .history
+        .iter()
+        .filter_map(|(player_id, _)| core_state.player_or_bot(player_id))
+        .map(|p| p.alias.to_string())
+        .collect::<Vec<_>>();
 
     let t = use_translation();
     fn attr<T: Into<AttrValue>>(s: T) -> Option<AttrValue> {
@@ -148,9 +183,34 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
     let tower_type = props.tower.tower_type;
     let basis = tower_type.basis();
 
+    let gctw = use_gctw::<TowerGame>();
+    let blueprints = &gctw.settings_cache.blueprints;
+    let can_save_blueprint =
+        basis != tower_type && !blueprints.contains_steps(&Blueprint::new("", tower_type).steps);
+    let on_save_blueprint = {
+        let name = t.tower_type_label(tower_type);
+        gctw.change_settings_callback.reform(move |_: MouseEvent| {
+            Box::new(
+                move |settings: &mut TowerSettings, browser_storages: &mut BrowserStorages| {
+                    let blueprints = settings.blueprints.save(Blueprint::new(name, tower_type));
+                    settings.set_blueprints(blueprints, browser_storages);
+                },
+            )
+        })
+    };
+
     // Only render cursor once.
     let mut has_cursor = true;
 
+    let menu_cursor = props.menu_cursor;
+    let cursor_outline = |i: usize| {
+        if menu_cursor == Some(i) {
+            " outline: 0.2rem solid white;"
+        } else {
+            ""
+        }
+    };
+
     html! {
         <Button
             style={format!("left: {}px; bottom: {}px;", props.client_position.x + 10, props.client_position.y + 10)}
@@ -171,7 +231,7 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
                 }
             }).collect::<Html>()}
             if is_mine && props.tower.active() {
-                {props.tower.tower_type.upgrades().chain((basis != tower_type).then_some(basis)).map(|upgrade| {
+                {props.tower.tower_type.upgrades().chain((basis != tower_type).then_some(basis)).enumerate().map(|(i, upgrade)| {
                     let locked = locked(upgrade);
                     let downgrade = basis == upgrade;
                     let upgradable = upgrade.has_prerequisites(&props.tower_counts);
@@ -182,7 +242,7 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
                                 disabled={!upgradable}
                                 onclick={if locked { on_open_lock_dialog_factory(upgrade) } else { on_upgrade_factory(upgrade) }}
                                 title={(if downgrade { Translation::downgrade_to_label } else { Translation::upgrade_to_label })(t, t.tower_type_label(upgrade))}
-                                style={format!("overflow: visible; background-color: {};", color.background_color_css())}
+                                style={format!("overflow: visible; background-color: {};{}", color.background_color_css(), cursor_outline(i))}
                             >
                                 <img
                                     alt={"tower"}
@@ -221,12 +281,58 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
                                             </p>
                                         }
                                     }).collect::<Html>()}
+                                    <p style="margin: 0;">
+                                        {format!("{} {}", t.build_cost_label(), upgrade.build_cost())}
+                                    </p>
                                 </div>
                             }
                         </div>
                     }
                 }).collect::<Html>()}
+                {blueprints.0.iter().enumerate().filter_map(|(index, blueprint)| Some((index, blueprint, blueprint.remaining(tower_type)?))).map(|(index, blueprint, steps)| {
+                    let disabled = steps.iter().any(|&step| locked(step)) || Blueprint::validate(steps, tower_type, &props.tower_counts).is_err();
+                    html_nested!{
+                        <Button
+                            {disabled}
+                            onclick={on_apply_blueprint_factory(index)}
+                            style={format!("background-color: {};", Color::Blue.background_color_css())}
+                        >
+                            {format!("{} {}", t.apply_blueprint_label(), blueprint.name)}
+                        </Button>
+                    }
+                }).collect::<Html>()}
+                if can_save_blueprint {
+                    <Button
+                        onclick={on_save_blueprint}
+                        style={format!("background-color: {};", Color::Gray.background_color_css())}
+                    >
+                        {t.save_blueprint_label()}
+                    </Button>
+                }
+            }
+            if is_mine && props.tower.supply_line.is_some() {
+                <Button
+                    onclick={on_toggle_supply_pull}
+                    style={format!("background-color: {};", Color::Gray.background_color_css())}
+                >
+                    {if props.tower.supply_pull { t.supply_push_label() } else { t.supply_pull_label() }}
+                </Button>
             }
+            if !previous_aliases.is_empty() {
+                <p style="margin: 0;">
+                    {format!("{} {}", t.previously_held_by_label(), previous_aliases.join(", "))}
+                </p>
+            }
+            {props.allies.iter().filter_map(|&ally| core_state.player_or_bot(ally).map(|p| (ally, p.alias))).map(|(ally, alias)| {
+                html_nested! {
+                    <Button
+                        onclick={on_gift_factory(ally)}
+                        style={format!("background-color: {};", Color::Purple.background_color_css())}
+                    >
+                        {format!("{} {}", t.gift_to_label(), alias)}
+                    </Button>
+                }
+            }).collect::<Html>()}
             {enemy_player_alias.map(|enemy_player_alias| {
                 let break_alliance = outgoing_alliance;
                 let (color, path_id, title) = if break_alliance {
@@ -238,6 +344,11 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
                 } else {
                     (Color::Purple, PathId::RequestAlliance, t.request_alliance_hint())
                 };
+                let title = if props.confirm_break_alliance {
+                    t.confirm_break_alliance_hint()
+                } else {
+                    title
+                };
                 let alt = title;
 
                 html_nested! {
@@ -245,11 +356,17 @@ pub fn tower_overlay(props: &TowerOverlayProps) -> Html {
                         <Button
                             onclick={on_alliance_factory(break_alliance)}
                             {title}
-                            style={format!("background-color: {};", color.background_color_css())}
+                            style={format!("background-color: {};{}", color.background_color_css(), cursor_outline(0))}
                         >
                             <img {alt} style={"width: 2.5rem; height: 2.5rem; vertical-align: bottom; user-drag: none; -webkit-user-drag: none;"} src={attr(SvgCache::get(path_id, color))}/>
                         </Button>
-                        <p style="margin: 0;">{enemy_player_alias.to_string()}</p>
+                        <p style="margin: 0;">
+                            {enemy_player_alias.to_string()}
+                            if props.confirm_break_alliance {
+                                <br/>
+                                <b>{t.confirm_break_alliance_hint()}</b>
+                            }
+                        </p>
                     </div>
                 }
             })}
@@ -8,8 +8,14 @@ use common::tower::{TowerArray, TowerType};
 use common::unit::Unit;
 use glam::UVec2;
 use stylist::yew::styled_component;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use yew::virtual_dom::AttrValue;
-use yew::{classes, html, html_nested, Callback, Html, Properties};
+use yew::web_sys::HtmlInputElement;
+use yew::{
+    classes, html, html_nested, use_state, Callback, Html, InputEvent, KeyboardEvent, Properties,
+    TargetCast,
+};
 use yew_frontend::dialog::dialog::Dialog;
 use yew_frontend::translation::{use_translation, Translation};
 use yew_router::prelude::use_navigator;
@@ -50,6 +56,40 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
         "#
     );
 
+    let search_css = css!(
+        r#"
+        width: 100%;
+        box-sizing: border-box;
+        padding: 0.5rem;
+        border-radius: 0.25rem;
+        border: 0;
+        font-size: 1rem;
+        "#
+    );
+
+    let result_css = css!(
+        r#"
+        display: flex;
+        align-items: center;
+        gap: 0.5rem;
+        padding: 0.25rem 0.5rem;
+        cursor: pointer;
+        border-radius: 0.25rem;
+        "#
+    );
+
+    let result_highlighted_css = css!(
+        r#"
+        background-color: #ffffff30;
+        "#
+    );
+
+    let tower_filtered_css = css!(
+        r#"
+        opacity: 0.25;
+        "#
+    );
+
     let diagram_css = css!(
         r#"
         position: absolute;
@@ -107,6 +147,50 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
 
     let t = use_translation();
     let navigator = use_navigator().unwrap();
+
+    let query = use_state(String::new);
+    let highlighted = use_state(|| 0usize);
+    let search = fold(&query);
+    let results: Vec<TowerType> = if search.is_empty() {
+        Vec::new()
+    } else {
+        TowerType::iter()
+            .filter(|&tower| fold(t.tower_type_label(tower)).contains(&search))
+            .collect()
+    };
+    // Results may have shrunk since it was highlighted.
+    let highlighted_index = (*highlighted).min(results.len().saturating_sub(1));
+
+    let oninput = {
+        let query = query.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |event: InputEvent| {
+            query.set(event.target_unchecked_into::<HtmlInputElement>().value());
+            highlighted.set(0);
+        })
+    };
+
+    let onkeydown = {
+        let highlighted = highlighted.clone();
+        let navigator = navigator.clone();
+        let results = results.clone();
+        Callback::from(move |event: KeyboardEvent| match event.key().as_str() {
+            "ArrowDown" => {
+                event.prevent_default();
+                highlighted.set((highlighted_index + 1).min(results.len().saturating_sub(1)));
+            }
+            "ArrowUp" => {
+                event.prevent_default();
+                highlighted.set(highlighted_index.saturating_sub(1));
+            }
+            "Enter" => {
+                if let Some(&tower) = results.get(highlighted_index) {
+                    navigator.push(&TowerRoute::towers_specific(tower));
+                }
+            }
+            _ => {}
+        })
+    };
     let total_depth =
         coord(TowerType::iter().map(|t| t.level()).max().unwrap() as u32 + 1) + TOWER_SCALE - SCALE;
     let total_breadth = coord(do_layout(
@@ -150,6 +234,35 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
 
     html! {
         <Dialog title={props.selected.map(|selected| t.tower_type_label(selected)).unwrap_or("Towers")}>
+            <input
+                type="text"
+                value={(*query).clone()}
+                {oninput}
+                {onkeydown}
+                placeholder={t.tower_search_placeholder()}
+                autocomplete="off"
+                class={search_css}
+            />
+            if !search.is_empty() {
+                if results.is_empty() {
+                    <p>{t.tower_search_no_results()}</p>
+                } else {
+                    <div>
+                        {results.iter().enumerate().map(|(i, &tower)| {
+                            let navigator = navigator.clone();
+                            html_nested! {
+                                <div
+                                    class={classes!(result_css.clone(), (i == highlighted_index).then(|| result_highlighted_css.clone()))}
+                                    onclick={Callback::from(move |_| navigator.push(&TowerRoute::towers_specific(tower)))}
+                                >
+                                    <TowerIcon tower_type={tower}/>
+                                    {t.tower_type_label(tower)}
+                                </div>
+                            }
+                        }).collect::<Html>()}
+                    </div>
+                }
+            }
              if let Some(selected) = props.selected {
                 if let Some(downgrade) = selected.downgrade() {
                     <p>
@@ -167,7 +280,7 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
                                 }
                             }).intersperse_with(|| html!{{", "}}).collect::<Html>()}
                         }
-                        {"."}
+                        {t.upgrade_cost_clause(selected.build_cost())}
                     </p>
                 }
                 <p>
@@ -221,6 +334,7 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
                     let upgrade_css = upgrade_css.clone();
                     let prerequisite_css = prerequisite_css.clone();
                     let selected = Some(tower) == props.selected;
+                    let filtered = !search.is_empty() && !results.contains(&tower);
 
                     html!{
                         <>
@@ -231,7 +345,7 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
                                 height={TOWER_SCALE.to_string()}
                                 href={AttrValue::Static(SvgCache::get(PathId::Tower(tower), if selected { Color::Blue } else { Color::Gray }))}
                                 onclick={Callback::from(move |_| navigator.push(&TowerRoute::towers_specific(tower)))}
-                                class={classes!((!selected).then(|| tower_unselected_css.clone()))}
+                                class={classes!((!selected).then(|| tower_unselected_css.clone()), filtered.then(|| tower_filtered_css.clone()))}
                             >
                             <title>{t.tower_type_label(tower)}</title>
                             </image>
@@ -252,3 +366,24 @@ pub fn towers_dialog(props: &TowersDialogProps) -> Html {
         </Dialog>
     }
 }
+
+/// Lowercases and strips accents, so searches are case and accent insensitive.
+fn fold(s: &str) -> String {
+    s.nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::towers_dialog::fold;
+
+    #[test]
+    fn fold_search() {
+        assert_eq!(fold("Aeródromo"), "aerodromo");
+        assert_eq!(fold("Tòa tháp"), "toa thap");
+        assert_eq!(fold("DORF"), "dorf");
+        assert!(fold("Türme").contains(&fold("TUR")));
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::force::Path;
+use common::tower::TowerId;
+use common::world::World;
+use core_protocol::id::PlayerId;
+use std::collections::VecDeque;
+
+/// A supply line that the player cleared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClearedSupplyLine {
+    pub tower_id: TowerId,
+    pub path: Path,
+    pub pull: bool,
+}
+
+impl ClearedSupplyLine {
+    /// Whether `me` could set the supply line again, by the same rules as the server.
+    pub fn is_viable(&self, world: &World, me: PlayerId) -> bool {
+        let Some(tower) = world.chunk.get(self.tower_id) else {
+            return false;
+        };
+        // Don't replace a supply line that was set since.
+        if tower.player_id != Some(me) || tower.supply_line.is_some() {
+            return false;
+        }
+        let max_edge_distance = if self.pull {
+            let source = world.chunk.get(self.path.destination());
+            if tower.tower_type.ranged_distance().is_some()
+                || !source.is_some_and(|s| {
+                    s.player_id == Some(me) && s.tower_type.ranged_distance().is_none()
+                })
+            {
+                return false;
+            }
+            None
+        } else {
+            if !tower.generates_mobile_units() {
+                return false;
+            }
+            tower.tower_type.ranged_distance()
+        };
+        self.path
+            .clone()
+            .validate(world, self.tower_id, max_edge_distance)
+            .is_ok()
+    }
+}
+
+/// Recently cleared supply lines, so that accidentally clearing them can be undone.
+#[derive(Debug, Default)]
+pub struct SupplyLineUndo {
+    /// Oldest first, with when they were cleared.
+    cleared: VecDeque<(ClearedSupplyLine, f32)>,
+}
+
+impl SupplyLineUndo {
+    /// How long a cleared supply line can be restored.
+    pub const SECONDS: f32 = 5.0;
+    /// Forget the oldest beyond this many.
+    const MAX_CLEARED: usize = 32;
+
+    /// Remembers a supply line `cleared` at `time`, replacing any previous one of its tower.
+    pub fn push(&mut self, cleared: ClearedSupplyLine, time: f32) {
+        self.cleared.retain(|(c, _)| c.tower_id != cleared.tower_id);
+        if self.cleared.len() >= Self::MAX_CLEARED {
+            self.cleared.pop_front();
+        }
+        self.cleared.push_back((cleared, time));
+    }
+
+    /// Forgets all supply lines, returning those cleared within [`Self::SECONDS`] of `time`.
+    pub fn take(&mut self, time: f32) -> impl Iterator<Item = ClearedSupplyLine> + '_ {
+        self.cleared
+            .drain(..)
+            .filter(move |&(_, t)| time - t <= Self::SECONDS)
+            .map(|(cleared, _)| cleared)
+    }
+
+    /// Call when the player's towers change hands wholesale, e.g. when they die.
+    pub fn clear(&mut self) {
+        self.cleared.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::undo::{ClearedSupplyLine, SupplyLineUndo};
+    use common::force::Path;
+    use common::tower::TowerId;
+
+    #[test]
+    fn supply_line_undo() {
+        let cleared = |x: u16| ClearedSupplyLine {
+            tower_id: TowerId::new(x, 10),
+            path: Path::new(vec![TowerId::new(x, 10), TowerId::new(x, 11)]),
+            pull: false,
+        };
+        let mut undo = SupplyLineUndo::default();
+        undo.push(cleared(1), 0.0);
+        undo.push(cleared(2), 3.0);
+        undo.push(cleared(2), 4.0);
+        assert_eq!(undo.take(6.0).collect::<Vec<_>>(), [cleared(2)]);
+        assert_eq!(undo.take(6.0).count(), 0);
+
+        // Bounded.
+        for x in 0..100 {
+            undo.push(cleared(x), 0.0);
+        }
+        assert_eq!(undo.take(0.0).count(), SupplyLineUndo::MAX_CLEARED);
+    }
+}
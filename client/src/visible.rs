@@ -28,21 +28,35 @@ impl Visible {
             .filter_map(|(id, _)| Some(id).zip(towers.get(id)))
     }
 
+    /// Like [`Self::iter`], but only within `rect` (e.g. the viewport).
+    pub fn iter_rect<'a>(
+        &'a self,
+        towers: &'a WorldChunks,
+        rect: TowerRectangle,
+    ) -> impl Iterator<Item = (TowerId, &'a Tower)> {
+        self.refs
+            .iter_rect(rect)
+            .filter_map(|(id, _)| Some(id).zip(towers.get(id)))
+    }
+
     /// Only set each game tick (ie 4 times per second).
     pub fn ticked(&mut self) {
         self.ticked = true;
     }
 
-    pub fn update(&mut self, world: &World, me: PlayerId, all_visible: bool) {
+    /// Sees around towers owned by `me`, and if `ally_vision`, those of mutual allies.
+    pub fn update(&mut self, world: &World, me: PlayerId, ally_vision: bool, all_visible: bool) {
         // Towers can only change every tick.
         if !std::mem::take(&mut self.ticked) {
             return;
         }
 
-        let iter = world
-            .chunk
-            .iter_towers()
-            .filter(|(_, t)| all_visible || t.player_id == Some(me));
+        let iter = world.chunk.iter_towers().filter(move |(_, t)| {
+            all_visible
+                || t.player_id.map_or(false, |player_id| {
+                    player_id == me || (ally_vision && world.have_alliance(me, player_id))
+                })
+        });
 
         let mut min = U16Vec2::splat(WorldChunks::SIZE as u16 - 1);
         let mut max = U16Vec2::ZERO;
@@ -15,6 +15,10 @@ pub struct Alerts {
     pub overflowing: Option<TowerId>,
     /// The most damaging tower forces that are currently full.
     pub full: Option<TowerId>,
+    /// How many towers are [`Self::overflowing`] (saturating).
+    pub overflowing_count: u8,
+    /// How many towers are [`Self::full`] (saturating).
+    pub full_count: u8,
     /// Zombies are attacking this tower.
     pub zombies: Option<TowerId>,
     /// Packed bit flags. TODO don't gamma.
@@ -27,6 +31,8 @@ impl Alerts {
         self.ruler_position = None;
         self.full = None;
         self.overflowing = None;
+        self.full_count = 0;
+        self.overflowing_count = 0;
         self.zombies = None;
         self.set_flags(self.flags() - (AlertFlag::RulerUnderAttack | AlertFlag::RulerNotSafe));
     }
@@ -48,5 +54,10 @@ flags! {
         UpgradedAnyTower,
         SetAnySupplyLine,
         UnsetAnySupplyLine,
+        /// Will be kicked soon unless a command is sent.
+        Afk,
+        /// An alliance was rejected because of the limit on mutual allies. Cleared once under
+        /// the limit.
+        AllianceLimitReached,
     }
 }
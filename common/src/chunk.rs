@@ -168,7 +168,7 @@ impl Chunk {
             } else if tower.player_id.is_some() {
                 for unit in Unit::iter() {
                     if let Some(period) = tower.tower_type.unit_generation(unit) {
-                        if tick.every(period) {
+                        if tick.every(singleton.tower_unit_generation(tower.tower_type, period)) {
                             // Add 2 but subtract up to 1 of the added ones to see if there is room.
                             let a = tower.units.add_to_tower(unit, 2, tower.tower_type, false);
                             tower.units.subtract(unit, a.saturating_sub(1));
@@ -182,11 +182,13 @@ impl Chunk {
             }
 
             if deploy && !tower.units.has_ruler() {
-                if let Some(path) = tower.supply_line.as_ref() {
+                if let Some(path) = tower.push_supply_line() {
                     // Don't send soldiers along nuke supply line.
                     if tower.force_units().max_edge_distance() >= tower.tower_type.ranged_distance()
                     {
-                        for AddressedChunkEvent { dst, event } in tower.deploy_force(path.clone()) {
+                        for AddressedChunkEvent { dst, event } in
+                            tower.deploy_force(path.clone(), Tower::FULL_DEPLOY_PERCENT)
+                        {
                             on_event(dst, event); // TODO make on_event take AddressedChunkEvent.
                         }
                     }
@@ -311,14 +313,41 @@ impl Chunk {
 
             let position = tower_id.as_vec2();
 
-            // Force vs. tower.
-            for mut force in tower.inbound_forces.drain_filter(|f| f.tick(tower_id)) {
+            // Force vs. tower. Remember where arriving forces came from, in case they are repelled.
+            let mut came_from = Vec::new();
+            let arrived: Vec<_> = tower
+                .inbound_forces
+                .drain_filter(|f| {
+                    let from = f.current_source();
+                    let arrived = f.tick(tower_id);
+                    if arrived {
+                        came_from.push(from);
+                    }
+                    arrived
+                })
+                .collect();
+            for (came_from, mut force) in came_from.into_iter().zip(arrived) {
                 let tower_player_id = tower.player_id;
                 if tower_player_id.is_some() || !tower.units.is_empty() {
                     let force_player_id = force.player_id;
                     if relationship(tower_player_id, force_player_id)
                         .is_unfriendly(force.units.has_ruler())
                     {
+                        // Spawn protected rulers repel attackers, without a fight, back where they
+                        // came from.
+                        if tower.units.has_ruler()
+                            && tower_player_id.is_some_and(|p| players(p).spawn_protected)
+                        {
+                            force.bounce(came_from);
+                            let (chunk_id, source_id) = force.current_source().split();
+                            on_event(chunk_id, ChunkEvent::add_outbound_force(source_id, &force));
+                            let (chunk_id, tower_id) = force.current_destination().split();
+                            on_event(chunk_id, ChunkEvent::AddInboundForce { tower_id, force });
+                            continue;
+                        }
+
+                        let attacker_before = force.units.len();
+                        let defender_before = tower.units.len();
                         let mut force_combatants = Combatants::force(&mut force.units);
                         let mut tower_combatants =
                             Combatants::tower(tower.tower_type, &mut tower.units);
@@ -337,6 +366,15 @@ impl Chunk {
                             },
                         );
 
+                        context.on_info(InfoEvent::new(
+                            position,
+                            Info::Combat {
+                                tower_id,
+                                attacker_lost: attacker_before.saturating_sub(force.units.len()),
+                                defender_lost: defender_before.saturating_sub(tower.units.len()),
+                            },
+                        ));
+
                         if tower_emped {
                             let emp_delay = NonZeroU8::new(
                                 Ticks::from_whole_secs(Unit::EMP_SECONDS as TicksRepr)
@@ -350,18 +388,18 @@ impl Chunk {
 
                         if winner != Some(CombatSide::Attacker) {
                             if let Some(force_player_id) = force_player_id {
-                                context.on_info(InfoEvent {
+                                context.on_info(InfoEvent::new(
                                     position,
-                                    info: Info::LostForce(force_player_id),
-                                });
+                                    Info::LostForce(force_player_id),
+                                ));
                             }
                         }
 
                         if winner != Some(CombatSide::Defender) {
                             if let Some(tower_player_id) = tower_player_id {
-                                context.on_info(InfoEvent {
+                                context.on_info(InfoEvent::new(
                                     position,
-                                    info: Info::LostTower {
+                                    Info::LostTower {
                                         tower_id,
                                         player_id: tower_player_id,
                                         reason: if winner == Some(CombatSide::Attacker) {
@@ -370,25 +408,29 @@ impl Chunk {
                                             LostTowerReason::DestroyedBy(force_player_id)
                                         },
                                     },
-                                });
+                                ));
                             }
 
                             let new_player_id = if let Some(force_player_id) =
                                 force_player_id.filter(|_| winner == Some(CombatSide::Attacker))
                             {
-                                context.on_info(InfoEvent {
+                                context.on_info(InfoEvent::new(
                                     position,
-                                    info: Info::GainedTower {
+                                    Info::GainedTower {
                                         tower_id,
                                         player_id: force_player_id,
                                         reason: GainedTowerReason::CapturedFrom(tower_player_id),
                                     },
-                                });
+                                ));
                                 Some(force_player_id)
                             } else {
                                 None
                             };
 
+                            if let Some(tower_player_id) = tower_player_id {
+                                tower.history.record(tower_player_id, singleton.tick);
+                            }
+
                             // Don't crash when zombies are nuked.
                             if tower.player_id.is_some() || new_player_id.is_some() {
                                 Tower::set_player_id_inner(
@@ -414,14 +456,14 @@ impl Chunk {
                     force.player_id.filter(|_| force.units.is_alive())
                 {
                     // Force explored a tower.
-                    context.on_info(InfoEvent {
-                        position: tower_id.as_vec2(),
-                        info: Info::GainedTower {
+                    context.on_info(InfoEvent::new(
+                        tower_id.as_vec2(),
+                        Info::GainedTower {
                             tower_id,
                             player_id: force_player_id,
                             reason: GainedTowerReason::Explored,
                         },
-                    });
+                    ));
 
                     // Cannot borrow so manually inline functions.
                     Tower::set_player_id_inner(
@@ -442,17 +484,17 @@ impl Chunk {
                 } else if force.fuel == 0 {
                     // Expire.
                     if let Some(player_id) = force.player_id {
-                        context.on_info(InfoEvent {
-                            position: tower_id.as_vec2(),
-                            info: Info::LostForce(player_id),
-                        });
+                        context.on_info(InfoEvent::new(
+                            tower_id.as_vec2(),
+                            Info::LostForce(player_id),
+                        ));
                     }
                 } else if matches!(relationship, Relationship::Ally | Relationship::Comrade)
                     && force.try_move_on(
                         tower.tower_type,
                         &mut tower.units,
                         tower.player_id.filter(|_| relationship.is_ally()),
-                        tower.supply_line.as_ref(),
+                        tower.supply_line.as_ref().filter(|_| !tower.supply_pull),
                     )
                 {
                     if force.units.is_many()
@@ -465,10 +507,10 @@ impl Chunk {
                     {
                         // Cramming.
                         if let Some(player_id) = force.player_id {
-                            context.on_info(InfoEvent {
-                                position: tower_id.as_vec2(),
-                                info: Info::LostForce(player_id),
-                            });
+                            context.on_info(InfoEvent::new(
+                                tower_id.as_vec2(),
+                                Info::LostForce(player_id),
+                            ));
                         }
                     } else {
                         let (chunk_id, tower_id) = force.current_source().split();
@@ -487,13 +529,13 @@ impl Chunk {
                     );
                 } else if force.units.available(Unit::Ruler) > 0 {
                     if let Some(player_id) = force.player_id {
-                        context.on_info(InfoEvent {
+                        context.on_info(InfoEvent::new(
                             position,
-                            info: Info::LostRuler {
+                            Info::LostRuler {
                                 player_id,
                                 reason: LostRulerReason::KilledBy(None, Unit::Shield),
                             },
-                        })
+                        ))
                     } else {
                         debug_assert!(false, "force with ruler didn't have player");
                     }
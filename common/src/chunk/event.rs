@@ -4,8 +4,8 @@
 use crate::chunk::{Chunk, ChunkId, RelativeTowerId};
 use crate::force::{Force, Path};
 use crate::info::*;
-use crate::tower::Tower;
 use crate::tower::TowerType;
+use crate::tower::{Tower, TowerId};
 use crate::unit::Unit;
 use crate::units::Units;
 use crate::world::Apply;
@@ -37,11 +37,11 @@ pub trait OnChunkEvent {
 impl Tower {
     // TODO move?
     #[must_use]
-    pub fn deploy_force(&mut self, path: Path) -> [AddressedChunkEvent; 2] {
+    pub fn deploy_force(&mut self, path: Path, percent: u8) -> [AddressedChunkEvent; 2] {
         #[cfg(debug_assertions)]
         let had = self.units.clone();
 
-        let units = self.take_force_units();
+        let units = self.take_force_units(percent);
         let player_id = self.player_id.unwrap();
         if units.is_empty() {
             #[cfg(debug_assertions)]
@@ -86,13 +86,33 @@ pub enum ChunkInput {
     DeployForce {
         tower_id: RelativeTowerId,
         path: Path,
+        /// See [`Tower::force_units_percent`].
+        percent: u8,
     },
     Generate {
         tower_ids: Vec<RelativeTowerId>, // TODO RelativeTowerIdSet
+        /// Use [`TowerId::mirrored_tower_type`] (debug only).
+        mirror: bool,
+        /// Generate [`TowerType::Cliff`]s instead, for `Singleton::obstacles`.
+        obstacle: bool,
+    },
+    /// Transfers ownership of a tower without a ruler to `player_id`.
+    GiftTower {
+        tower_id: RelativeTowerId,
+        player_id: PlayerId,
+    },
+    /// Redirects (see [`Force::redirect`]) or halts a player's forces headed to `tower_id` from
+    /// `source`.
+    RedirectForce {
+        tower_id: RelativeTowerId,
+        source: TowerId,
+        player_id: PlayerId,
+        path: Option<Path>,
     },
     SetSupplyLine {
         tower_id: RelativeTowerId,
         path: Option<Path>,
+        pull: bool,
     },
     Spawn {
         tower_id: RelativeTowerId,
@@ -119,15 +139,90 @@ impl<C: OnInfo + OnChunkEvent> Apply<ChunkInput, C> for Chunk {
                     tower.units.clear();
                 }
             }
-            ChunkInput::DeployForce { tower_id, path } => {
-                context.on_chunk_events(self.chunk_id, self[tower_id].deploy_force(path));
+            ChunkInput::DeployForce {
+                tower_id,
+                path,
+                percent,
+            } => {
+                context.on_chunk_events(self.chunk_id, self[tower_id].deploy_force(path, percent));
             }
-            ChunkInput::Generate { tower_ids } => {
+            ChunkInput::Generate {
+                tower_ids,
+                mirror,
+                obstacle,
+            } => {
                 for tower_id in tower_ids {
-                    self.insert(tower_id, Tower::new(tower_id.upgrade(self.chunk_id)));
+                    let absolute = tower_id.upgrade(self.chunk_id);
+                    let tower = if obstacle {
+                        Tower::with_type(TowerType::Cliff)
+                    } else if mirror {
+                        Tower::with_type(absolute.mirrored_tower_type())
+                    } else {
+                        Tower::new(absolute)
+                    };
+                    self.insert(tower_id, tower);
                 }
             }
-            ChunkInput::SetSupplyLine { tower_id, path } => self[tower_id].supply_line = path,
+            ChunkInput::GiftTower {
+                tower_id,
+                player_id,
+            } => {
+                let chunk_id = self.chunk_id;
+                let tower = &mut self[tower_id];
+                let tower_id = tower_id.upgrade(chunk_id);
+                let Some(previous) = tower.player_id else {
+                    debug_assert!(false, "gifted unowned tower");
+                    return;
+                };
+                debug_assert!(!tower.units.has_ruler());
+
+                // Shields belong to the previous owner.
+                tower.units.subtract(Unit::Shield, usize::MAX);
+                tower.set_player_id(Some(player_id));
+
+                let position = tower_id.as_vec2();
+                context.on_info(InfoEvent::new(
+                    position,
+                    Info::LostTower {
+                        tower_id,
+                        player_id: previous,
+                        reason: LostTowerReason::GiftedTo(player_id),
+                    },
+                ));
+                context.on_info(InfoEvent::new(
+                    position,
+                    Info::GainedTower {
+                        tower_id,
+                        player_id,
+                        reason: GainedTowerReason::GiftedBy(previous),
+                    },
+                ));
+            }
+            ChunkInput::RedirectForce {
+                tower_id,
+                source,
+                player_id,
+                path,
+            } => {
+                for force in self[tower_id].inbound_forces.iter_mut().filter(|force| {
+                    force.player_id == Some(player_id) && force.current_source() == source
+                }) {
+                    if let Some(path) = &path {
+                        force.redirect(path);
+                    } else {
+                        force.halt();
+                    }
+                }
+            }
+            ChunkInput::SetSupplyLine {
+                tower_id,
+                path,
+                pull,
+            } => {
+                let tower = &mut self[tower_id];
+                tower.supply_line = path;
+                tower.supply_pull = pull;
+            }
             ChunkInput::Spawn {
                 tower_id,
                 player_id,
@@ -142,14 +237,14 @@ impl<C: OnInfo + OnChunkEvent> Apply<ChunkInput, C> for Chunk {
                 tower.units = Units::default();
                 tower.set_player_id(Some(player_id));
 
-                context.on_info(InfoEvent {
-                    info: Info::GainedTower {
+                context.on_info(InfoEvent::new(
+                    tower_id.as_vec2(),
+                    Info::GainedTower {
                         player_id,
                         tower_id,
                         reason: GainedTowerReason::Spawned,
                     },
-                    position: tower_id.as_vec2(),
-                });
+                ));
 
                 tower
                     .units
@@ -183,7 +278,7 @@ impl<C: OnInfo + OnChunkEvent> Apply<ChunkInput, C> for Chunk {
                 // The new tower may have different unit capacities.
                 tower.reconcile_units();
 
-                if tower.supply_line.is_some() && !tower.generates_mobile_units() {
+                if tower.push_supply_line().is_some() && !tower.generates_mobile_units() {
                     tower.supply_line = None;
                 }
             }
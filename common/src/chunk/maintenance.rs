@@ -39,14 +39,14 @@ impl<C: OnInfo> Apply<ChunkMaintenance, C> for Chunk {
                         tower.set_player_id(None);
 
                         // Don't trigger LostRulerEvents.
-                        context.on_info(InfoEvent {
-                            position: tower_id.as_vec2(),
-                            info: Info::LostTower {
+                        context.on_info(InfoEvent::new(
+                            tower_id.as_vec2(),
+                            Info::LostTower {
                                 tower_id,
                                 player_id,
                                 reason: LostTowerReason::PlayerKilled,
                             },
-                        });
+                        ));
                     }
                     tower
                         .inbound_forces
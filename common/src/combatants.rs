@@ -26,25 +26,23 @@ impl CombatInfo {
         attacker: Option<PlayerId>,
         defender: Option<PlayerId>,
     ) -> InfoEvent {
-        InfoEvent {
-            position,
-            info: match self {
-                Self::AttackerLostRuler(unit) => Info::LostRuler {
-                    player_id: attacker.unwrap(), // Ruler must have a player id.
-                    reason: LostRulerReason::KilledBy(defender, unit),
-                },
-                Self::DefenderLostRuler(unit) => Info::LostRuler {
-                    player_id: defender.unwrap(),
-                    reason: LostRulerReason::KilledBy(attacker, unit),
-                },
-                Self::Emp(side) => match side {
-                    CombatSide::Attacker => Info::Emp(attacker),
-                    CombatSide::Defender => Info::Emp(defender),
-                },
-                Self::NuclearExplosion => Info::NuclearExplosion,
-                Self::ShellExplosion => Info::ShellExplosion,
+        let info = match self {
+            Self::AttackerLostRuler(unit) => Info::LostRuler {
+                player_id: attacker.unwrap(), // Ruler must have a player id.
+                reason: LostRulerReason::KilledBy(defender, unit),
             },
-        }
+            Self::DefenderLostRuler(unit) => Info::LostRuler {
+                player_id: defender.unwrap(),
+                reason: LostRulerReason::KilledBy(attacker, unit),
+            },
+            Self::Emp(side) => match side {
+                CombatSide::Attacker => Info::Emp(attacker),
+                CombatSide::Defender => Info::Emp(defender),
+            },
+            Self::NuclearExplosion => Info::NuclearExplosion,
+            Self::ShellExplosion => Info::ShellExplosion,
+        };
+        InfoEvent::new(position, info)
     }
 }
 
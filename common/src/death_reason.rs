@@ -8,6 +8,8 @@ use diff::Diff;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DeathReason {
+    /// Kicked for not doing anything.
+    Afk,
     RulerKilled {
         /// Is [`None`] if was killed by zombies.
         alias: Option<PlayerAlias>,
@@ -0,0 +1,35 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ticks::Ticks;
+use crate::tower::TowerId;
+use core_protocol::prelude::*;
+use core_protocol::PlayerId;
+use strum::{EnumIter, IntoEnumIterator};
+
+/// Predefined emotes that can be sent without typing in chat.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Encode, Decode, EnumIter)]
+pub enum EmoteId {
+    Smile,
+    Frown,
+    Heart,
+    Exclamation,
+}
+
+impl EmoteId {
+    /// Minimum time between emotes from the same player.
+    pub const COOLDOWN: Ticks = Ticks::from_whole_secs(3);
+
+    pub fn iter() -> impl Iterator<Item = Self> + 'static {
+        <Self as IntoEnumIterator>::iter()
+    }
+}
+
+/// An emote that was sent this tick.
+#[derive(Copy, Clone, Debug, Encode, Decode)]
+pub struct EmoteEvent {
+    pub player_id: PlayerId,
+    /// Where the emote should be displayed (the sender's ruler).
+    pub tower_id: TowerId,
+    pub emote_id: EmoteId,
+}
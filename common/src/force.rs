@@ -6,6 +6,7 @@ use crate::tower::{TowerId, TowerType};
 use crate::unit::{Speed, Unit};
 use crate::units::Units;
 use crate::world::{World, WorldChunks};
+use common_util::ticks::TicksRepr;
 use core_protocol::id::PlayerId;
 use core_protocol::prelude::*;
 use glam::Vec2;
@@ -28,7 +29,7 @@ impl Path {
     /// Validates a path and returns Ok with a valid [`Path`] or Err with a [`str`] error.
     pub fn validate(
         self,
-        towers: &WorldChunks,
+        world: &World,
         source_tower_id: TowerId,
         max_edge_distance: Option<u32>,
     ) -> Result<Self, &'static str> {
@@ -38,7 +39,7 @@ impl Path {
 
         // Direct paths Some(max_edge_distance) must be 2, path must be < max path roads.
         if (max_edge_distance.is_some() && self.path.len() != 2)
-            || (max_edge_distance.is_none() && self.path.len() > World::MAX_PATH_ROADS)
+            || (max_edge_distance.is_none() && self.path.len() > world.max_path_roads())
         {
             return Err("path too long");
         }
@@ -68,9 +69,12 @@ impl Path {
                 return Err("not neighbor");
             }
 
-            if !towers.contains(next) {
+            if !world.chunk.contains(next) {
                 return Err("not generated");
             }
+            if world.is_obstacle(next) {
+                return Err("obstacle");
+            }
             prev = next;
         }
 
@@ -105,6 +109,13 @@ impl Path {
         self.iter().last().unwrap()
     }
 
+    /// The same towers, in the opposite order.
+    pub fn reversed(&self) -> Self {
+        Self {
+            path: self.path.iter().rev().copied().collect(),
+        }
+    }
+
     /// Pops a TowerId off the path signifying that it was reached.
     fn pop(&mut self) {
         self.path.pop().unwrap();
@@ -163,17 +174,72 @@ impl Force {
     }
 
     pub fn interpolated_position(&self, time_since_tick: f32) -> Vec2 {
+        self.interpolated_position_ticks(time_since_tick * (1.0 / Ticks::PERIOD_SECS))
+    }
+
+    /// Like [`Self::interpolated_position`] but `ticks_since_tick` is a (fractional) number of
+    /// ticks, so the caller decides how to normalize by the tick interval.
+    pub fn interpolated_position_ticks(&self, ticks_since_tick: f32) -> Vec2 {
         let source = self.current_source().as_vec2();
         let destination = self.current_destination().as_vec2();
         source.lerp(
             destination,
-            ((self.path_progress as f32
-                + time_since_tick * (1.0 / Ticks::PERIOD_SECS) * self.progress_per_tick() as f32)
+            ((self.path_progress as f32 + ticks_since_tick * self.progress_per_tick() as f32)
                 / self.progress_required() as f32)
                 .min(1.0),
         )
     }
 
+    /// Returns the distance (in the units of [`Self::interpolated_position`]) left to travel
+    /// until reaching [`Path::destination`].
+    pub fn remaining_distance(&self) -> f32 {
+        let current = self
+            .interpolated_position_ticks(0.0)
+            .distance(self.current_destination().as_vec2());
+        let rest: f32 = self
+            .remaining_segments()
+            .map(|(a, b)| a.as_vec2().distance(b.as_vec2()))
+            .sum();
+        current + rest
+    }
+
+    /// Returns the number of ticks until arriving at [`Path::destination`], assuming the force's
+    /// speed doesn't change.
+    pub fn remaining_ticks(&self) -> Ticks {
+        let per_tick = self.progress_per_tick().max(1) as u32;
+        // Takes at least one tick to arrive, even if no progress is required.
+        let ticks = |progress: u32| progress.div_ceil(per_tick).max(1);
+
+        let current = ticks(self.progress_required().saturating_sub(self.path_progress) as u32);
+        let rest: u32 = self
+            .remaining_segments()
+            .map(|(a, b)| ticks(Self::progress_required_between(a, b) as u32))
+            .sum();
+        Ticks::from_repr((current + rest).min(TicksRepr::MAX as u32) as TicksRepr)
+    }
+
+    /// Segments of the path after the current one.
+    fn remaining_segments(&self) -> impl Iterator<Item = (TowerId, TowerId)> + '_ {
+        self.path.iter().skip(1).zip(self.path.iter().skip(2))
+    }
+
+    /// Force will arrive at current destination and then follow `path`, which must start there.
+    pub fn redirect(&mut self, path: &Path) {
+        debug_assert_eq!(path.source(), self.current_destination());
+        self.path = Path::new(
+            std::iter::once(self.current_source())
+                .chain(path.iter())
+                .collect(),
+        );
+    }
+
+    /// Force, having just arrived, heads back `to` (e.g. where it came from) instead.
+    pub fn bounce(&mut self, to: TowerId) {
+        self.path = Path::new(vec![self.current_source(), to]);
+        self.path_progress = 0;
+        self.fuel = self.fuel.saturating_sub(1);
+    }
+
     /// Force will arrive at current destination but not continue.
     pub fn halt(&mut self) {
         self.path = Path::new(self.path.iter().take(2).collect());
@@ -278,7 +344,11 @@ impl Force {
     }
 
     pub fn progress_required(&self) -> u8 {
-        let distance = self.current_source().distance(self.current_destination());
+        Self::progress_required_between(self.current_source(), self.current_destination())
+    }
+
+    fn progress_required_between(source: TowerId, destination: TowerId) -> u8 {
+        let distance = source.distance(destination);
         // The constant controls the speed. 255 was the original value, and 180 is about 40% faster.
         (distance * 180 / World::MAX_ROAD_LENGTH / 2).min(u8::MAX as u32) as u8
     }
@@ -356,6 +426,7 @@ impl Force {
 #[cfg(test)]
 mod tests {
     use crate::force::{Force, Path};
+    use crate::ticks::Ticks;
     use crate::tower::TowerId;
     use crate::unit::{Speed, Unit};
     use crate::units::Units;
@@ -389,4 +460,73 @@ mod tests {
         force.units.subtract(Unit::Tank, 3);
         assert_eq!(force.speed(), Speed::Fast);
     }
+
+    #[test]
+    fn redirect() {
+        let [a, b, c, d] = [(0, 0), (0, 1), (0, 2), (1, 1)].map(|(x, y)| TowerId::new(x, y));
+        let mut units = Units::default();
+        units.add(Unit::Soldier, 5);
+        let mut force = Force::new(PlayerId::SOLO_OFFLINE, units, Path::new(vec![a, b, c]));
+        force.path_progress = 3;
+
+        force.redirect(&Path::new(vec![b, d]));
+        assert_eq!(force.path().iter().collect::<Vec<_>>(), vec![a, b, d]);
+        assert_eq!(force.current_source(), a);
+        assert_eq!(force.current_destination(), b);
+        assert_eq!(force.path_progress, 3);
+
+        // Back the way it came.
+        force.redirect(&Path::new(vec![b, a]));
+        assert_eq!(force.path().iter().collect::<Vec<_>>(), vec![a, b, a]);
+    }
+
+    #[test]
+    fn bounce() {
+        let [a, b] = [(0, 0), (0, 1)].map(|(x, y)| TowerId::new(x, y));
+        let mut units = Units::default();
+        units.add(Unit::Soldier, 5);
+        let mut force = Force::new(PlayerId::SOLO_OFFLINE, units, Path::new(vec![a, b]));
+        while !force.tick(b) {}
+
+        force.bounce(a);
+        assert_eq!(force.current_source(), b);
+        assert_eq!(force.current_destination(), a);
+        assert_eq!(force.path_progress, 0);
+        assert_eq!(force.units.available(Unit::Soldier), 5);
+    }
+
+    #[test]
+    fn remaining() {
+        let [a, b, c] = [(0, 0), (0, 1), (1, 2)].map(|(x, y)| TowerId::new(x, y));
+        let mut units = Units::default();
+        units.add(Unit::Soldier, 5);
+        let mut force = Force::new(PlayerId::SOLO_OFFLINE, units, Path::new(vec![a, b, c]));
+
+        // Start.
+        let total_ticks = force.remaining_ticks();
+        let total_distance = a.as_vec2().distance(b.as_vec2()) + b.as_vec2().distance(c.as_vec2());
+        assert!(total_ticks > Ticks::ZERO);
+        assert!((force.remaining_distance() - total_distance).abs() < 0.01);
+
+        let mut elapsed = Ticks::ZERO;
+        loop {
+            // Middle.
+            assert_eq!(force.remaining_ticks(), total_ticks - elapsed);
+            let remaining_distance = force.remaining_distance();
+            let position = force.interpolated_position_ticks(0.0);
+            assert!(remaining_distance >= position.distance(c.as_vec2()) - 0.01);
+
+            elapsed += Ticks::ONE;
+            if force.raw_tick(None) {
+                if force.path.is_empty() {
+                    break;
+                }
+                force.path_progress = 0;
+            }
+            assert!(force.remaining_distance() < remaining_distance);
+        }
+
+        // End.
+        assert_eq!(elapsed, total_ticks);
+    }
 }
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::ticks::Ticks;
 use crate::tower::TowerId;
 use crate::unit::Unit;
 use core_protocol::id::PlayerId;
@@ -8,6 +9,13 @@ use glam::Vec2;
 
 pub trait OnInfo {
     fn on_info(&mut self, info: InfoEvent);
+
+    /// The world no longer matches the server's checksum (see [`World::get_update_with_checksum`]).
+    ///
+    /// [`World::get_update_with_checksum`]: crate::world::World::get_update_with_checksum
+    fn on_desync(&mut self, diff: String) {
+        panic!("desync {diff}")
+    }
 }
 
 impl<T: FnMut(InfoEvent)> OnInfo for T {
@@ -20,6 +28,21 @@ impl<T: FnMut(InfoEvent)> OnInfo for T {
 pub struct InfoEvent {
     pub position: Vec2,
     pub info: Info,
+    /// When it happened, set by [`World`] as the event leaves it, so even when several ticks are
+    /// applied at once, each event knows its own.
+    ///
+    /// [`World`]: crate::world::World
+    pub tick: Ticks,
+}
+
+impl InfoEvent {
+    pub fn new(position: Vec2, info: Info) -> Self {
+        Self {
+            position,
+            info,
+            tick: Ticks::ZERO,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -41,6 +64,12 @@ pub enum Info {
         player_id: PlayerId,
         reason: LostTowerReason,
     },
+    /// A force fought a tower. Counts include units that were consumed.
+    Combat {
+        tower_id: TowerId,
+        attacker_lost: usize,
+        defender_lost: usize,
+    },
     Emp(Option<PlayerId>),
     NuclearExplosion,
     ShellExplosion,
@@ -55,6 +84,8 @@ pub enum LostRulerReason {
 pub enum GainedTowerReason {
     CapturedFrom(Option<PlayerId>),
     Explored,
+    /// A mutual ally gave it away.
+    GiftedBy(PlayerId),
     Spawned,
 }
 
@@ -62,6 +93,8 @@ pub enum GainedTowerReason {
 pub enum LostTowerReason {
     CapturedBy(Option<PlayerId>),
     DestroyedBy(Option<PlayerId>),
+    /// The owner gave it to a mutual ally.
+    GiftedTo(PlayerId),
     /// The owner was killed.
     PlayerKilled,
 }
@@ -26,10 +26,12 @@ mod macros;
 pub mod alerts;
 pub mod chunk;
 pub mod death_reason;
+pub mod emote;
 pub mod enum_array;
 pub mod field;
 pub mod force;
 pub mod info;
+pub mod ping;
 pub mod player;
 pub mod protocol;
 pub mod singleton;
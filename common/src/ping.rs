@@ -0,0 +1,19 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ticks::Ticks;
+use crate::tower::TowerId;
+use core_protocol::prelude::*;
+use core_protocol::PlayerId;
+
+/// A tower that was pinged this tick, to draw the attention of mutual allies.
+#[derive(Copy, Clone, Debug, Encode, Decode)]
+pub struct PingEvent {
+    pub player_id: PlayerId,
+    pub tower_id: TowerId,
+}
+
+impl PingEvent {
+    /// Minimum time between pings from the same player.
+    pub const COOLDOWN: Ticks = Ticks::from_whole_secs(2);
+}
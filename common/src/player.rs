@@ -13,6 +13,8 @@ pub use core_protocol::PlayerId;
 pub struct Player {
     pub allies: Hashable<FxHashSet<PlayerId>>, // TODO better set/map.
     pub new_alliances: Hashable<FxHashSet<PlayerId>>,
+    /// Recently spawned, so their ruler's tower can't be attacked.
+    pub spawn_protected: bool,
 }
 
 impl Actor for Player {
@@ -33,6 +35,8 @@ pub enum PlayerInput {
     NewAlliance(PlayerId),
     /// Cancel signle direction alliance request.
     RemoveAlly(PlayerId),
+    /// Spawn protection started or ended.
+    SpawnProtected(bool),
 }
 
 impl Message for PlayerInput {}
@@ -42,6 +46,7 @@ impl<C> Apply<PlayerInput, C> for Player {
         match u.clone() {
             PlayerInput::Died => {
                 self.allies.clear();
+                self.spawn_protected = false;
                 //self.new_alliances.clear();
             }
             PlayerInput::AddAlly(player_id) => {
@@ -55,6 +60,9 @@ impl<C> Apply<PlayerInput, C> for Player {
                 let _removed = self.allies.remove(&player_id);
                 //debug_assert!(_removed);
             }
+            PlayerInput::SpawnProtected(spawn_protected) => {
+                self.spawn_protected = spawn_protected;
+            }
         }
     }
 }
@@ -72,6 +80,7 @@ impl<C> Apply<PlayerMaintainance, C> for Player {
         match u.clone() {
             PlayerMaintainance::Died => {
                 self.allies.clear();
+                self.spawn_protected = false;
             }
             PlayerMaintainance::RemoveDeadAlly(player_id) => {
                 let _removed = self.allies.remove(&player_id);
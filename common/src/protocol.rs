@@ -4,11 +4,14 @@
 use crate::alerts::Alerts;
 use crate::chunk::ChunkRectangle;
 use crate::death_reason::OptionDeathReason;
+use crate::emote::{EmoteEvent, EmoteId};
 use crate::force::Path;
-use crate::tower::{TowerArray, TowerId, TowerRectangle, TowerType};
+use crate::ping::PingEvent;
+use crate::tower::{Tower, TowerArray, TowerId, TowerRectangle, TowerType};
 use core_protocol::prelude::*;
 use core_protocol::PlayerId;
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
 pub use diff::Diff;
 
@@ -21,13 +24,36 @@ pub enum Command {
     DeployForce {
         tower_id: TowerId,
         path: Path,
+        /// See [`Tower::force_units_percent`].
+        percent: u8,
     },
+    Emote(EmoteId),
+    /// Gives a tower (without a ruler) to a mutual ally.
+    GiftTower {
+        tower_id: TowerId,
+        to: PlayerId,
+    },
+    /// Draws the attention of mutual allies to a tower.
+    Ping(TowerId),
+    /// Changes where the player's forces travelling from `source` to `tower_id` go after arriving
+    /// at `tower_id` (where `path` starts), or halts them there if `path` is [`None`].
+    RedirectForce {
+        tower_id: TowerId,
+        source: TowerId,
+        path: Option<Path>,
+    },
+    /// Sets the supply line of `tower_id` (where `path` starts), or clears it if `path` is
+    /// [`None`]. If `pull`, units are pulled from the end of `path` instead of pushed to it.
     SetSupplyLine {
         tower_id: TowerId,
         path: Option<Path>,
+        pull: bool,
     },
     SetViewport(ChunkRectangle),
-    Spawn,
+    Spawn(Option<SpawnPreference>),
+    /// Like [`Self::Spawn`], but waits (up to a timeout) for room in the preferred region
+    /// instead of spawning elsewhere.
+    QueueSpawn(SpawnPreference),
     Upgrade {
         tower_id: TowerId,
         tower_type: TowerType,
@@ -36,13 +62,36 @@ pub enum Command {
 
 impl Command {
     pub fn deploy_force_from_path(path: Vec<TowerId>) -> Self {
+        Self::deploy_percent_from_path(path, Tower::FULL_DEPLOY_PERCENT)
+    }
+
+    /// Like [`Self::deploy_force_from_path`], but only deploys `percent` of the units.
+    pub fn deploy_percent_from_path(path: Vec<TowerId>, percent: u8) -> Self {
         Self::DeployForce {
             tower_id: path[0],
             path: Path::new(path),
+            percent,
         }
     }
 }
 
+/// Where a player would like to spawn, relative to other players.
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Hash, Encode, Decode, EnumIter, EnumString, Display,
+)]
+pub enum SpawnPreference {
+    /// Among other players.
+    Contested,
+    /// Just beyond other players.
+    Quiet,
+}
+
+impl SpawnPreference {
+    pub fn iter() -> impl Iterator<Item = Self> + 'static {
+        <Self as IntoEnumIterator>::iter()
+    }
+}
+
 /// Non actor model data that the client needs. Diffed for efficiency.
 #[derive(Debug, Diff)]
 #[diff(attr(#[derive(Debug, Serialize, Deserialize)]))]
@@ -57,6 +106,18 @@ pub struct NonActor {
     pub death_reason: OptionDeathReason,
     /// An approximation of inhabited towers.
     pub bounding_rectangle: TowerRectangle,
+    /// How far, in towers, the client may see from the center of its view (unlimited if [`None`]).
+    pub max_view_radius: Option<u16>,
+    /// Whether the client also sees around the towers of mutual allies.
+    pub ally_vision: bool,
+    /// Whether the arena is paused, so nothing happens until it resumes.
+    pub paused: bool,
+    /// Position in line (starting at 1) for room in the preferred spawn region, if waiting
+    /// (see [`Command::QueueSpawn`]).
+    pub spawn_queue: Option<u16>,
+    /// Seconds between server ticks, in case they differ from
+    /// [`Ticks::PERIOD_SECS`](crate::ticks::Ticks::PERIOD_SECS).
+    pub tick_period_secs: f32,
 }
 
 impl Default for NonActor {
@@ -75,4 +136,8 @@ pub struct Update {
     /// (TODO)
     #[bitcode(with_serde)]
     pub non_actor_diff: NonActorDiff,
+    /// Emotes sent by visible players this tick.
+    pub emotes: Vec<EmoteEvent>,
+    /// Pings sent by the player or their mutual allies this tick.
+    pub pings: Vec<PingEvent>,
 }
@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::ticks::Ticks;
+use crate::tower::{TowerId, TowerRectangle, TowerType};
 use crate::world::Apply;
 use common_util::actor2::*;
 use core_protocol::prelude::*;
+use std::ops::RangeInclusive;
+use strum::{Display, EnumString};
 
 #[derive(
     Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Serialize, Deserialize, Encode, Decode,
@@ -16,9 +19,102 @@ impl ActorId for SingletonId {
     type Map<T> = Option<(Self, T)>;
 }
 
-#[derive(Clone, Debug, Default, Hash, Serialize, Deserialize, Encode, Decode)]
+#[derive(Clone, Debug, Hash, Serialize, Deserialize, Encode, Decode)]
 pub struct Singleton {
     pub tick: Ticks,
+    /// The current [`WorldEvent`] and how much longer it will last.
+    pub world_event: Option<(WorldEvent, Ticks)>,
+    /// Impassable regions, generated as [`TowerType::Cliff`][crate::tower::TowerType::Cliff]s,
+    /// that the server was configured with.
+    pub obstacles: Vec<TowerRectangle>,
+    /// Unit generation rates of some tower types, as percentages (e.g. 200 is twice as fast), for
+    /// balancing without recompiling. Other tower types generate at 100%.
+    pub generation_multipliers: Vec<(TowerType, u16)>,
+    /// The most towers a (non-ranged) deployment path may have, including its source, that the
+    /// server was configured with.
+    pub max_path_roads: u8,
+}
+
+impl Default for Singleton {
+    fn default() -> Self {
+        Self {
+            tick: Default::default(),
+            world_event: None,
+            obstacles: Vec::new(),
+            generation_multipliers: Vec::new(),
+            max_path_roads: Self::DEFAULT_MAX_PATH_ROADS,
+        }
+    }
+}
+
+impl Singleton {
+    /// Default of [`Self::max_path_roads`].
+    pub const DEFAULT_MAX_PATH_ROADS: u8 = 16;
+    /// Valid values of [`Self::max_path_roads`]. Paths need a source and a destination, and long
+    /// paths are expensive to find.
+    pub const MAX_PATH_ROADS_RANGE: RangeInclusive<u8> = 2..=64;
+
+    /// Applies [`Self::world_event`] to a unit generation `period`.
+    pub fn unit_generation(&self, period: Ticks) -> Ticks {
+        match self.world_event {
+            Some((WorldEvent::DoubleGeneration, _)) => {
+                Ticks::from_repr(period.0.div_ceil(2)).max(Ticks::ONE)
+            }
+            None => period,
+        }
+    }
+
+    /// Applies [`Self::generation_multipliers`], then [`Self::unit_generation`], to the unit
+    /// generation `period` of a `tower_type`.
+    pub fn tower_unit_generation(&self, tower_type: TowerType, period: Ticks) -> Ticks {
+        let period = self
+            .generation_multipliers
+            .iter()
+            .find(|(t, _)| *t == tower_type)
+            .map_or(period, |&(_, percent)| {
+                let scaled = (period.0 as u32 * 100).div_ceil(percent.max(1) as u32);
+                Ticks::from_repr(scaled.min(u16::MAX as u32) as u16).max(Ticks::ONE)
+            });
+        self.unit_generation(period)
+    }
+
+    /// Whether `tower_id` is in one of [`Self::obstacles`].
+    pub fn is_obstacle(&self, tower_id: TowerId) -> bool {
+        self.obstacles.iter().any(|o| o.contains(tower_id))
+    }
+
+    /// Increments [`Self::tick`] and counts down [`Self::world_event`], reverting it once it is
+    /// over.
+    pub(crate) fn advance(&mut self) {
+        self.tick = self.tick.next();
+        if let Some((_, remaining)) = &mut self.world_event {
+            *remaining = remaining.saturating_sub(Ticks::ONE);
+            if *remaining == Ticks::ZERO {
+                self.world_event = None;
+            }
+        }
+    }
+}
+
+/// A temporary modification of a game parameter that applies to the whole world, started by
+/// an admin.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    Display,
+    EnumString,
+)]
+pub enum WorldEvent {
+    /// Units are generated twice as fast.
+    DoubleGeneration,
 }
 
 impl Actor for Singleton {
@@ -26,10 +122,110 @@ impl Actor for Singleton {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
-pub enum SingletonInput {}
+pub enum SingletonInput {
+    /// Starts (or replaces) [`Singleton::world_event`].
+    StartWorldEvent { event: WorldEvent, duration: Ticks },
+    /// Replaces [`Singleton::generation_multipliers`].
+    SetGenerationMultipliers(Vec<(TowerType, u16)>),
+    /// Replaces [`Singleton::max_path_roads`], clamped to [`Singleton::MAX_PATH_ROADS_RANGE`].
+    SetMaxPathRoads(u8),
+}
 
 impl Message for SingletonInput {}
 
 impl<C> Apply<SingletonInput, C> for Singleton {
-    fn apply(&mut self, _: &SingletonInput, _: &mut C) {}
+    fn apply(&mut self, input: &SingletonInput, _: &mut C) {
+        match input {
+            &SingletonInput::StartWorldEvent { event, duration } => {
+                self.world_event = (duration != Ticks::ZERO).then_some((event, duration));
+            }
+            SingletonInput::SetGenerationMultipliers(multipliers) => {
+                self.generation_multipliers = multipliers.clone();
+            }
+            &SingletonInput::SetMaxPathRoads(max_path_roads) => {
+                self.max_path_roads = max_path_roads.clamp(
+                    *Self::MAX_PATH_ROADS_RANGE.start(),
+                    *Self::MAX_PATH_ROADS_RANGE.end(),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::singleton::{Singleton, SingletonInput, WorldEvent};
+    use crate::ticks::Ticks;
+    use crate::tower::TowerType;
+    use crate::world::Apply;
+
+    #[test]
+    fn world_event() {
+        let period = Ticks::from_whole_secs(5);
+        let mut singleton = Singleton::default();
+        assert_eq!(singleton.unit_generation(period), period);
+
+        let duration = Ticks::from_whole_secs(60);
+        singleton.apply(
+            &SingletonInput::StartWorldEvent {
+                event: WorldEvent::DoubleGeneration,
+                duration,
+            },
+            &mut (),
+        );
+        assert_eq!(
+            singleton.unit_generation(period),
+            Ticks::from_repr(period.0 / 2)
+        );
+        assert_eq!(singleton.unit_generation(Ticks::ONE), Ticks::ONE);
+
+        for _ in 0..duration.0 - 1 {
+            singleton.advance();
+            assert!(singleton.world_event.is_some());
+        }
+        singleton.advance();
+        assert_eq!(singleton.world_event, None);
+        assert_eq!(singleton.unit_generation(period), period);
+    }
+
+    #[test]
+    fn generation_multipliers() {
+        let period = Ticks::from_whole_secs(5);
+        let mut singleton = Singleton::default();
+        assert_eq!(
+            singleton.tower_unit_generation(TowerType::Barracks, period),
+            period
+        );
+
+        singleton.apply(
+            &SingletonInput::SetGenerationMultipliers(vec![
+                (TowerType::Barracks, 200),
+                (TowerType::Mine, 50),
+            ]),
+            &mut (),
+        );
+        assert_eq!(
+            singleton.tower_unit_generation(TowerType::Barracks, period),
+            Ticks::from_repr(period.0 / 2)
+        );
+        assert_eq!(
+            singleton.tower_unit_generation(TowerType::Mine, period),
+            Ticks::from_repr(period.0 * 2)
+        );
+        assert_eq!(
+            singleton.tower_unit_generation(TowerType::Town, period),
+            period
+        );
+    }
+
+    #[test]
+    fn max_path_roads() {
+        let mut singleton = Singleton::default();
+        assert_eq!(singleton.max_path_roads, Singleton::DEFAULT_MAX_PATH_ROADS);
+
+        for (max_path_roads, expected) in [(8, 8), (0, 2), (255, 64)] {
+            singleton.apply(&SingletonInput::SetMaxPathRoads(max_path_roads), &mut ());
+            assert_eq!(singleton.max_path_roads, expected);
+        }
+    }
 }
@@ -5,6 +5,7 @@ use crate::unit::Unit;
 use crate::units::Units;
 use core_protocol::id::PlayerId;
 use core_protocol::prelude::*;
+pub use history::OwnerHistory;
 pub use id::TowerId;
 use macros::TowerTypeData;
 pub use map::TowerMap;
@@ -16,6 +17,7 @@ use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
 #[cfg(any(test, feature = "server"))]
 mod connectivity;
+mod history;
 mod id;
 mod map;
 mod rectangle;
@@ -56,10 +58,17 @@ pub struct Tower {
     pub outbound_forces: Vec<Force>,
     /// Where the tower will send its units when it can't generate or is overflowing.
     pub supply_line: Option<Path>,
+    /// If true, [`Self::supply_line`] instead brings units from its destination to this tower,
+    /// when this tower has room for them.
+    pub supply_pull: bool,
+    /// Who recently lost the tower.
+    pub history: OwnerHistory,
 }
 
 impl Tower {
     pub const RULER_SHIELD_BOOST: usize = 10;
+    /// Deploys all of a tower's mobile units (see [`Self::force_units_percent`]).
+    pub const FULL_DEPLOY_PERCENT: u8 = 100;
 
     pub fn new(tower_id: TowerId) -> Self {
         Self::with_type(tower_id.tower_type())
@@ -74,6 +83,8 @@ impl Tower {
             inbound_forces: Vec::new(),
             outbound_forces: Vec::new(),
             supply_line: None,
+            supply_pull: false,
+            history: OwnerHistory::default(),
         }
     }
 
@@ -83,6 +94,11 @@ impl Tower {
         self.delay.is_none()
     }
 
+    /// [`Self::supply_line`], unless it pulls (see [`Self::supply_pull`]).
+    pub fn push_supply_line(&self) -> Option<&Path> {
+        self.supply_line.as_ref().filter(|_| !self.supply_pull)
+    }
+
     /// Returns true if the [`Tower`] is eligible to be destroyed.
     pub fn can_destroy(&self) -> bool {
         self.inbound_forces.is_empty() && self.player_id.is_none()
@@ -112,9 +128,20 @@ impl Tower {
         ret
     }
 
-    /// Takes all units that can be deployed in a force.
-    pub fn take_force_units(&mut self) -> Units {
-        let ret = self.force_units();
+    /// Like [`Self::force_units`], but only `percent` of each unit, rounded up so that no kind of
+    /// unit is left out.
+    pub fn force_units_percent(&self, percent: u8) -> Units {
+        let mut ret = Units::default();
+        for (unit, count) in self.force_units().iter() {
+            ret.add(unit, (count * percent as usize).div_ceil(100));
+        }
+        ret
+    }
+
+    /// Takes `percent` (see [`Self::force_units_percent`]) of the units that can be deployed in
+    /// a force.
+    pub fn take_force_units(&mut self, percent: u8) -> Units {
+        let ret = self.force_units_percent(percent);
         for (unit, count) in ret.iter() {
             debug_assert!(unit.is_mobile(Some(self.tower_type)));
 
@@ -140,6 +167,44 @@ impl Tower {
         units
     }
 
+    /// How full of units (besides the ruler) the tower is, from 0 to 1. Each unit is weighted by
+    /// its capacity, which is derived from [`TowerType::raw_unit_capacity`].
+    pub fn fullness(&self) -> f32 {
+        let units = Unit::iter().filter(|&unit| unit != Unit::Ruler);
+        let (units, capacity) = units.fold((0, 0), |(units, capacity), unit| {
+            let unit_capacity = self.units.capacity(unit, Some(self.tower_type));
+            (
+                units + self.units.available(unit).min(unit_capacity),
+                capacity + unit_capacity,
+            )
+        });
+        if capacity == 0 {
+            0.0
+        } else {
+            units as f32 / capacity as f32
+        }
+    }
+
+    /// Approximately how long until the tower can't fit any more of a mobile unit it generates,
+    /// after which generation is wasted unless there is a supply line. [`Ticks::ZERO`] if that
+    /// already happened and [`None`] if it isn't generating any mobile units.
+    pub fn will_overflow_in(&self) -> Option<Ticks> {
+        // Same conditions as generation in chunk tick.
+        if self.player_id.is_none() || !self.active() {
+            return None;
+        }
+        Unit::iter()
+            .filter(|unit| unit.is_mobile(Some(self.tower_type)))
+            .filter_map(|unit| {
+                let period = self.tower_type.unit_generation(unit)?;
+                let remaining = self
+                    .units
+                    .space_remaining(unit, Some(self.tower_type), false);
+                Some(Ticks::from_repr(period.0.saturating_mul(remaining as u16)))
+            })
+            .min()
+    }
+
     pub fn unit_generation(&self, unit: Unit) -> Option<Ticks> {
         if unit != Unit::Shield && self.units.has_ruler() {
             // TODO maybe check capacity instead.
@@ -230,16 +295,17 @@ impl Tower {
 #[prerequisite(10)]
 #[generate(Shield = 5)]
 pub enum TowerType {
-    #[tower(spawnable)]
+    #[tower(spawnable, cost = 3)]
     #[prerequisite(Runway, 20, Factory = 2, Radar = 1)]
     #[capacity(Fighter = 4, Bomber = 4, Soldier = 4, Tank = 3, Shield = 10)]
     #[generate(Bomber = 30)]
     Airfield,
-    #[tower(spawnable)]
+    #[tower(spawnable, cost = 2)]
     #[prerequisite(Barracks, 25, Factory = 1, Mine = 1)]
     #[capacity(Soldier = 4, Tank = 5, Shield = 15)]
     #[generate(Tank = 15)]
     Armory,
+    #[tower(cost = 5)]
     #[prerequisite(Bunker, 40, Refinery = 2, Radar = 3)]
     #[capacity(Shell = 3, Shield = 20)]
     #[generate(Shell = 15)]
@@ -248,6 +314,7 @@ pub enum TowerType {
     #[capacity(Soldier = 12, Tank = 2, Shield = 10)]
     #[generate(Soldier = 6)]
     Barracks,
+    #[tower(cost = 2)]
     #[prerequisite(Mine, 30, Headquarters = 1, Ews = 1)]
     #[capacity(Soldier = 6, Shield = 40)]
     Bunker,
@@ -255,16 +322,17 @@ pub enum TowerType {
     //#[capacity(Soldier = 8, Tank = 2, Shield = 60)]
     //#[generate(Shield = 3)]
     //Capitol,
+    #[tower(cost = 3)]
     #[prerequisite(Factory, 30, Mine = 3)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 15)]
     Centrifuge,
-    #[tower(score_weight = 5)]
+    #[tower(score_weight = 5, cost = 6)]
     #[prerequisite(Town, 30, Quarry = 2, Reactor = 1, Town = 3)]
     #[capacity(Fighter = 2, Soldier = 6, Tank = 2, Shield = 15)]
     City,
     #[capacity(Soldier = 4, Tank = 2, Shield = 30)]
     Cliff,
-    #[tower(sensor_radius = 20)]
+    #[tower(sensor_radius = 20, cost = 2)]
     #[prerequisite(Radar, 30, Generator = 2)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 15)]
     Ews,
@@ -273,10 +341,11 @@ pub enum TowerType {
     Factory,
     #[capacity(Soldier = 4, Tank = 2, Shield = 10)]
     Generator,
+    #[tower(cost = 1)]
     #[prerequisite(Village, 20, Radar = 1)]
     #[capacity(Soldier = 8, Tank = 2, Shield = 40)]
     Headquarters,
-    #[tower(spawnable)]
+    #[tower(spawnable, cost = 5)]
     #[prerequisite(Airfield, 20, Armory = 2, Factory = 3)]
     #[capacity(Chopper = 3, Soldier = 4, Tank = 2, Shield = 15)]
     #[generate(Chopper = 30)]
@@ -291,6 +360,7 @@ pub enum TowerType {
     //#[capacity(Shield = 40)]
     //#[generate(Shield = 3)]
     //Laser,
+    #[tower(cost = 2)]
     #[prerequisite(Rocket, 30, Airfield = 2)]
     #[capacity(Emp = 1, Shield = 15)]
     #[generate(Emp = 80)]
@@ -302,28 +372,32 @@ pub enum TowerType {
     #[tower(score_weight = 2)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 15)]
     Mine,
+    #[tower(cost = 4)]
     #[prerequisite(Centrifuge, 20, Rampart = 2, Reactor = 2)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 10)]
     #[generate(Shield = 3)]
     Projector,
-    #[tower(score_weight = 2)]
+    #[tower(score_weight = 2, cost = 1)]
     #[prerequisite(Cliff, 20, Village = 1)]
     #[capacity(Soldier = 6, Tank = 2, Shield = 10)]
     Quarry,
     #[tower(sensor_radius = 16)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 10)]
     Radar,
+    #[tower(cost = 2)]
     #[prerequisite(Cliff, 20, Barracks = 2)]
     #[capacity(Soldier = 8, Shield = 45)]
     #[generate(Shield = 3)]
     Rampart,
+    #[tower(cost = 1)]
     #[prerequisite(Generator, 40, Centrifuge = 1)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 10)]
     Reactor,
-    #[tower(score_weight = 3)]
+    #[tower(score_weight = 3, cost = 4)]
     #[prerequisite(Factory, 20, Generator = 3, Cliff = 1)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 5)]
     Refinery,
+    #[tower(cost = 1)]
     #[prerequisite(Radar, 20, Refinery = 1)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 15)]
     Rocket,
@@ -331,15 +405,16 @@ pub enum TowerType {
     #[capacity(Fighter = 4, Soldier = 4, Tank = 2, Shield = 5)]
     #[generate(Fighter = 30)]
     Runway,
-    #[tower(sensor_radius = 30)]
+    #[tower(sensor_radius = 30, cost = 7)]
     #[prerequisite(Ews, 40, Rocket = 2, Generator = 5)]
     #[capacity(Soldier = 4, Tank = 2, Shield = 15)]
     Satellite,
+    #[tower(cost = 2)]
     #[prerequisite(Quarry, 40, Centrifuge = 2)]
     #[capacity(Nuke = 1, Soldier = 4, Tank = 1, Shield = 20)]
     #[generate(Nuke = 120)]
     Silo,
-    #[tower(score_weight = 2)]
+    #[tower(score_weight = 2, cost = 4)]
     #[prerequisite(Village, 20, Generator = 1, Village = 3)]
     #[capacity(Fighter = 1, Soldier = 4, Tank = 1, Shield = 10)]
     Town,
@@ -370,6 +445,12 @@ impl TowerType {
         Self::iter().filter(move |&other| self.can_upgrade_to(other))
     }
 
+    /// Upgrades whose prerequisites are met by `tower_counts`.
+    pub fn upgrade_targets(self, tower_counts: &TowerArray<u8>) -> impl Iterator<Item = Self> + '_ {
+        self.upgrades()
+            .filter(move |upgrade| upgrade.has_prerequisites(tower_counts))
+    }
+
     pub fn prerequisites(self) -> impl Iterator<Item = (Self, u8)> {
         TowerType::iter().filter_map(move |tower_type| {
             NonZeroU8::new(self.prerequisite(tower_type)).map(|u| (tower_type, u.get()))
@@ -473,22 +554,118 @@ impl Distribution<TowerType> for rand::distributions::Standard {
 
 #[cfg(test)]
 mod tests {
-    use crate::tower::{fast_integer_sqrt, integer_sqrt, Tower, TowerId, TowerType};
+    use crate::ticks::Ticks;
+    use crate::tower::{fast_integer_sqrt, integer_sqrt, Tower, TowerArray, TowerId, TowerType};
     use crate::unit::Unit;
+    use core_protocol::id::PlayerId;
     use rand::{thread_rng, Rng};
+    use std::num::NonZeroU8;
+    use strum::IntoEnumIterator;
     use test::{black_box, Bencher};
 
+    #[allow(dead_code)]
+    mod derive {
+        use crate::ticks::Ticks;
+        use crate::tower::TowerType;
+        use crate::unit::Unit;
+        use macros::TowerTypeData;
+
+        #[derive(Copy, Clone, TowerTypeData)]
+        #[tower(sensor_radius = 1)]
+        pub enum TestTowerType {
+            #[tower(cost = 7)]
+            Costly,
+            Free,
+        }
+    }
+
     #[test]
     fn size_of() {
         size_of!(Tower)
     }
 
+    #[test]
+    fn build_cost() {
+        use derive::TestTowerType;
+        assert_eq!(TestTowerType::Costly.build_cost(), 7);
+        assert_eq!(TestTowerType::Free.build_cost(), 0);
+
+        for tower_type in TowerType::iter() {
+            // Only upgrades cost anything.
+            assert_eq!(
+                tower_type.build_cost() > 0,
+                tower_type.downgrade().is_some(),
+                "{tower_type:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn upgrade_targets() {
+        let none = TowerArray::default();
+        let mut all = TowerArray::default();
+        all.iter_mut().for_each(|(_, count)| *count = u8::MAX);
+
+        for tower_type in TowerType::iter() {
+            assert!(tower_type.upgrade_targets(&all).eq(tower_type.upgrades()));
+            for upgrade in tower_type.upgrade_targets(&none) {
+                assert!(upgrade.prerequisites().next().is_none(), "{upgrade:?}");
+            }
+        }
+        assert!(TowerType::Radar
+            .upgrade_targets(&none)
+            .all(|upgrade| upgrade != TowerType::Ews));
+    }
+
     #[test]
     fn serialized_size() {
         serialized_size_enum!(TowerType);
         serialized_size_value!("Tower", Tower::with_type(TowerType::City));
     }
 
+    #[test]
+    fn fullness() {
+        let mut tower = Tower::with_type(TowerType::Barracks);
+        assert_eq!(tower.fullness(), 0.0);
+
+        // 12 soldiers, 2 tanks, 10 shields.
+        tower.units.add(Unit::Soldier, 12);
+        assert_eq!(tower.fullness(), 0.5);
+        tower.units.add(Unit::Shield, 10);
+        assert!((tower.fullness() - 22.0 / 24.0).abs() < 0.0001);
+
+        // Overflow doesn't count.
+        tower.units.add(Unit::Soldier, 5);
+        assert!((tower.fullness() - 22.0 / 24.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn will_overflow_in() {
+        let mut tower = Tower::with_type(TowerType::Barracks);
+        // Unowned towers don't generate.
+        assert_eq!(tower.will_overflow_in(), None);
+
+        tower.player_id = Some(PlayerId::nth_bot(0).unwrap());
+        // 12 soldiers at 1 per 6 seconds.
+        assert_eq!(tower.will_overflow_in(), Some(Ticks::from_whole_secs(72)));
+        tower.units.add(Unit::Soldier, 10);
+        assert_eq!(tower.will_overflow_in(), Some(Ticks::from_whole_secs(12)));
+        tower.units.add(Unit::Soldier, 2);
+        assert_eq!(tower.will_overflow_in(), Some(Ticks::ZERO));
+
+        // Shields aren't mobile.
+        let mut tower = Tower::with_type(TowerType::Rampart);
+        tower.player_id = Some(PlayerId::nth_bot(0).unwrap());
+        assert_eq!(tower.will_overflow_in(), None);
+
+        // Upgrading towers don't generate.
+        let mut tower = Tower::with_type(TowerType::Armory);
+        tower.player_id = Some(PlayerId::nth_bot(0).unwrap());
+        assert_eq!(tower.will_overflow_in(), Some(Ticks::from_whole_secs(75)));
+        tower.delay = NonZeroU8::new(10);
+        assert_eq!(tower.will_overflow_in(), None);
+    }
+
     #[test]
     fn distance() {
         assert_eq!(TowerId::new(5, 10).distance(TowerId::new(15, 10)), 49);
@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::ticks::Ticks;
+use core_protocol::id::PlayerId;
+use core_protocol::prelude::*;
+
+/// The last few players to lose a tower, and the tick they lost it on. Tiny to bound the memory
+/// of every tower in the world.
+#[derive(
+    Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize, Encode, Decode,
+)]
+pub struct OwnerHistory([Option<(PlayerId, Ticks)>; Self::LEN]);
+
+impl OwnerHistory {
+    pub const LEN: usize = 3;
+
+    /// Records that `player_id` lost the tower on `tick`, forgetting the oldest owner if full.
+    pub fn record(&mut self, player_id: PlayerId, tick: Ticks) {
+        self.0.rotate_right(1);
+        self.0[0] = Some((player_id, tick));
+    }
+
+    /// Iterates previous owners and when they lost the tower, most recent first.
+    pub fn iter(&self) -> impl Iterator<Item = (PlayerId, Ticks)> + '_ {
+        self.0.iter().flatten().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0[0].is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ticks::Ticks;
+    use crate::tower::OwnerHistory;
+    use core_protocol::id::PlayerId;
+
+    #[test]
+    fn owner_history() {
+        let player = |n| PlayerId::nth_bot(n).unwrap();
+        let mut history = OwnerHistory::default();
+        assert!(history.is_empty());
+
+        for n in 0..5 {
+            history.record(player(n), Ticks::from_repr(n as u16));
+        }
+        assert!(!history.is_empty());
+        assert_eq!(
+            history.iter().collect::<Vec<_>>(),
+            [4, 3, 2].map(|n| (player(n), Ticks::from_repr(n as u16)))
+        );
+    }
+}
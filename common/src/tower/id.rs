@@ -66,6 +66,23 @@ impl TowerId {
         TowerType::generate(hash)
     }
 
+    /// Point reflection about the center of [`WorldChunks::RECTANGLE`], which it stays within.
+    pub fn mirrored(self) -> Self {
+        const MAX: u16 = WorldChunks::SIZE as u16 - 1;
+        Self::new(MAX - self.x, MAX - self.y)
+    }
+
+    /// Like [`Self::tower_type`], but the same as that of [`Self::mirrored`], for generating
+    /// point-symmetric worlds.
+    pub fn mirrored_tower_type(self) -> TowerType {
+        let mirrored = self.mirrored();
+        if (mirrored.y, mirrored.x) < (self.y, self.x) {
+            mirrored.tower_type()
+        } else {
+            self.tower_type()
+        }
+    }
+
     #[inline]
     pub fn split(self) -> (ChunkId, RelativeTowerId) {
         (ChunkId::from(self), RelativeTowerId::from(self))
@@ -482,7 +499,7 @@ impl NeighborTable {
 mod tests {
     use crate::tower::id::{NeighborTable, OffsetTable, TowerNeighbor};
     use crate::tower::TowerId;
-    use crate::world::World;
+    use crate::world::{World, WorldChunks};
     use std::hint::black_box;
     use test::Bencher;
 
@@ -498,6 +515,26 @@ mod tests {
         test(TowerNeighbor::SE, TowerNeighbor::NW);
     }
 
+    #[test]
+    fn mirrored() {
+        let max = WorldChunks::SIZE as u16 - 1;
+        assert_eq!(TowerId::new(0, 0).mirrored(), TowerId::new(max, max));
+        for tower_id in [
+            TowerId::new(0, 0),
+            TowerId::new(12, 300),
+            TowerId::new(max, 7),
+            World::CENTER,
+        ] {
+            let mirrored = tower_id.mirrored();
+            assert!(WorldChunks::RECTANGLE.contains(mirrored));
+            assert_eq!(mirrored.mirrored(), tower_id);
+            assert_eq!(
+                tower_id.mirrored_tower_type(),
+                mirrored.mirrored_tower_type()
+            );
+        }
+    }
+
     fn neighbor_pair() -> (TowerId, TowerId) {
         let tower_id = World::CENTER;
         (tower_id, tower_id.neighbors().next().unwrap())
@@ -104,6 +104,13 @@ impl<T> TowerMap<T> {
             .filter_map(|id| self.get(id).map(|i| (id, i)))
     }
 
+    /// Iterates the map within `rect` in an unspecified order, without visiting the rest.
+    pub fn iter_rect(&self, rect: TowerRectangle) -> impl Iterator<Item = (TowerId, &T)> + '_ {
+        rect.clamp_to(self.bounds)
+            .into_iter()
+            .filter_map(|id| self.get(id).map(|i| (id, i)))
+    }
+
     /// Returns the length of the set. This operation is O(1).
     pub fn len(&self) -> usize {
         self.len as usize
@@ -8,7 +8,7 @@ use common_util::x_vec2::U16Vec2;
 use core_protocol::prelude::*;
 use diff::Diff;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Encode, Decode, Diff)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, Encode, Decode, Diff)]
 #[diff(attr(#[derive(Debug, Serialize, Deserialize)]))]
 pub struct TowerRectangle {
     pub bottom_left: TowerId,
@@ -147,10 +147,18 @@ impl Units {
     }
 
     /// Iterates all unit types, NOT including the ones with zero counts.
+    /// Both iterate in the order of [`Unit`], which is also the display order.
     pub fn iter(&self) -> impl Iterator<Item = (Unit, usize)> {
         self.iter_with_zeros().filter(|(_, c)| *c != 0)
     }
 
+    /// Returns the most numerous unit and its count, if any. Ties go to whichever comes first
+    /// in [`Self::iter`].
+    pub fn dominant(&self) -> Option<(Unit, usize)> {
+        self.iter()
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+    }
+
     /// Takes into account the ruler boost.
     pub fn capacity(&self, unit: Unit, tower_type: Option<TowerType>) -> usize {
         Units::CAPACITY.min(
@@ -169,7 +177,12 @@ impl Units {
     }
 
     /// How much space is remaining for this unit type.
-    fn space_remaining(&self, unit: Unit, tower_type: Option<TowerType>, overflow: bool) -> usize {
+    pub(crate) fn space_remaining(
+        &self,
+        unit: Unit,
+        tower_type: Option<TowerType>,
+        overflow: bool,
+    ) -> usize {
         match unit.category() {
             UnitCategory::Always => (),
             UnitCategory::Many => {
@@ -405,6 +418,30 @@ mod tests {
         assert_eq!(units.available(Unit::Soldier), 0);
     }
 
+    #[test]
+    fn iter_and_dominant() {
+        let mut units = Units::default();
+        assert_eq!(units.iter().count(), 0);
+        assert_eq!(units.dominant(), None);
+
+        units.add(Unit::Soldier, 3);
+        units.add(Unit::Shield, 2);
+        units.add(Unit::Fighter, 3);
+        assert_eq!(
+            units.iter().collect::<Vec<_>>(),
+            [(Unit::Shield, 2), (Unit::Fighter, 3), (Unit::Soldier, 3)]
+        );
+        assert_eq!(
+            units.iter_with_zeros().map(|(u, _)| u).collect::<Vec<_>>(),
+            Unit::iter().collect::<Vec<_>>()
+        );
+
+        // Tie goes to the first.
+        assert_eq!(units.dominant(), Some((Unit::Fighter, 3)));
+        units.add(Unit::Soldier, 1);
+        assert_eq!(units.dominant(), Some((Unit::Soldier, 4)));
+    }
+
     #[test]
     fn fuzz() {
         let mut rng = thread_rng();
@@ -5,9 +5,11 @@ use crate::chunk::*;
 use crate::info::*;
 use crate::player::*;
 use crate::singleton::*;
-use crate::tower::{integer_sqrt, TowerId};
+use crate::ticks::Ticks;
+use crate::tower::{integer_sqrt, Tower, TowerId, TowerRectangle};
 use common_util::actor2::*;
 use core_protocol::prelude::*;
+use fxhash::FxHashSet;
 use std::collections::BTreeMap;
 
 mod towers;
@@ -32,14 +34,19 @@ define_events!(Player, Server, PlayerMaintainance, PlayerInput; Encode, Decode);
 define_actor_state!(Player, Server; Encode, Decode);
 define_events!(Singleton, Server, SingletonInput; Encode, Decode);
 define_actor_state!(Singleton, Server; Encode, Decode);
-define_world!((), Chunk, Player, Singleton; Encode, Decode); // todo cksum
+// Only checksummed for clients that opt in, since it's expensive.
+define_world!(Option<u32>, Chunk, Player, Singleton; Encode, Decode);
 
 impl<C: OnInfo> WorldTick<C> for World {
     fn tick_before_inputs(&mut self, context: &mut C) {
         let Some(singleton) = singleton_mut!(self) else {
             return;
         };
-        singleton.tick = singleton.tick.next();
+        singleton.advance();
+        let context = &mut Stamp {
+            tick: singleton.tick,
+            context,
+        };
 
         // TODO move to halt.rs
         let mut halt_events = vec![];
@@ -111,19 +118,24 @@ impl<C: OnInfo> WorldTick<C> for World {
     }
 
     fn tick_after_inputs(&mut self, context: &mut C) {
+        let context = &mut self.stamp(context);
         // We apply chunk events after inputs since `ChunkInput`s may create `ChunkEvent`s.
         // TODO detect if events weren't applied in debug mode.
         apply!(self, Chunk, ChunkId, ChunkEvent, context);
     }
 
     fn tick_client(&mut self, context: &mut C) {
-        apply_inputs!(self, Chunk, ChunkMaintenance, context);
-        apply_inputs!(self, Player, PlayerMaintainance, context);
+        {
+            let context = &mut self.stamp(context);
+            apply_inputs!(self, Chunk, ChunkMaintenance, context);
+            apply_inputs!(self, Player, PlayerMaintainance, context);
+        }
         self.tick_before_inputs(context);
         {
+            let mut stamp = self.stamp(context);
             let mut context = InputContext {
                 events: Default::default(),
-                on_info: &mut *context,
+                on_info: &mut stamp,
             };
             apply_inputs!(self, Chunk, ChunkInput, &mut context);
             self.extend(context.events);
@@ -132,12 +144,15 @@ impl<C: OnInfo> WorldTick<C> for World {
         apply_inputs!(self, Singleton, SingletonInput, context);
         self.tick_after_inputs(context);
     }
+
+    fn on_desync(&mut self, diff: String, context: &mut C) {
+        context.on_desync(diff);
+    }
 }
 
 impl World {
     pub const MAX_ROAD_LENGTH: u32 = 5;
     pub const MAX_ROAD_LENGTH_SQUARED: u64 = (Self::MAX_ROAD_LENGTH as u64 + 1).pow(2) - 1;
-    pub const MAX_PATH_ROADS: usize = 16;
 
     pub const CENTER: TowerId =
         TowerId::new(WorldChunks::SIZE as u16 / 2, WorldChunks::SIZE as u16 / 2);
@@ -186,10 +201,46 @@ impl World {
         })
     }
 
+    /// Wraps `context` to stamp events with the current tick (see [`InfoEvent::tick`]).
+    fn stamp<'a, C>(&self, context: &'a mut C) -> Stamp<'a, C> {
+        Stamp {
+            tick: singleton!(self).map_or(Ticks::ZERO, |singleton| singleton.tick),
+            context,
+        }
+    }
+
     pub fn singleton(&self) -> &Singleton {
         singleton!(self).expect("no singleton")
     }
 
+    /// Like [`Singleton::world_event`], but [`None`] if the singleton hasn't been received yet.
+    pub fn world_event(&self) -> Option<(WorldEvent, Ticks)> {
+        singleton!(self).and_then(|singleton| singleton.world_event)
+    }
+
+    /// Like [`Singleton::is_obstacle`], but `false` if the singleton hasn't been received yet.
+    pub fn is_obstacle(&self, tower_id: TowerId) -> bool {
+        singleton!(self).is_some_and(|singleton| singleton.is_obstacle(tower_id))
+    }
+
+    /// Like [`Singleton::max_path_roads`], but the default if the singleton hasn't been received
+    /// yet.
+    pub fn max_path_roads(&self) -> usize {
+        singleton!(self).map_or(Singleton::DEFAULT_MAX_PATH_ROADS, |singleton| {
+            singleton.max_path_roads
+        }) as usize
+    }
+
+    /// Iterates the towers owned by `player_id`.
+    pub fn iter_player_towers(
+        &self,
+        player_id: PlayerId,
+    ) -> impl Iterator<Item = (TowerId, &Tower)> + Clone {
+        self.chunk
+            .iter_towers()
+            .filter(move |(_, tower)| tower.player_id == Some(player_id))
+    }
+
     pub fn have_alliance(&self, a: PlayerId, b: PlayerId) -> bool {
         Self::have_alliance_inner(&self.player, a, b)
     }
@@ -220,16 +271,20 @@ impl World {
 
     #[cfg(feature = "server")]
     pub fn new() -> Self {
+        Self::with_obstacles(vec![])
+    }
+
+    /// Like [`Self::new`], but with [`Singleton::obstacles`].
+    #[cfg(feature = "server")]
+    pub fn with_obstacles(obstacles: Vec<TowerRectangle>) -> Self {
+        let singleton = Singleton {
+            obstacles,
+            ..Default::default()
+        };
         Self {
             chunk: ChunkMap::from_fn(|id| Some(Chunk::new(id).into())),
             player: Default::default(),
-            singleton: Some((
-                SingletonId,
-                Singleton {
-                    tick: Default::default(),
-                }
-                .into(),
-            )),
+            singleton: Some((SingletonId, singleton.into())),
         }
     }
 
@@ -240,9 +295,10 @@ impl World {
         input: ChunkMaintenance,
         mut on_info: impl FnMut(InfoEvent),
     ) {
+        let mut stamp = self.stamp(&mut on_info);
         Map::get_mut(&mut self.chunk, chunk_id)
             .unwrap()
-            .apply_owned(input, &mut on_info);
+            .apply_owned(input, &mut stamp);
     }
 
     #[cfg(feature = "server")]
@@ -252,9 +308,10 @@ impl World {
         input: ChunkInput,
         mut on_info: impl FnMut(InfoEvent),
     ) {
+        let mut stamp = self.stamp(&mut on_info);
         let mut context = InputContext {
             events: vec![], // TODO don't allocate (only needs 1 event).
-            on_info: &mut on_info,
+            on_info: &mut stamp,
         };
 
         Map::get_mut(&mut self.chunk, chunk_id)
@@ -307,11 +364,13 @@ impl World {
         filter: impl Fn(TowerId) -> bool,
     ) -> Option<Vec<TowerId>> {
         if let Some(d) = max_edge_distance {
-            (src.distance(dst) <= d && filter(dst)).then(|| vec![src, dst])
+            self.is_direct_path(src, dst, d, &filter)
+                .then(|| vec![src, dst])
         } else {
+            // Longer paths would fail validation.
             self.astar(src, dst, player_id, &filter)
                 .ok()
-                .filter(|p| p.len() >= 2)
+                .filter(|p| p.len() >= 2 && p.len() <= self.max_path_roads())
         }
     }
 
@@ -324,7 +383,7 @@ impl World {
         filter: impl Fn(TowerId) -> bool,
     ) -> Vec<TowerId> {
         if let Some(d) = max_edge_distance {
-            (src.distance(dst) <= d && filter(dst))
+            self.is_direct_path(src, dst, d, &filter)
                 .then(|| vec![src, dst])
                 .unwrap_or_else(|| vec![src])
         } else {
@@ -336,6 +395,69 @@ impl World {
         }
     }
 
+    /// Towers that [`Self::find_best_path`] can (approximately) reach from `src`, for previewing
+    /// the range of a deployment. Paths of ground units are limited by hops, not distance.
+    pub fn reachable_towers(
+        &self,
+        src: TowerId,
+        max_edge_distance: Option<u32>,
+        player_id: PlayerId,
+        filter: impl Fn(TowerId) -> bool,
+    ) -> FxHashSet<TowerId> {
+        if let Some(d) = max_edge_distance {
+            // Tower positions are offset by less than a tower from their grid position.
+            let radius = (d as u16).saturating_add(TowerId::CONVERSION);
+            return src
+                .iter_radius(radius)
+                .filter(|&dst| self.is_direct_path(src, dst, d, &filter))
+                .collect();
+        }
+
+        let mut reachable = FxHashSet::default();
+        let mut frontier = vec![src];
+        for _ in 1..self.max_path_roads() {
+            let mut next = Vec::new();
+            for tower_id in frontier {
+                for neighbor in tower_id.neighbors() {
+                    let Some(tower) = self.chunk.get(neighbor) else {
+                        continue;
+                    };
+                    if neighbor == src
+                        || self.is_obstacle(neighbor)
+                        || !filter(neighbor)
+                        || !reachable.insert(neighbor)
+                    {
+                        continue;
+                    }
+                    // Like `astar`, paths can end at, but not pass through, allies.
+                    let allied = tower
+                        .player_id
+                        .is_some_and(|p| self.have_alliance(player_id, p));
+                    if !allied {
+                        next.push(neighbor);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        reachable
+    }
+
+    /// Whether `src` can go straight to `dst` (see `max_edge_distance`).
+    fn is_direct_path(
+        &self,
+        src: TowerId,
+        dst: TowerId,
+        max_edge_distance: u32,
+        filter: &impl Fn(TowerId) -> bool,
+    ) -> bool {
+        src != dst
+            && src.distance(dst) <= max_edge_distance
+            && self.chunk.contains(dst)
+            && !self.is_obstacle(dst)
+            && filter(dst)
+    }
+
     fn astar(
         &self,
         src: TowerId,
@@ -362,7 +484,10 @@ impl World {
                         let passes_through_allicance = t.player_id.is_some_and(|p| {
                             Some(p) != dst_player_id && self.have_alliance(player_id, p)
                         });
-                        (!passes_through_allicance && filter(tower_id)).then(|| {
+                        let passable = !passes_through_allicance
+                            && !self.is_obstacle(tower_id)
+                            && filter(tower_id);
+                        passable.then(|| {
                             let d2 = pos.distance_squared(tower_id);
                             (tower_id, integer_sqrt(d2 * D2_SCALE))
                         })
@@ -398,6 +523,23 @@ impl World {
     }
 }
 
+/// Stamps [`InfoEvent::tick`] on events as they leave the [`World`].
+struct Stamp<'a, C> {
+    tick: Ticks,
+    context: &'a mut C,
+}
+
+impl<C: OnInfo> OnInfo for Stamp<'_, C> {
+    fn on_info(&mut self, mut info: InfoEvent) {
+        info.tick = self.tick;
+        self.context.on_info(info);
+    }
+
+    fn on_desync(&mut self, diff: String) {
+        self.context.on_desync(diff);
+    }
+}
+
 /// Context needed during ChunkInput apply.
 struct InputContext<I> {
     events: Vec<(ChunkId, (ChunkId, ChunkEvent))>,
@@ -418,8 +560,363 @@ impl<I> OnChunkEvent for InputContext<I> {
 
 #[cfg(test)]
 mod tests {
-    use crate::tower::integer_sqrt;
-    use crate::world::World;
+    use crate::chunk::{ChunkId, ChunkInput, ChunkMaintenance, RelativeTowerId};
+    use crate::force::Path;
+    use crate::singleton::SingletonInput;
+    use crate::tower::{integer_sqrt, TowerId, TowerMap, TowerRectangle};
+    use crate::world::{World, WorldChunks};
+    use core_protocol::id::PlayerId;
+    use fxhash::FxHashSet;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeMap;
+
+    const PLAYER_ID: PlayerId = PlayerId::SOLO_OFFLINE;
+
+    /// A world where only the (unowned) towers in `rect` are generated.
+    fn world(rect: TowerRectangle) -> World {
+        let mut world = World::new();
+        let mut chunks = BTreeMap::<ChunkId, Vec<RelativeTowerId>>::new();
+        for tower_id in rect {
+            let (chunk_id, tower_id) = tower_id.split();
+            chunks.entry(chunk_id).or_default().push(tower_id);
+        }
+        for (chunk_id, tower_ids) in chunks {
+            let input = ChunkInput::Generate {
+                tower_ids,
+                mirror: false,
+                obstacle: false,
+            };
+            world.dispatch_chunk_input(chunk_id, input, |_| {});
+        }
+        world
+    }
+
+    fn rect(bottom_left: (u16, u16), top_right: (u16, u16)) -> TowerRectangle {
+        TowerRectangle::new(
+            TowerId::new(bottom_left.0, bottom_left.1),
+            TowerId::new(top_right.0, top_right.1),
+        )
+    }
+
+    /// Asserts `path` is contiguous, within `max_edge_distance`, passes `filter`, and isn't
+    /// longer than [`World::max_path_roads`].
+    fn assert_valid(
+        world: &World,
+        path: &[TowerId],
+        src: TowerId,
+        max_edge_distance: Option<u32>,
+        filter: impl Fn(TowerId) -> bool,
+    ) {
+        if let Err(e) = Path::new(path.to_vec()).validate(&world, src, max_edge_distance) {
+            panic!("{e}: {path:?}");
+        }
+        assert!(
+            path[1..].iter().all(|&tower_id| filter(tower_id)),
+            "{path:?}"
+        );
+    }
+
+    #[test]
+    fn find_best_path_unreachable() {
+        let world = world(rect((10, 10), (20, 20)));
+        let src = TowerId::new(12, 15);
+
+        // Not generated.
+        let dst = TowerId::new(30, 15);
+        assert_eq!(
+            world.find_best_path(src, dst, None, PLAYER_ID, |_| true),
+            None
+        );
+        let incomplete = world.find_best_incomplete_path(src, dst, None, PLAYER_ID, |_| true);
+        assert_eq!(incomplete.first(), Some(&src));
+        assert_ne!(incomplete.last(), Some(&dst));
+        if incomplete.len() >= 2 {
+            assert_valid(&world, &incomplete, src, None, |_| true);
+        }
+
+        // Only path filtered out (e.g. not visible).
+        let dst = TowerId::new(18, 15);
+        let filter = |tower_id: TowerId| tower_id.x != 15;
+        assert!(world
+            .find_best_path(src, dst, None, PLAYER_ID, |_| true)
+            .is_some());
+        assert_eq!(
+            world.find_best_path(src, dst, None, PLAYER_ID, filter),
+            None
+        );
+        let incomplete = world.find_best_incomplete_path(src, dst, None, PLAYER_ID, filter);
+        assert!(incomplete.iter().all(|tower_id| tower_id.x < 15));
+    }
+
+    #[test]
+    fn find_best_path_max_edge_distance() {
+        let world = world(rect((10, 10), (20, 20)));
+        let src = TowerId::new(12, 12);
+        let dst = TowerId::new(15, 13);
+        let d = src.distance(dst);
+
+        // Exactly at the boundary.
+        assert_eq!(
+            world.find_best_path(src, dst, Some(d), PLAYER_ID, |_| true),
+            Some(vec![src, dst])
+        );
+        assert_eq!(
+            world.find_best_path(src, dst, Some(d - 1), PLAYER_ID, |_| true),
+            None
+        );
+        assert_eq!(
+            world.find_best_incomplete_path(src, dst, Some(d - 1), PLAYER_ID, |_| true),
+            vec![src]
+        );
+
+        // Destination filtered out.
+        assert_eq!(
+            world.find_best_path(src, dst, Some(d), PLAYER_ID, |t| t != dst),
+            None
+        );
+    }
+
+    #[test]
+    fn find_best_path_max_path_roads() {
+        // A long strip.
+        let world = world(rect((10, 10), (60, 20)));
+        let src = TowerId::new(10, 10);
+
+        // Roads aren't always straight, but this is 7 roads away.
+        let near = TowerId::new(14, 10);
+        let path = world
+            .find_best_path(src, near, None, PLAYER_ID, |_| true)
+            .unwrap();
+        assert_valid(&world, &path, src, None, |_| true);
+
+        // Reachable, but over 50 roads away.
+        let far = TowerId::new(42, 10);
+        assert_eq!(
+            world.find_best_path(src, far, None, PLAYER_ID, |_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn find_best_path_fuzz() {
+        let bounds = rect((100, 100), (131, 131));
+        let world = world(bounds);
+        let mut rng = StdRng::seed_from_u64(0);
+        // Includes some towers that aren't generated.
+        let random_tower_id = |rng: &mut StdRng| {
+            TowerId::new(
+                rng.gen_range(bounds.bottom_left.x..=bounds.top_right.x + 2),
+                rng.gen_range(bounds.bottom_left.y..=bounds.top_right.y + 2),
+            )
+        };
+
+        for _ in 0..500 {
+            let hidden: FxHashSet<TowerId> = (0..rng.gen_range(0..200))
+                .map(|_| random_tower_id(&mut rng))
+                .collect();
+            let filter = |tower_id: TowerId| !hidden.contains(&tower_id);
+            let src = random_tower_id(&mut rng);
+            let dst = random_tower_id(&mut rng);
+            let max_edge_distance = rng.gen_bool(0.5).then(|| rng.gen_range(1..=20));
+            if !bounds.contains(src) {
+                continue;
+            }
+
+            if let Some(path) = world.find_best_path(src, dst, max_edge_distance, PLAYER_ID, filter)
+            {
+                assert_eq!(path.last(), Some(&dst));
+                assert_valid(&world, &path, src, max_edge_distance, filter);
+            }
+
+            let path =
+                world.find_best_incomplete_path(src, dst, max_edge_distance, PLAYER_ID, filter);
+            assert_eq!(path.first(), Some(&src));
+            if path.len() >= 2 {
+                // Incomplete paths aren't capped, since they are only for display.
+                let mut path = path;
+                path.truncate(world.max_path_roads());
+                assert_valid(&world, &path, src, max_edge_distance, filter);
+            }
+        }
+    }
+
+    #[test]
+    fn reachable_towers() {
+        let world = world(rect((10, 10), (40, 40)));
+        let src = TowerId::new(20, 20);
+        // A wall.
+        let filter = |tower_id: TowerId| tower_id.x != 24;
+
+        // Ranged, so the wall doesn't matter, except to itself.
+        let reachable = world.reachable_towers(src, Some(30), PLAYER_ID, filter);
+        assert!(!reachable.contains(&src));
+        for dst in rect((10, 10), (40, 40)) {
+            assert_eq!(
+                reachable.contains(&dst),
+                world
+                    .find_best_path(src, dst, Some(30), PLAYER_ID, filter)
+                    .is_some(),
+                "{dst:?}"
+            );
+        }
+        assert!(reachable.iter().any(|tower_id| tower_id.x > 24));
+
+        // Ground units can't cross the wall.
+        let reachable = world.reachable_towers(src, None, PLAYER_ID, filter);
+        assert!(!reachable.contains(&src));
+        assert!(src
+            .neighbors()
+            .filter(|&tower_id| world.chunk.contains(tower_id))
+            .all(|tower_id| reachable.contains(&tower_id)));
+        assert!(reachable.iter().all(|tower_id| tower_id.x < 24));
+        assert!(!reachable.contains(&TowerId::new(20, 40)));
+    }
+
+    #[test]
+    fn iter_player_towers() {
+        let mut world = world(rect((10, 10), (30, 30)));
+        let other = PlayerId::nth_bot(0).unwrap();
+        let spawn = |world: &mut World, player_id: PlayerId, tower_id: TowerId| {
+            let (chunk_id, tower_id) = tower_id.split();
+            let input = ChunkInput::Spawn {
+                tower_id,
+                player_id,
+            };
+            world.dispatch_chunk_input(chunk_id, input, |_| {});
+        };
+        let owned = |world: &World, player_id: PlayerId| -> FxHashSet<TowerId> {
+            world
+                .iter_player_towers(player_id)
+                .map(|(tower_id, _)| tower_id)
+                .collect()
+        };
+
+        let mine = [TowerId::new(12, 12), TowerId::new(20, 20)];
+        let theirs = [TowerId::new(28, 28)];
+        for tower_id in mine {
+            spawn(&mut world, PLAYER_ID, tower_id);
+        }
+        for tower_id in theirs {
+            spawn(&mut world, other, tower_id);
+        }
+        assert_eq!(owned(&world, PLAYER_ID), mine.into_iter().collect());
+        assert_eq!(owned(&world, other), theirs.into_iter().collect());
+
+        // Lost when killed.
+        let chunk_ids: Vec<_> = world.chunk.iter_chunks().map(|(id, _)| id).collect();
+        for chunk_id in chunk_ids {
+            let input = ChunkMaintenance::KillPlayer {
+                player_id: PLAYER_ID,
+            };
+            world.dispatch_chunk_maintenance(chunk_id, input, |_| {});
+        }
+        assert!(owned(&world, PLAYER_ID).is_empty());
+        assert_eq!(owned(&world, other), theirs.into_iter().collect());
+
+        // Gained when taken over.
+        spawn(&mut world, other, mine[1]);
+        assert_eq!(
+            owned(&world, other),
+            [theirs[0], mine[1]].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn max_path_roads() {
+        let mut world = world(rect((10, 10), (40, 40)));
+        let src = TowerId::new(25, 25);
+        world.dispatch_singleton_input(SingletonInput::SetMaxPathRoads(4), |_| {});
+        assert_eq!(world.max_path_roads(), 4);
+
+        let (mut previewed_count, mut too_long) = (0, None);
+        for dst in rect((18, 18), (32, 32)) {
+            if dst == src {
+                continue;
+            }
+            let path = world.find_best_incomplete_path(src, dst, None, PLAYER_ID, |_| true);
+            let complete = path.last() == Some(&dst);
+            // Like the client's drag path preview.
+            let previewed = complete && path.len() <= world.max_path_roads();
+            previewed_count += previewed as usize;
+            if complete && !previewed {
+                too_long = Some(dst);
+            }
+
+            // The server accepts exactly what the client previews.
+            assert_eq!(
+                world
+                    .find_best_path(src, dst, None, PLAYER_ID, |_| true)
+                    .is_some(),
+                previewed,
+                "{dst:?}"
+            );
+            if complete {
+                let validated = Path::new(path.clone()).validate(&world, src, None);
+                assert_eq!(validated.is_ok(), previewed, "{dst:?}");
+            }
+            if path.len() >= 2 {
+                let mut path = path;
+                path.truncate(world.max_path_roads());
+                assert_valid(&world, &path, src, None, |_| true);
+            }
+        }
+        assert!(previewed_count > 0);
+
+        // A longer cap reaches farther.
+        let far = too_long.unwrap();
+        world.dispatch_singleton_input(SingletonInput::SetMaxPathRoads(32), |_| {});
+        assert!(world
+            .find_best_path(src, far, None, PLAYER_ID, |_| true)
+            .is_some());
+    }
+
+    #[test]
+    fn iter_towers_in() {
+        let world = world(rect((10, 10), (40, 30)));
+        let mut map = TowerMap::with_bounds(rect((5, 5), (35, 35)));
+        for (tower_id, _) in world.chunk.iter_towers() {
+            if map.bounds().contains(tower_id) && tower_id.x % 3 != 0 {
+                map.insert(tower_id, ());
+            }
+        }
+
+        let max = WorldChunks::SIZE as u16 - 1;
+        for r in [
+            rect((0, 0), (max, max)),
+            rect((12, 12), (20, 25)),
+            rect((15, 0), (17, 100)),
+            rect((0, 20), (500, 20)),
+            rect((35, 28), (60, 60)),
+            rect((100, 100), (200, 200)),
+            rect((max, max), (u16::MAX, u16::MAX)),
+            rect((20, 20), (10, 10)),
+            TowerRectangle::invalid(),
+        ] {
+            let brute_force: FxHashSet<_> = world
+                .chunk
+                .iter_towers()
+                .filter(|&(tower_id, _)| r.contains(tower_id))
+                .map(|(tower_id, _)| tower_id)
+                .collect();
+            let iterated: Vec<_> = world
+                .chunk
+                .iter_towers_in(r)
+                .map(|(tower_id, _)| tower_id)
+                .collect();
+            assert_eq!(iterated.len(), brute_force.len(), "{r:?}");
+            assert_eq!(iterated.into_iter().collect::<FxHashSet<_>>(), brute_force);
+
+            let brute_force: FxHashSet<_> = map
+                .iter()
+                .filter(|&(tower_id, _)| r.contains(tower_id))
+                .map(|(tower_id, _)| tower_id)
+                .collect();
+            let iterated: Vec<_> = map.iter_rect(r).map(|(tower_id, _)| tower_id).collect();
+            assert_eq!(iterated.len(), brute_force.len(), "{r:?}");
+            assert_eq!(iterated.into_iter().collect::<FxHashSet<_>>(), brute_force);
+        }
+    }
 
     #[test]
     fn max_edge_distance() {
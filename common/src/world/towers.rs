@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::chunk::{Chunk, ChunkId};
+use crate::chunk::{Chunk, ChunkId, ChunkRectangle};
 use crate::tower::{Tower, TowerId, TowerRectangle};
 use crate::world::ChunkState;
 use common_util::storage::*;
@@ -191,6 +191,28 @@ impl WorldChunks {
         self.iter_towers_rect(rect).flatten()
     }
 
+    /// Iterates towers in a rectangle, only visiting the chunks it overlaps, and skipping empty
+    /// or out of bounds towers.
+    pub fn iter_towers_in(
+        &self,
+        rect: TowerRectangle,
+    ) -> impl Iterator<Item = (TowerId, &Tower)> + Clone {
+        let rect = rect.clamp_to(Self::RECTANGLE);
+        let chunk_rect = if rect.is_valid() {
+            ChunkRectangle::from(rect)
+        } else {
+            ChunkRectangle::invalid()
+        };
+        chunk_rect
+            .into_iter()
+            .filter_map(|chunk_id| Some(chunk_id).zip(self.get_chunk(chunk_id)))
+            .flat_map(move |(chunk_id, chunk)| {
+                chunk
+                    .iter(chunk_id)
+                    .filter(move |&(tower_id, _)| rect.contains(tower_id))
+            })
+    }
+
     /// Like iter_towers_rectangle, but it doesn't skip towers.
     pub fn iter_towers_rect(
         &self,
@@ -43,9 +43,9 @@ struct Inner<A: Audio> {
     /// What music volume is or is ramping up/down to.
     music_volume_target: f32,
     track: Option<AudioBuffer>,
-    /// Audio indexed by [`Audio::index`].
-    playing: Box<[Vec<AudioBufferSourceNode>]>,
-    /// The game wants to mute all audio.
+    /// Audio indexed by [`Audio::index`], with their individual gain nodes (for fading).
+    playing: Box<[Vec<(AudioBufferSourceNode, GainNode)>]>,
+    /// The game wants to mute all sound effects. Music is faded in and out by the game instead.
     muted_by_game: bool,
     /// Whether muted because the page is unfocused.
     muted_by_visibility: bool,
@@ -55,6 +55,8 @@ struct Inner<A: Audio> {
     volume_setting: f32,
     /// Music (kept up to date with the corresponding setting).
     music_setting: bool,
+    /// Music volume relative to [`Self::volume_setting`] (kept up to date by the game).
+    music_volume_setting: f32,
     spooky: PhantomData<A>,
 }
 
@@ -80,6 +82,7 @@ impl<A: Audio> Default for AudioPlayer<A> {
             music_volume_target: 1.0,
             volume_setting: 0.0,
             music_setting: false,
+            music_volume_setting: 1.0,
             spooky: PhantomData,
         };
 
@@ -264,12 +267,17 @@ impl<A: Audio> AudioPlayer<A> {
 
     /// Plays a particular sound once, with a specified volume.
     pub fn play_with_volume(&self, audio: A, volume: f32) {
-        Inner::play(&self.inner, audio, volume);
+        Inner::play(&self.inner, audio, volume, 0.0);
     }
 
     /// Plays a particular sound once, with a specified volume and delay in seconds.
     pub fn play_with_volume_and_delay(&self, audio: A, volume: f32, _delay: f32) {
-        Inner::play(&self.inner, audio, volume);
+        Inner::play(&self.inner, audio, volume, 0.0);
+    }
+
+    /// Plays a particular sound once, ramping up to a specified volume over `fade` seconds.
+    pub fn play_with_fade(&self, audio: A, volume: f32, fade: f32) {
+        Inner::play(&self.inner, audio, volume, fade);
     }
 
     pub fn is_playing(&self, audio: A) -> bool {
@@ -277,7 +285,13 @@ impl<A: Audio> AudioPlayer<A> {
     }
 
     pub fn stop_playing(&self, audio: A) {
-        self.inner.borrow_mut().stop_playing(audio);
+        self.inner.borrow_mut().stop_playing(audio, 0.0);
+    }
+
+    /// Like [`Self::stop_playing`], but ramps the volume down over `fade` seconds first. Doesn't
+    /// count as playing while fading out.
+    pub fn stop_playing_with_fade(&self, audio: A, fade: f32) {
+        self.inner.borrow_mut().stop_playing(audio, fade);
     }
 
     // Sets a multiplier for the volume of all sounds.
@@ -288,7 +302,14 @@ impl<A: Audio> AudioPlayer<A> {
         inner.update_volume();
     }
 
-    /// For the game to mute/unmute all audio.
+    /// Sets a multiplier for the volume of music, on top of the overall volume.
+    pub fn set_music_volume_setting(&self, music_volume_setting: f32) {
+        let mut inner = self.inner.borrow_mut();
+        inner.music_volume_setting = music_volume_setting;
+        inner.update_volume();
+    }
+
+    /// For the game to mute/unmute all sound effects.
     pub fn set_muted_by_game(&self, muted_by_game: bool) {
         let mut inner = self.inner.borrow_mut();
         inner.muted_by_game = muted_by_game;
@@ -312,12 +333,17 @@ impl<A: Audio> AudioPlayer<A> {
 
 impl<A: Audio> Inner<A> {
     fn recalculate_volume(&self, music: bool) -> f32 {
-        if self.muted_by_game
-            || self.muted_by_visibility
+        if self.muted_by_visibility
             || self.muted_by_ad
-            || (music && !self.music_setting)
+            || if music {
+                !self.music_setting
+            } else {
+                self.muted_by_game
+            }
         {
             0.0
+        } else if music {
+            self.volume_setting * self.music_volume_setting
         } else {
             self.volume_setting
         }
@@ -335,6 +361,15 @@ impl<A: Audio> Inner<A> {
         }
     }
 
+    /// Ramps `gain` from its current value to `volume` over `fade` seconds.
+    fn fade(gain: &GainNode, volume: f32, current_time: f64, fade: f64) {
+        let _ = gain.gain().cancel_scheduled_values(current_time);
+        let _ = gain
+            .gain()
+            .set_value_at_time(gain.gain().value(), current_time);
+        Self::ramp(gain, volume, current_time, fade);
+    }
+
     fn update_volume(&mut self) {
         for music in [false, true] {
             let new_volume = self.recalculate_volume(music);
@@ -359,20 +394,16 @@ impl<A: Audio> Inner<A> {
 
     /// Plays a particular sound, optionally in a loop. This is private, since looping is never
     /// determined at runtime.
-    fn play(rc: &Rc<RefCell<Self>>, audio: A, volume: f32) {
+    fn play(rc: &Rc<RefCell<Self>>, audio: A, volume: f32, fade: f32) {
         let mut inner = rc.borrow_mut();
-        if inner.recalculate_volume(false) == 0.0 {
+        let sprite = &A::sprites()[audio.index()];
+        if inner.recalculate_volume(sprite.music) == 0.0 {
             return;
         }
 
         if inner.context.state() == AudioContextState::Suspended {
             let _ = inner.context.resume();
         } else if let Some(track) = inner.track.as_ref() {
-            let sprite = &A::sprites()[audio.index()];
-            if inner.recalculate_volume(sprite.music) == 0.0 {
-                return;
-            }
-
             let source: AudioBufferSourceNode = inner
                 .context
                 .create_buffer_source()
@@ -383,7 +414,12 @@ impl<A: Audio> Inner<A> {
             source.set_buffer(Some(track));
 
             let gain = web_sys::GainNode::new(&inner.context).unwrap();
-            gain.gain().set_value(volume);
+            if fade > 0.0 {
+                gain.gain().set_value(0.0);
+                Self::fade(&gain, volume, inner.context.current_time(), fade as f64);
+            } else {
+                gain.gain().set_value(volume);
+            }
             let _ = source.connect_with_audio_node(&gain);
 
             let _ = gain.connect_with_audio_node(if sprite.music {
@@ -410,7 +446,7 @@ impl<A: Audio> Inner<A> {
                 let event: Event = value.dyn_into().unwrap();
                 let mut inner = cloned_rc.borrow_mut();
                 let playing = &mut inner.playing[audio.index()];
-                for source in playing.drain_filter(|p| {
+                for (source, _) in playing.drain_filter(|(p, _)| {
                     *p == event
                         .target()
                         .unwrap()
@@ -424,7 +460,7 @@ impl<A: Audio> Inner<A> {
 
             source.set_onended(Some(stop.as_ref().unchecked_ref()));
 
-            inner.playing[audio.index()].push(source);
+            inner.playing[audio.index()].push((source, gain));
         }
     }
 
@@ -432,12 +468,18 @@ impl<A: Audio> Inner<A> {
         !self.playing[audio.index()].is_empty()
     }
 
-    fn stop_playing(&mut self, audio: A) {
+    fn stop_playing(&mut self, audio: A, fade: f32) {
+        let time = self.context.current_time();
         let playing = &mut self.playing[audio.index()];
-        for removed in playing.drain(..) {
-            // WebAudio bug makes unsetting loop required?
-            removed.set_loop(false);
-            let _ = removed.stop();
+        for (removed, gain) in playing.drain(..) {
+            if fade > 0.0 {
+                Self::fade(&gain, 0.0, time, fade as f64);
+                let _ = removed.stop_with_when(time + fade as f64);
+            } else {
+                // WebAudio bug makes unsetting loop required?
+                removed.set_loop(false);
+                let _ = removed.stop();
+            }
         }
     }
 }
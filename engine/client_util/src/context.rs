@@ -5,21 +5,24 @@ use crate::apply::Apply;
 use crate::browser_storage::BrowserStorages;
 use crate::frontend::Frontend;
 use crate::game_client::GameClient;
-use crate::js_util::{host, invitation_id, is_https, ws_protocol};
+use crate::jitter_monitor::JitterMonitor;
+use crate::js_util::{host, invitation_id, is_https, spectator, ws_protocol};
 use crate::keyboard::KeyboardState;
 use crate::mouse::MouseState;
 use crate::reconn_web_socket::ReconnWebSocket;
 use crate::setting::CommonSettings;
 use crate::visibility::VisibilityState;
 use core_protocol::dto::{
-    LeaderboardScoreDto, LiveboardDto, MessageDto, PlayerDto, ServerDto, TeamDto, YourScoreDto,
+    FeatureFlags, LeaderboardScoreDto, LiveboardDto, MessageDto, PlayerDto, ServerDto, TeamDto,
+    YourScoreDto,
 };
 use core_protocol::id::{CohortId, InvitationId, PeriodId, PlayerId, TeamId};
 use core_protocol::name::PlayerAlias;
 use core_protocol::owned::{dedup_into_inner, owned_into_box, owned_into_iter};
 use core_protocol::rpc::{
     ChatUpdate, ClientRequest, ClientUpdate, InvitationUpdate, LeaderboardUpdate, LiveboardUpdate,
-    PlayerUpdate, Request, SystemUpdate, TeamUpdate, Update, WebSocketQuery,
+    PlayerUpdate, Request, RequestErrorKind, ServerFull, SystemUpdate, TeamUpdate, Update,
+    WebSocketQuery, PROTOCOL_VERSION,
 };
 use core_protocol::ServerNumber;
 use heapless::HistoryBuffer;
@@ -63,6 +66,10 @@ pub struct ClientState {
     pub time_seconds: f32,
     /// Supports rewarded ads.
     pub rewarded_ads: bool,
+    /// Measures irregularity of game update arrival times.
+    pub jitter_monitor: JitterMonitor,
+    /// [`Self::time_seconds`] when a game request or heartbeat was last sent.
+    pub last_activity_seconds: f32,
 }
 
 /// Obtained from server via websocket.
@@ -86,6 +93,12 @@ pub struct CoreState {
     pub messages: HistoryBuffer<MessageDto, 9>,
     pub(crate) players: HashMap<PlayerId, PlayerDto>,
     pub real_players: u32,
+    /// Latest network round trip time, in milliseconds.
+    pub rtt: Option<u16>,
+    /// Why the server rejected a request, until a chat message is sent successfully.
+    pub request_error: Option<RequestErrorKind>,
+    /// Server-controlled features.
+    pub features: FeatureFlags,
     pub teams: HashMap<TeamId, TeamDto>,
     pub servers: BTreeMap<ServerNumber, ServerDto>,
     pub your_score: Option<YourScoreDto>,
@@ -184,25 +197,37 @@ impl<G: GameClient> Apply<Update<G::GameUpdate>> for ServerState<G> {
         let mut core = Rc::borrow_mut(&mut self.core);
 
         match update {
-            Update::Chat(update) => {
-                if let ChatUpdate::Received(received) = update {
+            Update::Chat(update) => match update {
+                ChatUpdate::Received(received) => {
                     // Need to use into_vec since
                     // https://github.com/rust-lang/rust/issues/59878 is incomplete.
                     core.messages
                         .extend(received.into_vec().into_iter().map(dedup_into_inner));
                 }
-            }
-            Update::Client(update) => {
-                if let ClientUpdate::SessionCreated {
+                ChatUpdate::Sent => {
+                    core.request_error = None;
+                }
+                _ => {}
+            },
+            Update::Client(update) => match update {
+                ClientUpdate::RoundTripTime(rtt) => {
+                    core.rtt = Some(rtt);
+                }
+                ClientUpdate::SessionCreated {
                     cohort_id,
                     player_id,
+                    features,
                     ..
-                } = update
-                {
+                } => {
                     core.cohort_id = Some(cohort_id);
                     core.player_id = Some(player_id);
+                    core.features = features;
                 }
-            }
+                ClientUpdate::RequestFailed(kind) => {
+                    core.request_error = Some(kind);
+                }
+                _ => {}
+            },
             Update::Game(update) => {
                 self.game.apply(update);
             }
@@ -355,6 +380,7 @@ impl<G: GameClient> Context<G> {
         // crate::console_log!("override={:?} ideal server={:?}, host={:?}, ideal_host={:?}", override_server_id, ideal_server_id, host, ideal_host);
 
         let web_socket_query = WebSocketQuery {
+            protocol_version: Some(PROTOCOL_VERSION),
             player_id: common_settings.player_id,
             token: common_settings.token,
             session_token: common_settings.session_token,
@@ -362,6 +388,8 @@ impl<G: GameClient> Context<G> {
             date_created: common_settings.date_created,
             cohort_id: common_settings.cohort_id,
             referrer: frontend.get_real_referrer(),
+            spectator: spectator(),
+            queue_ticket: None,
         };
 
         // TODO to_string should take &impl Serialize.
@@ -385,6 +413,16 @@ impl<G: GameClient> Context<G> {
         self.socket.is_terminated()
     }
 
+    /// Whether the server rejected this client for being outdated, so it should be refreshed.
+    pub fn outdated(&self) -> bool {
+        self.socket.is_outdated()
+    }
+
+    /// Whether waiting for a slot because the arena is full.
+    pub fn server_full(&self) -> Option<ServerFull> {
+        self.socket.server_full()
+    }
+
     /// Send a game command on the socket.
     pub fn send_to_game(&mut self, request: G::GameRequest) {
         self.send_to_server(Request::Game(request));
@@ -402,6 +440,12 @@ impl<G: GameClient> Context<G> {
 
     /// Send a request on the socket.
     pub fn send_to_server(&mut self, request: Request<G::GameRequest>) {
+        if matches!(
+            request,
+            Request::Game(_) | Request::Client(ClientRequest::Heartbeat)
+        ) {
+            self.client.last_activity_seconds = self.client.time_seconds;
+        }
         self.socket.send(request);
     }
 
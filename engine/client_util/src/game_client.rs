@@ -32,6 +32,9 @@ pub trait GameClient: Sized + 'static {
     type GameSettings: Settings + Clone + PartialEq + Default;
     /// Open source licenses.
     const LICENSES: &'static [(&'static str, &'static [&'static str])] = &[];
+    /// Seconds without game requests after which to send a heartbeat, or [`None`] to never
+    /// send heartbeats.
+    const HEARTBEAT_PERIOD_SECS: Option<f32> = Some(60.0);
 
     fn new(context: &Context<Self>) -> Result<Self, String>;
 
@@ -11,6 +11,7 @@ use crate::keyboard::{Key, KeyboardEvent as GameClientKeyboardEvent};
 use crate::mouse::{MouseButton, MouseEvent as GameClientMouseEvent};
 use crate::reconn_web_socket::ReconnWebSocket;
 use crate::setting::CommonSettings;
+use crate::settings_sync::SettingsSync;
 use crate::visibility::VisibilityEvent;
 use common_util::range::map_ranges;
 use core_protocol::id::{PlayerId, TeamId};
@@ -37,6 +38,7 @@ pub struct Infrastructure<G: GameClient> {
     /// screen in a gesture, used to emulate right click.
     right_touch_id: Option<i32>,
     statistic_fps_monitor: FpsMonitor,
+    settings_sync: SettingsSync,
 }
 
 impl<G: GameClient> Infrastructure<G> {
@@ -63,6 +65,7 @@ impl<G: GameClient> Infrastructure<G> {
                 left_touch_id: None,
                 right_touch_id: None,
                 statistic_fps_monitor: FpsMonitor::new(60.0),
+                settings_sync: SettingsSync::default(),
             }),
             Err(e) => Err((
                 e,
@@ -131,21 +134,38 @@ impl<G: GameClient> Infrastructure<G> {
                     let _ = Function::new_no_args(snippet).call0(&JsValue::NULL);
                     // TODO: send result back to server.
                 }
+                Update::Client(ClientUpdate::LoggedIn(_)) => {
+                    self.settings_sync.logged_in();
+                }
+                Update::Client(ClientUpdate::SettingsSynced(newer)) => {
+                    self.settings_sync.synced(newer.clone(), &mut self.context);
+                }
                 _ => {}
             }
 
             if let Update::Game(update) = &inbound {
+                self.context.client.jitter_monitor.update(time_seconds);
                 self.game.peek_game(update, &mut self.context);
             }
             self.context.state.apply(inbound);
         }
 
         self.game.tick(elapsed_seconds, &mut self.context);
+        self.settings_sync
+            .update(elapsed_seconds, &mut self.context);
 
         if let Some(fps) = self.statistic_fps_monitor.update(elapsed_seconds) {
             self.context
                 .send_to_server(Request::Client(ClientRequest::TallyFps(fps)));
         }
+
+        // Frames aren't requested while hidden, so this only happens while visible.
+        let idle_seconds =
+            self.context.client.time_seconds - self.context.client.last_activity_seconds;
+        if G::HEARTBEAT_PERIOD_SECS.map_or(false, |period| idle_seconds >= period) {
+            self.context
+                .send_to_server(Request::Client(ClientRequest::Heartbeat));
+        }
     }
 
     pub fn keyboard(&mut self, event: KeyboardEvent) {
@@ -216,6 +236,10 @@ impl<G: GameClient> Infrastructure<G> {
             "mousedown" | "mouseup" => {
                 if let Some(button) = MouseButton::try_from_button(event.button()) {
                     let down = type_ == "mousedown";
+                    if button == MouseButton::Middle && down {
+                        // Otherwise, browsers may start autoscrolling instead of dragging.
+                        event.prevent_default();
+                    }
 
                     let e = GameClientMouseEvent::Button {
                         button,
@@ -387,6 +411,13 @@ impl<G: GameClient> Infrastructure<G> {
         self.context.visibility.apply(e)
     }
 
+    /// Recreates the game, and therefore its renderer, after the canvas was recreated (e.g. due to
+    /// the WebGL context being lost). Game state is preserved, since it lives in the context.
+    pub fn recreate_renderer(&mut self) -> Result<(), String> {
+        self.game = G::new(&self.context)?;
+        Ok(())
+    }
+
     /// Creates a mouse wheel event with the given delta.
     pub fn raw_zoom(&mut self, delta: f32) {
         let e = GameClientMouseEvent::Wheel(delta);
@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+/// Estimates jitter (variation in the interval between arrivals) of periodic updates, similar
+/// to RFC 3550.
+#[derive(Default)]
+pub struct JitterMonitor {
+    last_arrival: Option<f32>,
+    last_interval: Option<f32>,
+    jitter: Option<f32>,
+}
+
+impl JitterMonitor {
+    /// How quickly the estimate responds to new samples.
+    const GAIN: f32 = 1.0 / 16.0;
+
+    /// Records an arrival at `time_seconds`.
+    pub fn update(&mut self, time_seconds: f32) {
+        if let Some(last_arrival) = self.last_arrival.replace(time_seconds) {
+            let interval = time_seconds - last_arrival;
+            if let Some(last_interval) = self.last_interval.replace(interval) {
+                let deviation = (interval - last_interval).abs();
+                let jitter = self.jitter.get_or_insert(deviation);
+                *jitter += (deviation - *jitter) * Self::GAIN;
+            }
+        }
+    }
+
+    /// Returns the estimated jitter in seconds, if enough arrivals have been recorded.
+    pub fn jitter(&self) -> Option<f32> {
+        self.jitter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jitter_monitor::JitterMonitor;
+
+    #[test]
+    fn jitter_monitor() {
+        let mut monitor = JitterMonitor::default();
+        assert_eq!(monitor.jitter(), None);
+
+        for i in 0..100 {
+            monitor.update(i as f32 * 0.25);
+        }
+        assert!(monitor.jitter().unwrap() < 0.001);
+
+        for i in 100..200 {
+            let late = if i % 2 == 0 { 0.1 } else { 0.0 };
+            monitor.update(i as f32 * 0.25 + late);
+        }
+        assert!(monitor.jitter().unwrap() > 0.15);
+    }
+}
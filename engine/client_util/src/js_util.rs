@@ -29,6 +29,16 @@ pub fn invitation_id() -> Option<InvitationId> {
         .map(InvitationId)
 }
 
+/// Returns `true` if the page was opened with `?spectator`, to watch without playing.
+pub fn spectator() -> bool {
+    window().location().search().map_or(false, |search| {
+        search
+            .trim_start_matches('?')
+            .split('&')
+            .any(|param| param.split('=').next() == Some("spectator"))
+    })
+}
+
 /// Gets the HTTP referrer.
 pub fn referrer() -> Option<Referrer> {
     Referrer::new(&document().referrer())
@@ -3,10 +3,10 @@
 
 use crate::apply::Apply;
 use std::num::NonZeroU8;
-use strum_macros::Display;
+use strum_macros::{Display, EnumString};
 
 /// Each variant is a possible key. Not guaranteed to support all keys.
-#[derive(Copy, Clone, Eq, PartialEq, Display)]
+#[derive(Copy, Clone, Eq, PartialEq, Display, EnumString)]
 pub enum Key {
     A,
     B,
@@ -60,6 +60,7 @@ pub enum Key {
     EqualsPlus,
     Right,
     Shift,
+    SlashQuestion,
     Space,
     Tab,
     Up,
@@ -121,6 +122,7 @@ impl Key {
             90 => Self::Z,
             187 => Self::EqualsPlus,
             189 => Self::MinusUnderscore,
+            191 => Self::SlashQuestion,
             _ => return None,
         })
     }
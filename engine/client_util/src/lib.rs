@@ -19,6 +19,7 @@ pub mod fps_monitor;
 pub mod frontend;
 pub mod game_client;
 pub mod infrastructure;
+pub mod jitter_monitor;
 #[cfg(feature = "joined")]
 pub mod joined;
 pub mod joystick;
@@ -29,6 +30,7 @@ pub mod pan_zoom;
 pub mod rate_limiter;
 pub mod reconn_web_socket;
 pub mod setting;
+pub mod settings_sync;
 pub mod un_jitter;
 pub mod visibility;
 pub mod web_socket;
@@ -8,6 +8,25 @@ pub struct PanZoom {
     top_right: Vec2,
     debug: bool,
     ready: bool,
+    animation: Option<PanAnimation>,
+    /// Further limits [`Self::max_zoom`] (see [`Self::set_zoom_limit`]).
+    zoom_limit: Option<f32>,
+    /// Deceleration rate of inertia, if enabled (see [`Self::set_inertia`]).
+    inertia: Option<f32>,
+    /// Rate of change of center, in world space per second.
+    velocity: Vec2,
+    /// Sum of [`Self::pan`]s since the last [`Self::update`].
+    pending_pan: Vec2,
+    /// Pan input is ongoing (see [`Self::hold`]).
+    held: bool,
+}
+
+/// An in-progress [`PanZoom::pan_to_animated`].
+struct PanAnimation {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+    duration: f32,
 }
 
 impl Default for PanZoom {
@@ -26,6 +45,12 @@ impl PanZoom {
             top_right: Vec2::splat(1.0),
             debug: false,
             ready: false,
+            animation: None,
+            zoom_limit: None,
+            inertia: None,
+            velocity: Vec2::ZERO,
+            pending_pan: Vec2::ZERO,
+            held: false,
         }
     }
 
@@ -66,15 +91,127 @@ impl PanZoom {
 
     /// Takes mouse movement in world space.
     pub fn pan(&mut self, delta: Vec2) {
+        if delta != Vec2::ZERO {
+            // Manual panning takes precedence.
+            self.animation = None;
+            self.pending_pan += delta;
+        }
         self.center -= delta;
         self.clamp_center();
     }
 
+    /// Indicates that pan input is ongoing (e.g. a mouse button is held) even if [`Self::pan`]
+    /// isn't being called, so inertia shouldn't start yet.
+    pub fn hold(&mut self) {
+        self.held = true;
+    }
+
+    /// Enables inertia after panning, with a deceleration rate (per second), or disables it.
+    pub fn set_inertia(&mut self, deceleration: Option<f32>) {
+        debug_assert!(deceleration.map_or(true, |d| d.is_finite() && d > 0.0));
+        self.inertia = deceleration;
+        if deceleration.is_none() {
+            self.velocity = Vec2::ZERO;
+        }
+    }
+
     pub fn pan_to(&mut self, target: Vec2) {
+        self.animation = None;
+        self.velocity = Vec2::ZERO;
         self.center = target;
         self.clamp_center();
     }
 
+    /// Like [`Self::pan_to`] but eases there over `duration` seconds (see [`Self::update`]).
+    /// Calling it again with the same target doesn't restart the animation.
+    pub fn pan_to_animated(&mut self, target: Vec2, duration: f32) {
+        if duration <= 0.0 {
+            self.pan_to(target);
+        } else if self.animation.as_ref().map_or(true, |a| a.to != target) {
+            self.velocity = Vec2::ZERO;
+            self.animation = Some(PanAnimation {
+                from: self.center,
+                to: target,
+                elapsed: 0.0,
+                duration,
+            });
+        }
+    }
+
+    /// Advances any animation started by [`Self::pan_to_animated`] or inertia.
+    pub fn update(&mut self, elapsed_seconds: f32) {
+        self.update_inertia(elapsed_seconds);
+        let Some(animation) = &mut self.animation else {
+            return;
+        };
+        animation.elapsed += elapsed_seconds;
+        let t = (animation.elapsed / animation.duration).min(1.0);
+        // Ease in out cubic.
+        let eased = if t < 0.5 {
+            4.0 * t * t * t
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(3) * 0.5
+        };
+        self.center = animation.from.lerp(animation.to, eased);
+        if t >= 1.0 {
+            self.animation = None;
+        }
+        self.clamp_center();
+    }
+
+    fn update_inertia(&mut self, elapsed_seconds: f32) {
+        let pending_pan = std::mem::take(&mut self.pending_pan);
+        let held = std::mem::take(&mut self.held);
+        let Some(deceleration) = self.inertia else {
+            return;
+        };
+
+        if pending_pan != Vec2::ZERO {
+            // New input cancels any coasting.
+            self.velocity = -pending_pan / elapsed_seconds.max(0.001);
+        } else if held {
+            self.velocity = Vec2::ZERO;
+        } else if self.velocity != Vec2::ZERO {
+            let target = self.center + self.velocity * elapsed_seconds;
+            self.center = target;
+            self.clamp_center();
+            // Don't keep coasting into the edge of the bounds.
+            for i in 0..2 {
+                if self.center[i] != target[i] {
+                    self.velocity[i] = 0.0;
+                }
+            }
+            self.velocity = Self::decay_velocity(self.velocity, deceleration, elapsed_seconds);
+        }
+    }
+
+    /// Exponentially decays `velocity` at a rate of `deceleration` over `elapsed_seconds`,
+    /// snapping to zero once it is negligible.
+    fn decay_velocity(velocity: Vec2, deceleration: f32, elapsed_seconds: f32) -> Vec2 {
+        const MIN_SPEED: f32 = 0.1;
+        let velocity = velocity * (-deceleration * elapsed_seconds).exp();
+        if velocity.length_squared() < MIN_SPEED.powi(2) {
+            Vec2::ZERO
+        } else {
+            velocity
+        }
+    }
+
+    /// Centers on and zooms to fit a rectangle in world space, subject to zoom limits.
+    pub fn fit(&mut self, bottom_left: Vec2, top_right: Vec2) {
+        debug_assert!(top_right.cmpge(bottom_left).all());
+        let half_span = (top_right - bottom_left) * 0.5;
+        self.animation = None;
+        self.velocity = Vec2::ZERO;
+        self.center = bottom_left + half_span;
+        // Visible half height is zoom / aspect ratio (see get_zooms).
+        self.zoom = half_span
+            .x
+            .max(half_span.y * self.aspect_ratio)
+            .clamp(self.min_zoom(), self.max_zoom());
+        self.clamp_center();
+    }
+
     fn clamp_center(&mut self) {
         let min = self.bottom_left;
         let max = self.top_right;
@@ -108,11 +245,25 @@ impl PanZoom {
             self.reset_center();
             self.reset_zoom();
             self.ready = true;
+        } else if self.zoom_limit.is_some() {
+            self.zoom = self.zoom.min(self.max_zoom());
+        }
+    }
+
+    /// Limits how far out it's possible to zoom, in world space (like [`Self::get_zoom`]).
+    /// Doesn't apply in debug mode (see [`Self::set_bounds`]).
+    pub fn set_zoom_limit(&mut self, zoom_limit: Option<f32>) {
+        debug_assert!(zoom_limit.map_or(true, f32::is_finite));
+        self.zoom_limit = zoom_limit;
+        if zoom_limit.is_some() {
+            self.zoom = self.zoom.min(self.max_zoom());
         }
     }
 
     /// Sets center to that of bounds.
     pub fn reset_center(&mut self) {
+        self.animation = None;
+        self.velocity = Vec2::ZERO;
         self.center = (self.bottom_left + self.top_right) * 0.5;
     }
 
@@ -131,6 +282,158 @@ impl PanZoom {
 
     fn max_zoom(&self) -> f32 {
         let span = self.top_right - self.bottom_left;
-        span.max_element() * 0.75
+        let max_zoom = span.max_element() * 0.75;
+        if let Some(zoom_limit) = self.zoom_limit.filter(|_| !self.debug) {
+            max_zoom.min(zoom_limit).max(self.min_zoom())
+        } else {
+            max_zoom
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pan_zoom::PanZoom;
+    use glam::Vec2;
+
+    fn pan_zoom() -> PanZoom {
+        let mut pan_zoom = PanZoom::new();
+        pan_zoom.set_bounds(Vec2::ZERO, Vec2::splat(100.0), false);
+        pan_zoom
+    }
+
+    #[test]
+    fn pan_to_animated() {
+        let mut pan_zoom = pan_zoom();
+        let start = pan_zoom.get_center();
+        let target = Vec2::new(80.0, 20.0);
+        pan_zoom.pan_to_animated(target, 0.25);
+        assert_eq!(pan_zoom.get_center(), start);
+
+        let mut previous = start.distance(target);
+        for _ in 0..9 {
+            pan_zoom.update(0.03);
+            // Same target doesn't restart.
+            pan_zoom.pan_to_animated(target, 0.25);
+            let distance = pan_zoom.get_center().distance(target);
+            assert!(distance < previous, "{distance} {previous}");
+            previous = distance;
+        }
+        assert_eq!(pan_zoom.get_center(), target);
+    }
+
+    #[test]
+    fn pan_to_animated_interrupted() {
+        let mut pan_zoom = pan_zoom();
+        pan_zoom.pan_to_animated(Vec2::new(80.0, 20.0), 0.25);
+        pan_zoom.update(0.1);
+        pan_zoom.pan(Vec2::new(1.0, 0.0));
+        let center = pan_zoom.get_center();
+        pan_zoom.update(0.5);
+        assert_eq!(pan_zoom.get_center(), center);
+
+        // A subsequent pan_to wins.
+        pan_zoom.pan_to_animated(Vec2::new(80.0, 20.0), 0.25);
+        pan_zoom.pan_to(Vec2::new(10.0, 10.0));
+        pan_zoom.update(0.5);
+        assert_eq!(pan_zoom.get_center(), Vec2::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn zoom_limit() {
+        let mut pan_zoom = pan_zoom();
+        pan_zoom.multiply_zoom(pan_zoom.get_center(), 100.0);
+        assert_eq!(pan_zoom.get_zoom(), 75.0);
+
+        pan_zoom.set_zoom_limit(Some(30.0));
+        assert_eq!(pan_zoom.get_zoom(), 30.0);
+        pan_zoom.multiply_zoom(pan_zoom.get_center(), 2.0);
+        assert_eq!(pan_zoom.get_zoom(), 30.0);
+        pan_zoom.fit(Vec2::ZERO, Vec2::splat(100.0));
+        assert_eq!(pan_zoom.get_zoom(), 30.0);
+
+        // Never below the minimum zoom.
+        pan_zoom.set_zoom_limit(Some(1.0));
+        assert_eq!(pan_zoom.get_zoom(), 10.0);
+
+        // Doesn't apply in debug mode.
+        pan_zoom.set_bounds(Vec2::ZERO, Vec2::splat(100.0), true);
+        pan_zoom.multiply_zoom(pan_zoom.get_center(), 100.0);
+        assert_eq!(pan_zoom.get_zoom(), 75.0);
+
+        pan_zoom.set_zoom_limit(None);
+        pan_zoom.set_bounds(Vec2::ZERO, Vec2::splat(100.0), false);
+        pan_zoom.multiply_zoom(pan_zoom.get_center(), 100.0);
+        assert_eq!(pan_zoom.get_zoom(), 75.0);
+    }
+
+    #[test]
+    fn decay_velocity() {
+        let velocity = Vec2::new(30.0, -40.0);
+        let once = PanZoom::decay_velocity(velocity, 5.0, 0.1);
+        assert!(once.length() < velocity.length());
+        assert_eq!(once.normalize(), velocity.normalize());
+
+        // Independent of frame rate.
+        let twice =
+            PanZoom::decay_velocity(PanZoom::decay_velocity(velocity, 5.0, 0.05), 5.0, 0.05);
+        assert!(once.distance(twice) < 0.001, "{once} {twice}");
+
+        // Eventually stops.
+        let mut velocity = velocity;
+        for _ in 0..100 {
+            velocity = PanZoom::decay_velocity(velocity, 5.0, 1.0 / 60.0);
+        }
+        assert_eq!(velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn inertia() {
+        let mut pan_zoom = pan_zoom();
+        pan_zoom.set_inertia(Some(5.0));
+        let start = pan_zoom.get_center();
+        pan_zoom.pan(Vec2::new(1.0, 0.0));
+        pan_zoom.update(0.1);
+        let released = pan_zoom.get_center();
+        assert_eq!(released, start - Vec2::new(1.0, 0.0));
+
+        // Coasts after release, slower and slower.
+        pan_zoom.update(0.1);
+        let first = released.x - pan_zoom.get_center().x;
+        pan_zoom.update(0.1);
+        let second = released.x - first - pan_zoom.get_center().x;
+        assert!(
+            first > 0.0 && second > 0.0 && second < first,
+            "{first} {second}"
+        );
+
+        // New input cancels it immediately.
+        pan_zoom.hold();
+        pan_zoom.update(0.1);
+        let held = pan_zoom.get_center();
+        pan_zoom.update(0.1);
+        assert_eq!(pan_zoom.get_center(), held);
+
+        // Doesn't coast past the bounds.
+        pan_zoom.pan(Vec2::new(-40.0, 0.0));
+        pan_zoom.update(0.01);
+        for _ in 0..100 {
+            pan_zoom.update(0.01);
+            assert!(pan_zoom.get_center().x <= 100.0);
+        }
+        assert_eq!(pan_zoom.get_center().x, 100.0);
+        assert_eq!(pan_zoom.velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn pan_to_animated_bounds() {
+        let mut pan_zoom = pan_zoom();
+        pan_zoom.pan_to_animated(Vec2::new(500.0, -500.0), 0.25);
+        for _ in 0..20 {
+            pan_zoom.update(0.02);
+            let center = pan_zoom.get_center();
+            assert!(center.cmpge(Vec2::ZERO).all() && center.cmple(Vec2::splat(100.0)).all());
+        }
+        assert_eq!(pan_zoom.get_center(), Vec2::new(100.0, 0.0));
     }
 }
@@ -66,6 +66,47 @@ impl RateLimiter {
     }
 }
 
+/// For rate-limiting bursty tasks on the client, allowing up to `capacity` at once and then a
+/// sustained rate (where durations are expressed in seconds).
+#[derive(Debug)]
+pub struct TokenBucket {
+    tokens: f32,
+    capacity: u32,
+    refill_per_sec: f32,
+}
+
+impl TokenBucket {
+    /// Starts full.
+    pub fn new(capacity: u32, refill_per_sec: f32) -> Self {
+        debug_assert!(refill_per_sec >= 0.0);
+        Self {
+            tokens: capacity as f32,
+            capacity,
+            refill_per_sec,
+        }
+    }
+
+    /// Takes how much time passed, in seconds, since last update.
+    pub fn update(&mut self, elapsed: f32) {
+        debug_assert!(elapsed >= 0.0);
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity as f32);
+    }
+
+    /// Whole tokens that could be consumed right now.
+    pub fn available(&self) -> u32 {
+        self.tokens as u32
+    }
+
+    /// Returns whether `n` tokens were available, consuming them if so.
+    pub fn try_consume(&mut self, n: u32) -> bool {
+        let ret = self.available() >= n;
+        if ret {
+            self.tokens -= n as f32;
+        }
+        ret
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +123,27 @@ mod tests {
         assert!(!limiter.update_ready(0.06));
         assert_eq!(limiter.iter_updates(0.15).count(), 2);
     }
+
+    #[test]
+    fn test_token_bucket() {
+        let mut bucket = TokenBucket::new(3, 2.0);
+        // Starts full, so allows a burst.
+        assert_eq!(bucket.available(), 3);
+        assert!(bucket.try_consume(2));
+        assert!(bucket.try_consume(1));
+        assert!(!bucket.try_consume(1));
+
+        // Refills at a sustained rate.
+        bucket.update(0.25);
+        assert!(!bucket.try_consume(1));
+        bucket.update(0.25);
+        assert!(bucket.try_consume(1));
+        assert!(!bucket.try_consume(1));
+
+        // Never exceeds capacity.
+        bucket.update(10.0);
+        assert_eq!(bucket.available(), 3);
+        assert!(!bucket.try_consume(4));
+        assert!(bucket.try_consume(3));
+    }
 }
@@ -4,6 +4,7 @@
 use crate::apply::Apply;
 use crate::web_socket::{ProtoWebSocket, State};
 use core_protocol::prelude::*;
+use core_protocol::rpc::ServerFull;
 use std::marker::PhantomData;
 
 /// Reconnectable WebSocket (generic over inbound, outbound, and state).
@@ -15,6 +16,8 @@ pub struct ReconnWebSocket<I, O, S> {
     preamble: Option<O>,
     tries: u8,
     next_try: f32,
+    /// Whether waiting for a slot in a full arena, as of the last attempt.
+    server_full: Option<ServerFull>,
     _spooky: PhantomData<S>,
 }
 
@@ -26,6 +29,8 @@ where
 {
     const MAX_TRIES: u8 = 5;
     const SECONDS_PER_TRY: f32 = 1.0;
+    /// How often to retry while waiting for a slot in a full arena.
+    const SECONDS_PER_QUEUED_TRY: f32 = 5.0;
 
     pub fn new(host: String, preamble: Option<O>) -> Self {
         let mut inner = ProtoWebSocket::new(&host);
@@ -40,6 +45,7 @@ where
             host,
             tries: 0,
             next_try: 0.0,
+            server_full: None,
             _spooky: PhantomData,
         }
     }
@@ -54,6 +60,16 @@ where
         self.inner.is_open()
     }
 
+    /// Returns whether the server rejected this (outdated) client.
+    pub fn is_outdated(&self) -> bool {
+        self.inner.is_outdated()
+    }
+
+    /// Returns whether waiting for a slot in a full arena.
+    pub fn server_full(&self) -> Option<ServerFull> {
+        self.server_full
+    }
+
     pub fn is_reconnecting(&self) -> bool {
         matches!(self.inner.state(), State::Opening | State::Error)
             && (1..=Self::MAX_TRIES).contains(&self.tries)
@@ -102,10 +118,26 @@ where
                 self.tries = 0;
                 self.next_try = time_seconds + Self::SECONDS_PER_TRY * 0.5;
             }
+            self.server_full = None;
         } else if time_seconds < self.next_try {
             // Wait...
+        } else if let Some(server_full) = self.inner.server_full() {
+            // Waiting in line isn't a failed attempt, so never give up.
+            self.server_full = Some(server_full);
+            // Present the ticket to keep our place in line.
+            let host = if let Some(ticket) = server_full.queue_ticket {
+                format!("{}&queue_ticket={ticket}", self.host)
+            } else {
+                self.host.clone()
+            };
+            self.inner = ProtoWebSocket::new(&host);
+            if let Some(p) = self.preamble.as_ref() {
+                self.inner.send(p.clone());
+            }
+            self.next_try = time_seconds + Self::SECONDS_PER_QUEUED_TRY;
         } else if self.inner.is_error() && self.tries < Self::MAX_TRIES {
             // Try again.
+            self.server_full = None;
             self.inner = ProtoWebSocket::new(&self.host);
             if let Some(p) = self.preamble.as_ref() {
                 self.inner.send(p.clone());
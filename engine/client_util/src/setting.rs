@@ -6,10 +6,14 @@ use core_protocol::id::{CohortId, LanguageId, ServerNumber, SessionId};
 use core_protocol::name::PlayerAlias;
 use core_protocol::{PlayerId, SessionToken, Token, UnixTime};
 pub use engine_macros::Settings;
-use strum_macros::IntoStaticStr;
+use strum_macros::{EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 /// Settings backed by local storage.
 pub trait Settings: Sized {
+    /// Local storage keys of non-sensitive settings, which follow logged in players between
+    /// devices (see [`SettingsSync`][crate::settings_sync::SettingsSync]).
+    const SYNCED: &'static [&'static str];
+
     /// Loads all settings from local storage.
     fn load(l: &BrowserStorages, default: Self) -> Self;
 
@@ -42,6 +46,8 @@ pub enum SettingCategory {
 
 // Useful if you don't want settings.
 impl Settings for () {
+    const SYNCED: &'static [&'static str] = &[];
+
     fn load(_: &BrowserStorages, _: Self) -> Self {}
     fn display(
         &self,
@@ -72,28 +78,28 @@ pub struct CommonSettings {
     #[setting(checkbox = "Audio/Music")]
     pub music: bool,
     /// Last [`CohortId`].
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub cohort_id: Option<CohortId>,
     /// Last-used/chosen [`ServerId`].
     #[setting(optional, volatile)]
     pub server_number: Option<ServerNumber>,
     /// Last-used [`PlayerId`].
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub player_id: Option<PlayerId>,
     /// Last-used [`Token`].
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub token: Option<Token>,
     /// Not manually set by the player.
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub date_created: Option<UnixTime>,
     /// Not manually set by the player.
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub session_id: Option<SessionId>,
     /// Not manually set by the player.
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub session_token: Option<SessionToken>,
     /// Not manually set by the player.
-    #[setting(optional)]
+    #[setting(optional, no_sync)]
     pub nick_name: Option<String>,
     #[setting(volatile)]
     pub store_enabled: bool,
@@ -104,6 +110,12 @@ pub struct CommonSettings {
     #[setting(checkbox = "High contrast")]
     #[cfg(feature = "high_contrast_setting")]
     pub high_contrast: bool,
+    /// Whether to replace pulsing animations with static indicators.
+    #[setting(checkbox = "Graphics/Reduced motion")]
+    pub reduced_motion: bool,
+    /// Enlarges text, both in game and UI.
+    #[setting(dropdown = "Graphics/Text size")]
+    pub text_scale: TextScale,
     /// Whether team menu is open.
     #[setting(volatile)]
     pub team_dialog_shown: bool,
@@ -134,9 +146,46 @@ impl Default for CommonSettings {
             chat_message: String::new(),
             #[cfg(feature = "high_contrast_setting")]
             high_contrast: false,
+            reduced_motion: js_hooks::prefers_reduced_motion(),
+            text_scale: TextScale::default(),
             team_dialog_shown: true,
             chat_dialog_shown: true,
             leaderboard_dialog_shown: true,
         }
     }
 }
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    EnumIter,
+    EnumMessage,
+    EnumString,
+    IntoStaticStr,
+    strum::Display,
+)]
+pub enum TextScale {
+    #[default]
+    #[strum(message = "Normal")]
+    Normal,
+    #[strum(message = "Large")]
+    Large,
+    #[strum(message = "Larger")]
+    Larger,
+}
+
+impl TextScale {
+    /// The smallest text may be, in CSS pixels, if raised above the default. Text that is
+    /// already bigger stays the same size.
+    pub fn min_px(self) -> Option<f32> {
+        match self {
+            Self::Normal => None,
+            Self::Large => Some(14.0),
+            Self::Larger => Some(18.0),
+        }
+    }
+}
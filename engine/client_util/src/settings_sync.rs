@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::browser_storage::BrowserStorages;
+use crate::context::Context;
+use crate::game_client::GameClient;
+use crate::rate_limiter::RateLimiter;
+use crate::setting::{CommonSettings, Settings};
+use core_protocol::dto::SettingsDto;
+use core_protocol::rpc::{ClientRequest, Request};
+
+/// Syncs [`Settings::SYNCED`] settings of logged in players to their profile, so they follow
+/// them between devices. Anonymous players' settings stay local.
+pub struct SettingsSync {
+    /// Checks for changes periodically instead of hooking every setter.
+    rate_limiter: RateLimiter,
+    /// Synced settings as of the last check, or [`None`] before the first check.
+    settings: Option<Vec<(String, String)>>,
+    /// Whether the server has yet to acknowledge the current settings.
+    pending: bool,
+}
+
+impl Default for SettingsSync {
+    fn default() -> Self {
+        Self {
+            rate_limiter: RateLimiter::new(Self::PERIOD_SECS),
+            settings: None,
+            pending: false,
+        }
+    }
+}
+
+impl SettingsSync {
+    /// Changes are only sent once settings stop changing for this long.
+    const PERIOD_SECS: f32 = 2.0;
+    /// Local storage key of [`SettingsDto::version`].
+    const VERSION_KEY: &'static str = "settingsVersion";
+
+    /// Call every frame.
+    pub fn update<G: GameClient>(&mut self, elapsed_seconds: f32, context: &mut Context<G>) {
+        if !self.rate_limiter.update_ready(elapsed_seconds)
+            || context.common_settings.session_token.is_none()
+        {
+            return;
+        }
+
+        let storages = &mut context.browser_storages;
+        let settings = Self::capture::<G>(storages);
+        let changed = match &self.settings {
+            // Sync what we have, in case the profile has newer settings.
+            None => {
+                self.pending = true;
+                false
+            }
+            Some(previous) => previous != &settings,
+        };
+        if changed {
+            let version = Self::version(storages).saturating_add(1);
+            let version = version.max(js_sys::Date::now() as u64);
+            let _ = storages.local.set(Self::VERSION_KEY, Some(version));
+            self.pending = true;
+        } else if self.pending {
+            let request = ClientRequest::SyncSettings(SettingsDto {
+                version: Self::version(storages),
+                settings: settings.clone(),
+            });
+            context.send_to_server(Request::Client(request));
+        }
+        self.settings = Some(settings);
+    }
+
+    /// Call when the player logs in, to sync settings with their profile.
+    pub fn logged_in(&mut self) {
+        self.settings = None;
+        self.rate_limiter.fast_track();
+    }
+
+    /// Call with [`ClientUpdate::SettingsSynced`](core_protocol::rpc::ClientUpdate::SettingsSynced),
+    /// which either acknowledges a sync or restores settings from the profile.
+    pub fn synced<G: GameClient>(&mut self, newer: Option<SettingsDto>, context: &mut Context<G>) {
+        let Some(newer) = newer else {
+            self.pending = false;
+            return;
+        };
+
+        let storages = &mut context.browser_storages;
+        if newer.version <= Self::version(storages) {
+            // Ours are at least as new, and are synced (or will be) instead.
+            return;
+        }
+        for key in Self::keys::<G>() {
+            // Missing settings were default.
+            let value = newer
+                .settings
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v);
+            let _ = storages.local.set(key, value);
+        }
+        let _ = storages.local.set(Self::VERSION_KEY, Some(newer.version));
+        context.common_settings = CommonSettings::load(storages, CommonSettings::default());
+        context.settings = G::GameSettings::load(storages, G::GameSettings::default());
+
+        // Don't sync them back.
+        self.settings = Some(Self::capture::<G>(storages));
+        self.pending = false;
+    }
+
+    fn keys<G: GameClient>() -> impl Iterator<Item = &'static str> {
+        CommonSettings::SYNCED
+            .iter()
+            .chain(G::GameSettings::SYNCED)
+            .copied()
+    }
+
+    /// Synced settings that aren't default (because they were never set).
+    fn capture<G: GameClient>(storages: &BrowserStorages) -> Vec<(String, String)> {
+        Self::keys::<G>()
+            .filter_map(|key| Some((key.to_owned(), storages.local.get::<String>(key)?)))
+            .collect()
+    }
+
+    fn version(storages: &BrowserStorages) -> u64 {
+        storages.local.get(Self::VERSION_KEY).unwrap_or(0)
+    }
+}
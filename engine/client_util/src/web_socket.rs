@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use core_protocol::prelude::*;
+use core_protocol::rpc::{ServerFull, PROTOCOL_MISMATCH_CLOSE_CODE, SERVER_FULL_CLOSE_CODE};
 use js_hooks::console_error;
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -22,6 +23,10 @@ pub enum State {
 struct ProtoWebSocketInner<I, O> {
     socket: WebSocket,
     state: State,
+    /// Whether the server rejected our [`core_protocol::rpc::PROTOCOL_VERSION`].
+    outdated: bool,
+    /// Whether the server rejected us because the arena is full.
+    server_full: Option<ServerFull>,
     outbound_buffer: Vec<O>,
     /// Only used in State::Opening.
     inbound_buffer: Vec<I>,
@@ -45,6 +50,8 @@ where
                 inbound_buffer: Vec::new(),
                 outbound_buffer: Vec::new(),
                 state: State::Opening,
+                outdated: false,
+                server_full: None,
             })),
         };
 
@@ -109,12 +116,21 @@ where
 
         let inner_copy = ret.inner.clone();
         let onclose_callback = Closure::once(move |e: CloseEvent| {
-            let state = &mut inner_copy.deref().borrow_mut().state;
+            let mut inner = inner_copy.deref().borrow_mut();
+            let state = &mut inner.state;
             if e.code() == 1000 {
                 // Normal closure.
                 if *state != State::Error {
                     *state = State::Closed;
                 }
+            } else if e.code() == PROTOCOL_MISMATCH_CLOSE_CODE {
+                // Reconnecting wouldn't help, but refreshing would.
+                *state = State::Closed;
+                inner.outdated = true;
+            } else if e.code() == SERVER_FULL_CLOSE_CODE {
+                // Reconnecting will, eventually.
+                *state = State::Error;
+                inner.server_full = Some(ServerFull::from_reason(&e.reason()));
             } else {
                 // Abnormal closure.
                 *state = State::Error;
@@ -147,6 +163,16 @@ where
         matches!(self.state(), State::Error)
     }
 
+    /// Returns whether the server rejected this (outdated) client.
+    pub fn is_outdated(&self) -> bool {
+        self.inner.borrow().outdated
+    }
+
+    /// Returns whether the server rejected us because the arena is full.
+    pub fn server_full(&self) -> Option<ServerFull> {
+        self.inner.borrow().server_full
+    }
+
     /// Returns whether socket is open.
     pub fn is_open(&self) -> bool {
         matches!(self.state(), State::Open)
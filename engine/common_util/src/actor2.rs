@@ -183,6 +183,9 @@ pub trait Accumulate<T> {
 pub trait Checksum: PartialEq {
     fn diff(&self, server: &Self) -> String;
 
+    /// An empty checksum to accumulate the client's world into, for comparison with `server`.
+    fn empty_like(server: &Self) -> Self;
+
     /// Can skip accumulates if this returns false.
     fn is_some(&self) -> bool {
         true
@@ -200,6 +203,8 @@ impl Checksum for () {
         String::new()
     }
 
+    fn empty_like(_: &Self) -> Self {}
+
     fn is_some(&self) -> bool {
         false
     }
@@ -217,6 +222,33 @@ impl Checksum for u32 {
     fn diff(&self, server: &Self) -> String {
         format!("client: {self:?} server: {server:?}")
     }
+
+    fn empty_like(_: &Self) -> Self {
+        0
+    }
+}
+
+/// Like [`u32`] but the server can opt in per update (e.g. only for debugging).
+impl<T: Hash> Accumulate<T> for Option<u32> {
+    fn accumulate(&mut self, t: T) {
+        if let Some(checksum) = self {
+            checksum.accumulate(t);
+        }
+    }
+}
+
+impl Checksum for Option<u32> {
+    fn diff(&self, server: &Self) -> String {
+        format!("client: {self:?} server: {server:?}")
+    }
+
+    fn empty_like(server: &Self) -> Self {
+        server.map(|_| 0)
+    }
+
+    fn is_some(&self) -> bool {
+        self.is_some()
+    }
 }
 
 // TODO HashMap/BTreeMap based checksums.
@@ -233,6 +265,11 @@ pub trait WorldTick<C> {
     }
     /// Tick code that gets run on client during update apply.
     fn tick_client(&mut self, context: &mut C);
+    /// Called on client if its checksum doesn't match the server's, with [`Checksum::diff`].
+    fn on_desync(&mut self, diff: String, context: &mut C) {
+        let _ = context;
+        panic!("desync {diff}")
+    }
 }
 
 /// A client's knowledge of a particular [`Actor`].
@@ -438,7 +475,21 @@ macro_rules! define_world {
                     knowledge: &mut Knowledge,
                     visibility: Visibility<$(impl FnOnce(&Knowledge) -> [<$actor T>]),+>,
                 ) -> Update {
-                    let mut update = Update::default();
+                    self.get_update_with_checksum(knowledge, visibility, Default::default())
+                }
+
+                /// Like [`Self::get_update`] but accumulates into `checksum` (e.g. [`None`] to
+                /// skip the checksum of an [`Option`] checksum).
+                pub fn get_update_with_checksum<$([<$actor T>]: IntoIterator<Item = <$actor as Actor>::Id>), +>(
+                    &self,
+                    knowledge: &mut Knowledge,
+                    visibility: Visibility<$(impl FnOnce(&Knowledge) -> [<$actor T>]),+>,
+                    checksum: $checksum,
+                ) -> Update {
+                    let mut update = Update {
+                        checksum,
+                        ..Default::default()
+                    };
 
                     $(
                         let mut removals_len = 0;
@@ -580,7 +631,7 @@ macro_rules! define_world {
                         }
                     )+
 
-                    let mut checksum = <$checksum>::default();
+                    let mut checksum = <$checksum as Checksum>::empty_like(&update.checksum);
                     if Checksum::is_some(&checksum) {
                         $(
                             for (actor_id, actor_state) in Map::iter(&self.[<$actor:snake>]) {
@@ -590,7 +641,8 @@ macro_rules! define_world {
                     }
 
                     if &checksum != &update.checksum {
-                        panic!("desync {}", Checksum::diff(&checksum, &update.checksum))
+                        let diff = Checksum::diff(&checksum, &update.checksum);
+                        WorldTick::on_desync(self, diff, context);
                     }
                 }
             }
@@ -884,6 +936,24 @@ mod tests {
             println!("client: {client_world:?}");
         }
     }
+
+    #[test]
+    fn option_checksum() {
+        let server: Option<u32> = None;
+        assert_eq!(Checksum::empty_like(&server), None);
+        assert!(!Checksum::is_some(&server));
+
+        let mut server = Some(0u32);
+        Accumulate::accumulate(&mut server, (VISIBLE_ID, 5u32));
+        let mut client = Checksum::empty_like(&server);
+        assert!(Checksum::is_some(&client));
+        Accumulate::accumulate(&mut client, (VISIBLE_ID, 5u32));
+        assert_eq!(client, server);
+
+        Accumulate::accumulate(&mut client, (OTHER_ID, 5u32));
+        assert_ne!(client, server);
+        assert!(Checksum::diff(&client, &server).contains("server"));
+    }
 }
 
 #[cfg(test)]
@@ -73,6 +73,11 @@ impl<const FREQUENCY_HZ: TicksRepr> GenTicks<FREQUENCY_HZ> {
         self.0 as f32 * Self::PERIOD_SECS
     }
 
+    /// Same as [`Self::to_secs`], named like [`Duration::as_secs_f32`].
+    pub fn as_secs_f32(self) -> f32 {
+        self.to_secs()
+    }
+
     /// Returns the duration as whole seconds (floored).
     pub fn to_whole_secs(self) -> TicksRepr {
         self.0 / Self::SECOND.0
@@ -185,6 +190,15 @@ impl<const FREQUENCY_HZ: TicksRepr> fmt::Debug for GenTicks<FREQUENCY_HZ> {
     }
 }
 
+/// Human-readable seconds, e.g. `3.2s`. Defaults to one decimal place, but respects the
+/// formatter's precision (e.g. `{:.2}`).
+impl<const FREQUENCY_HZ: TicksRepr> fmt::Display for GenTicks<FREQUENCY_HZ> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(1);
+        write!(f, "{:.*}s", precision, self.to_secs())
+    }
+}
+
 impl<const FREQUENCY_HZ: TicksRepr> Serialize for GenTicks<FREQUENCY_HZ> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -212,3 +226,37 @@ impl<'de, const FREQUENCY_HZ: TicksRepr> Deserialize<'de> for GenTicks<FREQUENCY
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ticks::{GenTicks, TicksRepr};
+
+    type Ticks = GenTicks<4>;
+
+    #[test]
+    fn arithmetic() {
+        let a = Ticks::from_whole_secs(2);
+        let b = Ticks::from_secs(0.5);
+        assert_eq!(a.0, 8);
+        assert_eq!(b.0, 2);
+        assert_eq!(a.as_secs_f32(), 2.0);
+        assert_eq!(b.saturating_sub(a), Ticks::ZERO);
+        assert_eq!(a.saturating_sub(b).as_secs_f32(), 1.5);
+        assert_eq!(a.checked_add(b), Some(Ticks::from_repr(10)));
+        assert_eq!(
+            Ticks::from_repr(TicksRepr::MAX).checked_add(Ticks::ONE),
+            None
+        );
+        assert_eq!(Ticks::from_whole_millis(750), Ticks::from_repr(3));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Ticks::ZERO.to_string(), "0.0s");
+        assert_eq!(Ticks::from_repr(14).to_string(), "3.5s");
+        assert_eq!(format!("{:.2}", Ticks::from_repr(13)), "3.25s");
+        assert_eq!(format!("{:.0}", Ticks::from_whole_secs(60)), "60s");
+        // Respects the tick rate.
+        assert_eq!(GenTicks::<10>::from_repr(32).to_string(), "3.2s");
+    }
+}
@@ -10,6 +10,58 @@ use crate::UnixTime;
 use bitcode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Server-controlled client features, for gradual rollout. Identified by name, so that clients
+/// ignore flags they don't know about. Written like `emotes,-spectate` where a leading `-`
+/// disables a feature.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct FeatureFlags(Vec<(String, bool)>);
+
+impl FeatureFlags {
+    /// Whether `feature` is enabled, or `default` if the server didn't say.
+    pub fn enabled(&self, feature: &str, default: bool) -> bool {
+        self.0
+            .iter()
+            .rev()
+            .find(|(name, _)| name == feature)
+            .map_or(default, |&(_, enabled)| enabled)
+    }
+}
+
+impl Display for FeatureFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (name, enabled)) in self.0.iter().enumerate() {
+            let comma = if i == 0 { "" } else { "," };
+            let minus = if *enabled { "" } else { "-" };
+            write!(f, "{comma}{minus}{name}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FeatureFlags {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|flag| !flag.is_empty())
+            .map(|flag| {
+                let (name, enabled) = match flag.strip_prefix('-') {
+                    Some(name) => (name, false),
+                    None => (flag, true),
+                };
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Err("invalid feature name");
+                }
+                Ok((name.to_owned(), enabled))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Encode, Decode)]
 pub struct InvitationDto {
@@ -55,9 +107,31 @@ pub struct YourScoreDto {
 
 #[cfg(test)]
 mod test {
-    use crate::dto::LiveboardDto;
+    use crate::dto::{FeatureFlags, LiveboardDto};
     use crate::id::{PlayerId, TeamId};
     use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn feature_flags() {
+        let flags = FeatureFlags::from_str("emotes, -spectate,future_feature").unwrap();
+        assert_eq!(flags.to_string(), "emotes,-spectate,future_feature");
+        assert_eq!(
+            FeatureFlags::from_str(&flags.to_string()),
+            Ok(flags.clone())
+        );
+        assert_eq!(FeatureFlags::from_str(""), Ok(FeatureFlags::default()));
+        assert!(FeatureFlags::from_str("-").is_err());
+        assert!(FeatureFlags::from_str("a b").is_err());
+
+        // Survives the trip to the client, which only consults flags it knows about.
+        let decoded: FeatureFlags = bitcode::decode(&bitcode::encode(&flags).unwrap()).unwrap();
+        assert_eq!(decoded, flags);
+        assert!(decoded.enabled("emotes", false));
+        assert!(!decoded.enabled("spectate", true));
+        assert!(decoded.enabled("unmentioned", true));
+        assert!(!decoded.enabled("unmentioned", false));
+    }
 
     #[test]
     fn sort_order() {
@@ -151,6 +225,31 @@ impl Ord for ServerDto {
     }
 }
 
+/// The Settings Data Transfer Object (DTO) holds a logged in player's non-sensitive settings,
+/// as browser storage keys and values, so they follow the player between devices.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct SettingsDto {
+    /// When the settings were last changed (in milliseconds), so the latest write wins.
+    pub version: u64,
+    pub settings: Vec<(String, String)>,
+}
+
+impl SettingsDto {
+    pub const MAX_SETTINGS: usize = 128;
+    pub const MAX_BYTES: usize = 16384;
+
+    /// Whether the settings are small enough to store.
+    pub fn is_valid(&self) -> bool {
+        self.settings.len() <= Self::MAX_SETTINGS
+            && self
+                .settings
+                .iter()
+                .map(|(key, value)| key.len() + value.len())
+                .sum::<usize>()
+                <= Self::MAX_BYTES
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SnippetDto {
     #[serde(flatten)]
@@ -342,6 +342,7 @@ impl DiscreteExtremaMetric {
         if self.count == 0 {
             self.min = sample;
             self.max = sample;
+            self.count = 1;
         } else if self.count < u32::MAX {
             self.min = self.min.min(sample);
             self.max = self.max.max(sample);
@@ -9,7 +9,7 @@ use crate::{
 };
 use crate::{
     ArenaToken, ClientHash, LeaderboardScoreDto, NickName, Notification, PeriodId, PlayerId,
-    RealmDto, RegionId, ServerDto, ServerNumber, ServerToken, UnixTime, UserId,
+    RealmDto, RegionId, ServerDto, ServerNumber, ServerToken, SettingsDto, UnixTime, UserId,
 };
 use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
@@ -160,6 +160,22 @@ pub enum PlasmaRequestV1 {
         realm_name: Option<RealmName>,
         players: Box<[UserId]>,
     },
+    /// A logged in player synced their settings. Plasma stores them in the user's profile,
+    /// unless it has a later version, in which case it sends [`ConfigPlayer`] with those.
+    UpdateSettings {
+        game_id: GameId,
+        server_id: ServerId,
+        #[serde(default, skip_serializing_if = "is_default")]
+        realm_name: Option<RealmName>,
+        user_id: UserId,
+        /// For idempotency.
+        arena_token: ArenaToken,
+        /// For idempotency.
+        session_token: SessionToken,
+        /// Id of player, for efficient lookup.
+        player_id: PlayerId,
+        settings: SettingsDto,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -207,6 +223,9 @@ pub enum PlasmaUpdateV1 {
         /// Unique nick name.
         #[serde(default, skip_serializing_if = "is_default")]
         nick_name: Option<NickName>,
+        /// Latest settings synced to the user's profile, to restore on every device.
+        #[serde(default, skip_serializing_if = "is_default")]
+        settings: Option<SettingsDto>,
     },
     /// [`Heartbeat`] (self-healing), and when updated.
     ConfigServer {
@@ -5,9 +5,11 @@ use crate::dto::*;
 use crate::id::*;
 use crate::name::*;
 use crate::owned::{Dedup, Owned};
+use crate::serde_util::is_default;
 use crate::UnixTime;
 use bitcode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
+use std::num::NonZeroU64;
 
 /// See https://docs.rs/actix/latest/actix/dev/trait.MessageResponse.html
 macro_rules! actix_response {
@@ -61,9 +63,57 @@ pub struct SystemResponse {
 
 actix_response!(SystemResponse);
 
+/// Incremented whenever [`Request`] or [`Update`] change incompatibly, so outdated (e.g. cached)
+/// clients can be asked to refresh instead of failing to decode updates.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The websocket close code with which the server rejects clients of another
+/// [`PROTOCOL_VERSION`].
+pub const PROTOCOL_MISMATCH_CLOSE_CODE: u16 = 4000;
+
+/// The websocket close code with which the server rejects clients while the arena is full. The
+/// close reason is a [`ServerFull`].
+pub const SERVER_FULL_CLOSE_CODE: u16 = 4001;
+
+/// Sent (as a close reason) to clients that must wait for a slot in a full arena. Reconnecting
+/// with the [`WebSocketQuery::queue_ticket`] keeps their place in the queue.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ServerFull {
+    /// Estimated position in the queue, starting at 1, if known.
+    pub queue_position: Option<u32>,
+    /// Identifies this client's place in the queue, if any.
+    pub queue_ticket: Option<NonZeroU64>,
+}
+
+impl ServerFull {
+    pub fn to_reason(self) -> String {
+        let position = self
+            .queue_position
+            .map(|p| p.to_string())
+            .unwrap_or_default();
+        if let Some(ticket) = self.queue_ticket {
+            format!("{position}:{ticket}")
+        } else {
+            position
+        }
+    }
+
+    /// Inverse of [`Self::to_reason`]. Garbled reasons still mean the server is full.
+    pub fn from_reason(reason: &str) -> Self {
+        let (position, ticket) = reason.split_once(':').unwrap_or((reason, ""));
+        Self {
+            queue_position: position.parse().ok(),
+            queue_ticket: ticket.parse().ok(),
+        }
+    }
+}
+
 /// Initiate a websocket with these optional parameters in the URL query string.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WebSocketQuery {
+    /// The client's [`PROTOCOL_VERSION`] ([`None`] if it predates versioning).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub player_id: Option<PlayerId>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -78,6 +128,23 @@ pub struct WebSocketQuery {
     pub cohort_id: Option<CohortId>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date_created: Option<UnixTime>,
+    /// Watch without playing (see [`Request::Game`]).
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub spectator: bool,
+    /// From [`ServerFull::queue_ticket`], when retrying to join a full arena.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_ticket: Option<NonZeroU64>,
+}
+
+impl WebSocketQuery {
+    /// Errs if the client speaks another [`PROTOCOL_VERSION`]. Clients that predate versioning
+    /// are given the benefit of the doubt.
+    pub fn check_protocol_version(&self) -> Result<(), &'static str> {
+        match self.protocol_version {
+            Some(version) if version != PROTOCOL_VERSION => Err("protocol version mismatch"),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Client to server request.
@@ -232,6 +299,9 @@ pub enum InvitationUpdate {
 /// General request from client to server.
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
 pub enum ClientRequest {
+    /// Sent periodically while the client is open but idle, so the server knows the player is
+    /// still watching.
+    Heartbeat,
     /// Present a Plasma session id.
     Login(SessionToken),
     SetAlias(PlayerAlias),
@@ -241,6 +311,9 @@ pub enum ClientRequest {
     Trace {
         message: String,
     },
+    /// Store settings in the logged in player's profile, unless it has newer ones (which are
+    /// sent back as [`ClientUpdate::SettingsSynced`]).
+    SyncSettings(SettingsDto),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
@@ -257,7 +330,13 @@ pub enum ClientUpdate {
     AliasSet(PlayerAlias),
     EvalSnippet(Owned<str>),
     FpsTallied,
+    /// Acknowledges [`ClientRequest::Heartbeat`].
+    HeartbeatReceived,
     LoggedIn(SessionToken),
+    /// A request was rejected, so the client can react without parsing log strings.
+    RequestFailed(RequestErrorKind),
+    /// Latest network round trip time measured by the server, in milliseconds.
+    RoundTripTime(u16),
     SessionCreated {
         cohort_id: CohortId,
         server_number: Option<ServerNumber>,
@@ -265,10 +344,27 @@ pub enum ClientUpdate {
         player_id: PlayerId,
         token: Token,
         date_created: UnixTime,
+        features: FeatureFlags,
     },
+    /// Acknowledges [`ClientRequest::SyncSettings`] if [`None`], otherwise restores the
+    /// profile's settings (after logging in, or if they were newer).
+    SettingsSynced(Option<SettingsDto>),
     Traced,
 }
 
+/// Why a request was rejected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub enum RequestErrorKind {
+    /// Too many requests of this kind.
+    RateLimited,
+    /// The requester lacks permission (e.g. bots, or non-moderators).
+    NotAllowed,
+    /// The request doesn't make sense right now (e.g. player doesn't exist or is alive).
+    InvalidState,
+    /// The request itself was malformed or out of range.
+    InvalidInput,
+}
+
 /// General update from server to client.
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
 pub enum SystemUpdate {
@@ -294,6 +390,11 @@ mod admin {
         ClearSnippet {
             snippet_id: SnippetId,
         },
+        /// Recent history of an arena (the main one if [`None`]), if replays are enabled.
+        ExportReplay {
+            realm_name: Option<RealmName>,
+            seconds: u32,
+        },
         MutePlayer {
             player_id: PlayerId,
             minutes: usize,
@@ -306,10 +407,19 @@ mod admin {
             player_id: PlayerId,
             moderator: bool,
         },
+        /// Pauses or resumes an arena (the main one if [`None`]), e.g. for teaching. Only private
+        /// arenas can be paused, which the main arena is if the server was started with
+        /// `--private-arena`.
+        PauseArena {
+            realm_name: Option<RealmName>,
+            paused: bool,
+        },
         RequestDay {
             filter: Option<MetricFilter>,
         },
         RequestGames,
+        /// Peak number of clients connected at once to each arena, over the last day.
+        RequestPeakClients,
         RequestPlayers,
         RequestProfile,
         RequestReferrers,
@@ -341,6 +451,11 @@ mod admin {
             message: String,
         },
         SetGameClient(minicdn::EmbeddedMiniCdn),
+        /// Changes how fast each kind of tower generates units, in a game specific format, until
+        /// further notice (unlike [`Self::StartWorldEvent`]).
+        SetGenerationMultipliers {
+            multipliers: String,
+        },
         SetRustrictTrie(rustrict::Trie),
         SetRustrictReplacements(rustrict::Replacements),
         SetSnippet {
@@ -348,18 +463,27 @@ mod admin {
             snippet_id: SnippetId,
             snippet: Owned<str>,
         },
+        /// Temporarily modifies a game parameter for everyone, e.g. to double generation.
+        StartWorldEvent {
+            event: String,
+            minutes: usize,
+        },
     }
 
     /// Admin related responses from the server.
     #[derive(Clone, Debug, Serialize)]
     pub enum AdminUpdate {
+        ArenaPaused(bool),
         ChatSent,
         DayRequested(Owned<[(UnixTime, MetricsDataPointDto)]>),
         GameClientSet(ClientHash),
+        GenerationMultipliersSet,
         RustrictTrieSet,
         RustrictReplacementsSet,
         GamesRequested(Box<[(GameId, f32)]>),
         HttpServerRestarting,
+        /// By arena, where the main one is [`None`].
+        PeakClientsRequested(Box<[(Option<RealmName>, u32)]>),
         PlayerAliasOverridden(PlayerAlias),
         PlayerModeratorOverridden(bool),
         PlayerMuted(usize),
@@ -370,6 +494,8 @@ mod admin {
         RedirectSet(Option<ServerNumber>),
         ReferrersRequested(Box<[(Referrer, f32)]>),
         RegionsRequested(Box<[(RegionId, f32)]>),
+        /// Bitcode-encoded frames of game updates.
+        ReplayExported(Box<[u8]>),
         SeriesRequested(Owned<[(UnixTime, MetricsDataPointDto)]>),
         ServerIdRequested(ServerId),
         SnippetCleared,
@@ -377,5 +503,55 @@ mod admin {
         SnippetsRequested(Box<[SnippetDto]>),
         SummaryRequested(Box<MetricsSummaryDto>),
         UserAgentsRequested(Box<[(UserAgentId, f32)]>),
+        WorldEventStarted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rpc::{ServerFull, WebSocketQuery, PROTOCOL_VERSION};
+    use std::num::NonZeroU64;
+
+    #[test]
+    fn protocol_version() {
+        let query = |protocol_version| WebSocketQuery {
+            protocol_version,
+            player_id: None,
+            token: None,
+            session_token: None,
+            invitation_id: None,
+            referrer: None,
+            cohort_id: None,
+            date_created: None,
+            spectator: false,
+            queue_ticket: None,
+        };
+        assert_eq!(
+            query(Some(PROTOCOL_VERSION)).check_protocol_version(),
+            Ok(())
+        );
+        assert_eq!(query(None).check_protocol_version(), Ok(()));
+        assert!(query(Some(PROTOCOL_VERSION + 1))
+            .check_protocol_version()
+            .is_err());
+    }
+
+    #[test]
+    fn server_full() {
+        for queue_position in [None, Some(1), Some(42)] {
+            for queue_ticket in [None, NonZeroU64::new(u64::MAX)] {
+                let server_full = ServerFull {
+                    queue_position,
+                    queue_ticket,
+                };
+                assert_eq!(
+                    ServerFull::from_reason(&server_full.to_reason()),
+                    server_full
+                );
+            }
+        }
+        let garbled = ServerFull::from_reason("?");
+        assert_eq!(garbled.queue_position, None);
+        assert_eq!(garbled.queue_ticket, None);
     }
 }
@@ -20,6 +20,7 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
             let mut setters = Vec::with_capacity(named.len());
             let mut validators = Vec::with_capacity(named.len());
             let mut displayers = Vec::with_capacity(named.len());
+            let mut synced = Vec::with_capacity(named.len());
 
             for Field {
                 ident, ty, attrs, ..
@@ -33,6 +34,7 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
 
                 let mut storage = quote! { local };
                 let mut optional = false;
+                let mut sync = true;
                 let mut validations = Vec::new();
 
                 for attribute in attrs.into_iter().filter(|a| a.path.is_ident("setting")) {
@@ -104,8 +106,12 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
                                         optional = true;
                                     } else if path.is_ident("volatile") {
                                         storage = quote! { session };
+                                        sync = false;
                                     } else if path.is_ident("no_store") {
                                         storage = quote! { no_op };
+                                        sync = false;
+                                    } else if path.is_ident("no_sync") {
+                                        sync = false;
                                     } else {
                                         panic!("Unexpected path: {}", path.get_ident().unwrap());
                                     }
@@ -169,6 +175,9 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
                     validators.push(validator);
                 }
 
+                if sync {
+                    synced.push(ident_string);
+                }
                 loaders.push(loader);
                 getters.push(getter);
                 setters.push(setter);
@@ -176,6 +185,8 @@ pub(crate) fn derive_settings(input: TokenStream) -> TokenStream {
 
             let output = quote! {
                 impl Settings for #ident {
+                    const SYNCED: &'static [&'static str] = &[#(#synced),*];
+
                     fn load(browser_storages: &BrowserStorages, default: Self) -> Self {
                         Self {
                             #(#loaders)*
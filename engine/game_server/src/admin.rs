@@ -1,11 +1,12 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::arena::ArenaRepo;
 use crate::client::ClientRepo;
 use crate::context::Context;
 use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
-use crate::metric::{Bundle, MetricBundle, MetricRepo};
+use crate::metric::{Bundle, MetricBundle, MetricRepo, PeakClients};
 use crate::player::PlayerRepo;
 use crate::static_files::static_size_and_hash;
 use actix::{fut, ActorFutureExt, Handler, ResponseActFuture, WrapFuture};
@@ -14,7 +15,7 @@ use core_protocol::id::{PlayerId, RegionId, UserAgentId};
 use core_protocol::metrics::{MetricFilter, Metrics};
 use core_protocol::name::{PlayerAlias, Referrer};
 use core_protocol::rpc::{AdminRequest, AdminUpdate};
-use core_protocol::{get_unix_time_now, ClientHash, SnippetId};
+use core_protocol::{get_unix_time_now, ClientHash, RealmName, SnippetId};
 use minicdn::{EmbeddedMiniCdn, MiniCdn};
 use std::collections::HashMap;
 use std::hash::Hash;
@@ -23,7 +24,7 @@ use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr};
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Responsible for the admin interface.
 pub struct AdminRepo<G: GameArenaService> {
@@ -31,16 +32,23 @@ pub struct AdminRepo<G: GameArenaService> {
     pub(crate) client_hash: ClientHash,
     #[cfg(unix)]
     profile: Option<pprof::ProfilerGuard<'static>>,
+    /// When the last world event was started, for rate limiting.
+    last_world_event: Option<Instant>,
     _spooky: PhantomData<G>,
 }
 
 impl<G: GameArenaService> AdminRepo<G> {
+    /// Minimum time between starting world events.
+    const WORLD_EVENT_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+    const MAX_WORLD_EVENT_MINUTES: usize = 60;
+
     pub fn new(game_client: Arc<RwLock<MiniCdn>>, client_hash: ClientHash) -> Self {
         Self {
             game_client,
             client_hash,
             #[cfg(unix)]
             profile: None,
+            last_world_event: None,
             _spooky: PhantomData,
         }
     }
@@ -138,6 +146,65 @@ impl<G: GameArenaService> AdminRepo<G> {
         )))
     }
 
+    /// Starts a world event lasting a configurable amount of minutes. Limited to one per
+    /// [`Self::WORLD_EVENT_COOLDOWN`].
+    fn start_world_event(
+        &mut self,
+        event: &str,
+        minutes: usize,
+        service: &mut G,
+    ) -> Result<AdminUpdate, &'static str> {
+        if !(1..=Self::MAX_WORLD_EVENT_MINUTES).contains(&minutes) {
+            return Err("invalid world event duration");
+        }
+        if self
+            .last_world_event
+            .map_or(false, |last| last.elapsed() < Self::WORLD_EVENT_COOLDOWN)
+        {
+            return Err("world event rate limited");
+        }
+        service.start_world_event(event, Duration::from_secs(minutes as u64 * 60))?;
+        self.last_world_event = Some(Instant::now());
+        Ok(AdminUpdate::WorldEventStarted)
+    }
+
+    /// Exports the last `seconds` of an arena's history (see
+    /// [`ReplayBuffer`][`crate::replay::ReplayBuffer`]).
+    fn export_replay(
+        realm_name: Option<RealmName>,
+        seconds: u32,
+        arenas: &ArenaRepo<G>,
+    ) -> Result<AdminUpdate, &'static str> {
+        let replay = arenas
+            .get(realm_name)
+            .ok_or("nonexistent realm")?
+            .replay
+            .as_ref()
+            .ok_or("replays disabled")?;
+        let ticks = (seconds as f32 / G::TICK_PERIOD_SECS).ceil() as u32;
+        Ok(AdminUpdate::ReplayExported(
+            replay.export(ticks)?.into_boxed_slice(),
+        ))
+    }
+
+    /// Pauses or resumes a private arena. The main arena is public unless configured otherwise
+    /// (see [`Options::private_arena`][`crate::options::Options::private_arena`]).
+    fn pause_arena(
+        realm_name: Option<RealmName>,
+        paused: bool,
+        arenas: &mut ArenaRepo<G>,
+    ) -> Result<AdminUpdate, &'static str> {
+        if !arenas.is_private(realm_name) {
+            return Err("public arena can't be paused");
+        }
+        arenas
+            .get_mut(realm_name)
+            .ok_or("nonexistent realm")?
+            .service
+            .set_paused(paused)?;
+        Ok(AdminUpdate::ArenaPaused(paused))
+    }
+
     /// Restrict a given real player's chat to safe phrases for a configurable amount of minutes
     /// (0 means disable restriction).
     fn restrict_player(
@@ -238,6 +305,21 @@ impl<G: GameArenaService> AdminRepo<G> {
         ))
     }
 
+    /// Request the peak number of clients connected at once to each arena, over the current and
+    /// last 24 calendar hours.
+    fn request_peak_clients(metrics: &MetricRepo<G>) -> Result<AdminUpdate, &'static str> {
+        let mut peaks = PeakClients::default();
+        for bundle in iter::once(&metrics.current).chain(metrics.history.iter()) {
+            for (&realm_name, &peak) in &bundle.peak_clients {
+                let max = peaks.entry(realm_name).or_default();
+                *max = (*max).max(peak);
+            }
+        }
+        let mut list: Vec<_> = peaks.into_iter().collect();
+        list.sort_unstable();
+        Ok(AdminUpdate::PeakClientsRequested(list.into_boxed_slice()))
+    }
+
     fn request_category_inner<T: Hash + Eq + Copy>(
         &self,
         initial: impl IntoIterator<Item = T>,
@@ -425,6 +507,14 @@ impl<G: GameArenaService> Handler<AdminRequest> for Infrastructure<G> {
             AdminRequest::ClearSnippet { snippet_id } => Box::pin(fut::ready(
                 AdminRepo::clear_snippet(&mut self.clients, snippet_id),
             )),
+            AdminRequest::ExportReplay {
+                realm_name,
+                seconds,
+            } => Box::pin(fut::ready(AdminRepo::export_replay(
+                realm_name,
+                seconds,
+                &self.arenas,
+            ))),
             AdminRequest::SetSnippet {
                 snippet_id,
                 snippet,
@@ -441,6 +531,9 @@ impl<G: GameArenaService> Handler<AdminRequest> for Infrastructure<G> {
                 Box::pin(fut::ready(AdminRepo::request_day(&self.metrics, filter)))
             }
             AdminRequest::RequestGames => Box::pin(fut::ready(self.admin.request_games())),
+            AdminRequest::RequestPeakClients => {
+                Box::pin(fut::ready(AdminRepo::request_peak_clients(&self.metrics)))
+            }
             AdminRequest::RequestPlayers => Box::pin(fut::ready(
                 self.admin
                     .request_players(&self.arenas.main().context.players),
@@ -460,6 +553,9 @@ impl<G: GameArenaService> Handler<AdminRequest> for Infrastructure<G> {
                 moderator,
                 &self.arenas.main().context.players,
             ))),
+            AdminRequest::PauseArena { realm_name, paused } => Box::pin(fut::ready(
+                AdminRepo::pause_arena(realm_name, paused, &mut self.arenas),
+            )),
             AdminRequest::RestrictPlayer { player_id, minutes } => Box::pin(fut::ready(
                 self.admin
                     .restrict_player(player_id, minutes, &self.arenas.main().context.players),
@@ -493,6 +589,17 @@ impl<G: GameArenaService> Handler<AdminRequest> for Infrastructure<G> {
                 message,
                 &mut self.arenas.main_mut().context,
             ))),
+            AdminRequest::StartWorldEvent { event, minutes } => Box::pin(fut::ready(
+                self.admin
+                    .start_world_event(&event, minutes, &mut self.arenas.main_mut().service),
+            )),
+            AdminRequest::SetGenerationMultipliers { multipliers } => Box::pin(fut::ready(
+                self.arenas
+                    .main_mut()
+                    .service
+                    .set_generation_multipliers(&multipliers)
+                    .map(|_| AdminUpdate::GenerationMultipliersSet),
+            )),
             AdminRequest::SetGameClient(client) => {
                 Box::pin(fut::ready(self.admin.set_game_client(client)))
             }
@@ -521,3 +628,57 @@ impl<G: GameArenaService> Handler<AdminRequest> for Infrastructure<G> {
 fn seconds_ceil(duration: Duration) -> usize {
     ((duration.as_secs() + 59) / 60) as usize
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::admin::AdminRepo;
+    use crate::arena::ArenaRepo;
+    use crate::context_service::ContextService;
+    use crate::game_service::MockGame;
+    use crate::metric::{MetricBundle, MetricRepo};
+    use core_protocol::rpc::AdminUpdate;
+    use core_protocol::RealmName;
+
+    #[test]
+    fn pause_arena() {
+        let arena = || ContextService::<MockGame>::new(None, None, None, None);
+        let mut public = ArenaRepo::new(arena(), false);
+        assert_eq!(
+            AdminRepo::pause_arena(None, true, &mut public).unwrap_err(),
+            "public arena can't be paused"
+        );
+        assert_eq!(
+            AdminRepo::pause_arena(Some(RealmName::new("test")), true, &mut public).unwrap_err(),
+            "nonexistent realm"
+        );
+
+        let mut private = ArenaRepo::new(arena(), true);
+        assert!(matches!(
+            AdminRepo::pause_arena(None, true, &mut private),
+            Ok(AdminUpdate::ArenaPaused(true))
+        ));
+        assert!(matches!(
+            AdminRepo::pause_arena(None, false, &mut private),
+            Ok(AdminUpdate::ArenaPaused(false))
+        ));
+    }
+
+    #[test]
+    fn request_peak_clients() {
+        let realm_name = Some(RealmName::new("foo"));
+        let mut metrics = MetricRepo::<MockGame>::new();
+        let mut previous = MetricBundle::new(0);
+        previous.peak_clients.insert(None, 5);
+        previous.peak_clients.insert(realm_name, 2);
+        metrics.history.write(previous);
+        metrics.current.peak_clients.insert(None, 3);
+        metrics.current.peak_clients.insert(realm_name, 4);
+
+        let Ok(AdminUpdate::PeakClientsRequested(peaks)) =
+            AdminRepo::request_peak_clients(&metrics)
+        else {
+            panic!("expected peak clients");
+        };
+        assert_eq!(&*peaks, &[(None, 5), (realm_name, 4)]);
+    }
+}
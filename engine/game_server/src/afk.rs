@@ -0,0 +1,146 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use server_util::rate_limiter::{RateLimiterProps, RateLimiterState};
+use std::time::{Duration, Instant};
+
+/// What should happen to a client that hasn't sent a game command in a while.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AfkAction {
+    /// Tell the player they will be kicked soon.
+    Warn,
+    /// Remove the player from the game.
+    Kick,
+}
+
+/// AFK related data stored in player.
+#[derive(Debug)]
+pub struct ClientAfkData {
+    /// When the last game command was received.
+    last_command: Instant,
+    /// Whether the player was warned since [`Self::last_command`].
+    warned: bool,
+    /// Limits how often heartbeats may count as activity.
+    heartbeat_rate_limit: RateLimiterState,
+}
+
+impl ClientAfkData {
+    /// Maximum amount of warning before being kicked.
+    const WARNING: Duration = Duration::from_secs(30);
+    /// Clients are expected to send heartbeats far less often than this.
+    const HEARTBEAT_RATE: RateLimiterProps =
+        RateLimiterProps::const_new(Duration::from_secs(10), 0);
+
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_command: now,
+            warned: false,
+            heartbeat_rate_limit: RateLimiterState::default(),
+        }
+    }
+
+    /// Call when a game command is received.
+    pub fn command(&mut self, now: Instant) {
+        self.last_command = now;
+        self.warned = false;
+    }
+
+    /// Call when a heartbeat is received from an idle but present client. Counts as a command
+    /// unless heartbeats are arriving too quickly, in which case returns false.
+    pub fn heartbeat(&mut self, now: Instant) -> bool {
+        if self
+            .heartbeat_rate_limit
+            .should_limit_rate_with_now(&Self::HEARTBEAT_RATE, now)
+        {
+            return false;
+        }
+        self.command(now);
+        true
+    }
+
+    /// Returns an action at most once per warning and kick. `timeout` is how long without
+    /// commands before kicking.
+    pub fn check(&mut self, now: Instant, timeout: Duration) -> Option<AfkAction> {
+        let idle = now.saturating_duration_since(self.last_command);
+        if idle >= timeout {
+            // Start over so the kick isn't repeated.
+            self.command(now);
+            Some(AfkAction::Kick)
+        } else if !self.warned && idle >= timeout - Self::WARNING.min(timeout / 2) {
+            self.warned = true;
+            Some(AfkAction::Warn)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::afk::{AfkAction, ClientAfkData};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn afk_transition() {
+        let timeout = Duration::from_secs(120);
+        let start = Instant::now();
+        let mut afk = ClientAfkData::new(start);
+
+        let mut actions = Vec::new();
+        for secs in 0..=240 {
+            if let Some(action) = afk.check(start + Duration::from_secs(secs), timeout) {
+                actions.push((secs, action));
+            }
+        }
+
+        assert_eq!(
+            actions,
+            vec![
+                (90, AfkAction::Warn),
+                (120, AfkAction::Kick),
+                (210, AfkAction::Warn),
+                (240, AfkAction::Kick)
+            ]
+        );
+    }
+
+    #[test]
+    fn afk_command_resets() {
+        let timeout = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut afk = ClientAfkData::new(start);
+
+        // Short timeouts get proportionally short warnings.
+        assert_eq!(
+            afk.check(start + Duration::from_secs(5), timeout),
+            Some(AfkAction::Warn)
+        );
+        afk.command(start + Duration::from_secs(6));
+        assert_eq!(afk.check(start + Duration::from_secs(10), timeout), None);
+        assert_eq!(
+            afk.check(start + Duration::from_secs(11), timeout),
+            Some(AfkAction::Warn)
+        );
+        assert_eq!(afk.check(start + Duration::from_secs(15), timeout), None);
+        assert_eq!(
+            afk.check(start + Duration::from_secs(16), timeout),
+            Some(AfkAction::Kick)
+        );
+    }
+
+    #[test]
+    fn afk_heartbeat() {
+        let timeout = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut afk = ClientAfkData::new(start);
+
+        assert!(afk.heartbeat(start + Duration::from_secs(40)));
+        assert_eq!(afk.check(start + Duration::from_secs(60), timeout), None);
+        // Too soon after the last heartbeat.
+        assert!(!afk.heartbeat(start + Duration::from_secs(45)));
+        assert_eq!(
+            afk.check(start + Duration::from_secs(100), timeout),
+            Some(AfkAction::Kick)
+        );
+    }
+}
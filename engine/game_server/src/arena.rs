@@ -4,18 +4,26 @@ use std::collections::HashMap;
 
 pub(crate) struct ArenaRepo<G: GameArenaService> {
     main: ContextService<G>,
+    /// Whether [`Self::main`] is private, like all realms are.
+    main_private: bool,
     realms: HashMap<RealmName, ContextService<G>>,
 }
 
 #[allow(unused)]
 impl<G: GameArenaService> ArenaRepo<G> {
-    pub(crate) fn new(main: ContextService<G>) -> Self {
+    pub(crate) fn new(main: ContextService<G>, main_private: bool) -> Self {
         Self {
             main,
+            main_private,
             realms: HashMap::new(),
         }
     }
 
+    /// Private arenas may be paused, unlike public ones.
+    pub(crate) fn is_private(&self, realm_name: Option<RealmName>) -> bool {
+        realm_name.is_some() || self.main_private
+    }
+
     pub(crate) fn main(&self) -> &ContextService<G> {
         &self.main
     }
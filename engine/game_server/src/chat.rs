@@ -4,6 +4,7 @@
 use crate::game_service::GameArenaService;
 use crate::metric::MetricRepo;
 use crate::player::PlayerRepo;
+use crate::request_error::RequestError;
 #[cfg(feature = "teams")]
 use crate::team::TeamRepo;
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, AhoCorasickKind};
@@ -101,21 +102,23 @@ impl<G: GameArenaService> ChatRepo<G> {
         req_player_id: PlayerId,
         mute_player_id: PlayerId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         if req_player_id == mute_player_id {
-            return Err("cannot mute self");
+            return Err(RequestError::InvalidInput("cannot mute self"));
         }
         if !players.contains(mute_player_id) {
-            return Err("cannot mute nonexistent player");
+            return Err(RequestError::InvalidState("cannot mute nonexistent player"));
         }
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("nonexistent player")?;
-        let req_client = req_player.client_mut().ok_or("only clients can mute")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
+        let req_client = req_player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can mute"))?;
         if req_client.chat.muted.insert(mute_player_id) {
             Ok(ChatUpdate::Muted(mute_player_id))
         } else {
-            Err("already muted")
+            Err(RequestError::InvalidState("already muted"))
         }
     }
 
@@ -125,18 +128,20 @@ impl<G: GameArenaService> ChatRepo<G> {
         req_player_id: PlayerId,
         unmute_player_id: PlayerId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         if req_player_id == unmute_player_id {
-            return Err("cannot unmute self");
+            return Err(RequestError::InvalidInput("cannot unmute self"));
         }
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("nonexistent player")?;
-        let req_client = req_player.client_mut().ok_or("only clients can unmute")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
+        let req_client = req_player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can unmute"))?;
         if req_client.chat.muted.remove(&unmute_player_id) {
             Ok(ChatUpdate::Unmuted(unmute_player_id))
         } else {
-            Err("player wasn't muted")
+            Err(RequestError::InvalidState("player wasn't muted"))
         }
     }
 
@@ -152,21 +157,25 @@ impl<G: GameArenaService> ChatRepo<G> {
         restrict_player_id: PlayerId,
         minutes: u32,
         players: &PlayerRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         if req_player_id == restrict_player_id {
-            return Err("cannot restrict self");
+            return Err(RequestError::InvalidInput("cannot restrict self"));
         }
         let req_player = players
             .borrow_player(req_player_id)
-            .ok_or("nonexistent player")?;
-        let req_client = req_player.client().ok_or("not a real player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
+        let req_client = req_player
+            .client()
+            .ok_or(RequestError::NotAllowed("not a real player"))?;
         if !req_client.moderator {
-            return Err("permission denied");
+            return Err(RequestError::NotAllowed("permission denied"));
         }
         let mut restrict_player = players
             .borrow_player_mut(restrict_player_id)
-            .ok_or("nonexistent player")?;
-        let restrict_client = restrict_player.client_mut().ok_or("not a real player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
+        let restrict_client = restrict_player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("not a real player"))?;
         let minutes = minutes.min(1440);
         if let Some(restrict_until) =
             Self::minutes_to_instant(minutes, restrict_client.chat.context.restricted_until())
@@ -190,7 +199,7 @@ impl<G: GameArenaService> ChatRepo<G> {
                 minutes,
             })
         } else {
-            Err("overflow")
+            Err(RequestError::InvalidInput("overflow"))
         }
     }
 
@@ -199,13 +208,15 @@ impl<G: GameArenaService> ChatRepo<G> {
         req_player_id: PlayerId,
         minutes: u32,
         players: &PlayerRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         let req_player = players
             .borrow_player(req_player_id)
-            .ok_or("nonexistent player")?;
-        let req_client = req_player.client().ok_or("not a real player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
+        let req_client = req_player
+            .client()
+            .ok_or(RequestError::NotAllowed("not a real player"))?;
         if !req_client.moderator {
-            return Err("permission denied");
+            return Err(RequestError::NotAllowed("permission denied"));
         }
         let clamped = minutes.min(60);
         self.safe_mode_until = Self::minutes_to_instant(clamped, None);
@@ -217,13 +228,15 @@ impl<G: GameArenaService> ChatRepo<G> {
         req_player_id: PlayerId,
         minutes: u32,
         players: &PlayerRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         let req_player = players
             .borrow_player(req_player_id)
-            .ok_or("nonexistent player")?;
-        let req_client = req_player.client().ok_or("not a real player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
+        let req_client = req_player
+            .client()
+            .ok_or(RequestError::NotAllowed("not a real player"))?;
         if !req_client.moderator {
-            return Err("permission denied");
+            return Err(RequestError::NotAllowed("permission denied"));
         }
         let clamped = minutes.min(120);
         self.slow_mode_until = Self::minutes_to_instant(clamped, None);
@@ -241,7 +254,7 @@ impl<G: GameArenaService> ChatRepo<G> {
         players: &mut PlayerRepo<G>,
         #[cfg(feature = "teams")] teams: &TeamRepo<G>,
         metrics: &mut MetricRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         if let Some(text) = self.try_execute_command(req_player_id, &message, service, players) {
             if let Some(mut req_player) = players.borrow_player_mut(req_player_id) {
                 let alias = req_player.alias();
@@ -270,7 +283,7 @@ impl<G: GameArenaService> ChatRepo<G> {
 
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("nonexistent player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
 
         #[cfg(feature = "teams")]
         let team = req_player.team_id().and_then(|t| teams.get(t));
@@ -278,11 +291,11 @@ impl<G: GameArenaService> ChatRepo<G> {
         let team = service.get_team_members(req_player_id);
 
         if !req_player.is_alive() {
-            return Err("must be alive to chat");
+            return Err(RequestError::InvalidState("must be alive to chat"));
         }
 
         if whisper && team.is_none() {
-            return Err("no one to whisper to");
+            return Err(RequestError::InvalidState("no one to whisper to"));
         }
 
         // If the team no longer exists, no members should exist.
@@ -418,6 +431,11 @@ impl<G: GameArenaService> ChatRepo<G> {
                 }
             }
             Err(reason) => {
+                let error = match reason {
+                    BlockReason::Spam(_) => RequestError::RateLimited("spam"),
+                    BlockReason::Muted(_) => RequestError::NotAllowed("muted"),
+                    _ => RequestError::InvalidInput("message blocked"),
+                };
                 if let Some(req_client) = req_player.client_mut() {
                     let warning = MessageDto {
                         alias: G::authority_alias(),
@@ -435,6 +453,7 @@ impl<G: GameArenaService> ChatRepo<G> {
                 } else {
                     debug_assert!(false, "non-clients cannot end up here");
                 }
+                return Err(error);
             }
         }
         Ok(ChatUpdate::Sent)
@@ -459,7 +478,7 @@ impl<G: GameArenaService> ChatRepo<G> {
         players: &mut PlayerRepo<G>,
         #[cfg(feature = "teams")] teams: &TeamRepo<G>,
         metrics: &mut MetricRepo<G>,
-    ) -> Result<ChatUpdate, &'static str> {
+    ) -> Result<ChatUpdate, RequestError> {
         match request {
             ChatRequest::Mute(player_id) => self.mute_player(req_player_id, player_id, players),
             ChatRequest::Unmute(player_id) => self.unmute_player(req_player_id, player_id, players),
@@ -627,7 +646,7 @@ impl<G: GameArenaService> ChatRepo<G> {
                         if let Some(minutes) = parse_minutes(arg) {
                             self.$setter(req_player_id, minutes, players)
                                 .map(|_| print_until_status($name, self.$getter))
-                                .unwrap_or_else(|e| String::from(e))
+                                .unwrap_or_else(|e| e.to_string())
                         } else {
                             String::from("failed to parse argument as minutes")
                         }
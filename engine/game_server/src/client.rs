@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::afk::ClientAfkData;
 use crate::chat::{ChatRepo, ClientChatData};
 use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
@@ -10,15 +11,18 @@ use crate::liveboard::LiveboardRepo;
 use crate::metric::{ClientMetricData, MetricRepo};
 use crate::plasma::PlasmaClient;
 use crate::player::{PlayerData, PlayerRepo, PlayerTuple};
+use crate::queue::AdmissionQueue;
+use crate::request_error::RequestError;
 use crate::system::SystemRepo;
+use crate::trace::{TraceFormat, TraceRecord};
 use actix::{Context as ActorContext, Handler, Message};
 use atomic_refcell::AtomicRefCell;
-use core_protocol::dto::{InvitationDto, ServerDto};
+use core_protocol::dto::{FeatureFlags, InvitationDto, ServerDto, SettingsDto};
 use core_protocol::id::{CohortId, InvitationId, PlayerId, ServerId, UserAgentId};
 use core_protocol::name::{PlayerAlias, Referrer};
 use core_protocol::rpc::{
     AdType, ClientRequest, ClientUpdate, LeaderboardUpdate, LiveboardUpdate, PlayerUpdate, Request,
-    SystemUpdate, Update,
+    ServerFull, SystemUpdate, Update,
 };
 use core_protocol::{
     get_unix_time_now, ArenaToken, NickName, PlasmaRequestV1, RealmName, ServerNumber,
@@ -39,6 +43,7 @@ use std::fs::OpenOptions;
 use std::io::Write;
 use std::marker::PhantomData;
 use std::net::IpAddr;
+use std::num::NonZeroU64;
 use std::str::{self, FromStr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -62,24 +67,141 @@ pub struct ClientRepo<G: GameArenaService> {
     pub(crate) snippets: HashMap<SnippetId, Arc<str>>,
     /// Where to log traces to.
     trace_log: Option<Arc<str>>,
+    trace_format: TraceFormat,
+    /// How long alive players may go without sending game commands.
+    afk_timeout: Option<Duration>,
+    /// Sent to clients when they connect.
+    features: FeatureFlags,
+    /// Whether aliases of live players must be unique within an arena.
+    alias_uniqueness: AliasUniqueness,
+    /// Real players per arena beyond which new clients must wait in [`Self::queues`].
+    max_players: Option<usize>,
+    /// Clients waiting for a slot, per arena (realm).
+    queues: HashMap<Option<RealmName>, AdmissionQueue>,
+    /// Connected websockets per ip address beyond which new clients are rejected.
+    max_connections_per_ip: usize,
+    /// Connected websockets per ip address. Clients are never forgotten while connected, so
+    /// only [`Self::register`] and [`Self::unregister`] change this.
+    connections: HashMap<IpAddr, usize>,
     _spooky: PhantomData<G>,
 }
 
+/// What to do when a player requests an alias that a live player in the same arena (realm)
+/// already has.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum AliasUniqueness {
+    /// Allow duplicates.
+    #[default]
+    Permissive,
+    /// Fail the request.
+    Reject,
+    /// Append a number, e.g. `Bob 2`.
+    Suffix,
+}
+
+impl FromStr for AliasUniqueness {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "permissive" => Self::Permissive,
+            "reject" => Self::Reject,
+            "suffix" => Self::Suffix,
+            _ => return Err("expected permissive, reject, or suffix"),
+        })
+    }
+}
+
 #[derive(RustEmbed)]
 #[folder = "./src/snippets"]
 struct ReferrerSnippet;
 
 impl<G: GameArenaService> ClientRepo<G> {
-    pub fn new(trace_log: Option<String>, authenticate: RateLimiterProps) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        trace_log: Option<String>,
+        trace_format: TraceFormat,
+        authenticate: RateLimiterProps,
+        afk_timeout: Option<Duration>,
+        features: FeatureFlags,
+        alias_uniqueness: AliasUniqueness,
+        max_players: Option<usize>,
+        max_connections_per_ip: usize,
+    ) -> Self {
         Self {
             authenticate_rate_limiter: authenticate.into(),
             prune_rate_limiter: RateLimiter::new(Duration::from_secs(1), 0),
             snippets: Self::load_default_snippets(),
             trace_log: trace_log.map(Into::into),
+            trace_format,
+            afk_timeout,
+            features,
+            alias_uniqueness,
+            max_players,
+            queues: HashMap::new(),
+            max_connections_per_ip,
+            connections: HashMap::new(),
             _spooky: PhantomData,
         }
     }
 
+    /// Whether a new client holding `queue_ticket` may join the arena of `players`, or must wait
+    /// because it is full (see [`Self::max_players`]).
+    pub(crate) fn admit(
+        &mut self,
+        realm_name: Option<RealmName>,
+        queue_ticket: Option<NonZeroU64>,
+        players: &PlayerRepo<G>,
+        now: Instant,
+    ) -> Result<(), ServerFull> {
+        let Some(max_players) = self.max_players else {
+            return Ok(());
+        };
+        let real_players = players.iter_borrow().filter(|p| !p.is_bot()).count();
+        let free = max_players.saturating_sub(real_players);
+        let queue = self.queues.entry(realm_name).or_default();
+        let result = queue.admit(queue_ticket, free, now);
+        if queue.is_empty() {
+            self.queues.remove(&realm_name);
+        }
+        result.map_err(|(queue_position, queue_ticket)| ServerFull {
+            queue_position: Some(queue_position),
+            queue_ticket: Some(queue_ticket),
+        })
+    }
+
+    /// Whether `queue_ticket` holds a place in line for the arena of `realm_name`.
+    fn is_waiting(&self, realm_name: Option<RealmName>, queue_ticket: Option<NonZeroU64>) -> bool {
+        queue_ticket
+            .zip(self.queues.get(&realm_name))
+            .map_or(false, |(ticket, queue)| queue.is_waiting(ticket))
+    }
+
+    /// Whether `ip_address` may connect another new client (see
+    /// [`Self::max_connections_per_ip`]).
+    fn check_connections(&self, ip_address: IpAddr) -> Result<(), &'static str> {
+        if self.connections.get(&ip_address).copied().unwrap_or(0) < self.max_connections_per_ip {
+            Ok(())
+        } else {
+            Err("too many connections from this ip address")
+        }
+    }
+
+    fn connect(&mut self, ip_address: IpAddr) {
+        *self.connections.entry(ip_address).or_default() += 1;
+    }
+
+    fn disconnect(&mut self, ip_address: IpAddr) {
+        if let Entry::Occupied(mut occupied) = self.connections.entry(ip_address) {
+            *occupied.get_mut() -= 1;
+            if *occupied.get() == 0 {
+                occupied.remove();
+            }
+        } else {
+            debug_assert!(false, "disconnect without connect");
+        }
+    }
+
     fn load_default_snippets() -> HashMap<SnippetId, Arc<str>> {
         let mut hash_map = HashMap::new();
         for key in ReferrerSnippet::iter() {
@@ -125,6 +247,9 @@ impl<G: GameArenaService> ClientRepo<G> {
         realm_name: Option<RealmName>,
         game: &mut G,
     ) {
+        #[cfg(feature = "tracing")]
+        let _span = crate::log::register_span(player_id, realm_name).entered();
+
         let player_tuple = match players.get(player_id) {
             Some(player_tuple) => player_tuple,
             None => {
@@ -152,6 +277,7 @@ impl<G: GameArenaService> ClientRepo<G> {
                 player_id,
                 token: client.token,
                 date_created: client.metrics.date_created,
+                features: self.features.clone(),
             }),
         });
 
@@ -160,6 +286,7 @@ impl<G: GameArenaService> ClientRepo<G> {
         client.chat.forget_state();
         #[cfg(feature = "teams")]
         client.team.forget_state();
+        client.afk = ClientAfkData::new(Instant::now());
 
         // If there is a JS snippet for the cohort and referrer, send it to client for eval.
         let snippet = client
@@ -198,6 +325,14 @@ impl<G: GameArenaService> ClientRepo<G> {
             observer: register_observer.clone(),
         };
         let old_status = std::mem::replace(&mut client.status, new_status);
+        // Pending clients reserved their connection in [`Authenticate`].
+        if matches!(
+            old_status,
+            ClientStatus::Limbo { .. } | ClientStatus::LeavingLimbo { .. }
+        ) {
+            let ip_address = client.ip_address;
+            self.connect(ip_address);
+        }
 
         match old_status {
             ClientStatus::Connected { observer } => {
@@ -210,7 +345,7 @@ impl<G: GameArenaService> ClientRepo<G> {
                 drop(player);
             }
             ClientStatus::Pending { .. } => {
-                metrics.start_visit(client);
+                metrics.start_visit(client, realm_name);
 
                 drop(player);
 
@@ -284,6 +419,8 @@ impl<G: GameArenaService> ClientRepo<G> {
                 client.status = ClientStatus::Limbo {
                     expiry: Instant::now() + G::LIMBO,
                 };
+                let ip_address = client.ip_address;
+                self.disconnect(ip_address);
                 info!("player {:?} is in limbo", player_id);
             }
         }
@@ -468,6 +605,7 @@ impl<G: GameArenaService> ClientRepo<G> {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn prune(
         &mut self,
+        realm_name: Option<RealmName>,
         service: &mut G,
         players: &mut PlayerRepo<G>,
         #[cfg(feature = "teams")] teams: &mut TeamRepo<G>,
@@ -483,6 +621,9 @@ impl<G: GameArenaService> ClientRepo<G> {
             return;
         }
 
+        #[cfg(feature = "tracing")]
+        let _span = crate::log::prune_span().entered();
+
         let immut_players = &*players;
         let to_forget: Vec<PlayerId> = immut_players
             .players
@@ -493,6 +634,16 @@ impl<G: GameArenaService> ClientRepo<G> {
                 if let Some(client_data) = player.client_mut() {
                     match &client_data.status {
                         ClientStatus::Connected { .. } => {
+                            if let Some(action) = self
+                                .afk_timeout
+                                .and_then(|timeout| client_data.afk.check(now, timeout))
+                            {
+                                drop(player);
+                                if service.is_alive(player_tuple) {
+                                    info!("player_id {:?} is afk ({:?})", player_id, action);
+                                    service.player_afk(player_tuple, action);
+                                }
+                            }
                             // Wait for transition to limbo via unregister, which is the "proper" channel.
                             false
                         }
@@ -524,15 +675,21 @@ impl<G: GameArenaService> ClientRepo<G> {
                                         session_token,
                                     });
                                 }
-                                metrics.stop_visit(&mut *player);
+                                metrics.stop_visit(&mut *player, realm_name);
                                 info!("player_id {:?} expired from limbo", player_id);
 
                                 true
                             }
                         }
                         ClientStatus::Pending { expiry } => {
-                            // Not actually in game, so no cleanup required.
-                            &now > expiry
+                            // Not actually in game, so only the reserved connection needs to be
+                            // released.
+                            let expired = &now > expiry;
+                            if expired {
+                                let ip_address = client_data.ip_address;
+                                self.disconnect(ip_address);
+                            }
+                            expired
                         }
                     }
                 } else {
@@ -558,13 +715,20 @@ impl<G: GameArenaService> ClientRepo<G> {
         command: G::GameRequest,
         service: &mut G,
         players: &PlayerRepo<G>,
-    ) -> Result<Option<G::GameUpdate>, &'static str> {
+    ) -> Result<Option<G::GameUpdate>, RequestError> {
         if let Some(player_data) = players.get(player_id) {
+            if let Some(client) = player_data.borrow_player_mut().client_mut() {
+                if client.spectator && !G::spectator_command(&command) {
+                    return Err(RequestError::NotAllowed("spectators can't play"));
+                }
+                client.afk.command(Instant::now());
+            }
+
             // Game updates for all players are usually processed at once, but we also allow
             // one-off responses.
             Ok(service.player_command(command, player_data, players))
         } else {
-            Err("nonexistent observer")
+            Err(RequestError::InvalidState("nonexistent observer"))
         }
     }
 
@@ -575,7 +739,7 @@ impl<G: GameArenaService> ClientRepo<G> {
         player_id: PlayerId,
         session_token: SessionToken,
         plasma: &PlasmaClient,
-    ) -> Result<ClientUpdate, &'static str> {
+    ) -> Result<ClientUpdate, RequestError> {
         if let Some(mut player) = players.borrow_player_mut(player_id) {
             if let Some(client) = player.client_mut() {
                 if client.session_token != Some(session_token) {
@@ -595,10 +759,10 @@ impl<G: GameArenaService> ClientRepo<G> {
                 Ok(ClientUpdate::LoggedIn(session_token))
             } else {
                 debug_assert!(false);
-                Err("bot")
+                Err(RequestError::NotAllowed("bot"))
             }
         } else {
-            Err("nonexistent observer")
+            Err(RequestError::InvalidState("nonexistent observer"))
         }
     }
 
@@ -606,37 +770,115 @@ impl<G: GameArenaService> ClientRepo<G> {
     fn set_alias(
         player_id: PlayerId,
         alias: PlayerAlias,
+        alias_uniqueness: AliasUniqueness,
         players: &PlayerRepo<G>,
-    ) -> Result<ClientUpdate, &'static str> {
+    ) -> Result<ClientUpdate, RequestError> {
+        let censored_alias = PlayerAlias::new_sanitized(alias.as_str());
+        let censored_alias =
+            Self::unique_alias(player_id, censored_alias, alias_uniqueness, players)?;
+
         let mut player = players
             .borrow_player_mut(player_id)
-            .ok_or("player doesn't exist")?;
+            .ok_or(RequestError::InvalidState("player doesn't exist"))?;
 
         if player
             .alive_duration()
             .map(|d| d > Duration::from_secs(1))
             .unwrap_or(false)
         {
-            return Err("cannot change alias while alive");
+            return Err(RequestError::InvalidState(
+                "cannot change alias while alive",
+            ));
         }
 
-        let client = player.client_mut().ok_or("only clients can set alias")?;
-        let censored_alias = PlayerAlias::new_sanitized(alias.as_str());
+        let client = player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can set alias"))?;
         client.alias = censored_alias;
         Ok(ClientUpdate::AliasSet(censored_alias))
     }
 
+    /// Applies `alias_uniqueness` to an alias requested by `player_id`. The default alias is
+    /// exempt, as is the requesting player's own alias.
+    fn unique_alias(
+        player_id: PlayerId,
+        alias: PlayerAlias,
+        alias_uniqueness: AliasUniqueness,
+        players: &PlayerRepo<G>,
+    ) -> Result<PlayerAlias, RequestError> {
+        if alias_uniqueness == AliasUniqueness::Permissive || alias == G::default_alias() {
+            return Ok(alias);
+        }
+
+        let in_use = |alias: PlayerAlias| {
+            players
+                .iter_borrow()
+                .any(|p| p.player_id != player_id && p.is_alive() && p.alias() == alias)
+        };
+
+        if !in_use(alias) {
+            return Ok(alias);
+        }
+
+        match alias_uniqueness {
+            AliasUniqueness::Permissive => Ok(alias),
+            AliasUniqueness::Reject => Err(RequestError::InvalidInput("alias in use")),
+            AliasUniqueness::Suffix => (2..=99)
+                .map(|n| Self::suffixed_alias(alias, n))
+                .find(|&suffixed| !in_use(suffixed))
+                .ok_or(RequestError::InvalidInput("alias in use")),
+        }
+    }
+
+    /// Re-applies [`Self::alias_uniqueness`] to players that just spawned, because aliases set
+    /// while dead (see [`Self::set_alias`]) may have been taken since. Spawning can't fail, so
+    /// taken aliases are suffixed even if [`AliasUniqueness::Reject`].
+    pub(crate) fn unique_spawned_aliases(&self, spawned: &[PlayerId], players: &PlayerRepo<G>) {
+        if self.alias_uniqueness == AliasUniqueness::Permissive {
+            return;
+        }
+        for &player_id in spawned {
+            let Some(alias) = players
+                .borrow_player(player_id)
+                .and_then(|p| p.client().map(|c| c.alias))
+            else {
+                continue;
+            };
+            // Only fails if every suffix is taken, in which case the duplicate is allowed.
+            let Ok(unique) = Self::unique_alias(player_id, alias, AliasUniqueness::Suffix, players)
+            else {
+                continue;
+            };
+            if unique != alias {
+                let mut player = players.borrow_player_mut(player_id).unwrap();
+                player.client_mut().unwrap().alias = unique;
+            }
+        }
+    }
+
+    /// `alias` followed by ` {n}`, truncating `alias` as necessary to fit.
+    fn suffixed_alias(alias: PlayerAlias, n: usize) -> PlayerAlias {
+        let suffix = format!(" {n}");
+        let mut base = alias.as_str();
+        while PlayerAlias::from_str(&format!("{base}{suffix}")).is_err() {
+            base = &base[..base.char_indices().last().map_or(0, |(i, _)| i)];
+        }
+        PlayerAlias::new_unsanitized(&format!("{}{suffix}", base.trim_end()))
+    }
+
     /// Record client frames per second (FPS) for statistical purposes.
     fn tally_ad(
         player_id: PlayerId,
         ad_type: AdType,
         players: &PlayerRepo<G>,
         metrics: &mut MetricRepo<G>,
-    ) -> Result<ClientUpdate, &'static str> {
+    ) -> Result<ClientUpdate, RequestError> {
         let mut player = players
             .borrow_player_mut(player_id)
-            .ok_or("player doesn't exist")?;
-        let client = player.client_mut().ok_or("only clients can tally ads")?;
+            .ok_or(RequestError::InvalidState("player doesn't exist"))?;
+        let client = player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can tally ads"))?;
         metrics.mutate_with(
             |metrics| {
                 let metric = match ad_type {
@@ -651,22 +893,43 @@ impl<G: GameArenaService> ClientRepo<G> {
         Ok(ClientUpdate::AdTallied)
     }
 
+    /// Treats an idle client's heartbeat as activity (see [`ClientAfkData::heartbeat`]).
+    fn heartbeat(
+        player_id: PlayerId,
+        players: &PlayerRepo<G>,
+    ) -> Result<ClientUpdate, RequestError> {
+        let mut player = players
+            .borrow_player_mut(player_id)
+            .ok_or(RequestError::InvalidState("player doesn't exist"))?;
+        let client = player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can heartbeat"))?;
+
+        if client.afk.heartbeat(Instant::now()) {
+            Ok(ClientUpdate::HeartbeatReceived)
+        } else {
+            Err(RequestError::RateLimited("heartbeat too soon"))
+        }
+    }
+
     /// Record client frames per second (FPS) for statistical purposes.
     fn tally_fps(
         player_id: PlayerId,
         fps: f32,
         players: &PlayerRepo<G>,
-    ) -> Result<ClientUpdate, &'static str> {
+    ) -> Result<ClientUpdate, RequestError> {
         let mut player = players
             .borrow_player_mut(player_id)
-            .ok_or("player doesn't exist")?;
-        let client = player.client_mut().ok_or("only clients can tally fps")?;
+            .ok_or(RequestError::InvalidState("player doesn't exist"))?;
+        let client = player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can tally fps"))?;
 
         client.metrics.fps = sanitize_tps(fps);
         if client.metrics.fps.is_some() {
             Ok(ClientUpdate::FpsTallied)
         } else {
-            Err("invalid fps")
+            Err(RequestError::InvalidInput("invalid fps"))
         }
     }
 
@@ -677,11 +940,13 @@ impl<G: GameArenaService> ClientRepo<G> {
         message: String,
         players: &PlayerRepo<G>,
         metrics: &mut MetricRepo<G>,
-    ) -> Result<ClientUpdate, &'static str> {
+    ) -> Result<ClientUpdate, RequestError> {
         let mut player = players
             .borrow_player_mut(player_id)
-            .ok_or("player doesn't exist")?;
-        let client = player.client_mut().ok_or("only clients can trace")?;
+            .ok_or(RequestError::InvalidState("player doesn't exist"))?;
+        let client = player
+            .client_mut()
+            .ok_or(RequestError::NotAllowed("only clients can trace"))?;
 
         #[cfg(debug_assertions)]
         let trace_limit = None;
@@ -689,7 +954,7 @@ impl<G: GameArenaService> ClientRepo<G> {
         let trace_limit = Some(25);
 
         if message.len() > 4096 {
-            Err("trace too long")
+            Err(RequestError::InvalidInput("trace too long"))
         } else if trace_limit
             .map(|limit| client.traces < limit)
             .unwrap_or(true)
@@ -702,46 +967,24 @@ impl<G: GameArenaService> ClientRepo<G> {
             );
             if let Some(trace_log) = self.trace_log.as_ref() {
                 let trace_log = Arc::clone(trace_log);
-                let mut line = Vec::with_capacity(256);
-                let mut writer = csv::Writer::from_writer(&mut line);
-                if let Err(e) = writer.write_record(
-                    [
-                        get_unix_time_now().to_string().as_str(),
-                        &format!("{:?}", G::GAME_ID),
-                        &client.ip_address.to_string(),
-                        &client
-                            .metrics
-                            .region_id
-                            .map(|r| Cow::Owned(format!("{:?}", r)))
-                            .unwrap_or(Cow::Borrowed("?")),
-                        client
-                            .metrics
-                            .referrer
-                            .as_ref()
-                            .map(|r| r.as_str())
-                            .unwrap_or("?"),
-                        &client
-                            .metrics
-                            .user_agent_id
-                            .map(|ua| Cow::Owned(format!("{:?}", ua)))
-                            .unwrap_or(Cow::Borrowed("?")),
-                        &message,
-                    ]
-                    .as_slice(),
-                ) {
-                    error!("error composing trace line: {:?}", e);
-                } else {
-                    drop(writer);
-                    tokio::task::spawn_blocking(move || {
-                        if let Err(e) = OpenOptions::new()
-                            .create(true)
-                            .append(true)
-                            .open(&*trace_log)
-                            .and_then(move |mut file| file.write_all(&line))
-                        {
-                            error!("error logging trace: {:?}", e);
-                        }
-                    });
+                let line = match self.trace_format {
+                    TraceFormat::Csv => Self::trace_csv(client, &message),
+                    TraceFormat::Bitcode => Self::trace_record(client, message).encode(),
+                };
+                match line {
+                    Ok(line) => {
+                        tokio::task::spawn_blocking(move || {
+                            if let Err(e) = OpenOptions::new()
+                                .create(true)
+                                .append(true)
+                                .open(&*trace_log)
+                                .and_then(move |mut file| file.write_all(&line))
+                            {
+                                error!("error logging trace: {:?}", e);
+                            }
+                        });
+                    }
+                    Err(e) => error!("error composing trace line: {}", e),
                 }
             } else {
                 info!("client_trace: {}", message);
@@ -749,10 +992,92 @@ impl<G: GameArenaService> ClientRepo<G> {
             client.traces += 1;
             Ok(ClientUpdate::Traced)
         } else {
-            Err("too many traces")
+            Err(RequestError::RateLimited("too many traces"))
         }
     }
 
+    /// A [`TraceFormat::Csv`] line.
+    fn trace_csv(client: &PlayerClientData<G>, message: &str) -> Result<Vec<u8>, &'static str> {
+        let mut line = Vec::with_capacity(256);
+        let mut writer = csv::Writer::from_writer(&mut line);
+        writer
+            .write_record(
+                [
+                    get_unix_time_now().to_string().as_str(),
+                    &format!("{:?}", G::GAME_ID),
+                    &client.ip_address.to_string(),
+                    &client
+                        .metrics
+                        .region_id
+                        .map(|r| Cow::Owned(format!("{:?}", r)))
+                        .unwrap_or(Cow::Borrowed("?")),
+                    client
+                        .metrics
+                        .referrer
+                        .as_ref()
+                        .map(|r| r.as_str())
+                        .unwrap_or("?"),
+                    &client
+                        .metrics
+                        .user_agent_id
+                        .map(|ua| Cow::Owned(format!("{:?}", ua)))
+                        .unwrap_or(Cow::Borrowed("?")),
+                    message,
+                ]
+                .as_slice(),
+            )
+            .map_err(|_| "couldn't write csv")?;
+        drop(writer);
+        Ok(line)
+    }
+
+    /// A [`TraceFormat::Bitcode`] record.
+    fn trace_record(client: &PlayerClientData<G>, message: String) -> TraceRecord {
+        TraceRecord {
+            unix_time: get_unix_time_now(),
+            game_id: G::GAME_ID,
+            ip_address: client.ip_address.to_string(),
+            region_id: client.metrics.region_id,
+            referrer: client.metrics.referrer.map(|r| r.to_string()),
+            user_agent_id: client.metrics.user_agent_id,
+            message,
+        }
+    }
+
+    /// Validates a logged in player's settings, returning the request that stores them in their
+    /// profile. Plasma keeps the latest version (last write wins).
+    fn sync_settings(
+        server_id: ServerId,
+        arena_token: ArenaToken,
+        player_id: PlayerId,
+        settings: SettingsDto,
+        players: &PlayerRepo<G>,
+    ) -> Result<PlasmaRequestV1, RequestError> {
+        if !settings.is_valid() {
+            return Err(RequestError::InvalidInput("settings too large"));
+        }
+        let player = players
+            .borrow_player(player_id)
+            .ok_or(RequestError::InvalidState("player doesn't exist"))?;
+        let client = player
+            .client()
+            .ok_or(RequestError::NotAllowed("only clients can sync settings"))?;
+        let (Some(user_id), Some(session_token)) = (client.user_id, client.session_token) else {
+            return Err(RequestError::InvalidState("not logged in"));
+        };
+
+        Ok(PlasmaRequestV1::UpdateSettings {
+            game_id: G::GAME_ID,
+            server_id,
+            realm_name: None,
+            user_id,
+            arena_token,
+            session_token,
+            player_id,
+            settings,
+        })
+    }
+
     /// Handles an arbitrary [`ClientRequest`].
     fn handle_client_request(
         &mut self,
@@ -763,8 +1088,9 @@ impl<G: GameArenaService> ClientRepo<G> {
         players: &PlayerRepo<G>,
         metrics: &mut MetricRepo<G>,
         plasma: &PlasmaClient,
-    ) -> Result<ClientUpdate, &'static str> {
+    ) -> Result<ClientUpdate, RequestError> {
         match request {
+            ClientRequest::Heartbeat => Self::heartbeat(player_id, players),
             ClientRequest::Login(session_token) => Self::login(
                 players,
                 server_id,
@@ -773,10 +1099,19 @@ impl<G: GameArenaService> ClientRepo<G> {
                 session_token,
                 plasma,
             ),
-            ClientRequest::SetAlias(alias) => Self::set_alias(player_id, alias, players),
+            ClientRequest::SetAlias(alias) => {
+                Self::set_alias(player_id, alias, self.alias_uniqueness, players)
+            }
             ClientRequest::TallyAd(ad_type) => Self::tally_ad(player_id, ad_type, players, metrics),
             ClientRequest::TallyFps(fps) => Self::tally_fps(player_id, fps, players),
             ClientRequest::Trace { message } => self.trace(player_id, message, players, metrics),
+            ClientRequest::SyncSettings(settings) => {
+                let request =
+                    Self::sync_settings(server_id, arena_token, player_id, settings, players)?;
+                // Newer settings, if any, are restored via `PlasmaUpdateV1::ConfigPlayer`.
+                plasma.do_request(request);
+                Ok(ClientUpdate::SettingsSynced(None))
+            }
         }
     }
 
@@ -796,7 +1131,10 @@ impl<G: GameArenaService> ClientRepo<G> {
         invitations: &mut InvitationRepo<G>,
         metrics: &mut MetricRepo<G>,
         plasma: &PlasmaClient,
-    ) -> Result<Option<Update<G::GameUpdate>>, &'static str> {
+    ) -> Result<Option<Update<G::GameUpdate>>, RequestError> {
+        #[cfg(feature = "tracing")]
+        let _span = crate::log::request_span(player_id, realm_name, &request).entered();
+
         match request {
             // Goes first (fast path).
             Request::Game(command) => {
@@ -836,11 +1174,12 @@ impl<G: GameArenaService> ClientRepo<G> {
                 .handle_team_request(player_id, request, players)
                 .map(|u| Some(Update::Team(u))),
             #[cfg(not(feature = "teams"))]
-            Request::Team(_) => Err("unhandled teams request"),
+            Request::Team(_) => Err(RequestError::NotAllowed("unhandled teams request")),
         }
     }
 
-    /// Record network round-trip-time measured by websocket for statistical purposes.
+    /// Record network round-trip-time measured by websocket for statistical purposes, and echo it
+    /// to the client so it can be displayed.
     fn handle_observer_rtt(&mut self, player_id: PlayerId, rtt: u16, players: &PlayerRepo<G>) {
         let mut player = match players.borrow_player_mut(player_id) {
             Some(player) => player,
@@ -856,6 +1195,12 @@ impl<G: GameArenaService> ClientRepo<G> {
         };
 
         client.metrics.rtt = Some(rtt);
+
+        if let ClientStatus::Connected { observer } = &client.status {
+            let _ = observer.send(ObserverUpdate::Send {
+                message: Update::Client(ClientUpdate::RoundTripTime(rtt)),
+            });
+        }
     }
 }
 
@@ -886,12 +1231,16 @@ pub struct PlayerClientData<G: GameArenaService> {
     pub admin: bool,
     /// Is moderator for in-game chat?
     pub moderator: bool,
+    /// Watches without playing (can't spawn or issue most game commands).
+    pub spectator: bool,
     /// Metrics-related information associated with each client.
     pub(crate) metrics: ClientMetricData<G>,
     /// Invitation-related information associated with each client.
     pub(crate) invitation: ClientInvitationData,
     /// Chat-related information associated with each client.
     pub(crate) chat: ClientChatData,
+    /// AFK-related information associated with each client.
+    pub(crate) afk: ClientAfkData,
     /// Team-related information associated with each client.
     #[cfg(feature = "teams")]
     pub(crate) team: ClientTeamData,
@@ -938,9 +1287,11 @@ impl<G: GameArenaService> PlayerClientData<G> {
             ip_address: ip,
             admin: false,
             moderator: false,
+            spectator: false,
             metrics,
             invitation: ClientInvitationData::new(invitation),
             chat: ClientChatData::default(),
+            afk: ClientAfkData::new(Instant::now()),
             #[cfg(feature = "teams")]
             team: ClientTeamData::default(),
             reported: Default::default(),
@@ -1013,7 +1364,7 @@ impl<G: GameArenaService> Handler<ObserverMessage<Request<G::GameRequest>, Updat
             ObserverMessageBody::Request { player_id, request } => {
                 let context = &mut context_service.context;
                 let service = &mut context_service.service;
-                match self.clients.handle_observer_request(
+                let (message, failed) = match self.clients.handle_observer_request(
                     player_id,
                     request,
                     service,
@@ -1028,33 +1379,35 @@ impl<G: GameArenaService> Handler<ObserverMessage<Request<G::GameRequest>, Updat
                     &mut self.metrics,
                     &self.plasma,
                 ) {
-                    Ok(Some(message)) => {
-                        let player = match context.players.borrow_player_mut(player_id) {
-                            Some(player) => player,
-                            None => {
-                                debug_assert!(false);
-                                return;
-                            }
-                        };
-
-                        let client = match player.client() {
-                            Some(client) => client,
-                            None => {
-                                debug_assert!(false);
-                                return;
-                            }
-                        };
+                    Ok(Some(message)) => (message, false),
+                    Ok(None) => return,
+                    Err(e) => {
+                        warn!("observer request resulted in {}", e);
+                        (e.into_update(), true)
+                    }
+                };
 
-                        if let ClientStatus::Connected { observer } = &client.status {
-                            let _ = observer.send(ObserverUpdate::Send { message });
-                        } else {
-                            debug_assert!(false, "impossible due to synchronous nature of code");
-                        }
+                // Errors may be due to the player or client not existing.
+                let player = match context.players.borrow_player_mut(player_id) {
+                    Some(player) => player,
+                    None => {
+                        debug_assert!(failed);
+                        return;
                     }
-                    Ok(None) => {}
-                    Err(s) => {
-                        warn!("observer request resulted in {}", s);
+                };
+
+                let client = match player.client() {
+                    Some(client) => client,
+                    None => {
+                        debug_assert!(failed);
+                        return;
                     }
+                };
+
+                if let ClientStatus::Connected { observer } = &client.status {
+                    let _ = observer.send(ObserverUpdate::Send { message });
+                } else {
+                    debug_assert!(false, "impossible due to synchronous nature of code");
                 }
             }
             ObserverMessageBody::RoundTripTime { player_id, rtt } => self
@@ -1065,7 +1418,7 @@ impl<G: GameArenaService> Handler<ObserverMessage<Request<G::GameRequest>, Updat
 }
 
 #[derive(Message)]
-#[rtype(result = "Result<(Option<RealmName>, PlayerId), &'static str>")]
+#[rtype(result = "Result<(Option<RealmName>, PlayerId), AuthenticateError>")]
 pub struct Authenticate {
     /// Client ip address.
     pub ip_address: IpAddr,
@@ -1085,27 +1438,43 @@ pub struct Authenticate {
     pub cohort_id: Option<CohortId>,
     /// When joined the system (maybe now).
     pub date_created: UnixTime,
+    /// Watch without playing.
+    pub spectator: bool,
+    /// Place in line, if retrying to join a full arena.
+    pub queue_ticket: Option<NonZeroU64>,
+}
+
+/// Why [`Authenticate`] failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AuthenticateError {
+    /// E.g. rate limited.
+    Rejected(&'static str),
+    /// The arena is full, so the client should retry later.
+    ServerFull(ServerFull),
 }
 
 impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
-    type Result = Result<(Option<RealmName>, PlayerId), &'static str>;
+    type Result = Result<(Option<RealmName>, PlayerId), AuthenticateError>;
 
     fn handle(&mut self, msg: Authenticate, _ctx: &mut ActorContext<Self>) -> Self::Result {
         let clients = &mut self.clients;
+        let realm_name = msg.realm_name;
 
-        if clients
-            .authenticate_rate_limiter
-            .should_limit_rate(msg.ip_address)
+        // Clients waiting in line retry periodically, which shouldn't count against them. Only
+        // tickets that were actually issued count, so the exemption can't be claimed by others.
+        if !clients.is_waiting(realm_name, msg.queue_ticket)
+            && clients
+                .authenticate_rate_limiter
+                .should_limit_rate(msg.ip_address)
         {
             // Should only log IP of malicious actors.
             warn!("IP {:?} was rate limited", msg.ip_address);
-            return Err("rate limit exceeded");
+            return Err(AuthenticateError::Rejected("rate limit exceeded"));
         }
 
-        let realm_name = msg.realm_name;
         let Some(context_service) = self.arenas.get_mut(realm_name) else {
             log::warn!("no arena {realm_name:?}");
-            return Err("no such arena");
+            return Err(AuthenticateError::Rejected("no such arena"));
         };
         let arena_token = context_service.context.token;
 
@@ -1117,7 +1486,7 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
             player_id: i.player_id,
         });
 
-        let player_id = if let Some(existing) = msg
+        let existing = msg
             .player_id_token
             .filter(|(player_id, token)| {
                 context_service
@@ -1127,10 +1496,26 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                     .and_then(|p| p.client().map(|c| c.token == *token))
                     .unwrap_or(false)
             })
-            .map(|(player_id, _)| player_id)
-        {
+            .map(|(player_id, _)| player_id);
+
+        let player_id = if let Some(existing) = existing {
             existing
         } else {
+            clients
+                .check_connections(msg.ip_address)
+                .map_err(AuthenticateError::Rejected)?;
+            // Only new clients wait, so reconnecting doesn't lose a slot.
+            clients
+                .admit(
+                    realm_name,
+                    msg.queue_ticket,
+                    &context_service.context.players,
+                    Instant::now(),
+                )
+                .map_err(AuthenticateError::ServerFull)?;
+            // Reserve the connection now, so that many concurrent upgrades can't all pass the
+            // check. Released if the upgrade never registers (see [`ClientRepo::prune`]).
+            clients.connect(msg.ip_address);
             loop {
                 let player_id = PlayerId(generate_id());
                 if !context_service.context.players.contains(player_id) {
@@ -1144,6 +1529,8 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
                 if let Some(client) = occupied.get_mut().borrow_player_mut().client_mut() {
                     // Update the referrer, such that the correct snippet may be served.
                     client.metrics.referrer = msg.referrer.or(client.metrics.referrer);
+                    // The same session may switch between playing and spectating.
+                    client.spectator = msg.spectator;
                 } else {
                     debug_assert!(false, "impossible to be a bot since session was valid");
                 }
@@ -1151,12 +1538,13 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
             Entry::Vacant(vacant) => {
                 let client_metric_data = ClientMetricData::new(&msg);
 
-                let client = PlayerClientData::new(
+                let mut client = PlayerClientData::new(
                     client_metric_data,
                     msg.session_token,
                     invitation_dto,
                     msg.ip_address,
                 );
+                client.spectator = msg.spectator;
 
                 if let Some(session_token) = msg.session_token {
                     self.plasma.do_request(PlasmaRequestV1::RegisterPlayer {
@@ -1179,3 +1567,457 @@ impl<G: GameArenaService> Handler<Authenticate> for Infrastructure<G> {
         Ok((realm_name, player_id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::chat::ChatRepo;
+    use crate::client::{AliasUniqueness, Authenticate, ClientRepo, PlayerClientData};
+    use crate::game_service::{GameArenaService, MockGame};
+    use crate::invitation::InvitationRepo;
+    use crate::metric::{ClientMetricData, MetricRepo};
+    use crate::player::{PlayerData, PlayerRepo, PlayerTuple};
+    use crate::request_error::RequestError;
+    #[cfg(feature = "teams")]
+    use crate::team::TeamRepo;
+    use crate::trace::TraceFormat;
+    use core_protocol::dto::{FeatureFlags, SettingsDto};
+    use core_protocol::id::{
+        ArenaToken, InvitationId, PlayerId, ServerId, ServerKind, ServerNumber, SessionToken,
+        UserId,
+    };
+    use core_protocol::name::PlayerAlias;
+    #[cfg(feature = "teams")]
+    use core_protocol::name::TeamName;
+    #[cfg(feature = "teams")]
+    use core_protocol::rpc::TeamRequest;
+    use core_protocol::rpc::{
+        ChatRequest, ClientUpdate, InvitationRequest, PlayerRequest, RequestErrorKind, ServerFull,
+        Update,
+    };
+    use core_protocol::PlasmaRequestV1;
+    use server_util::rate_limiter::RateLimiterProps;
+    use std::fmt::Debug;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::{NonZeroU32, NonZeroU64, NonZeroU8};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn client(spectator: bool) -> Box<PlayerClientData<MockGame>> {
+        let ip_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let authenticate = Authenticate {
+            ip_address,
+            user_agent_id: None,
+            referrer: None,
+            realm_name: None,
+            player_id_token: None,
+            session_token: None,
+            invitation_id: None,
+            cohort_id: None,
+            date_created: 0,
+            spectator,
+            queue_ticket: None,
+        };
+        let mut client =
+            PlayerClientData::new(ClientMetricData::new(&authenticate), None, None, ip_address);
+        client.spectator = spectator;
+        Box::new(client)
+    }
+
+    #[test]
+    fn spectator() {
+        let mut players = PlayerRepo::<MockGame>::default();
+        let player = PlayerId(NonZeroU32::new(1).unwrap());
+        let spectator = PlayerId(NonZeroU32::new(2).unwrap());
+        for (player_id, is_spectator) in [(player, false), (spectator, true)] {
+            let player_data = PlayerData::new(player_id, Some(client(is_spectator)));
+            players.insert(player_id, Arc::new(PlayerTuple::new(player_data)));
+        }
+        let mut game = MockGame::new(0);
+
+        // Both receive updates.
+        for player_id in [player, spectator] {
+            let player_tuple = players.get(player_id).unwrap();
+            assert!(game
+                .get_game_update(player_tuple, &mut (), &players)
+                .is_some());
+        }
+
+        // Only the player can play (e.g. spawn).
+        assert!(ClientRepo::handle_game_command(player, (), &mut game, &players).is_ok());
+        assert!(matches!(
+            ClientRepo::handle_game_command(spectator, (), &mut game, &players),
+            Err(RequestError::NotAllowed(_))
+        ));
+        assert!(players
+            .get(spectator)
+            .unwrap()
+            .borrow_player()
+            .is_spectator());
+    }
+
+    #[test]
+    fn request_error_kinds() {
+        fn kind<T: Debug>(result: Result<T, RequestError>) -> RequestErrorKind {
+            result.unwrap_err().kind()
+        }
+
+        let mut players = PlayerRepo::<MockGame>::default();
+        let captain = PlayerId(NonZeroU32::new(1).unwrap());
+        let member = PlayerId(NonZeroU32::new(2).unwrap());
+        for player_id in [captain, member] {
+            let mut player_data = PlayerData::new(player_id, Some(client(false)));
+            player_data.was_alive = true;
+            players.insert(player_id, Arc::new(PlayerTuple::new(player_data)));
+        }
+        let mut game = MockGame::new(0);
+        let mut metrics = MetricRepo::<MockGame>::new();
+        #[cfg(feature = "teams")]
+        let mut teams = TeamRepo::<MockGame>::default();
+
+        // Chatting too fast.
+        let mut chat = ChatRepo::<MockGame>::new(None);
+        let spam = [
+            "hello there",
+            "how are you",
+            "nice towers",
+            "good game",
+            "watch out",
+            "see you later",
+            "thanks a lot",
+            "well played",
+            "follow me",
+            "over here",
+            "almost done",
+            "one more",
+        ]
+        .into_iter()
+        .find_map(|message| {
+            chat.handle_chat_request(
+                captain,
+                ChatRequest::Send {
+                    message: message.to_owned(),
+                    whisper: false,
+                },
+                &mut game,
+                &mut players,
+                #[cfg(feature = "teams")]
+                &teams,
+                &mut metrics,
+            )
+            .err()
+        })
+        .expect("never rate limited");
+        assert_eq!(spam.kind(), RequestErrorKind::RateLimited);
+        assert!(matches!(
+            spam.into_update::<()>(),
+            Update::Client(ClientUpdate::RequestFailed(RequestErrorKind::RateLimited))
+        ));
+        let mute_self = chat.handle_chat_request(
+            captain,
+            ChatRequest::Mute(captain),
+            &mut game,
+            &mut players,
+            #[cfg(feature = "teams")]
+            &teams,
+            &mut metrics,
+        );
+        assert_eq!(kind(mute_self), RequestErrorKind::InvalidInput);
+
+        // Reporting without enough score.
+        assert_eq!(
+            kind(players.handle_player_request(
+                captain,
+                PlayerRequest::Report(member),
+                &mut metrics
+            )),
+            RequestErrorKind::NotAllowed
+        );
+
+        // Accepting an invitation that doesn't exist.
+        let server_id = ServerId {
+            kind: ServerKind::Local,
+            number: ServerNumber(NonZeroU8::new(1).unwrap()),
+        };
+        let invitation_id = InvitationId(NonZeroU32::new(1).unwrap());
+        assert_eq!(
+            kind(InvitationRepo::default().handle_invitation_request(
+                captain,
+                InvitationRequest::Accept(invitation_id),
+                None,
+                server_id,
+                &mut players,
+            )),
+            RequestErrorKind::InvalidState
+        );
+
+        // Kicking the captain as a mere member.
+        #[cfg(feature = "teams")]
+        {
+            let team_name = TeamName::new_sanitized("team");
+            assert!(teams
+                .handle_team_request(captain, TeamRequest::Create(team_name), &mut players)
+                .is_ok());
+            let team_id = players.borrow_player(captain).unwrap().team_id().unwrap();
+            assert!(teams
+                .handle_team_request(member, TeamRequest::Join(team_id), &mut players)
+                .is_ok());
+            assert!(teams
+                .handle_team_request(captain, TeamRequest::Accept(member), &mut players)
+                .is_ok());
+            assert_eq!(
+                kind(teams.handle_team_request(member, TeamRequest::Kick(captain), &mut players)),
+                RequestErrorKind::NotAllowed
+            );
+        }
+    }
+
+    #[test]
+    fn alias_uniqueness() {
+        let mut players = PlayerRepo::<MockGame>::default();
+        let alive = PlayerId(NonZeroU32::new(1).unwrap());
+        let other = PlayerId(NonZeroU32::new(2).unwrap());
+        for player_id in [alive, other] {
+            let player_data = PlayerData::new(player_id, Some(client(false)));
+            players.insert(player_id, Arc::new(PlayerTuple::new(player_data)));
+        }
+        let set_alias = |player_id, alias, alias_uniqueness| {
+            ClientRepo::set_alias(
+                player_id,
+                PlayerAlias::new_unsanitized(alias),
+                alias_uniqueness,
+                &players,
+            )
+            .map(|update| match update {
+                ClientUpdate::AliasSet(alias) => alias,
+                _ => unreachable!(),
+            })
+        };
+        let alias_set = |alias| Ok(PlayerAlias::new_unsanitized(alias));
+
+        assert_eq!(
+            set_alias(alive, "Bob", AliasUniqueness::Reject),
+            alias_set("Bob")
+        );
+        players.borrow_player_mut(alive).unwrap().was_alive = true;
+
+        assert_eq!(
+            set_alias(other, "Bob", AliasUniqueness::Permissive),
+            alias_set("Bob")
+        );
+        assert_eq!(
+            set_alias(other, "Bob", AliasUniqueness::Reject),
+            Err(RequestError::InvalidInput("alias in use"))
+        );
+        assert_eq!(
+            set_alias(other, "Bob", AliasUniqueness::Suffix),
+            alias_set("Bob 2")
+        );
+        // Truncated to fit.
+        assert_eq!(
+            set_alias(alive, "Bartholomew", AliasUniqueness::Reject),
+            alias_set("Bartholomew")
+        );
+        assert_eq!(
+            set_alias(other, "Bartholomew", AliasUniqueness::Suffix),
+            alias_set("Bartholome 2")
+        );
+        // Guest is exempt.
+        assert_eq!(
+            set_alias(alive, "Guest", AliasUniqueness::Reject),
+            alias_set("Guest")
+        );
+        assert_eq!(
+            set_alias(other, "Guest", AliasUniqueness::Reject),
+            alias_set("Guest")
+        );
+    }
+
+    #[test]
+    fn unique_spawned_aliases() {
+        let mut players = PlayerRepo::<MockGame>::default();
+        let a = PlayerId(NonZeroU32::new(1).unwrap());
+        let b = PlayerId(NonZeroU32::new(2).unwrap());
+        for player_id in [a, b] {
+            let player_data = PlayerData::new(player_id, Some(client(false)));
+            players.insert(player_id, Arc::new(PlayerTuple::new(player_data)));
+        }
+        let repo = ClientRepo::<MockGame>::new(
+            None,
+            TraceFormat::Csv,
+            RateLimiterProps::no_limit(),
+            None,
+            FeatureFlags::default(),
+            AliasUniqueness::Reject,
+            None,
+            usize::MAX,
+        );
+
+        // Both dead, so neither alias is in use yet.
+        for player_id in [a, b] {
+            ClientRepo::set_alias(
+                player_id,
+                PlayerAlias::new_unsanitized("Bob"),
+                AliasUniqueness::Reject,
+                &players,
+            )
+            .unwrap();
+        }
+
+        for player_id in [a, b] {
+            players.borrow_player_mut(player_id).unwrap().was_alive = true;
+        }
+        repo.unique_spawned_aliases(&[a, b], &players);
+        let alias = |player_id| players.borrow_player(player_id).unwrap().alias();
+        assert_eq!(alias(a), PlayerAlias::new_unsanitized("Bob 2"));
+        assert_eq!(alias(b), PlayerAlias::new_unsanitized("Bob"));
+    }
+
+    #[test]
+    fn max_players() {
+        let mut repo = ClientRepo::<MockGame>::new(
+            None,
+            TraceFormat::Csv,
+            RateLimiterProps::no_limit(),
+            None,
+            FeatureFlags::default(),
+            AliasUniqueness::default(),
+            Some(2),
+            usize::MAX,
+        );
+        let mut players = PlayerRepo::<MockGame>::default();
+        let now = Instant::now();
+        let mut tickets = [None; 5];
+        let mut admit = |n: u8, players: &PlayerRepo<MockGame>| {
+            let ticket = &mut tickets[n as usize];
+            repo.admit(None, *ticket, players, now).map_err(
+                |ServerFull {
+                     queue_position,
+                     queue_ticket,
+                 }| {
+                    *ticket = queue_ticket;
+                    queue_position
+                },
+            )
+        };
+
+        for n in 1..=2 {
+            assert_eq!(admit(n, &players), Ok(()));
+            let player_id = PlayerId(NonZeroU32::new(n as u32).unwrap());
+            let player_data = PlayerData::new(player_id, Some(client(false)));
+            players.insert(player_id, Arc::new(PlayerTuple::new(player_data)));
+        }
+        assert_eq!(admit(3, &players), Err(Some(1)));
+        assert_eq!(admit(4, &players), Err(Some(2)));
+
+        // Bots don't count.
+        let bot_id = PlayerId::nth_bot(0).unwrap();
+        players.insert(
+            bot_id,
+            Arc::new(PlayerTuple::new(PlayerData::new(bot_id, None))),
+        );
+        assert_eq!(admit(4, &players), Err(Some(2)));
+
+        // Freeing a slot promotes the next in line.
+        players
+            .players
+            .remove(&PlayerId(NonZeroU32::new(1).unwrap()));
+        assert_eq!(admit(4, &players), Err(Some(1)));
+        assert_eq!(admit(3, &players), Ok(()));
+    }
+
+    #[test]
+    fn max_connections_per_ip() {
+        let mut repo = ClientRepo::<MockGame>::new(
+            None,
+            TraceFormat::Csv,
+            RateLimiterProps::no_limit(),
+            None,
+            FeatureFlags::default(),
+            AliasUniqueness::default(),
+            None,
+            3,
+        );
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let other_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        for _ in 0..3 {
+            assert_eq!(repo.check_connections(ip), Ok(()));
+            repo.connect(ip);
+        }
+        assert!(repo.check_connections(ip).is_err());
+        assert_eq!(repo.check_connections(other_ip), Ok(()));
+
+        // Freeing a connection allows another.
+        repo.disconnect(ip);
+        assert_eq!(repo.check_connections(ip), Ok(()));
+        repo.connect(ip);
+        assert!(repo.check_connections(ip).is_err());
+
+        for _ in 0..3 {
+            repo.disconnect(ip);
+        }
+        assert!(repo.connections.is_empty());
+    }
+
+    #[test]
+    fn sync_settings() {
+        let mut players = PlayerRepo::<MockGame>::default();
+        let laptop = PlayerId(NonZeroU32::new(1).unwrap());
+        let phone = PlayerId(NonZeroU32::new(2).unwrap());
+        let anonymous = PlayerId(NonZeroU32::new(3).unwrap());
+        let user_id = UserId(NonZeroU64::new(42).unwrap());
+        for (i, player_id) in [laptop, phone, anonymous].into_iter().enumerate() {
+            let mut client = client(false);
+            if player_id != anonymous {
+                client.user_id = Some(user_id);
+                client.session_token = Some(SessionToken(NonZeroU64::new(i as u64 + 1).unwrap()));
+            }
+            let player_data = PlayerData::new(player_id, Some(client));
+            players.insert(player_id, Arc::new(PlayerTuple::new(player_data)));
+        }
+        let server_id = ServerId {
+            kind: ServerKind::Local,
+            number: ServerNumber(NonZeroU8::new(1).unwrap()),
+        };
+        let arena_token = ArenaToken(NonZeroU32::new(1).unwrap());
+        let settings = |version, volume: &str| SettingsDto {
+            version,
+            settings: vec![("volume".to_owned(), volume.to_owned())],
+        };
+        let sync = |player_id, settings| {
+            ClientRepo::<MockGame>::sync_settings(
+                server_id,
+                arena_token,
+                player_id,
+                settings,
+                &players,
+            )
+            .map(|request| match request {
+                PlasmaRequestV1::UpdateSettings {
+                    user_id,
+                    player_id,
+                    settings,
+                    ..
+                } => (user_id, player_id, settings),
+                _ => unreachable!(),
+            })
+        };
+
+        assert!(sync(anonymous, settings(1, "0.1")).is_err());
+        // Both devices store to the same profile.
+        assert_eq!(
+            sync(laptop, settings(2, "0.2")),
+            Ok((user_id, laptop, settings(2, "0.2")))
+        );
+        assert_eq!(
+            sync(phone, settings(1, "0.1")),
+            Ok((user_id, phone, settings(1, "0.1")))
+        );
+
+        let too_many = SettingsDto {
+            version: 4,
+            settings: vec![Default::default(); SettingsDto::MAX_SETTINGS + 1],
+        };
+        assert!(sync(laptop, too_many).is_err());
+    }
+}
@@ -8,15 +8,18 @@ use crate::game_service::GameArenaService;
 use crate::invitation::InvitationRepo;
 use crate::metric::MetricRepo;
 use crate::plasma::PlasmaClient;
+use crate::replay::ReplayBuffer;
 use core_protocol::dto::ServerDto;
 use core_protocol::id::ServerId;
-use core_protocol::ServerNumber;
+use core_protocol::{RealmName, ServerNumber};
 use std::sync::Arc;
 
 /// Contains a [`GameArenaService`] and the corresponding [`Context`].
 pub struct ContextService<G: GameArenaService> {
     pub context: Context<G>,
     pub service: G,
+    /// Recent history, if enabled.
+    pub replay: Option<ReplayBuffer>,
 }
 
 impl<G: GameArenaService> ContextService<G> {
@@ -31,12 +34,14 @@ impl<G: GameArenaService> ContextService<G> {
         Self {
             service: G::new(bots.min_bots),
             context: Context::new(bots, chat_log),
+            replay: None,
         }
     }
 
     #[allow(clippy::type_complexity)]
     pub(crate) fn update(
         &mut self,
+        realm_name: Option<RealmName>,
         clients: &mut ClientRepo<G>,
         invitations: &mut InvitationRepo<G>,
         metrics: &mut MetricRepo<G>,
@@ -46,6 +51,7 @@ impl<G: GameArenaService> ContextService<G> {
     ) {
         // Spawn/de-spawn clients and bots.
         clients.prune(
+            realm_name,
             &mut self.service,
             &mut self.context.players,
             #[cfg(feature = "teams")]
@@ -62,12 +68,17 @@ impl<G: GameArenaService> ContextService<G> {
 
         // Update game logic.
         self.service.tick(&mut self.context);
-        self.context.players.update_is_alive_and_team_id(
+        let spawned = self.context.players.update_is_alive_and_team_id(
             &mut self.service,
             #[cfg(feature = "teams")]
             &mut self.context.teams,
             metrics,
         );
+        clients.unique_spawned_aliases(&spawned, &self.context.players);
+
+        if let Some(replay) = &mut self.replay {
+            replay.record(self.service.replay_frame().as_ref());
+        }
 
         // Update clients and bots.
         clients.update(
@@ -98,4 +109,30 @@ impl<G: GameArenaService> ContextService<G> {
             .bots
             .post_update(&mut self.service, &self.context.players);
     }
+
+    /// Like [`Self::update`], but only for bots, without any clients or networking. Useful for
+    /// simulating games headlessly.
+    pub fn simulate(&mut self, metrics: &mut MetricRepo<G>) {
+        self.context
+            .bots
+            .update_count(&mut self.service, &mut self.context.players);
+
+        self.service.tick(&mut self.context);
+        self.context.players.update_is_alive_and_team_id(
+            &mut self.service,
+            #[cfg(feature = "teams")]
+            &mut self.context.teams,
+            metrics,
+        );
+
+        self.context
+            .bots
+            .update(&self.service, &self.context.players);
+
+        self.service.post_update(&mut self.context);
+
+        self.context
+            .bots
+            .post_update(&mut self.service, &self.context.players);
+    }
 }
@@ -4,7 +4,7 @@
 //! The game server has authority over all game logic. Clients are served the client, which connects
 //! via web_socket.
 
-use crate::client::Authenticate;
+use crate::client::{Authenticate, AuthenticateError};
 use crate::game_service::GameArenaService;
 use crate::infrastructure::Infrastructure;
 use crate::net::ip::{get_own_public_ip, ip_to_region_id};
@@ -23,7 +23,10 @@ use axum::http::{HeaderValue, Method, StatusCode, Uri};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use core_protocol::rpc::{Request, SystemQuery, Update, WebSocketQuery};
+use core_protocol::rpc::{
+    Request, SystemQuery, Update, WebSocketQuery, PROTOCOL_MISMATCH_CLOSE_CODE,
+    SERVER_FULL_CLOSE_CODE,
+};
 use core_protocol::{get_unix_time_now, AdminRequest, AdminUpdate, UnixTime};
 use core_protocol::{id::*, PlasmaUpdate, RealmName};
 use futures::pin_mut;
@@ -187,7 +190,7 @@ where
             thread_rng().gen::<NonZeroU64>().get()
         }, Ordering::Relaxed);
 
-        let options = Options::from_args();
+        let options = Options::<G::Options>::from_args();
 
         crate::log::init_logger(&options);
 
@@ -247,12 +250,24 @@ where
                 options.bot_percent,
                 options.chat_log,
                 options.trace_log,
+                options.trace_format,
                 Arc::clone(&game_client),
                 &SERVER_TOKEN,
                 RateLimiterProps::new(
                     Duration::from_secs(options.client_authenticate_rate_limit),
                     options.client_authenticate_burst,
                 ),
+                options.afk_timeout.map(Duration::from_secs),
+                options.max_view,
+                options.seed,
+                options.max_team_size,
+                options.features.unwrap_or_default(),
+                options.replay_seconds,
+                options.alias_uniqueness,
+                options.max_players,
+                options.max_connections_per_ip,
+                options.private_arena,
+                options.game,
             )
             .await,
         );
@@ -300,6 +315,14 @@ where
         let app = Router::new()
             .fallback_service(get(StaticFilesHandler{cdn: game_client, prefix: "", browser_router}))
             .route("/ws", axum::routing::get(async move |upgrade: WebSocketUpgrade, ConnectInfo(addr): ConnectInfo<SocketAddr>, user_agent: Option<TypedHeader<axum::headers::UserAgent>>, realm_name: Option<ExtractRealmName>, Query(query): Query<WebSocketQuery>| {
+                if let Err(e) = query.check_protocol_version() {
+                    // Close with a code the client understands, instead of sending it updates it
+                    // might fail to decode.
+                    return Ok(upgrade.on_upgrade(async move |mut web_socket| {
+                        let _ = web_socket.send(Message::Close(Some(CloseFrame{code: PROTOCOL_MISMATCH_CLOSE_CODE, reason: e.into()}))).await;
+                    }));
+                }
+
                 let user_agent_id = user_agent
                     .map(|h| UserAgent::new(h.as_str()))
                     .and_then(UserAgent::into_id);
@@ -316,6 +339,8 @@ where
                     date_created: query.date_created.filter(|&d| d > 1680570365768 && d <= now).unwrap_or(now),
                     invitation_id: query.invitation_id,
                     cohort_id: query.cohort_id,
+                    spectator: query.spectator,
+                    queue_ticket: query.queue_ticket,
                 };
 
                 const MAX_MESSAGE_SIZE: usize = 32768;
@@ -326,8 +351,12 @@ where
                 match ws_srv.send(authenticate).await {
                     Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()),
                     Ok(result) => match result {
-                        // Currently, if authentication fails, it was due to rate limit.
-                        Err(e) => Err((StatusCode::TOO_MANY_REQUESTS, e).into_response()),
+                        // Currently, if authentication is rejected, it was due to rate limit.
+                        Err(AuthenticateError::Rejected(e)) => Err((StatusCode::TOO_MANY_REQUESTS, e).into_response()),
+                        // Close with a code the client understands, so it waits and retries.
+                        Err(AuthenticateError::ServerFull(server_full)) => Ok(upgrade.on_upgrade(async move |mut web_socket| {
+                            let _ = web_socket.send(Message::Close(Some(CloseFrame{code: SERVER_FULL_CLOSE_CODE, reason: server_full.to_reason().into()}))).await;
+                        })),
                         Ok((realm_name, player_id)) => Ok(upgrade
                             .max_frame_size(MAX_MESSAGE_SIZE)
                             .max_message_size(MAX_MESSAGE_SIZE)
@@ -704,7 +733,7 @@ where
 
                 let mut parts = uri.into_parts();
                 parts.scheme = Some(Scheme::HTTPS);
-                let authority = if https_port == Options::STANDARD_HTTPS_PORT {
+                let authority = if https_port == Options::<G::Options>::STANDARD_HTTPS_PORT {
                     Authority::from_str(host.0.hostname())
                 } else {
                     // non-standard port.
@@ -712,7 +741,7 @@ where
                 }.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
                 parts.authority = Some(authority);
                 Uri::from_parts(parts)
-                    .map(|uri| if http_port == Options::STANDARD_HTTP_PORT { Redirect::permanent(&uri.to_string()) } else { Redirect::temporary(&uri.to_string()) })
+                    .map(|uri| if http_port == Options::<G::Options>::STANDARD_HTTP_PORT { Redirect::permanent(&uri.to_string()) } else { Redirect::temporary(&uri.to_string()) })
                     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())
             }));
 
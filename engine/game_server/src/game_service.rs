@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::afk::AfkAction;
 use crate::context::Context;
 use crate::player::{PlayerRepo, PlayerTuple};
 use core_protocol::id::{GameId, PlayerId, TeamId};
@@ -10,6 +11,7 @@ use std::fmt::Debug;
 use std::marker::Send;
 use std::sync::Arc;
 use std::time::Duration;
+use structopt::StructOpt;
 
 /// A modular game service (representing one arena).
 pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
@@ -41,6 +43,8 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
     type GameRequest: 'static + Debug + Decode + Send + Unpin;
     type PlayerData: 'static + Default + Unpin + Send + Sync + Debug;
     type PlayerExtension: 'static + Default + Unpin + Send + Sync;
+    /// Game specific server options (see [`Options::game`][`crate::options::Options::game`]).
+    type Options: 'static + StructOpt + Default + Debug + Send;
 
     fn new(min_players: usize) -> Self;
 
@@ -89,6 +93,13 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
         _players: &PlayerRepo<Self>,
     ) -> Option<Self::GameUpdate>;
 
+    /// Whether a spectator (a client that watches without playing) may issue `command`, e.g. to
+    /// move its viewport. Spectators may not issue any commands by default.
+    fn spectator_command(command: &Self::GameRequest) -> bool {
+        let _ = command;
+        false
+    }
+
     /// Called when a player's [`TeamId`] changes.
     #[cfg(feature = "teams")]
     fn player_changed_team(
@@ -106,6 +117,12 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
         let _ = player_tuple;
     }
 
+    /// Called when an alive real player hasn't sent a command in a while, if enabled. Either
+    /// warn them or remove them from the game (e.g. kill them), depending on `action`.
+    fn player_afk(&mut self, player_tuple: &Arc<PlayerTuple<Self>>, action: AfkAction) {
+        let _ = (player_tuple, action);
+    }
+
     fn chat_command(
         &mut self,
         command: &str,
@@ -140,6 +157,54 @@ pub trait GameArenaService: 'static + Unpin + Sized + Send + Sync {
     fn entities(&self) -> usize;
     /// For metrics.
     fn world_size(&self) -> f32;
+
+    /// Applies game specific options, once, right after [`Self::new`]. Ignored by default.
+    fn set_options(&mut self, options: Self::Options) {
+        let _ = options;
+    }
+
+    /// Limits how much of the world clients may see at once, as a fraction of its width (see
+    /// [`Options::max_view`][`crate::options::Options::max_view`]). Ignored by default.
+    fn set_max_view(&mut self, max_view: f32) {
+        let _ = max_view;
+    }
+
+    /// Temporarily modifies a game parameter, identified by `event`, for everyone. Must revert
+    /// automatically after `duration`. Triggered by `AdminRequest::StartWorldEvent`. Unsupported
+    /// by default.
+    fn start_world_event(&mut self, event: &str, duration: Duration) -> Result<(), &'static str> {
+        let _ = (event, duration);
+        Err("world events unsupported")
+    }
+
+    /// Changes how fast units are generated, for balancing a live game, until further notice.
+    /// `multipliers` is in a game specific format. Triggered by
+    /// `AdminRequest::SetGenerationMultipliers`. Unsupported by default.
+    fn set_generation_multipliers(&mut self, multipliers: &str) -> Result<(), &'static str> {
+        let _ = multipliers;
+        Err("generation multipliers unsupported")
+    }
+
+    /// Pauses or resumes the simulation, while clients stay connected. Triggered by
+    /// `AdminRequest::PauseArena`, which only applies to private arenas. Unsupported by default.
+    fn set_paused(&mut self, paused: bool) -> Result<(), &'static str> {
+        let _ = paused;
+        Err("pausing unsupported")
+    }
+
+    /// A self-contained (not relative to previous ones) snapshot of the whole arena, for
+    /// `AdminRequest::ExportReplay`. Only called, once per tick, if replays are enabled (see
+    /// [`Options::replay_seconds`][`crate::options::Options::replay_seconds`]). May skip ticks by
+    /// returning [`None`], which is the default.
+    fn replay_frame(&self) -> Option<Self::GameUpdate> {
+        None
+    }
+
+    /// Makes randomness reproducible (see [`Options::seed`][`crate::options::Options::seed`]).
+    /// Called before the service is constructed. Ignored by default.
+    fn seed(seed: u64) {
+        let _ = seed;
+    }
 }
 
 /// Implemented by game bots.
@@ -253,6 +318,7 @@ impl GameArenaService for MockGame {
     type GameRequest = ();
     type PlayerData = ();
     type PlayerExtension = ();
+    type Options = crate::options::NoOptions;
 
     fn new(_min_players: usize) -> Self {
         Self
@@ -286,4 +352,8 @@ impl GameArenaService for MockGame {
     }
 
     fn tick(&mut self, _context: &mut Context<Self>) {}
+
+    fn set_paused(&mut self, _paused: bool) -> Result<(), &'static str> {
+        Ok(())
+    }
 }
@@ -3,16 +3,19 @@
 
 use crate::admin::AdminRepo;
 use crate::arena::ArenaRepo;
-use crate::client::ClientRepo;
+use crate::client::{AliasUniqueness, ClientRepo};
 use crate::context_service::ContextService;
 use crate::game_service::GameArenaService;
 use crate::invitation::InvitationRepo;
 use crate::leaderboard::LeaderboardRepo;
 use crate::metric::MetricRepo;
 use crate::plasma::PlasmaClient;
+use crate::replay::ReplayBuffer;
 use crate::system::SystemRepo;
+use crate::trace::TraceFormat;
 use actix::AsyncContext;
 use actix::{Actor, Context as ActorContext};
+use core_protocol::dto::FeatureFlags;
 use core_protocol::id::{ClientHash, RegionId, ServerId};
 use core_protocol::{PlasmaRequestV1, PlasmaUpdate, RealmName, ServerNumber};
 use futures::stream::FuturesUnordered;
@@ -111,24 +114,58 @@ impl<G: GameArenaService> Infrastructure<G> {
         bot_percent: Option<usize>,
         chat_log: Option<String>,
         trace_log: Option<String>,
+        trace_format: TraceFormat,
         game_client: Arc<RwLock<MiniCdn>>,
         server_token: &'static AtomicU64,
         client_authenticate: RateLimiterProps,
+        afk_timeout: Option<Duration>,
+        max_view: Option<f32>,
+        seed: Option<u64>,
+        max_team_size: Option<usize>,
+        features: FeatureFlags,
+        replay_seconds: Option<u32>,
+        alias_uniqueness: AliasUniqueness,
+        max_players: Option<usize>,
+        max_connections_per_ip: usize,
+        private_arena: bool,
+        game_options: G::Options,
     ) -> Self {
+        if let Some(seed) = seed {
+            G::seed(seed);
+        }
+        let mut context_service = ContextService::new(min_bots, max_bots, bot_percent, chat_log);
+        context_service.service.set_options(game_options);
+        if let Some(max_view) = max_view {
+            context_service.service.set_max_view(max_view);
+        }
+        context_service.replay = replay_seconds
+            .map(|seconds| ReplayBuffer::new((seconds as f32 / G::TICK_PERIOD_SECS) as u32));
+        #[cfg(feature = "teams")]
+        context_service
+            .context
+            .teams
+            .set_max_team_size(max_team_size);
+        #[cfg(not(feature = "teams"))]
+        let _ = max_team_size;
+
         Self {
             server_id,
             ipv4_address,
             region_id,
-            clients: ClientRepo::new(trace_log, client_authenticate),
+            clients: ClientRepo::new(
+                trace_log,
+                trace_format,
+                client_authenticate,
+                afk_timeout,
+                features,
+                alias_uniqueness,
+                max_players,
+                max_connections_per_ip,
+            ),
             plasma: PlasmaClient::new(redirect_server_number, realm_routes, server_token),
             system: SystemRepo::new(),
             admin: AdminRepo::new(game_client, client_hash),
-            arenas: ArenaRepo::new(ContextService::new(
-                min_bots,
-                max_bots,
-                bot_percent,
-                chat_log,
-            )),
+            arenas: ArenaRepo::new(context_service, private_arena),
             health: Health::default(),
             invitations: InvitationRepo::default(),
             metrics: MetricRepo::new(),
@@ -147,8 +184,9 @@ impl<G: GameArenaService> Infrastructure<G> {
         self.last_update = now;
 
         let server_delta = self.system.delta();
-        for (_, context_service) in self.arenas.iter_mut() {
+        for (realm_name, context_service) in self.arenas.iter_mut() {
             context_service.update(
+                realm_name,
                 &mut self.clients,
                 &mut self.invitations,
                 &mut self.metrics,
@@ -3,6 +3,7 @@
 
 use crate::game_service::GameArenaService;
 use crate::player::{PlayerData, PlayerRepo};
+use crate::request_error::RequestError;
 use crate::unwrap_or_return;
 use atomic_refcell::AtomicRefMut;
 use core_protocol::dto::InvitationDto;
@@ -83,14 +84,14 @@ impl<G: GameArenaService> InvitationRepo<G> {
         req_player_id: PlayerId,
         invitation_id: InvitationId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<InvitationUpdate, &'static str> {
+    ) -> Result<InvitationUpdate, RequestError> {
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("req player doesn't exist")?;
+            .ok_or(RequestError::InvalidState("req player doesn't exist"))?;
 
-        let req_client = req_player
-            .client_mut()
-            .ok_or("only clients can accept invitations")?;
+        let req_client = req_player.client_mut().ok_or(RequestError::NotAllowed(
+            "only clients can accept invitations",
+        ))?;
 
         req_client.invitation.invitation_accepted =
             self.invitations
@@ -101,7 +102,7 @@ impl<G: GameArenaService> InvitationRepo<G> {
         if req_client.invitation.invitation_accepted.is_some() {
             Ok(InvitationUpdate::Accepted)
         } else {
-            Err("no such invitation")
+            Err(RequestError::InvalidState("no such invitation"))
         }
     }
 
@@ -112,14 +113,14 @@ impl<G: GameArenaService> InvitationRepo<G> {
         realm_name: Option<RealmName>,
         server_id: ServerId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<InvitationUpdate, &'static str> {
+    ) -> Result<InvitationUpdate, RequestError> {
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("req player doesn't exist")?;
+            .ok_or(RequestError::InvalidState("req player doesn't exist"))?;
 
-        let req_client = req_player
-            .client_mut()
-            .ok_or("only clients can request invitations")?;
+        let req_client = req_player.client_mut().ok_or(RequestError::NotAllowed(
+            "only clients can request invitations",
+        ))?;
 
         // Silently ignore case of previously created invitation id.
         let invitation_id = if let Some(invitation_id) = req_client.invitation.invitation_created {
@@ -148,7 +149,7 @@ impl<G: GameArenaService> InvitationRepo<G> {
         realm_name: Option<RealmName>,
         server_id: ServerId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<InvitationUpdate, &'static str> {
+    ) -> Result<InvitationUpdate, RequestError> {
         match request {
             InvitationRequest::Accept(invitation_id) => {
                 self.accept(player_id, invitation_id, players)
@@ -14,6 +14,7 @@
 #![feature(let_chains)]
 
 pub mod admin;
+pub mod afk;
 pub mod arena;
 pub mod bot;
 pub mod chat;
@@ -29,14 +30,18 @@ pub mod liveboard;
 pub mod metric;
 pub mod ordered_set;
 pub mod player;
+pub mod request_error;
 //pub mod status;
 pub mod team;
+pub mod trace;
 #[macro_use]
 pub mod util;
 pub(crate) mod log;
 pub(crate) mod net;
 pub(crate) mod options;
 pub mod plasma;
+mod queue;
+pub mod replay;
 mod shutdown;
 pub mod static_files;
 pub mod system;
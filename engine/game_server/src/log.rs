@@ -2,8 +2,16 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::options::Options;
+#[cfg(feature = "tracing")]
+use core_protocol::id::PlayerId;
+#[cfg(feature = "tracing")]
+use core_protocol::name::RealmName;
+#[cfg(feature = "tracing")]
+use core_protocol::rpc::Request;
+use structopt::StructOpt;
 
-pub(crate) fn init_logger(options: &Options) {
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn init_logger<O: StructOpt>(options: &Options<O>) {
     let mut logger = env_logger::builder();
     logger.format_timestamp(None);
     logger.filter_module("server", options.debug_game);
@@ -12,3 +20,149 @@ pub(crate) fn init_logger(options: &Options) {
     logger.filter_module("game_server::entry_point", options.debug_http);
     logger.init();
 }
+
+/// Like the [`env_logger`] version, but also emits spans. Records from `log` are forwarded, so
+/// they are printed within the context of the current span.
+#[cfg(feature = "tracing")]
+pub(crate) fn init_logger<O: StructOpt>(options: &Options<O>) {
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    fn level(level: log::LevelFilter) -> LevelFilter {
+        match level {
+            log::LevelFilter::Off => LevelFilter::OFF,
+            log::LevelFilter::Error => LevelFilter::ERROR,
+            log::LevelFilter::Warn => LevelFilter::WARN,
+            log::LevelFilter::Info => LevelFilter::INFO,
+            log::LevelFilter::Debug => LevelFilter::DEBUG,
+            log::LevelFilter::Trace => LevelFilter::TRACE,
+        }
+    }
+
+    let targets = Targets::new()
+        .with_target("server", level(options.debug_game))
+        .with_target("game_server", level(options.debug_engine))
+        .with_target("game_server::plasma", level(options.debug_plasma))
+        .with_target("game_server::entry_point", level(options.debug_http));
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().without_time())
+        .with(targets)
+        .init();
+}
+
+/// Span around handling a request from a real player.
+#[cfg(feature = "tracing")]
+pub(crate) fn request_span<GR>(
+    player_id: PlayerId,
+    realm_name: Option<RealmName>,
+    request: &Request<GR>,
+) -> tracing::Span {
+    let kind = match request {
+        Request::Chat(_) => "chat",
+        Request::Client(_) => "client",
+        Request::Game(_) => "game",
+        Request::Invitation(_) => "invitation",
+        Request::Player(_) => "player",
+        Request::Team(_) => "team",
+    };
+    tracing::info_span!(
+        "request",
+        player_id = player_id.0.get(),
+        realm_name = realm_name.as_deref(),
+        kind
+    )
+}
+
+/// Span around a client websocket (re)connecting.
+#[cfg(feature = "tracing")]
+pub(crate) fn register_span(player_id: PlayerId, realm_name: Option<RealmName>) -> tracing::Span {
+    tracing::info_span!(
+        "register",
+        player_id = player_id.0.get(),
+        realm_name = realm_name.as_deref()
+    )
+}
+
+/// Span around cleaning up old clients.
+#[cfg(feature = "tracing")]
+pub(crate) fn prune_span() -> tracing::Span {
+    tracing::info_span!("prune")
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::log::request_span;
+    use core_protocol::id::PlayerId;
+    use core_protocol::name::RealmName;
+    use core_protocol::rpc::{ChatRequest, Request};
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    type Fields = Arc<Mutex<Vec<(&'static str, String)>>>;
+
+    /// Records the fields of every new span.
+    struct Recorder(Fields);
+
+    impl Visit for Recorder {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name(), value.to_owned()));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name(), format!("{value:?}")));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut Recorder(Arc::clone(&self.0)));
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn request_span_fields() {
+        let fields = Fields::default();
+        let player_id = PlayerId::nth_bot(3).unwrap();
+        let realm_name = RealmName::new("test");
+        let request = Request::<()>::Chat(ChatRequest::Unmute(player_id));
+
+        tracing::subscriber::with_default(Recorder(Arc::clone(&fields)), || {
+            let _span = request_span(player_id, Some(realm_name), &request).entered();
+        });
+
+        assert_eq!(
+            *fields.lock().unwrap(),
+            [
+                ("player_id", player_id.0.get().to_string()),
+                ("realm_name", "test".to_owned()),
+                ("kind", "chat".to_owned())
+            ]
+        );
+    }
+}
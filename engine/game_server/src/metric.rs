@@ -12,7 +12,7 @@ use actix::{ActorFutureExt, ContextFutureSpawner, WrapFuture};
 use core_protocol::dto::MetricsDataPointDto;
 use core_protocol::id::{CohortId, RegionId, UserAgentId};
 use core_protocol::metrics::{MetricFilter, Metrics};
-use core_protocol::name::Referrer;
+use core_protocol::name::{RealmName, Referrer};
 use core_protocol::{get_unix_time_now, PlasmaRequestV1, UnixTime};
 use heapless::HistoryBuffer;
 use rand::{thread_rng, Rng};
@@ -23,12 +23,13 @@ use std::marker::PhantomData;
 use std::time::{Duration, Instant};
 
 /// Stores and updates metrics to increase observability.
-pub(crate) struct MetricRepo<G: GameArenaService> {
+pub struct MetricRepo<G: GameArenaService> {
     startup: Instant,
     next_update: UnixTime,
     next_swap: UnixTime,
+    clients: ClientCounter,
     pub(crate) current: MetricBundle,
-    pub history: HistoryBuffer<MetricBundle, 24>,
+    pub(crate) history: HistoryBuffer<MetricBundle, 24>,
     _spooky: PhantomData<G>,
 }
 
@@ -92,6 +93,38 @@ impl<G: GameArenaService> ClientMetricData<G> {
     }
 }
 
+/// Peak number of clients connected at once, by arena (the main one is [`None`]).
+pub(crate) type PeakClients = HashMap<Option<RealmName>, u32>;
+
+/// Counts connected clients of each arena, to record the peak of each metrics period.
+#[derive(Debug, Default)]
+struct ClientCounter(HashMap<Option<RealmName>, u32>);
+
+impl ClientCounter {
+    fn connect(&mut self, realm_name: Option<RealmName>, peaks: &mut PeakClients) {
+        let count = self.0.entry(realm_name).or_default();
+        *count = count.saturating_add(1);
+        let peak = peaks.entry(realm_name).or_default();
+        *peak = (*peak).max(*count);
+    }
+
+    fn disconnect(&mut self, realm_name: Option<RealmName>) {
+        if let Entry::Occupied(mut occupied) = self.0.entry(realm_name) {
+            *occupied.get_mut() -= 1;
+            if *occupied.get() == 0 {
+                occupied.remove();
+            }
+        } else {
+            debug_assert!(false, "disconnect without connect");
+        }
+    }
+
+    /// Call with the peaks of a new period, which start out with the clients still connected.
+    fn new_period(&self, peaks: &mut PeakClients) {
+        peaks.extend(&self.0);
+    }
+}
+
 /// Stores a T for each of several queries, and an aggregate.
 #[derive(Default)]
 pub(crate) struct Bundle<T> {
@@ -195,6 +228,7 @@ impl<T: 'static> Bundle<T> {
 pub(crate) struct MetricBundle {
     pub(crate) start: UnixTime,
     pub(crate) bundle: Bundle<Metrics>,
+    pub(crate) peak_clients: PeakClients,
 }
 
 impl MetricBundle {
@@ -202,6 +236,7 @@ impl MetricBundle {
         Self {
             start,
             bundle: Bundle::default(),
+            peak_clients: PeakClients::default(),
         }
     }
 
@@ -234,6 +269,7 @@ impl<G: GameArenaService> MetricRepo<G> {
             startup: Instant::now(),
             next_swap: current.start + Self::HOUR_IN_MILLIS,
             next_update: Self::round_down_to_minute(now) + Self::MINUTE_IN_MILLIS,
+            clients: ClientCounter::default(),
             current,
             history: HistoryBuffer::default(),
             _spooky: PhantomData,
@@ -254,8 +290,11 @@ impl<G: GameArenaService> MetricRepo<G> {
         );
     }
 
-    /// Call when a websocket connects.
-    pub fn start_visit(&mut self, client: &mut PlayerClientData<G>) {
+    /// Call when a websocket connects to the arena of `realm_name`.
+    pub fn start_visit(&mut self, client: &mut PlayerClientData<G>, realm_name: Option<RealmName>) {
+        self.clients
+            .connect(realm_name, &mut self.current.peak_clients);
+
         let renewed = client.metrics.renewed;
 
         debug_assert!(
@@ -334,8 +373,9 @@ impl<G: GameArenaService> MetricRepo<G> {
         client.metrics.play_stopped = Some(now);
     }
 
-    pub fn stop_visit(&mut self, player: &mut PlayerData<G>) {
+    pub fn stop_visit(&mut self, player: &mut PlayerData<G>, realm_name: Option<RealmName>) {
         let mut client = unwrap_or_return!(player.client_mut());
+        self.clients.disconnect(realm_name);
 
         if client.metrics.play_started.is_some() {
             debug_assert!(
@@ -476,6 +516,7 @@ impl<G: GameArenaService> MetricRepo<G> {
         metrics_repo.next_swap = new_current + Self::HOUR_IN_MILLIS;
 
         let mut current = MetricBundle::new(metrics_repo.current.start);
+        current.peak_clients = std::mem::take(&mut metrics_repo.current.peak_clients);
         current.bundle.total = Self::get_metrics(infrastructure, None);
 
         macro_rules! copy {
@@ -531,6 +572,10 @@ impl<G: GameArenaService> MetricRepo<G> {
 
         infrastructure.metrics.history.write(current);
         infrastructure.metrics.current = MetricBundle::new(new_current);
+        let metrics_repo = &mut infrastructure.metrics;
+        metrics_repo
+            .clients
+            .new_period(&mut metrics_repo.current.peak_clients);
 
         Some((timestamp, save_to_db))
     }
@@ -611,3 +656,46 @@ impl<G: GameArenaService> MetricRepo<G> {
         (time / Self::HOUR_IN_MILLIS) * Self::HOUR_IN_MILLIS
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::metric::{ClientCounter, PeakClients};
+    use core_protocol::name::RealmName;
+
+    #[test]
+    fn peak_clients() {
+        let mut counter = ClientCounter::default();
+        let mut peaks = PeakClients::default();
+        for _ in 0..5 {
+            counter.connect(None, &mut peaks);
+        }
+        for _ in 0..3 {
+            counter.disconnect(None);
+        }
+        counter.connect(None, &mut peaks);
+        assert_eq!(peaks[&None], 5);
+
+        // Arenas are counted separately.
+        let realm_name = Some(RealmName::new("foo"));
+        for _ in 0..4 {
+            counter.connect(realm_name, &mut peaks);
+        }
+        assert_eq!(peaks[&realm_name], 4);
+        assert_eq!(peaks[&None], 5);
+        counter.disconnect(realm_name);
+
+        // Resets at the start of the next period.
+        let mut next = PeakClients::default();
+        counter.new_period(&mut next);
+        assert_eq!(next[&None], 3);
+        assert_eq!(next[&realm_name], 3);
+        counter.connect(None, &mut next);
+        assert_eq!(next[&None], 4);
+        assert_eq!(next[&realm_name], 3);
+
+        for _ in 0..3 {
+            counter.disconnect(realm_name);
+        }
+        assert!(!counter.0.contains_key(&realm_name));
+    }
+}
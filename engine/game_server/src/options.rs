@@ -1,18 +1,23 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::client::AliasUniqueness;
+use crate::trace::TraceFormat;
+use core_protocol::dto::FeatureFlags;
 use core_protocol::id::RegionId;
 use log::LevelFilter;
 use std::{net::IpAddr, sync::Arc};
 use structopt::StructOpt;
 
-/// Server options, to be specified as arguments.
+/// Server options, to be specified as arguments, including game specific options `O` (see
+/// [`GameArenaService::Options`][`crate::game_service::GameArenaService::Options`]).
 #[derive(Debug, StructOpt)]
-pub struct Options {
+pub struct Options<O: StructOpt> {
     /// Minimum number of bots.
     #[structopt(long)]
     pub min_bots: Option<usize>,
-    /// Maximum number of bots.
+    /// Maximum number of bots, even if there are few real players. Limits CPU load. Takes
+    /// precedence over `--min-bots`.
     #[structopt(long)]
     pub max_bots: Option<usize>,
     /// This percent of real players will help determine number of bots.
@@ -40,6 +45,10 @@ pub struct Options {
     /// Log client traces here
     #[structopt(long)]
     pub trace_log: Option<String>,
+    /// Format of the trace log: `csv` or `bitcode` (more compact, see
+    /// [`TraceRecord`][crate::trace::TraceRecord]).
+    #[structopt(long, default_value = "csv")]
+    pub trace_format: TraceFormat,
     /// Server id.
     #[structopt(long, default_value = "0")]
     pub server_id: u8,
@@ -78,9 +87,54 @@ pub struct Options {
     /// Client authenticate rate limiting burst.
     #[structopt(long, default_value = "16")]
     pub client_authenticate_burst: u32,
+    /// Kick alive players that haven't sent a game command in this many seconds (disabled by
+    /// default).
+    #[structopt(long)]
+    pub afk_timeout: Option<u64>,
+    /// Fraction (0 to 1) of the world's width that clients may zoom out to see, if the game
+    /// supports it (unlimited by default).
+    #[structopt(long)]
+    pub max_view: Option<f32>,
+    /// Seed for game randomness, such as spawn locations and bots, if the game supports it
+    /// (random by default).
+    #[structopt(long)]
+    pub seed: Option<u64>,
+    /// Maximum members per team, if the game supports teams (limited only by the game by
+    /// default).
+    #[structopt(long)]
+    pub max_team_size: Option<usize>,
+    /// Keep this many seconds of each arena's history for `AdminRequest::ExportReplay`, if the
+    /// game supports it (disabled by default).
+    #[structopt(long)]
+    pub replay_seconds: Option<u32>,
+    /// Whether live players in the same realm may share an alias: `permissive`, `reject`, or
+    /// `suffix` (e.g. `Bob 2`).
+    #[structopt(long, default_value = "permissive")]
+    pub alias_uniqueness: AliasUniqueness,
+    /// Maximum real players per arena, beyond which new clients wait in a queue (unlimited by
+    /// default).
+    #[structopt(long)]
+    pub max_players: Option<usize>,
+    /// Maximum connected clients per ip address, beyond which new clients are rejected
+    /// (generous by default, so as not to harm players behind a shared NAT).
+    #[structopt(long, default_value = "64")]
+    pub max_connections_per_ip: usize,
+    /// Treat the main arena as private, e.g. for teaching, so that admins may pause it (public by
+    /// default).
+    #[structopt(long)]
+    pub private_arena: bool,
+    /// Client features to enable or disable, e.g. `emotes,-spectate` (see [`FeatureFlags`]).
+    #[structopt(long)]
+    pub features: Option<FeatureFlags>,
+    #[structopt(flatten)]
+    pub game: O,
 }
 
-impl Options {
+/// Game specific options of games that don't have any.
+#[derive(Debug, Default, StructOpt)]
+pub struct NoOptions {}
+
+impl<O: StructOpt> Options<O> {
     pub(crate) fn certificate_private_key_paths(&self) -> Option<(Arc<str>, Arc<str>)> {
         self.certificate_path
             .as_deref()
@@ -1,12 +1,14 @@
+use crate::client::ClientStatus;
 use crate::{game_service::GameArenaService, infrastructure::Infrastructure};
 use actix::{Handler, Recipient};
 use axum::http::Method;
 use core_protocol::{
-    ArenaToken, ClientHash, GameId, PlasmaRequest, PlasmaRequestV1, PlasmaUpdate, PlasmaUpdateV1,
-    RealmName, RegionId, ServerId, ServerNumber, ServerRole,
+    ArenaToken, ClientHash, ClientUpdate, GameId, PlasmaRequest, PlasmaRequestV1, PlasmaUpdate,
+    PlasmaUpdateV1, RealmName, RegionId, ServerId, ServerNumber, ServerRole, Update,
 };
 use log::{info, warn};
 use reqwest::Client;
+use server_util::observer::ObserverUpdate;
 use std::collections::HashMap;
 use std::future::Future;
 use std::net::Ipv4Addr;
@@ -241,6 +243,7 @@ impl<G: GameArenaService> Handler<PlasmaUpdate> for Infrastructure<G> {
                     admin,
                     moderator,
                     nick_name,
+                    settings,
                     ..
                 } => {
                     if let Some(context_service) = self.arenas.get_mut(realm_name) {
@@ -258,6 +261,17 @@ impl<G: GameArenaService> Handler<PlasmaUpdate> for Infrastructure<G> {
                                     info!(
                                         "set moderator status of {session_token:?} to {moderator}"
                                     );
+                                    if let Some(settings) = settings {
+                                        // Restore settings from the user's profile.
+                                        if let ClientStatus::Connected { observer } = &client.status
+                                        {
+                                            let _ = observer.send(ObserverUpdate::Send {
+                                                message: Update::Client(
+                                                    ClientUpdate::SettingsSynced(Some(settings)),
+                                                ),
+                                            });
+                                        }
+                                    }
                                     return;
                                 } else {
                                     warn!("user_id/session_id didn't match");
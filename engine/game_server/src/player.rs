@@ -5,6 +5,7 @@ use crate::client::PlayerClientData;
 use crate::game_service::GameArenaService;
 use crate::invitation::InvitationRepo;
 use crate::metric::MetricRepo;
+use crate::request_error::RequestError;
 use crate::team::PlayerTeamData;
 #[cfg(feature = "teams")]
 use crate::team::TeamRepo;
@@ -129,31 +130,33 @@ impl<G: GameArenaService> PlayerRepo<G> {
         req_player_id: PlayerId,
         report_player_id: PlayerId,
         metrics: &mut MetricRepo<G>,
-    ) -> Result<PlayerUpdate, &'static str> {
+    ) -> Result<PlayerUpdate, RequestError> {
         if req_player_id == report_player_id {
-            return Err("cannot report self");
+            return Err(RequestError::InvalidInput("cannot report self"));
         }
         let mut req_player = self
             .borrow_player_mut(req_player_id)
-            .ok_or("nonexistent player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
         if req_player.score < G::MINIMUM_REPORT_SCORE {
-            return Err("report requirements unmet");
+            return Err(RequestError::NotAllowed("report requirements unmet"));
         }
         let req_client = req_player
             .client_mut()
-            .ok_or("only clients can report players")?;
-        let mut report_player = self
-            .borrow_player_mut(report_player_id)
-            .ok_or("cannot report nonexistent player")?;
+            .ok_or(RequestError::NotAllowed("only clients can report players"))?;
+        let mut report_player =
+            self.borrow_player_mut(report_player_id)
+                .ok_or(RequestError::InvalidState(
+                    "cannot report nonexistent player",
+                ))?;
         let report_client = report_player
             .client_mut()
-            .ok_or("only clients can be reported")?;
+            .ok_or(RequestError::NotAllowed("only clients can be reported"))?;
         if req_client.reported.insert(report_player_id) {
             report_client.chat.context.report();
             metrics.mutate_with(|m| m.abuse_reports.increment(), &report_client.metrics);
             Ok(PlayerUpdate::Reported(report_player_id))
         } else {
-            Err("already reported")
+            Err(RequestError::InvalidState("already reported"))
         }
     }
 
@@ -163,7 +166,7 @@ impl<G: GameArenaService> PlayerRepo<G> {
         req_player_id: PlayerId,
         request: PlayerRequest,
         metrics: &mut MetricRepo<G>,
-    ) -> Result<PlayerUpdate, &'static str> {
+    ) -> Result<PlayerUpdate, RequestError> {
         match request {
             PlayerRequest::Report(player_id) => {
                 self.report_player(req_player_id, player_id, metrics)
@@ -171,13 +174,15 @@ impl<G: GameArenaService> PlayerRepo<G> {
         }
     }
 
-    /// Updates cache of whether players are alive, tallying metrics in the process.
+    /// Updates cache of whether players are alive, tallying metrics in the process. Returns the
+    /// players that just spawned.
     pub(crate) fn update_is_alive_and_team_id(
         &self,
         service: &mut G,
         #[cfg(feature = "teams")] teams: &mut TeamRepo<G>,
         metrics: &mut MetricRepo<G>,
-    ) {
+    ) -> Vec<PlayerId> {
+        let mut spawned = Vec::new();
         for pt in self.iter() {
             let is_alive = service.is_alive(pt);
             let mut p = pt.borrow_player_mut();
@@ -187,6 +192,7 @@ impl<G: GameArenaService> PlayerRepo<G> {
                 if is_alive {
                     // Play started.
                     metrics.start_play(&mut p);
+                    spawned.push(player_id);
                 } else {
                     // Play stopped.
                     metrics.stop_play(&mut *p);
@@ -232,6 +238,7 @@ impl<G: GameArenaService> PlayerRepo<G> {
                 }
             }
         }
+        spawned
     }
 
     /// Computes current set of player dtos, and number of real players (total and live).
@@ -245,7 +252,7 @@ impl<G: GameArenaService> PlayerRepo<G> {
         let player_dtos = self
             .iter_borrow()
             .filter_map(|p| {
-                if !p.is_bot() {
+                if !p.is_bot() && !p.is_spectator() {
                     real_players += 1;
                 }
 
@@ -512,6 +519,11 @@ impl<G: GameArenaService> PlayerData<G> {
         self.player_id.is_bot()
     }
 
+    /// Returns true iff the player is a real player that watches without playing.
+    pub fn is_spectator(&self) -> bool {
+        self.client().map_or(false, |c| c.spectator)
+    }
+
     /// Returns true iff the player 1) never played yet 2) stopped playing over half a minute ago.
     pub fn is_out_of_game(&self) -> bool {
         !self.was_ever_alive
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use server_util::generate_id::generate_id_64;
+use std::collections::VecDeque;
+use std::num::NonZeroU64;
+use std::time::{Duration, Instant};
+
+/// Clients waiting for a slot in a full arena, in order of arrival. Clients keep their place by
+/// retrying with the ticket they were issued, so that neither clients behind the same ip address
+/// nor anyone guessing it can take (or skip) their place.
+#[derive(Debug, Default)]
+pub(crate) struct AdmissionQueue {
+    /// With when they last tried.
+    waiting: VecDeque<(NonZeroU64, Instant)>,
+}
+
+impl AdmissionQueue {
+    /// Clients that stop retrying for this long lose their place.
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Admits the holder of `ticket` if they are among the first `free` (slots) in line,
+    /// otherwise returns their position in line (starting at 1) and the ticket to retry with.
+    /// Unknown (e.g. expired) or missing tickets are issued a new one at the back of the line.
+    pub fn admit(
+        &mut self,
+        ticket: Option<NonZeroU64>,
+        free: usize,
+        now: Instant,
+    ) -> Result<(), (u32, NonZeroU64)> {
+        self.waiting
+            .retain(|&(_, tried)| now.duration_since(tried) < Self::TIMEOUT);
+
+        let position = ticket.and_then(|ticket| self.position(ticket));
+        let index = if let Some(index) = position {
+            self.waiting[index].1 = now;
+            index
+        } else {
+            let ticket = loop {
+                let ticket = generate_id_64();
+                if self.position(ticket).is_none() {
+                    break ticket;
+                }
+            };
+            self.waiting.push_back((ticket, now));
+            self.waiting.len() - 1
+        };
+
+        if index < free {
+            self.waiting.remove(index);
+            Ok(())
+        } else {
+            Err(((index + 1 - free) as u32, self.waiting[index].0))
+        }
+    }
+
+    /// Whether `ticket` holds a place in line (that might have timed out).
+    pub fn is_waiting(&self, ticket: NonZeroU64) -> bool {
+        self.position(ticket).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiting.is_empty()
+    }
+
+    fn position(&self, ticket: NonZeroU64) -> Option<usize> {
+        self.waiting.iter().position(|&(t, _)| t == ticket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::queue::AdmissionQueue;
+    use std::num::NonZeroU64;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn admission_queue() {
+        let now = Instant::now();
+        let mut queue = AdmissionQueue::default();
+
+        // Free slots admit immediately.
+        assert_eq!(queue.admit(None, 1, now), Ok(()));
+        assert!(queue.is_empty());
+
+        // Full.
+        let (position, two) = queue.admit(None, 0, now).unwrap_err();
+        assert_eq!(position, 1);
+        let (position, three) = queue.admit(None, 0, now).unwrap_err();
+        assert_eq!(position, 2);
+        assert_ne!(two, three);
+        assert_eq!(queue.admit(Some(two), 0, now), Err((1, two)));
+        assert!(queue.is_waiting(three));
+
+        // Forged tickets go to the back of the line.
+        let forged = NonZeroU64::new(1).unwrap();
+        assert!(!queue.is_waiting(forged));
+        let (position, four) = queue.admit(Some(forged), 0, now).unwrap_err();
+        assert_eq!(position, 3);
+        assert_ne!(four, forged);
+
+        // Freeing a slot promotes the first in line, not newcomers.
+        assert_eq!(queue.admit(Some(four), 1, now), Err((2, four)));
+        assert_eq!(queue.admit(Some(two), 1, now), Ok(()));
+        assert!(!queue.is_waiting(two));
+        assert_eq!(queue.admit(Some(three), 0, now), Err((1, three)));
+
+        // Those who stop retrying lose their place.
+        let later = now + AdmissionQueue::TIMEOUT / 2;
+        assert_eq!(queue.admit(Some(four), 0, later), Err((2, four)));
+        let much_later = now + AdmissionQueue::TIMEOUT + Duration::from_secs(1);
+        assert_eq!(queue.admit(Some(four), 0, much_later), Err((1, four)));
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::bitcode;
+use core_protocol::prelude::*;
+use std::collections::VecDeque;
+
+/// Recent [`GameArenaService::replay_frame`][crate::game_service::GameArenaService::replay_frame]s
+/// of one arena, for `AdminRequest::ExportReplay`. Bounded by both duration and size.
+pub struct ReplayBuffer {
+    /// Encoded frames and the tick they were recorded on, oldest first.
+    frames: VecDeque<(u32, Box<[u8]>)>,
+    /// Total length of [`Self::frames`], in bytes.
+    bytes: usize,
+    /// Ticks recorded so far.
+    tick: u32,
+    /// Frames older than this many ticks are forgotten.
+    max_ticks: u32,
+    /// Oldest frames are forgotten beyond this many bytes, regardless of age.
+    max_bytes: usize,
+}
+
+impl ReplayBuffer {
+    const MAX_BYTES: usize = 64 << 20;
+
+    pub fn new(max_ticks: u32) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            bytes: 0,
+            tick: 0,
+            max_ticks,
+            max_bytes: Self::MAX_BYTES,
+        }
+    }
+
+    /// Call once per tick, with a frame if there is one.
+    pub fn record<U: Encode>(&mut self, frame: Option<&U>) {
+        self.tick = self.tick.wrapping_add(1);
+        if let Some(frame) = frame {
+            match bitcode::encode(frame) {
+                Ok(encoded) => {
+                    self.bytes += encoded.len();
+                    self.frames
+                        .push_back((self.tick, encoded.into_boxed_slice()));
+                }
+                Err(e) => log::error!("couldn't encode replay frame: {e}"),
+            }
+        }
+        while let Some((tick, frame)) = self.frames.front() {
+            if self.tick.wrapping_sub(*tick) < self.max_ticks && self.bytes <= self.max_bytes {
+                break;
+            }
+            self.bytes -= frame.len();
+            self.frames.pop_front();
+        }
+    }
+
+    /// Encodes the frames of the last `ticks` ticks, for [`Self::decode`].
+    pub fn export(&self, ticks: u32) -> Result<Vec<u8>, &'static str> {
+        let frames: Vec<_> = self
+            .frames
+            .iter()
+            .filter(|(tick, _)| self.tick.wrapping_sub(*tick) < ticks)
+            .map(|(tick, frame)| (tick.wrapping_sub(self.tick), frame.to_vec()))
+            .collect();
+        if frames.is_empty() {
+            return Err("no replay frames");
+        }
+        bitcode::encode(&frames).map_err(|_| "couldn't encode replay")
+    }
+
+    /// Decodes an exported replay into frames and the tick they were recorded on, relative to
+    /// (and therefore not after) the time of export.
+    pub fn decode<U: Decode>(replay: &[u8]) -> Result<Vec<(i32, U)>, &'static str> {
+        let frames: Vec<(u32, Vec<u8>)> =
+            bitcode::decode(replay).map_err(|_| "couldn't decode replay")?;
+        frames
+            .into_iter()
+            .map(|(tick, frame)| {
+                bitcode::decode(&frame)
+                    .map(|frame| (tick as i32, frame))
+                    .map_err(|_| "couldn't decode replay frame")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::replay::ReplayBuffer;
+
+    #[test]
+    fn replay_buffer() {
+        let mut buffer = ReplayBuffer::new(4);
+        assert!(buffer.export(10).is_err());
+
+        // Every other tick has a frame.
+        for i in 0..10u16 {
+            buffer.record((i % 2 == 0).then_some(&i));
+        }
+        let replay = buffer.export(10).unwrap();
+        assert_eq!(
+            ReplayBuffer::decode::<u16>(&replay),
+            Ok(vec![(-3, 6), (-1, 8)])
+        );
+
+        let replay = buffer.export(2).unwrap();
+        assert_eq!(ReplayBuffer::decode::<u16>(&replay), Ok(vec![(-1, 8)]));
+    }
+
+    #[test]
+    fn replay_buffer_max_bytes() {
+        let mut buffer = ReplayBuffer::new(u32::MAX);
+        buffer.max_bytes = 100;
+        for i in 0..200u32 {
+            buffer.record(Some(&i));
+        }
+        assert!(buffer.bytes <= 100);
+        assert!(!buffer.frames.is_empty());
+        let replay = buffer.export(u32::MAX).unwrap();
+        let frames = ReplayBuffer::decode::<u32>(&replay).unwrap();
+        assert_eq!(frames.last(), Some(&(-1, 199)));
+    }
+}
@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::rpc::{ClientUpdate, RequestErrorKind, Update};
+use std::fmt::{self, Display, Formatter};
+
+/// Why a client request was rejected. The message is for logs, whereas only the
+/// [`RequestErrorKind`] is sent to the client.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RequestError {
+    RateLimited(&'static str),
+    NotAllowed(&'static str),
+    InvalidState(&'static str),
+    InvalidInput(&'static str),
+}
+
+impl RequestError {
+    pub fn kind(self) -> RequestErrorKind {
+        match self {
+            Self::RateLimited(_) => RequestErrorKind::RateLimited,
+            Self::NotAllowed(_) => RequestErrorKind::NotAllowed,
+            Self::InvalidState(_) => RequestErrorKind::InvalidState,
+            Self::InvalidInput(_) => RequestErrorKind::InvalidInput,
+        }
+    }
+
+    /// Human-readable explanation, for logs.
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::RateLimited(s)
+            | Self::NotAllowed(s)
+            | Self::InvalidState(s)
+            | Self::InvalidInput(s) => s,
+        }
+    }
+
+    /// The update that informs the client of the error.
+    pub fn into_update<GU>(self) -> Update<GU> {
+        Update::Client(ClientUpdate::RequestFailed(self.kind()))
+    }
+}
+
+impl Display for RequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
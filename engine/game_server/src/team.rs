@@ -8,6 +8,8 @@ use crate::ordered_set::OrderedSet;
 #[cfg(feature = "teams")]
 use crate::player::{PlayerData, PlayerRepo};
 #[cfg(feature = "teams")]
+use crate::request_error::RequestError;
+#[cfg(feature = "teams")]
 use crate::unwrap_or_return;
 #[cfg(feature = "teams")]
 use crate::util::diff_small_n;
@@ -71,9 +73,10 @@ impl<G: GameArenaService> TeamData<G> {
         self.members.peek_front() == Some(player_id)
     }
 
-    /// Returns if the team has the maximum possible amount of members.
-    pub fn is_full(&self, players_online: usize) -> bool {
-        self.members.len() >= G::team_members_max(players_online)
+    /// Returns if the team has the maximum possible amount of members (see
+    /// [`TeamRepo::members_max`]).
+    pub fn is_full(&self, members_max: usize) -> bool {
+        self.members.len() >= members_max
     }
 
     /// Returns if the team has the maximum possible amount of joiners a.k.a. requests.
@@ -176,6 +179,8 @@ impl ClientTeamData {
 pub struct TeamRepo<G: GameArenaService> {
     teams: HashMap<TeamId, TeamData<G>>,
     previous: Arc<[TeamDto]>,
+    /// Further limits [`GameArenaService::team_members_max`], if set.
+    max_team_size: Option<usize>,
 }
 
 #[cfg(feature = "teams")]
@@ -184,6 +189,7 @@ impl<G: GameArenaService> Default for TeamRepo<G> {
         Self {
             teams: HashMap::new(),
             previous: Vec::new().into(),
+            max_team_size: None,
         }
     }
 }
@@ -195,44 +201,63 @@ impl<G: GameArenaService> TeamRepo<G> {
         self.teams.get(&team_id)
     }
 
+    /// Limits the number of members per team (unlimited beyond the game's own limit if
+    /// [`None`]). Doesn't kick members of existing teams that exceed it.
+    pub(crate) fn set_max_team_size(&mut self, max_team_size: Option<usize>) {
+        self.max_team_size = max_team_size;
+    }
+
+    /// Maximum number of members per team.
+    pub fn members_max(&self, players_online: usize) -> usize {
+        let max = G::team_members_max(players_online);
+        self.max_team_size.map_or(max, |m| m.min(max))
+    }
+
     fn accept_or_reject_player(
         &mut self,
         req_player_id: PlayerId,
         joiner_player_id: PlayerId,
         accept: bool,
         players: &mut PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
+    ) -> Result<TeamUpdate, RequestError> {
         if joiner_player_id == req_player_id {
-            return Err("cannot accept/reject self");
+            return Err(RequestError::InvalidInput("cannot accept/reject self"));
         }
 
         let req_player = players
             .borrow_player(req_player_id)
-            .ok_or("requesting player nonexistent")?;
+            .ok_or(RequestError::InvalidState("requesting player nonexistent"))?;
 
-        let team_id = req_player.team_id().ok_or("not in team")?;
+        let team_id = req_player
+            .team_id()
+            .ok_or(RequestError::InvalidState("not in team"))?;
 
+        let members_max = self.members_max(players.real_players_live);
         let team = self.teams.get_mut(&team_id).ok_or_else(|| {
             debug_assert!(false, "team id should have been cleared");
-            "nonexistent team"
+            RequestError::InvalidState("nonexistent team")
         })?;
         if !team.is_captain(req_player_id) {
-            return Err("not captain");
+            return Err(RequestError::NotAllowed("not captain"));
         }
-        if accept && team.is_full(players.real_players_live) {
-            return Err("team full");
+        if accept && team.is_full(members_max) {
+            return Err(RequestError::InvalidState("team full"));
         }
         if !team.joiners.remove(joiner_player_id) {
-            return Err("player wasn't requesting to join");
+            return Err(RequestError::InvalidState(
+                "player wasn't requesting to join",
+            ));
         }
 
         let mut joiner_player = players
             .borrow_player_mut(joiner_player_id)
-            .ok_or("nonexistent player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
 
         if joiner_player.team_id().is_some() {
             debug_assert!(false, "should have been removed from joiners");
-            return Err("cannot accept/reject player already on team");
+            return Err(RequestError::InvalidState(
+                "cannot accept/reject player already on team",
+            ));
         }
 
         Ok(if accept {
@@ -240,10 +265,7 @@ impl<G: GameArenaService> TeamRepo<G> {
             // into the team.
             team.members.insert_back(joiner_player_id);
 
-            debug_assert!(
-                team.members.len() <= G::team_members_max(players.real_players_live),
-                "team overfull"
-            );
+            debug_assert!(team.members.len() <= members_max, "team overfull");
 
             self.assign_team_and_cancel_joins(joiner_player, team_id);
 
@@ -302,28 +324,32 @@ impl<G: GameArenaService> TeamRepo<G> {
         req_player_id: PlayerId,
         assign_player_id: PlayerId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
+    ) -> Result<TeamUpdate, RequestError> {
         if assign_player_id == req_player_id {
-            return Err("cannot assign self");
+            return Err(RequestError::InvalidInput("cannot assign self"));
         }
 
         let req_player = players
             .borrow_player(req_player_id)
-            .ok_or("requesting player nonexistent")?;
+            .ok_or(RequestError::InvalidState("requesting player nonexistent"))?;
 
-        let team_id = req_player.team_id().ok_or("not in team")?;
+        let team_id = req_player
+            .team_id()
+            .ok_or(RequestError::InvalidState("not in team"))?;
         let team = self.teams.get_mut(&team_id).ok_or_else(|| {
             debug_assert!(false, "team id should have been cleared");
-            "nonexistent team"
+            RequestError::InvalidState("nonexistent team")
         })?;
         if !team.is_captain(req_player_id) {
-            return Err("not captain");
+            return Err(RequestError::NotAllowed("not captain"));
         }
         // This updates members_changed automatically.
         if team.assign_captain(assign_player_id) {
             Ok(TeamUpdate::Promoted(assign_player_id))
         } else {
-            Err("can only assign team members to be captain")
+            Err(RequestError::InvalidInput(
+                "can only assign team members to be captain",
+            ))
         }
     }
 
@@ -332,30 +358,30 @@ impl<G: GameArenaService> TeamRepo<G> {
         req_player_id: PlayerId,
         team_name: TeamName,
         players: &mut PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
-        if G::team_members_max(players.real_players_live) == 0 {
-            return Err("teams are currently disabled");
+    ) -> Result<TeamUpdate, RequestError> {
+        if self.members_max(players.real_players_live) == 0 {
+            return Err(RequestError::NotAllowed("teams are currently disabled"));
         }
 
         let req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("requesting player nonexistent")?;
+            .ok_or(RequestError::InvalidState("requesting player nonexistent"))?;
 
         if !req_player.is_alive() {
-            return Err("must be alive to create team");
+            return Err(RequestError::InvalidState("must be alive to create team"));
         }
 
         if req_player.team_id().is_some() {
-            return Err("already in team");
+            return Err(RequestError::InvalidState("already in team"));
         }
 
         let censored_team_name = TeamName::new_sanitized(team_name.as_str());
 
         if censored_team_name.is_empty() {
-            return Err("cannot use empty team name");
+            return Err(RequestError::InvalidInput("cannot use empty team name"));
         }
         if self.teams.values().any(|t| t.name == censored_team_name) {
-            return Err("team name in use");
+            return Err(RequestError::InvalidInput("team name in use"));
         }
 
         let team_data = TeamData::new(censored_team_name, req_player_id);
@@ -378,26 +404,28 @@ impl<G: GameArenaService> TeamRepo<G> {
         req_player_id: PlayerId,
         kick_player_id: PlayerId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
+    ) -> Result<TeamUpdate, RequestError> {
         if kick_player_id == req_player_id {
-            return Err("cannot kick self");
+            return Err(RequestError::InvalidInput("cannot kick self"));
         }
 
         let req_player = players
             .borrow_player(req_player_id)
-            .ok_or("requesting player nonexistent")?;
+            .ok_or(RequestError::InvalidState("requesting player nonexistent"))?;
 
-        let team_id = req_player.team_id().ok_or("not in team")?;
+        let team_id = req_player
+            .team_id()
+            .ok_or(RequestError::InvalidState("not in team"))?;
         let team = self.teams.get_mut(&team_id).ok_or_else(|| {
             debug_assert!(false, "team id should have been cleared");
-            "nonexistent team"
+            RequestError::InvalidState("nonexistent team")
         })?;
         if !team.is_captain(req_player_id) {
-            return Err("not captain");
+            return Err(RequestError::NotAllowed("not captain"));
         }
         let mut kick_player = players
             .borrow_player_mut(kick_player_id)
-            .ok_or("nonexistent player")?;
+            .ok_or(RequestError::InvalidState("nonexistent player"))?;
 
         if team.members.remove(kick_player_id) {
             debug_assert_eq!(kick_player.team_id(), Some(team_id));
@@ -406,7 +434,9 @@ impl<G: GameArenaService> TeamRepo<G> {
 
             Ok(TeamUpdate::Kicked(kick_player_id))
         } else {
-            Err("cannot kick player that isn't in team")
+            Err(RequestError::InvalidInput(
+                "cannot kick player that isn't in team",
+            ))
         }
     }
 
@@ -415,12 +445,14 @@ impl<G: GameArenaService> TeamRepo<G> {
         &mut self,
         req_player_id: PlayerId,
         players: &PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
+    ) -> Result<TeamUpdate, RequestError> {
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("requesting player nonexistent")?;
+            .ok_or(RequestError::InvalidState("requesting player nonexistent"))?;
 
-        let team_id = req_player.team_id().ok_or("not in team")?;
+        let team_id = req_player
+            .team_id()
+            .ok_or(RequestError::InvalidState("not in team"))?;
         req_player.team.status = PlayerTeamStatus::solo();
 
         // We may need to borrow other players later on, and we are done with this one.
@@ -428,7 +460,7 @@ impl<G: GameArenaService> TeamRepo<G> {
 
         let team = self.teams.get_mut(&team_id).ok_or_else(|| {
             debug_assert!(false, "team id should have been cleared");
-            "nonexistent team"
+            RequestError::InvalidState("nonexistent team")
         })?;
 
         if team.members.remove(req_player_id) {
@@ -470,31 +502,37 @@ impl<G: GameArenaService> TeamRepo<G> {
         req_player_id: PlayerId,
         join_team_id: TeamId,
         players: &mut PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
+    ) -> Result<TeamUpdate, RequestError> {
         let mut req_player = players
             .borrow_player_mut(req_player_id)
-            .ok_or("requesting player nonexistent")?;
+            .ok_or(RequestError::InvalidState("requesting player nonexistent"))?;
 
         if !req_player.is_alive() {
-            return Err("must be alive to join team");
+            return Err(RequestError::InvalidState("must be alive to join team"));
         }
 
         let join_team = self
             .teams
             .get_mut(&join_team_id)
-            .ok_or("nonexistent team")?;
+            .ok_or(RequestError::InvalidState("nonexistent team"))?;
 
         if join_team.is_closed() {
-            return Err("team is closed to requests");
+            return Err(RequestError::NotAllowed("team is closed to requests"));
         }
 
         let joins = match &mut req_player.team.status {
-            PlayerTeamStatus::Teamed { .. } => return Err("cannot request when already on team"),
+            PlayerTeamStatus::Teamed { .. } => {
+                return Err(RequestError::InvalidState(
+                    "cannot request when already on team",
+                ))
+            }
             PlayerTeamStatus::Solo { joins } => joins,
         };
 
         let no_longer_joining = if joins.contains(&join_team_id) {
-            return Err("already requested to join this team");
+            return Err(RequestError::InvalidState(
+                "already requested to join this team",
+            ));
         } else if joins.len() >= G::TEAM_JOINS_MAX {
             // Make room in joins.
             joins.pop_front()
@@ -530,7 +568,7 @@ impl<G: GameArenaService> TeamRepo<G> {
         req_player_id: PlayerId,
         request: TeamRequest,
         players: &mut PlayerRepo<G>,
-    ) -> Result<TeamUpdate, &'static str> {
+    ) -> Result<TeamUpdate, RequestError> {
         match request {
             TeamRequest::Accept(player_id) => {
                 self.accept_or_reject_player(req_player_id, player_id, true, players)
@@ -560,7 +598,7 @@ impl<G: GameArenaService> TeamRepo<G> {
             .map(|(&team_id, team_data)| TeamDto {
                 team_id,
                 name: team_data.name,
-                full: team_data.is_full(players.real_players_live),
+                full: team_data.is_full(self.members_max(players.real_players_live)),
                 closed: team_data.is_closed(),
             })
             .collect()
@@ -684,6 +722,7 @@ fn remove_join(join: TeamId, joins: &mut VecDeque<TeamId>) -> bool {
 mod test {
     use crate::game_service::{GameArenaService, MockGame};
     use crate::player::{PlayerData, PlayerRepo, PlayerTuple};
+    use crate::request_error::RequestError;
     use crate::team::TeamRepo;
     use core_protocol::id::{PlayerId, TeamId};
     use core_protocol::name::TeamName;
@@ -794,6 +833,59 @@ mod test {
         assert_eq!(teams.teams.len(), 0);
     }
 
+    #[test]
+    fn max_team_size() {
+        let mut players = PlayerRepo::<MockGame>::default();
+        let mut teams = TeamRepo::<MockGame>::default();
+        teams.set_max_team_size(Some(3));
+        assert_eq!(teams.members_max(0), 3);
+
+        let player_ids: Vec<_> = (1..=MockGame::TEAM_JOINERS_MAX + 1)
+            .map(|i| {
+                let player_id = PlayerId::nth_bot(i).unwrap();
+                let player = Arc::new(PlayerTuple::<MockGame>::new(PlayerData::new(
+                    player_id, None,
+                )));
+                player.borrow_player_mut().was_alive = true;
+                players.insert(player_id, player);
+                player_id
+            })
+            .collect();
+        let (&captain, joiners) = player_ids.split_first().unwrap();
+
+        let team_id = match teams.handle_team_request(
+            captain,
+            TeamRequest::Create(TeamName::new_sanitized("capped")),
+            &mut players,
+        ) {
+            Ok(TeamUpdate::Created(team_id, _)) => team_id,
+            res => unreachable!("{:?}", res),
+        };
+        for &joiner in joiners {
+            let res = teams.handle_team_request(joiner, TeamRequest::Join(team_id), &mut players);
+            assert!(matches!(res, Ok(TeamUpdate::Joining(_))), "{:?}", res);
+        }
+
+        // Fill the team to the cap.
+        for &joiner in &joiners[..2] {
+            let res = teams.handle_team_request(captain, TeamRequest::Accept(joiner), &mut players);
+            assert!(matches!(res, Ok(TeamUpdate::Accepted(_))), "{:?}", res);
+        }
+        assert!(teams.get(team_id).unwrap().is_full(teams.members_max(0)));
+
+        let res = teams.handle_team_request(captain, TeamRequest::Accept(joiners[2]), &mut players);
+        assert_eq!(res.unwrap_err(), RequestError::InvalidState("team full"));
+        assert_eq!(teams.get(team_id).unwrap().members.len(), 3);
+
+        // Clients are told the team is full.
+        let (added, _) = teams.delta(&players).unwrap();
+        assert!(added.iter().all(|dto| dto.full));
+
+        for player_id in player_ids {
+            let _ = teams.handle_team_request(player_id, TeamRequest::Leave, &mut players);
+        }
+    }
+
     #[test]
     fn fuzz() {
         let mut players = PlayerRepo::<MockGame>::default();
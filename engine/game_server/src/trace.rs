@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use core_protocol::bitcode;
+use core_protocol::id::{GameId, RegionId, UserAgentId};
+use core_protocol::prelude::*;
+use core_protocol::UnixTime;
+use std::str::FromStr;
+
+/// How [`ClientRepo`][crate::client::ClientRepo] writes client traces to the trace log.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// One human readable line per trace.
+    #[default]
+    Csv,
+    /// Length-prefixed [`TraceRecord`]s, read with [`TraceRecord::decode_all`].
+    Bitcode,
+}
+
+impl FromStr for TraceFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "csv" => Self::Csv,
+            "bitcode" => Self::Bitcode,
+            _ => return Err("expected csv or bitcode"),
+        })
+    }
+}
+
+/// A client trace, as written by [`TraceFormat::Bitcode`].
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct TraceRecord {
+    pub unix_time: UnixTime,
+    pub game_id: GameId,
+    pub ip_address: String,
+    pub region_id: Option<RegionId>,
+    pub referrer: Option<String>,
+    pub user_agent_id: Option<UserAgentId>,
+    pub message: String,
+}
+
+impl TraceRecord {
+    /// Encodes the record, prefixed by its length so that records can be appended to a file.
+    pub fn encode(&self) -> Result<Vec<u8>, &'static str> {
+        let encoded = bitcode::encode(self).map_err(|_| "couldn't encode trace")?;
+        let len = u32::try_from(encoded.len()).map_err(|_| "trace too long")?;
+        let mut ret = Vec::with_capacity(4 + encoded.len());
+        ret.extend_from_slice(&len.to_le_bytes());
+        ret.extend_from_slice(&encoded);
+        Ok(ret)
+    }
+
+    /// Decodes a trace log of concatenated [`Self::encode`]d records.
+    pub fn decode_all(mut bytes: &[u8]) -> Result<Vec<Self>, &'static str> {
+        let mut ret = Vec::new();
+        while !bytes.is_empty() {
+            let len: [u8; 4] = bytes
+                .get(..4)
+                .and_then(|len| len.try_into().ok())
+                .ok_or("truncated trace length")?;
+            let end = 4 + u32::from_le_bytes(len) as usize;
+            let record = bytes.get(4..end).ok_or("truncated trace")?;
+            ret.push(bitcode::decode(record).map_err(|_| "couldn't decode trace")?);
+            bytes = &bytes[end..];
+        }
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::{TraceFormat, TraceRecord};
+    use core_protocol::id::{GameId, RegionId, UserAgentId};
+    use std::str::FromStr;
+
+    #[test]
+    fn trace_record() {
+        let record = TraceRecord {
+            unix_time: 1_700_000_000,
+            game_id: GameId::Kiomet,
+            ip_address: "127.0.0.1".to_owned(),
+            region_id: Some(RegionId::Europe),
+            referrer: None,
+            user_agent_id: Some(UserAgentId::Desktop),
+            message: "panicked at 'oops'".to_owned(),
+        };
+        let other = TraceRecord {
+            message: "another".to_owned(),
+            ..record.clone()
+        };
+
+        let mut log = record.encode().unwrap();
+        log.extend(other.encode().unwrap());
+        assert_eq!(TraceRecord::decode_all(&log), Ok(vec![record, other]));
+        assert!(TraceRecord::decode_all(&log[..log.len() - 1]).is_err());
+
+        assert_eq!(TraceFormat::from_str("bitcode"), Ok(TraceFormat::Bitcode));
+        assert!(TraceFormat::from_str("json").is_err());
+    }
+}
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use core_protocol::rpc::AdminRequest;
+use core_protocol::RealmName;
 use minicdn::EmbeddedMiniCdn;
+use reqwest::StatusCode;
+use std::str::FromStr;
 use std::time::Duration;
 use structopt::StructOpt;
 
@@ -20,6 +23,13 @@ struct Options {
     url: String,
     #[structopt(long)]
     no_compress: bool,
+    /// Download recent history of this realm ("main" for the main arena) to `replay_path`.
+    #[structopt(long)]
+    export_replay: Option<String>,
+    #[structopt(long, default_value = "60")]
+    replay_seconds: u32,
+    #[structopt(long, default_value = "replay.bin")]
+    replay_path: String,
 }
 
 fn main() {
@@ -28,9 +38,18 @@ fn main() {
     upload_client(&options);
     upload_rustrict_trie(&options);
     upload_rustrict_replacements(&options);
+    export_replay(&options);
 }
 
 fn post(options: &Options, request: AdminRequest) {
+    match send(options, request) {
+        Ok((text, status)) => println!("received: {} (code {})", text, status),
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+/// Returns the response text and status code.
+fn send(options: &Options, request: AdminRequest) -> Result<(String, StatusCode), String> {
     let body = serde_json::to_string(&request).unwrap();
 
     eprintln!();
@@ -50,24 +69,16 @@ fn post(options: &Options, request: AdminRequest) {
         .build()
         .unwrap();
 
-    match client
+    let response = client
         .post(&options.url)
         .bearer_auth(&options.auth)
         .header("content-type", "application/json")
         .body(body)
         .send()
-    {
-        Ok(response) => {
-            let status = response.status();
-            match response.text() {
-                Ok(text) => {
-                    println!("received: {} (code {})", text, status);
-                }
-                Err(e) => eprintln!("{}", e.to_string()),
-            }
-        }
-        Err(e) => eprintln!("{}", e.to_string()),
-    }
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let text = response.text().map_err(|e| e.to_string())?;
+    Ok((text, status))
 }
 
 fn upload_client(options: &Options) {
@@ -118,3 +129,46 @@ fn upload_rustrict_replacements(options: &Options) {
         );
     }
 }
+
+fn export_replay(options: &Options) {
+    let Some(realm) = options.export_replay.as_deref() else {
+        return;
+    };
+    let realm_name = if realm == "main" {
+        None
+    } else {
+        match RealmName::from_str(realm) {
+            Ok(realm_name) => Some(realm_name),
+            Err(_) => {
+                eprintln!("invalid realm name");
+                std::process::exit(1);
+            }
+        }
+    };
+    println!("Exporting replay...");
+    let (text, status) = match send(
+        options,
+        AdminRequest::ExportReplay {
+            realm_name,
+            seconds: options.replay_seconds,
+        },
+    ) {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    #[derive(serde::Deserialize)]
+    enum Exported {
+        ReplayExported(Vec<u8>),
+    }
+    match serde_json::from_str(&text) {
+        Ok(Exported::ReplayExported(replay)) => {
+            std::fs::write(&options.replay_path, &replay).unwrap();
+            println!("wrote {} bytes to {}", replay.len(), options.replay_path);
+        }
+        Err(_) => eprintln!("received: {} (code {})", text, status),
+    }
+}
@@ -24,6 +24,22 @@ pub fn document() -> Document {
     window().document().expect("no document")
 }
 
+/// Copies `text` to the clipboard, if supported. Doesn't wait for it to complete.
+pub fn copy_to_clipboard(text: &str) {
+    if let Some(clipboard) = window().navigator().clipboard() {
+        let _ = clipboard.write_text(text);
+    }
+}
+
+/// Returns if the user asked their OS/browser to minimize non-essential motion.
+pub fn prefers_reduced_motion() -> bool {
+    window()
+        .match_media("(prefers-reduced-motion: reduce)")
+        .ok()
+        .flatten()
+        .map_or(false, |query| query.matches())
+}
+
 /// Gets the canvas for use with WebGL.
 pub fn canvas() -> HtmlCanvasElement {
     document()
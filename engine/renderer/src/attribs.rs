@@ -10,6 +10,8 @@ pub struct Attribs<'a> {
     gl: &'a Gl,
     aia: Option<&'a Aia>,
     bytes: u32,
+    /// Bytes of a single instance to set as constant attributes, if not instancing.
+    constant: Option<&'a [u8]>,
     index: u32,
     size: usize,
 }
@@ -20,6 +22,7 @@ impl<'a> Attribs<'a> {
             gl,
             aia: None,
             bytes: 0,
+            constant: None,
             index: 0,
             size: size_of::<V>(),
         }
@@ -31,15 +34,39 @@ impl<'a> Attribs<'a> {
             gl,
             aia: Some(aia),
             bytes: 0,
+            constant: None,
             index,
             size: size_of::<V>(),
         }
     }
 
+    /// Sets the attributes of a single `instance` as constants, starting at `index`. Fallback
+    /// for when instancing is unavailable.
+    pub(crate) fn new_constant<V: Vertex>(gl: &'a Gl, index: u32, instance: &'a V) -> Self {
+        Self {
+            gl,
+            aia: None,
+            bytes: 0,
+            constant: Some(bytemuck::bytes_of(instance)),
+            index,
+            size: size_of::<V>(),
+        }
+    }
+
+    /// Returns the index of the next attribute.
+    pub(crate) fn next_index(&self) -> u32 {
+        self.index
+    }
+
     fn attrib(&mut self) -> u32 {
         let i = self.index;
         self.index += 1;
 
+        if self.constant.is_some() {
+            self.gl.disable_vertex_attrib_array(i);
+            return i;
+        }
+
         self.gl.enable_vertex_attrib_array(i);
         if let Some(aia) = self.aia {
             aia.vertex_attrib_divisor_angle(i, 1);
@@ -56,13 +83,34 @@ impl<'a> Attribs<'a> {
     fn vertex_attrib_pointer<T>(&mut self, count: usize, typ: u32, normalized: bool) {
         debug_assert!((1..=4).contains(&count), "invalid count: {count:?}");
         debug_assert_eq!(count * size_of::<T>() % 4, 0, "not aligned to 4 bytes");
+        let index = self.attrib();
+        let offset = self.offset(count * size_of::<T>());
+
+        if let Some(bytes) = self.constant {
+            // Missing components default to 0, 0, 0, 1 like they would with a pointer.
+            let mut v = [0.0, 0.0, 0.0, 1.0];
+            let bytes = &bytes[offset as usize..];
+            for (i, v) in v.iter_mut().take(count).enumerate() {
+                *v = match (typ, normalized) {
+                    (Gl::FLOAT, false) => {
+                        f32::from_ne_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap())
+                    }
+                    (Gl::BYTE, true) => (bytes[i] as i8 as f32 * (1.0 / 127.0)).max(-1.0),
+                    (Gl::UNSIGNED_BYTE, true) => bytes[i] as f32 * (1.0 / 255.0),
+                    _ => unreachable!("unsupported constant attribute"),
+                };
+            }
+            self.gl.vertex_attrib4f(index, v[0], v[1], v[2], v[3]);
+            return;
+        }
+
         self.gl.vertex_attrib_pointer_with_i32(
-            self.attrib(),
+            index,
             count as i32,
             typ,
             normalized,
             self.size as i32,
-            self.offset(count * size_of::<T>()),
+            offset,
         );
     }
 
@@ -82,6 +130,7 @@ impl<'a> Attribs<'a> {
     fn vertex_attrib_i_pointer<T>(&mut self, count: usize, typ: u32) {
         debug_assert!((1..=4).contains(&count), "invalid count: {count:?}");
         debug_assert_eq!(count * size_of::<T>() % 4, 0, "not aligned to 4 bytes");
+        debug_assert!(self.constant.is_none(), "WebGL2 always supports instancing");
         self.gl.vertex_attrib_i_pointer_with_i32(
             self.attrib(),
             count as i32,
@@ -45,16 +45,16 @@ mod gl {
     }
 
     pub(crate) trait GlCompat {
-        fn get_extension_aia(&self) -> Aia;
+        fn get_extension_aia(&self) -> Option<Aia>;
         fn get_extension_ovao(&self) -> Ovao;
     }
 
     impl GlCompat for Gl {
-        fn get_extension_aia(&self) -> Aia {
+        fn get_extension_aia(&self) -> Option<Aia> {
             self.get_extension("ANGLE_instanced_arrays")
-                .unwrap()
-                .unwrap()
-                .unchecked_into::<Aia>()
+                .ok()
+                .flatten()
+                .map(|e| e.unchecked_into::<Aia>())
         }
         fn get_extension_ovao(&self) -> Ovao {
             self.get_extension("OES_vertex_array_object")
@@ -105,7 +105,7 @@ mod gl {
             source: &HtmlImageElement,
         ) -> Result<(), JsValue>;
         fn get_extension_ovao(&self) -> Ovao;
-        fn get_extension_aia(&self) -> Aia;
+        fn get_extension_aia(&self) -> Option<Aia>;
     }
 
     impl GlCompat for Gl {
@@ -148,8 +148,8 @@ mod gl {
         fn get_extension_ovao(&self) -> Ovao {
             self.clone()
         }
-        fn get_extension_aia(&self) -> Aia {
-            self.clone()
+        fn get_extension_aia(&self) -> Option<Aia> {
+            Some(self.clone())
         }
     }
 
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::attribs::Attribs;
 use crate::buffer::*;
 use crate::gl::*;
 use crate::index::Index;
@@ -8,7 +9,7 @@ use crate::renderer::{Layer, Renderer};
 use crate::vertex::Vertex;
 use crate::{DefaultRender, RenderLayer, ShaderBinding};
 use fxhash::FxHashMap;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::hash::Hash;
 use web_sys::{WebGlBuffer, WebGlVertexArrayObject};
 
@@ -130,6 +131,10 @@ impl<V: Vertex, I: Index, M: Vertex, ID: MeshId> RenderLayer<&ShaderBinding<'_>>
 /// [`InstanceBuffer`] facilitates drawing a [`TriangleBuffer`] multiple times.
 pub struct InstanceBuffer<M> {
     instances: GpuBuffer<M, { GpuBufferType::Array.to() }>,
+    /// Used instead of [`Self::instances`] if instancing is unavailable.
+    fallback_instances: Vec<M>,
+    /// Index of the first instance attribute if instancing is unavailable.
+    fallback_index: Cell<u32>,
     vao: WebGlVertexArrayObject,
     last_vertex_buffer: RefCell<Option<WebGlBuffer>>,
 }
@@ -138,6 +143,8 @@ impl<M: Vertex> DefaultRender for InstanceBuffer<M> {
     fn new(renderer: &Renderer) -> Self {
         Self {
             instances: GpuBuffer::new(&renderer.gl),
+            fallback_instances: Vec::new(),
+            fallback_index: Cell::new(0),
             vao: renderer.ovao.create_vertex_array_oes().unwrap(),
             last_vertex_buffer: Default::default(),
         }
@@ -147,12 +154,13 @@ impl<M: Vertex> DefaultRender for InstanceBuffer<M> {
 impl<M: Vertex> InstanceBuffer<M> {
     /// Returns true if there are no instances to draw (note does not check triangles).
     pub fn is_empty(&self) -> bool {
-        self.instances.is_empty()
+        self.len() == 0
     }
 
     /// Returns the number of instances in the [`InstanceBuffer`].
     pub fn len(&self) -> usize {
-        self.instances.len()
+        // At most one is non-empty.
+        self.instances.len() + self.fallback_instances.len()
     }
 
     /// Binds the [`InstanceBuffer`] and a [`TriangleBuffer`] to draw instances of triangles.
@@ -163,10 +171,7 @@ impl<M: Vertex> InstanceBuffer<M> {
         triangle_buffer: &'a TriangleBuffer<V, I>,
     ) -> InstanceBufferBinding<'a, V, I, M> {
         let gl = &renderer.gl;
-        let aia = renderer
-            .aia
-            .as_ref()
-            .expect("must enable AngleInstancedArrays");
+        let aia = renderer.aia.as_ref();
         let ovao = &renderer.ovao;
 
         // Don't redo attribs if buffer doesn't change.
@@ -194,8 +199,15 @@ impl<M: Vertex> InstanceBuffer<M> {
             let element_binding = triangle_buffer.indices.bind(gl);
 
             // Bind instance buffer.
-            let instance_binding = self.instances.bind(gl);
-            instance_binding.bind_attribs_instanced(aia, attribs);
+            let instance_binding = if let Some(aia) = aia {
+                let instance_binding = self.instances.bind(gl);
+                instance_binding.bind_attribs_instanced(aia, attribs);
+                Some(instance_binding)
+            } else {
+                // Instance attributes are set to constants before drawing each instance.
+                self.fallback_index.set(attribs.next_index());
+                None
+            };
 
             // Unbinding VAO is ALWAYS required (unlike all other render unbinds).
             ovao.bind_vertex_array_oes(None);
@@ -210,13 +222,19 @@ impl<M: Vertex> InstanceBuffer<M> {
 
     /// Copies instances into the [`InstanceBuffer`].
     pub fn buffer(&mut self, renderer: &Renderer, instances: &[M]) {
-        self.instances.buffer(&renderer.gl, instances);
+        if renderer.instancing() {
+            self.instances.buffer(&renderer.gl, instances);
+        } else {
+            self.fallback_instances.clear();
+            self.fallback_instances.extend_from_slice(instances);
+        }
     }
 }
 
 /// A bound [`InstanceBuffer`] that can draw instances of triangles.
 pub struct InstanceBufferBinding<'a, V, I, M> {
-    aia: &'a Aia,
+    gl: &'a Gl,
+    aia: Option<&'a Aia>,
     ovao: &'a Ovao,
     triangle_buffer: &'a TriangleBuffer<V, I>,
     buffer: &'a InstanceBuffer<M>,
@@ -225,7 +243,7 @@ pub struct InstanceBufferBinding<'a, V, I, M> {
 impl<'a, V: Vertex, I: Index, M: Vertex> InstanceBufferBinding<'a, V, I, M> {
     fn new(
         gl: &'a Gl,
-        aia: &'a Aia,
+        aia: Option<&'a Aia>,
         ovao: &'a Ovao,
         buffer: &'a InstanceBuffer<M>,
         triangle_buffer: &'a TriangleBuffer<V, I>,
@@ -239,6 +257,7 @@ impl<'a, V: Vertex, I: Index, M: Vertex> InstanceBufferBinding<'a, V, I, M> {
         ovao.bind_vertex_array_oes(Some(&buffer.vao));
 
         Self {
+            gl,
             aia,
             ovao,
             triangle_buffer,
@@ -248,9 +267,14 @@ impl<'a, V: Vertex, I: Index, M: Vertex> InstanceBufferBinding<'a, V, I, M> {
 
     /// Draws instances of triangles.
     pub fn draw(&self) {
+        let Some(aia) = self.aia else {
+            self.draw_fallback();
+            return;
+        };
+
         let primitive = Gl::TRIANGLES;
         if !self.triangle_buffer.indices.is_empty() {
-            self.aia.draw_elements_instanced_angle_with_i32(
+            aia.draw_elements_instanced_angle_with_i32(
                 primitive,
                 self.triangle_buffer.indices.len() as i32,
                 I::GL_ENUM,
@@ -258,7 +282,7 @@ impl<'a, V: Vertex, I: Index, M: Vertex> InstanceBufferBinding<'a, V, I, M> {
                 self.buffer.instances.len() as i32,
             );
         } else if !self.triangle_buffer.vertices.is_empty() {
-            self.aia.draw_arrays_instanced_angle(
+            aia.draw_arrays_instanced_angle(
                 primitive,
                 0,
                 self.triangle_buffer.vertices.len() as i32,
@@ -266,6 +290,28 @@ impl<'a, V: Vertex, I: Index, M: Vertex> InstanceBufferBinding<'a, V, I, M> {
             )
         }
     }
+
+    /// Draws instances of triangles one at a time, without instancing.
+    #[cold]
+    fn draw_fallback(&self) {
+        let primitive = Gl::TRIANGLES;
+        let index = self.buffer.fallback_index.get();
+        for instance in &self.buffer.fallback_instances {
+            M::bind_attribs(&mut Attribs::new_constant::<M>(self.gl, index, instance));
+
+            if !self.triangle_buffer.indices.is_empty() {
+                self.gl.draw_elements_with_i32(
+                    primitive,
+                    self.triangle_buffer.indices.len() as i32,
+                    I::GL_ENUM,
+                    0,
+                );
+            } else if !self.triangle_buffer.vertices.is_empty() {
+                self.gl
+                    .draw_arrays(primitive, 0, self.triangle_buffer.vertices.len() as i32)
+            }
+        }
+    }
 }
 
 impl<'a, V, I, M> Drop for InstanceBufferBinding<'a, V, I, M> {
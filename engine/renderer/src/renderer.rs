@@ -6,11 +6,12 @@ use crate::shader::Shader;
 use crate::OwnedFramebufferBinding;
 pub use engine_macros::Layer;
 use glam::{uvec2, UVec2, Vec4};
-use js_hooks::error_message;
+use js_hooks::{console_log, error_message};
 use linear_map::LinearMap;
 use std::cell::{Cell, RefCell};
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlCanvasElement;
+use web_sys::{Blob, HtmlAnchorElement, HtmlCanvasElement, Url};
 
 #[cfg(feature = "srgb")]
 use crate::srgb_layer::SrgbLayer;
@@ -381,9 +382,20 @@ impl Renderer {
             .unwrap()
     }
 
-    /// Call early on if using instancing. Still required if using WebGL2.
+    /// Call early on if using instancing. Still required if using WebGL2. If the extension is
+    /// unavailable, [`InstanceLayer`][`crate::InstanceLayer`]s draw one instance at a time.
     pub fn enable_angle_instanced_arrays(&mut self) {
-        self.aia = Some(self.gl.get_extension_aia());
+        self.aia = self.gl.get_extension_aia();
+        if self.aia.is_none() {
+            console_log!(
+                "ANGLE_instanced_arrays unavailable, falling back to non-instanced rendering"
+            );
+        }
+    }
+
+    /// Returns true if instanced rendering is active (see [`Self::enable_angle_instanced_arrays`]).
+    pub fn instancing(&self) -> bool {
+        self.aia.is_some()
     }
 
     /// Call early on if using RgbaF16 or RgbaF32 textures.
@@ -471,6 +483,44 @@ impl Renderer {
         }
     }
 
+    /// Resizes the canvas, in real pixels, until the page resizes it again. Useful for rendering
+    /// a higher resolution [`Self::download_png`], but must not exceed [`Self::max_canvas_size`].
+    pub fn set_canvas_size(&self, size: UVec2) {
+        self.canvas.set_width(size.x);
+        self.canvas.set_height(size.y);
+        self.cached_canvas_size.set(None);
+    }
+
+    /// The largest width or height of [`Self::set_canvas_size`] that can be rendered.
+    pub fn max_canvas_size(&self) -> u32 {
+        self.max_texture_size()
+    }
+
+    /// Downloads the canvas as a PNG named `file_name`. Must be called after rendering a frame,
+    /// before returning to the browser, since the drawing buffer isn't preserved. The browser
+    /// encodes the PNG asynchronously.
+    pub fn download_png(&self, file_name: &str) {
+        let file_name = file_name.to_owned();
+        let callback = Closure::once_into_js(move |blob: Option<Blob>| {
+            let Some(url) = blob.and_then(|blob| Url::create_object_url_with_blob(&blob).ok())
+            else {
+                console_log!("couldn't encode {file_name}");
+                return;
+            };
+            let anchor: HtmlAnchorElement = js_hooks::document()
+                .create_element("a")
+                .unwrap()
+                .unchecked_into();
+            anchor.set_href(&url);
+            anchor.set_download(&file_name);
+            anchor.click();
+            // Not revoking the url, since some browsers would cancel the download.
+        });
+        if let Err(e) = self.canvas.to_blob(callback.unchecked_ref()) {
+            console_log!("couldn't download {}: {:?}", file_name, e);
+        }
+    }
+
     /// Reports whether renderer was created with antialiasing.
     pub fn antialias(&self) -> bool {
         self.antialias
@@ -1,12 +1,17 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::frontend::use_core_state;
+use crate::frontend::{use_core_state, use_ctw};
 use crate::translation::{use_translation, Translation};
+use core_protocol::id::InvitationId;
+use core_protocol::rpc::InvitationRequest;
+use core_protocol::ServerNumber;
 use gloo::timers::callback::Timeout;
 use stylist::yew::styled_component;
 use web_sys::{window, MouseEvent};
-use yew::{hook, html, use_state, Callback, Html, Properties};
+use yew::{
+    hook, html, use_effect_with_deps, use_state, Callback, Html, Properties, UseStateHandle,
+};
 
 #[derive(PartialEq, Properties)]
 pub struct InvitationLinkProps;
@@ -39,37 +44,124 @@ pub fn invitation_link(_props: &InvitationLinkProps) -> Html {
     }
 }
 
-/// [`None`] indicates the button was pressed recently.
+/// [`None`] indicates the button was pressed recently. If no invitation was created yet (for
+/// the selected server), clicking requests one and copies it as soon as it arrives.
 #[hook]
 pub fn use_copy_invitation_link() -> Option<Callback<MouseEvent>> {
     let timeout = use_state::<Option<Timeout>, _>(|| None);
+    let pending = use_state(|| false);
+    let ctw = use_ctw();
+    let invitation_request_callback = ctw.invitation_request_callback;
+    let server_number = ctw.setting_cache.server_number;
     let created_invitation_id = use_core_state().created_invitation_id;
+    let link = created_invitation_id
+        .zip(window().unwrap().location().origin().ok())
+        .and_then(|(invitation_id, origin)| invitation_link(&origin, invitation_id, server_number));
+
+    {
+        let timeout = timeout.clone();
+        let pending = pending.clone();
+        use_effect_with_deps(
+            move |link: &Option<String>| {
+                if *pending {
+                    if let Some(link) = link {
+                        pending.set(false);
+                        copy_invitation_link(link, &timeout);
+                    }
+                }
+                || {}
+            },
+            link.clone(),
+        );
+    }
 
     timeout.is_none().then(|| {
         Callback::from(move |e: MouseEvent| {
             e.prevent_default();
             e.stop_propagation();
 
-            let window = window().unwrap();
+            if let Some(link) = &link {
+                copy_invitation_link(link, &timeout);
+            } else if !*pending {
+                pending.set(true);
+                invitation_request_callback.emit(InvitationRequest::Create);
+            }
+        })
+    })
+}
 
-            if let Some((invitation_id, (origin, clipboard))) = created_invitation_id.zip(
-                window
-                    .location()
-                    .origin()
-                    .ok()
-                    .zip(window.navigator().clipboard()),
-            ) {
-                let invitation_link = format!("{}/invite/{}", origin, invitation_id.0);
+fn copy_invitation_link(link: &str, timeout: &UseStateHandle<Option<Timeout>>) {
+    if let Some(clipboard) = window().unwrap().navigator().clipboard() {
+        // TODO: await this.
+        let _ = clipboard.write_text(link);
 
-                // TODO: await this.
-                let _ = clipboard.write_text(&invitation_link);
+        let timeout_clone = timeout.clone();
 
-                let timeout_clone = timeout.clone();
+        timeout.set(Some(Timeout::new(5000, move || {
+            timeout_clone.set(None);
+        })));
+    }
+}
 
-                timeout.set(Some(Timeout::new(5000, move || {
-                    timeout_clone.set(None);
-                })));
-            }
-        })
-    })
+/// Formats a link to `invitation_id`, which encodes the server it was created on. Returns
+/// [`None`] if that isn't the currently selected `server_number`, since following the link would
+/// lead to a different server than the one being played on.
+fn invitation_link(
+    origin: &str,
+    invitation_id: InvitationId,
+    server_number: Option<ServerNumber>,
+) -> Option<String> {
+    if server_number.is_some() && invitation_id.server_number() != server_number {
+        return None;
+    }
+    Some(format!("{}/invite/{}", origin, invitation_id.0))
+}
+
+/// Opposite of [`invitation_link`]. Returns [`None`] if `text` isn't a well-formed link.
+pub(crate) fn parse_invitation_link(text: &str) -> Option<InvitationId> {
+    let (_, path) = text.trim().split_once("/invite/")?;
+    path.split(['/', '?', '#']).next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{invitation_link, parse_invitation_link};
+    use core_protocol::id::InvitationId;
+    use core_protocol::ServerNumber;
+    use std::num::{NonZeroU32, NonZeroU8};
+
+    #[test]
+    fn invitation_link_server() {
+        let server_number = Some(ServerNumber(NonZeroU8::new(3).unwrap()));
+        let invitation_id = InvitationId(NonZeroU32::new((3 << 24) | 1234).unwrap());
+        let origin = "https://kiomet.com";
+
+        assert_eq!(
+            invitation_link(origin, invitation_id, server_number).as_deref(),
+            Some("https://kiomet.com/invite/50332882")
+        );
+        assert!(invitation_link(origin, invitation_id, None).is_some());
+        assert!(invitation_link(
+            origin,
+            invitation_id,
+            Some(ServerNumber(NonZeroU8::new(4).unwrap()))
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn parse_invitation_link_round_trip() {
+        let invitation_id = InvitationId(NonZeroU32::new((3 << 24) | 1234).unwrap());
+        let link = invitation_link("https://kiomet.com", invitation_id, None).unwrap();
+        assert_eq!(parse_invitation_link(&link), Some(invitation_id));
+        assert_eq!(
+            parse_invitation_link(&format!(" {link}/?ref=chat\n")),
+            Some(invitation_id)
+        );
+
+        assert_eq!(parse_invitation_link("https://kiomet.com/invite/"), None);
+        assert_eq!(parse_invitation_link("https://kiomet.com/invite/0"), None);
+        assert_eq!(parse_invitation_link("https://kiomet.com/invite/abc"), None);
+        assert_eq!(parse_invitation_link("https://kiomet.com/"), None);
+    }
 }
@@ -8,6 +8,7 @@ pub mod health_dialog;
 pub mod licensing_dialog;
 pub mod privacy_dialog;
 pub mod profile_dialog;
+pub mod servers_dialog;
 pub mod settings_dialog;
 pub mod store_dialog;
 pub mod terms_dialog;
@@ -0,0 +1,179 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::dialog::dialog::Dialog;
+use crate::frontend::{use_core_state, use_ctw};
+use core_protocol::{RegionId, ServerDto};
+use std::cmp::Reverse;
+use stylist::yew::styled_component;
+use yew::{classes, html, html_nested, use_state, Callback, Html, MouseEvent};
+
+/// How [`ServersDialog`] orders servers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ServerOrder {
+    /// Closest region first, approximating ping.
+    #[default]
+    Nearest,
+    /// Most players first.
+    Population,
+}
+
+impl ServerOrder {
+    /// Sorts `servers` relative to the `region_id` of the current server (if known).
+    pub fn sort(self, servers: &mut [ServerDto], region_id: Option<RegionId>) {
+        servers.sort_by_key(|server| {
+            let distance = match self {
+                Self::Nearest => region_id.map_or(0, |r| r.distance(server.region_id)),
+                Self::Population => 0,
+            };
+            (distance, Reverse(server.player_count), server.server_number)
+        });
+    }
+}
+
+/// Lists servers and their player counts, allowing switching between them.
+#[styled_component(ServersDialog)]
+pub fn servers_dialog() -> Html {
+    let table_style = css!(
+        r#"
+        border-collapse: collapse;
+        margin: auto;
+        width: 100%;
+
+        th, td {
+            padding: 0.5rem;
+        }
+        "#
+    );
+
+    let row_style = css!(
+        r#"
+        cursor: pointer;
+
+        :hover {
+            background-color: #00000020;
+        }
+        "#
+    );
+
+    let selected_style = css!(
+        r#"
+        background-color: #0075ff;
+        cursor: default;
+
+        :hover {
+            background-color: #0075ff;
+        }
+        "#
+    );
+
+    let button_style = css!(
+        r#"
+        background-color: #0075ff;
+        border-radius: 0.25rem;
+        border: 0;
+        color: white;
+        cursor: pointer;
+        font-size: 1em;
+        margin: 0.25rem;
+        padding: 0.5rem;
+
+        :disabled {
+            cursor: default;
+            filter: brightness(0.7);
+        }
+        "#
+    );
+
+    let ctw = use_ctw();
+    let core_state = use_core_state();
+    let order = use_state(ServerOrder::default);
+    let selected_server_number = ctw.setting_cache.server_number;
+
+    let mut servers: Vec<ServerDto> = core_state.servers.values().cloned().collect();
+    let region_id = selected_server_number
+        .and_then(|n| core_state.servers.get(&n))
+        .map(|s| s.region_id);
+    order.sort(&mut servers, region_id);
+
+    let order_button = |label: &'static str, value: ServerOrder| {
+        let onclick = {
+            let order = order.clone();
+            Callback::from(move |_: MouseEvent| order.set(value))
+        };
+        html! {
+            <button class={button_style.clone()} disabled={*order == value} {onclick}>{label}</button>
+        }
+    };
+
+    html! {
+        <Dialog title={"Servers"}>
+            <p>
+                {"Sort by "}
+                {order_button("Nearest", ServerOrder::Nearest)}
+                {order_button("Players", ServerOrder::Population)}
+            </p>
+            if servers.is_empty() {
+                <p>{"No servers found."}</p>
+            } else {
+                <table class={table_style}>
+                    <tr>
+                        <th>{"Server"}</th>
+                        <th>{"Region"}</th>
+                        <th>{"Players"}</th>
+                    </tr>
+                    {servers.into_iter().map(|ServerDto{server_number, region_id, player_count}| {
+                        let selected = selected_server_number == Some(server_number);
+                        let onclick = ctw
+                            .set_server_number_callback
+                            .reform(move |_: MouseEvent| Some(server_number));
+                        html_nested!{
+                            <tr
+                                class={classes!(row_style.clone(), selected.then(|| selected_style.clone()))}
+                                onclick={(!selected).then_some(onclick)}
+                            >
+                                <td>{server_number.to_string()}</td>
+                                <td>{region_id.as_human_readable_str()}</td>
+                                <td>{player_count}</td>
+                            </tr>
+                        }
+                    }).collect::<Html>()}
+                </table>
+            }
+        </Dialog>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dialog::servers_dialog::ServerOrder;
+    use core_protocol::{RegionId, ServerDto, ServerNumber};
+    use std::num::NonZeroU8;
+
+    #[test]
+    fn server_order() {
+        let server = |n: u8, region_id, player_count| ServerDto {
+            server_number: ServerNumber(NonZeroU8::new(n).unwrap()),
+            region_id,
+            player_count,
+        };
+        let mut servers = [
+            server(1, RegionId::Europe, 30),
+            server(2, RegionId::NorthAmerica, 5),
+            server(3, RegionId::NorthAmerica, 10),
+        ];
+        let numbers = |servers: &[ServerDto]| -> Vec<u8> {
+            servers.iter().map(|s| s.server_number.0.get()).collect()
+        };
+
+        ServerOrder::Nearest.sort(&mut servers, Some(RegionId::NorthAmerica));
+        assert_eq!(numbers(&servers), [3, 2, 1]);
+
+        ServerOrder::Population.sort(&mut servers, Some(RegionId::NorthAmerica));
+        assert_eq!(numbers(&servers), [1, 3, 2]);
+
+        // Unknown region falls back to population.
+        ServerOrder::Nearest.sort(&mut servers, None);
+        assert_eq!(numbers(&servers), [1, 3, 2]);
+    }
+}
@@ -11,7 +11,8 @@ use client_util::setting::CommonSettings;
 use core_protocol::id::{GameId, ServerId};
 use core_protocol::name::Referrer;
 use core_protocol::rpc::{
-    ChatRequest, ClientRequest, PlayerRequest, SystemQuery, SystemResponse, TeamRequest,
+    ChatRequest, ClientRequest, InvitationRequest, PlayerRequest, SystemQuery, SystemResponse,
+    TeamRequest,
 };
 use core_protocol::ServerNumber;
 use js_hooks::console_log;
@@ -72,6 +73,7 @@ pub struct Ctw {
         Callback<Box<dyn FnOnce(&mut CommonSettings, &mut BrowserStorages)>>,
     pub chat_request_callback: Callback<ChatRequest>,
     pub client_request_callback: Callback<ClientRequest>,
+    pub invitation_request_callback: Callback<InvitationRequest>,
     pub player_request_callback: Callback<PlayerRequest>,
     pub raw_zoom_callback: Callback<f32>,
     pub recreate_renderer_callback: Callback<()>,
@@ -99,6 +101,11 @@ pub fn use_client_request_callback() -> Callback<ClientRequest> {
     use_ctw().client_request_callback
 }
 
+#[hook]
+pub fn use_invitation_request_callback() -> Callback<InvitationRequest> {
+    use_ctw().invitation_request_callback
+}
+
 #[hook]
 pub fn use_player_request_callback() -> Callback<PlayerRequest> {
     use_ctw().player_request_callback
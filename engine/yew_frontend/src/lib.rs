@@ -21,18 +21,22 @@ pub mod window;
 
 use crate::canvas::Canvas;
 use crate::component::account_menu::{logout, set_login};
+use crate::component::invitation_link::parse_invitation_link;
 #[cfg(feature = "health")]
 use crate::dialog::health_dialog::HealthDialog;
 use crate::dialog::licensing_dialog::LicensingDialog;
 use crate::dialog::privacy_dialog::PrivacyDialog;
 use crate::dialog::profile_dialog::ProfileDialog;
+use crate::dialog::servers_dialog::ServersDialog;
 use crate::dialog::settings_dialog::SettingsDialog;
 use crate::dialog::store_dialog::StoreDialog;
 use crate::dialog::terms_dialog::TermsDialog;
 use crate::error_tracer::ErrorTracer;
 use crate::frontend::{post_message, RewardedAd};
 use crate::overlay::fatal_error::FatalError;
+use crate::overlay::invitation_prompt::InvitationPrompt;
 use crate::overlay::reconnecting::Reconnecting;
+use crate::translation::Translation;
 use crate::window::event_listener::WindowEventListener;
 use client_util::browser_storage::BrowserStorages;
 use client_util::context::WeakCoreState;
@@ -42,26 +46,73 @@ use client_util::infrastructure::Infrastructure;
 use client_util::setting::CommonSettings;
 use client_util::setting::Settings;
 use component::account_menu::renew_session;
-use core_protocol::id::InvitationId;
+use core_protocol::id::{InvitationId, LanguageId};
 use core_protocol::name::Referrer;
-use core_protocol::rpc::{AdType, ChatRequest, PlayerRequest, Request, TeamRequest};
+use core_protocol::rpc::{
+    AdType, ChatRequest, InvitationRequest, PlayerRequest, Request, TeamRequest,
+};
 use core_protocol::{ClientRequest, ServerNumber};
 use engine_macros::SmolRoutable;
 use frontend::{Ctw, Gctw, PropertiesWrapper, Yew};
 use gloo_render::{request_animation_frame, AnimationFrame};
 use js_hooks::console_log;
 use keyboard::KeyboardEventsListener;
+use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::num::NonZeroU8;
 use stylist::{global_style, GlobalStyle};
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::future_to_promise;
-use web_sys::{FocusEvent, KeyboardEvent, MessageEvent, MouseEvent, TouchEvent, WheelEvent};
+use web_sys::{
+    ClipboardEvent, Element, FocusEvent, KeyboardEvent, MessageEvent, MouseEvent, TouchEvent,
+    WheelEvent,
+};
 use yew::prelude::*;
+use yew::AttrValue;
 use yew_router::prelude::*;
 
 pub const CONTACT_EMAIL: &str = "contact@softbear.com";
 
+/// Fonts that render `language`'s script well, falling back to the generic `sans-serif`.
+fn font_family(language: LanguageId) -> &'static str {
+    match language {
+        LanguageId::Arabic => "'Noto Sans Arabic', 'Segoe UI', Tahoma, 'Geeza Pro', sans-serif",
+        LanguageId::Hindi => {
+            "'Noto Sans Devanagari', 'Nirmala UI', Mangal, 'Kohinoor Devanagari', sans-serif"
+        }
+        LanguageId::SimplifiedChinese => {
+            "'PingFang SC', 'Microsoft YaHei', 'Noto Sans CJK SC', 'Noto Sans SC', sans-serif"
+        }
+        LanguageId::Japanese => {
+            "'Hiragino Sans', 'Yu Gothic', Meiryo, 'Noto Sans CJK JP', 'Noto Sans JP', sans-serif"
+        }
+        _ => "sans-serif",
+    }
+}
+
+/// Inline style of the root element, which overrides the global style.
+fn root_style(common_settings: &CommonSettings) -> String {
+    let font_family = font_family(common_settings.language);
+    // Grows with the viewport, but no smaller than the minimum text size.
+    let font_size = "calc(7px + 0.8vmin)";
+    let font_size = match common_settings.text_scale.min_px() {
+        Some(min_px) => Cow::Owned(format!("max({font_size}, {min_px}px)")),
+        None => Cow::Borrowed(font_size),
+    };
+    format!("font-family: {font_family}; font-size: {font_size};")
+}
+
+/// Applies [`root_style`], raising the minimum root font size (which rem-based UI sizes follow)
+/// per [`CommonSettings::text_scale`] and picking fonts for [`CommonSettings::language`]. Call
+/// whenever either changes.
+fn apply_root_style(common_settings: &CommonSettings) {
+    if let Some(html) = js_hooks::document().document_element() {
+        let _ = html.set_attribute("style", &root_style(common_settings));
+        // Lets the browser pick appropriate glyphs (e.g. Japanese vs. Chinese).
+        let _ = html.set_attribute("lang", &common_settings.language.to_string());
+    }
+}
+
 struct App<
     G: GameClient,
     UI: BaseComponent<Properties = PropertiesWrapper<G::UiProps>>,
@@ -77,14 +128,21 @@ struct App<
     fatal_error: Option<String>,
     /// After [`AppMsg::RecreateCanvas`] is received, before [`AppMsg::RecreateRenderer`] is received.
     recreating_canvas: RecreatingCanvas,
+    /// Non-fatal error from the last attempt to recreate the renderer.
+    renderer_error: Option<String>,
     /// Whether outbound links are enabled.
     outbound_enabled: bool,
+    /// Pasted invitation link awaiting confirmation, containing [`None`] if it was malformed.
+    pasted_invitation: Option<Option<InvitationId>>,
     _animation_frame: AnimationFrame,
     _keyboard_events_listener: KeyboardEventsListener,
     _visibility_listener: WindowEventListener<Event>,
+    _webgl_context_lost_listener: WindowEventListener<Event>,
+    _webgl_context_restored_listener: WindowEventListener<Event>,
     /// Message from parent window.
     _message_listener: WindowEventListener<MessageEvent>,
     _context_menu_inhibitor: WindowEventListener<MouseEvent>,
+    _paste_listener: WindowEventListener<ClipboardEvent>,
     _error_tracer: ErrorTracer,
     _global_style: GlobalStyle,
     _spooky: PhantomData<(UI, R)>,
@@ -159,6 +217,8 @@ enum AppMsg<G: GameClient> {
     RecreateCanvasPart2,
     /// Signals just the renderer should be recreated.
     RecreateRenderer,
+    /// Retries recreation if it previously failed.
+    WebGlContextRestored,
     SetServerNumber(Option<ServerNumber>),
     #[allow(unused)]
     FatalError(String),
@@ -172,6 +232,7 @@ enum AppMsg<G: GameClient> {
     RawZoom(f32),
     SendChatRequest(ChatRequest),
     SendClientRequest(ClientRequest),
+    SendInvitationRequest(InvitationRequest),
     SendPlayerRequest(PlayerRequest),
     SendTeamRequest(TeamRequest),
     SendUiEvent(G::UiEvent),
@@ -187,6 +248,10 @@ enum AppMsg<G: GameClient> {
     ConsumeRewardedAd,
     /// Message from parent window.
     Message(String),
+    /// An invitation link (or [`None`] if malformed) was pasted.
+    PasteInvitation(Option<InvitationId>),
+    AcceptInvitation(InvitationId),
+    DismissInvitation,
     Wheel(WheelEvent),
 }
 
@@ -219,14 +284,18 @@ where
         let keyboard_callback = ctx.link().callback(AppMsg::Keyboard);
         let keyboard_focus_callback = ctx.link().callback(AppMsg::KeyboardFocus);
         let visibility_callback = ctx.link().callback(AppMsg::VisibilityChange);
+        let context_lost_callback = ctx.link().callback(|_| AppMsg::RecreateCanvas);
+        let context_restored_callback = ctx.link().callback(|_| AppMsg::WebGlContextRestored);
         let message_callback = ctx.link().callback(AppMsg::Message);
         let trace_callback = ctx.link().callback(AppMsg::Trace);
+        let paste_invitation_callback = ctx.link().callback(AppMsg::PasteInvitation);
 
         // First load local storage common settings.
         // Not guaranteed to set either or both to Some. Could fail to load.
         let browser_storages = BrowserStorages::default();
         let common_settings = CommonSettings::load(&browser_storages, CommonSettings::default());
         let settings = G::GameSettings::load(&browser_storages, G::GameSettings::default());
+        apply_root_style(&common_settings);
 
         renew_session(
             ctx.link().callback(AppMsg::SendClientRequest),
@@ -244,9 +313,11 @@ where
             ui_event_buffer: Vec::new(),
             ui_props: G::UiProps::default(),
             recreating_canvas: RecreatingCanvas::default(),
+            renderer_error: None,
             rewarded_ad: RewardedAd::Unavailable,
             fatal_error: None,
             outbound_enabled: true,
+            pasted_invitation: None,
             _animation_frame: Self::create_animation_frame(ctx),
             _keyboard_events_listener: KeyboardEventsListener::new(
                 keyboard_callback,
@@ -259,6 +330,23 @@ where
                 },
                 false,
             ),
+            // Context events are fired on the canvas and don't bubble.
+            _webgl_context_lost_listener: WindowEventListener::new_capture(
+                "webglcontextlost",
+                move |event: &Event| {
+                    // Signals that we intend to handle restoration.
+                    event.prevent_default();
+                    context_lost_callback.emit(());
+                },
+                true,
+            ),
+            _webgl_context_restored_listener: WindowEventListener::new_capture(
+                "webglcontextrestored",
+                move |_: &Event| {
+                    context_restored_callback.emit(());
+                },
+                false,
+            ),
             _message_listener: WindowEventListener::new(
                 "message",
                 move |event: &MessageEvent| {
@@ -281,13 +369,34 @@ where
                 move |event: &MouseEvent| event.prevent_default(),
                 true,
             ),
+            _paste_listener: WindowEventListener::new(
+                "paste",
+                move |event: &ClipboardEvent| {
+                    // Let text fields (e.g. chat) receive links as usual.
+                    let text_field = event
+                        .target()
+                        .and_then(|target| target.dyn_into::<Element>().ok())
+                        .map_or(false, |e| {
+                            matches!(e.tag_name().as_str(), "INPUT" | "TEXTAREA")
+                        });
+                    if text_field {
+                        return;
+                    }
+                    if let Some(text) = event
+                        .clipboard_data()
+                        .and_then(|data| data.get_data("text").ok())
+                        .filter(|text| text.contains("/invite/"))
+                    {
+                        paste_invitation_callback.emit(parse_invitation_link(&text));
+                    }
+                },
+                false,
+            ),
             _error_tracer: ErrorTracer::new(trace_callback),
             _global_style: global_style!(
                 r#"
                 html {
                     font-family: sans-serif;
-                    font-size: 1.5vmin;
-                    font-size: calc(7px + 0.8vmin);
                 }
 
                 body {
@@ -318,6 +427,7 @@ where
                             &mut infrastructure.context.common_settings,
                             &mut infrastructure.context.browser_storages,
                         );
+                        apply_root_style(&infrastructure.context.common_settings);
                     }
                     PendingInfrastructure::Pending {
                         common_settings,
@@ -325,6 +435,7 @@ where
                         ..
                     } => {
                         change(common_settings, browser_storages);
+                        apply_root_style(common_settings);
                     }
                     PendingInfrastructure::Swapping => {
                         debug_assert!(
@@ -416,18 +527,23 @@ where
             }
             AppMsg::RecreateRenderer => {
                 self.recreating_canvas = RecreatingCanvas::None;
-                console_log!("could not recreate renderer.");
-                /*
                 if let Some(infrastructure) = self.infrastructure.as_mut() {
                     if let Err(e) = infrastructure.recreate_renderer() {
                         console_log!("could not recreate renderer: {}", e);
+                        self.renderer_error = Some(e);
                     } else {
                         console_log!("finished recreating renderer");
+                        self.renderer_error = None;
                     }
                 }
-                */
                 return true;
             }
+            AppMsg::WebGlContextRestored => {
+                if self.renderer_error.is_some() && self.recreating_canvas == RecreatingCanvas::None
+                {
+                    ctx.link().send_message(AppMsg::RecreateCanvas);
+                }
+            }
             AppMsg::SetServerNumber(server_number) => {
                 if let Some(infrastructure) = self.infrastructure.as_mut() {
                     infrastructure.choose_server_id(server_number);
@@ -480,6 +596,11 @@ where
                     infrastructure.send_request(Request::Client(request));
                 }
             }
+            AppMsg::SendInvitationRequest(request) => {
+                if let Some(infrastructure) = self.infrastructure.as_mut() {
+                    infrastructure.send_request(Request::Invitation(request));
+                }
+            }
             AppMsg::SetContextMenuProps(props) => {
                 self.context_menu = props;
                 return true;
@@ -628,6 +749,32 @@ where
                     _ => {}
                 }
             }
+            AppMsg::PasteInvitation(invitation_id) => {
+                self.pasted_invitation = Some(invitation_id);
+                return true;
+            }
+            AppMsg::AcceptInvitation(invitation_id) => {
+                // Reload with the invitation in the path, so the usual startup flow (including
+                // `renew_session`) joins it on the server it was created on.
+                if let Some(server_number) = invitation_id.server_number() {
+                    self.update(
+                        ctx,
+                        AppMsg::ChangeCommonSettings(Box::new(
+                            move |common_settings, browser_storages| {
+                                common_settings
+                                    .set_server_number(Some(server_number), browser_storages);
+                            },
+                        )),
+                    );
+                }
+                let _ = js_hooks::window()
+                    .location()
+                    .set_href(&format!("/invite/{}/", invitation_id.0));
+            }
+            AppMsg::DismissInvitation => {
+                self.pasted_invitation = None;
+                return true;
+            }
             AppMsg::Wheel(event) => {
                 if let Some(infrastructure) = self.infrastructure.as_mut() {
                     infrastructure.wheel(event);
@@ -642,6 +789,7 @@ where
         let change_settings_callback = ctx.link().callback(AppMsg::ChangeSettings);
         let chat_request_callback = ctx.link().callback(AppMsg::SendChatRequest);
         let client_request_callback = ctx.link().callback(AppMsg::SendClientRequest);
+        let invitation_request_callback = ctx.link().callback(AppMsg::SendInvitationRequest);
         let player_request_callback = ctx.link().callback(AppMsg::SendPlayerRequest);
         let raw_zoom_callback = ctx.link().callback(AppMsg::RawZoom);
         let recreate_renderer_callback = ctx.link().callback(|_| AppMsg::RecreateCanvas);
@@ -675,11 +823,30 @@ where
             })
             .collect::<Vec<_>>();
 
+        let graphics_restoring_message = setting_cache.language.graphics_restoring_message();
+        let outdated_client_message = self
+            .infrastructure
+            .as_ref()
+            .filter(|i| i.context.outdated())
+            .map(|_| setting_cache.language.outdated_client_message());
+        let server_full_message = self
+            .infrastructure
+            .as_ref()
+            .and_then(|i| i.context.server_full())
+            .map(|server_full| {
+                AttrValue::from(
+                    setting_cache
+                        .language
+                        .server_full(server_full.queue_position),
+                )
+            });
+
         let context = Ctw {
             chat_request_callback,
             client_request_callback,
             change_common_settings_callback,
             game_id: G::GAME_ID,
+            invitation_request_callback,
             outbound_enabled: self.outbound_enabled,
             rewarded_ad: self.rewarded_ad.clone(),
             player_request_callback,
@@ -727,7 +894,7 @@ where
                             />
                         }
                         if self.infrastructure.as_ref().map(|i| i.context.connection_lost()).unwrap_or_default() {
-                            <FatalError/>
+                            <FatalError message={outdated_client_message}/>
                         } else if let Some(message) = self.fatal_error.as_ref() {
                             <FatalError message={message.to_owned()}/>
                         } else {
@@ -737,8 +904,19 @@ where
                                 if let Some(context_menu) = self.context_menu.as_ref() {
                                     {context_menu.clone()}
                                 }
-                                if self.infrastructure.as_ref().map(|i| i.context.socket.is_reconnecting()).unwrap_or_default() {
+                                if let Some(message) = server_full_message {
+                                    <Reconnecting {message}/>
+                                } else if self.infrastructure.as_ref().map(|i| i.context.socket.is_reconnecting()).unwrap_or_default() {
                                     <Reconnecting/>
+                                } else if self.recreating_canvas != RecreatingCanvas::None || self.renderer_error.is_some() {
+                                    <Reconnecting message={graphics_restoring_message}/>
+                                }
+                                if let Some(invitation_id) = self.pasted_invitation {
+                                    <InvitationPrompt
+                                        {invitation_id}
+                                        on_join={ctx.link().callback(AppMsg::AcceptInvitation)}
+                                        on_dismiss={ctx.link().callback(|_| AppMsg::DismissInvitation)}
+                                    />
                                 }
                             </>
                         }
@@ -788,6 +966,8 @@ pub enum Route {
     Store,
     #[at("/settings/")]
     Settings,
+    #[at("/servers/")]
+    Servers,
     #[at("/privacy/")]
     Privacy,
     #[at("/terms/")]
@@ -814,6 +994,9 @@ fn switch<G: GameClient>(routes: Route) -> Html {
         Route::Settings => html! {
             <SettingsDialog<G>/>
         },
+        Route::Servers => html! {
+            <ServersDialog/>
+        },
         Route::Privacy => html! {
             <PrivacyDialog/>
         },
@@ -829,3 +1012,40 @@ fn switch<G: GameClient>(routes: Route) -> Html {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{font_family, root_style};
+    use client_util::setting::{CommonSettings, TextScale};
+    use core_protocol::id::LanguageId;
+
+    #[test]
+    fn root_style_follows_language() {
+        let mut common_settings = CommonSettings::default();
+        assert!(root_style(&common_settings).contains("font-family: sans-serif;"));
+
+        for language in [
+            LanguageId::Arabic,
+            LanguageId::Japanese,
+            LanguageId::English,
+        ] {
+            common_settings.language = language;
+            let font_family = font_family(language);
+            assert!(font_family.ends_with("sans-serif"));
+            assert!(root_style(&common_settings).contains(&format!("font-family: {font_family};")));
+        }
+        assert_ne!(
+            font_family(LanguageId::Japanese),
+            font_family(LanguageId::SimplifiedChinese)
+        );
+    }
+
+    #[test]
+    fn root_style_text_scale() {
+        let mut common_settings = CommonSettings::default();
+        assert!(root_style(&common_settings).contains("font-size: calc(7px + 0.8vmin);"));
+
+        common_settings.text_scale = TextScale::Larger;
+        assert!(root_style(&common_settings).contains("font-size: max(calc(7px + 0.8vmin), 18px);"));
+    }
+}
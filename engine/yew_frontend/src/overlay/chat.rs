@@ -15,7 +15,7 @@ use crate::window::event_listener::WindowEventListener;
 use client_util::browser_storage::BrowserStorages;
 use client_util::setting::CommonSettings;
 use core_protocol::id::LanguageId;
-use core_protocol::rpc::{ChatRequest, PlayerRequest};
+use core_protocol::rpc::{ChatRequest, PlayerRequest, RequestErrorKind};
 use js_sys::JsString;
 use std::str::pattern::Pattern;
 use stylist::yew::styled_component;
@@ -384,6 +384,9 @@ pub fn chat_overlay(props: &ChatProps) -> Html {
             {on_open_changed}
         >
             {items}
+            if core_state.request_error == Some(RequestErrorKind::RateLimited) {
+                <p><b>{t.chat_rate_limited_warning()}</b></p>
+            }
             if let Some(help_hint) = *help_hint {
                 <p><b>{"Automated help: "}{help_hint}</b></p>
             }
@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::component::curtain::Curtain;
+use crate::component::positioner::{Position, Positioner};
+use crate::translation::{use_translation, Translation};
+use core_protocol::id::InvitationId;
+use stylist::yew::styled_component;
+use yew::{classes, html, Callback, Html, MouseEvent, Properties};
+
+#[derive(PartialEq, Properties)]
+pub struct InvitationPromptProps {
+    /// [`None`] if the pasted link was malformed.
+    pub invitation_id: Option<InvitationId>,
+    pub on_join: Callback<InvitationId>,
+    pub on_dismiss: Callback<()>,
+}
+
+/// Asks whether to follow a pasted invitation link.
+#[styled_component(InvitationPrompt)]
+pub fn invitation_prompt(props: &InvitationPromptProps) -> Html {
+    let container_css = css!(
+        r#"
+        background-color: #f6f6f6;
+        border-radius: 1rem;
+        box-shadow: 0em 0.25rem 0 #cccccc;
+        color: #000000;
+        padding: 0.5rem 1rem;
+        "#
+    );
+
+    let button_css = css!(
+        r#"
+        border-radius: 0.5rem;
+        border: none;
+        color: white;
+        cursor: pointer;
+        font-size: 1.25rem;
+        margin: 0.5rem;
+        min-width: 8rem;
+        padding: 0.5rem;
+
+        :hover {
+            filter: brightness(0.95);
+        }
+        "#
+    );
+
+    let t = use_translation();
+    let on_dismiss = props.on_dismiss.reform(|_: MouseEvent| ());
+
+    html! {
+        <Curtain>
+            <Positioner position={Position::Center} class={classes!(container_css)}>
+                if let Some(invitation_id) = props.invitation_id {
+                    <p>{t.invitation_join_prompt()}</p>
+                    <button
+                        class={button_css.clone()}
+                        style="background-color: #549f57;"
+                        onclick={props.on_join.reform(move |_: MouseEvent| invitation_id)}
+                    >
+                        {t.invitation_join_label()}
+                    </button>
+                } else {
+                    <p>{t.invitation_invalid_message()}</p>
+                }
+                <button class={button_css} style="background-color: #888888;" onclick={on_dismiss}>
+                    {t.alert_dismiss()}
+                </button>
+            </Positioner>
+        </Curtain>
+    }
+}
@@ -4,6 +4,7 @@
 pub mod chat;
 pub mod fatal_error;
 pub mod instructions;
+pub(crate) mod invitation_prompt;
 pub mod leaderboard;
 pub(crate) mod reconnecting;
 pub mod spawn;
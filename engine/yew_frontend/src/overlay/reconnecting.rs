@@ -6,11 +6,23 @@ use crate::component::positioner::{Position, Positioner};
 use crate::component::spinner::Spinner;
 use crate::translation::{use_translation, Translation};
 use stylist::yew::styled_component;
-use yew::{html, Html};
+use yew::virtual_dom::AttrValue;
+use yew::{html, Html, Properties};
+
+#[derive(Default, PartialEq, Properties)]
+pub struct ReconnectingProps {
+    /// Overrides the default connection losing message.
+    #[prop_or_default]
+    pub message: Option<AttrValue>,
+}
 
 #[styled_component(Reconnecting)]
-pub fn reconnecting() -> Html {
-    let message = use_translation().connection_losing_message();
+pub fn reconnecting(props: &ReconnectingProps) -> Html {
+    let t = use_translation();
+    let message = props
+        .message
+        .clone()
+        .unwrap_or_else(|| t.connection_losing_message().into());
     html! {
         <Curtain>
             <Positioner position={Position::Center}>
@@ -270,7 +270,7 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
                         html_nested!{
                             <tr class={tr_css_class.clone()}>
                                 <td class={classes!(name_css_class.clone(), name_pending_css_class.clone())}>{alias}</td>
-                                <td><button class={classes!(button_css_class.clone(), team_full.then(|| disabled_css_class.clone()))} onclick={move |_| on_accept_join_team(player_id)} title={t.team_accept_hint()}>{CHECK_MARK}</button></td>
+                                <td><button class={classes!(button_css_class.clone(), team_full.then(|| disabled_css_class.clone()))} onclick={move |_| on_accept_join_team(player_id)} title={if team_full { t.team_accept_full_hint() } else { t.team_accept_hint() }}>{CHECK_MARK}</button></td>
                                 <td><button class={button_css_class.clone()} onclick={move |_| on_reject_join_team(player_id)} title={t.team_deny_hint()}>{X_MARK}</button></td>
                             </tr>
                         }
@@ -280,7 +280,7 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
             } else {
                 <form onsubmit={move |e: SubmitEvent| {e.prevent_default(); on_create_team();}}>
                     <table>
-                        {core_state.teams.iter().sorted_by(cmp_teams).take(5).map(|(_, &TeamDto{closed, name, team_id, ..})| {
+                        {core_state.teams.iter().sorted_by(cmp_teams).take(5).map(|(_, &TeamDto{closed, full, name, team_id})| {
                             let on_request_join_team = on_request_join_team.clone();
                             let unavailable = closed || core_state.joins.contains(&team_id);
 
@@ -288,7 +288,7 @@ pub fn team_overlay(props: &TeamOverlayProps) -> Html {
                                 <tr>
                                     <td class={name_css_class.clone()}>{name}</td>
                                     <td>
-                                        <button type="button" class={classes!(button_css_class.clone(), unavailable.then(|| hidden_css_class.clone()))} onclick={move |_| on_request_join_team(team_id)}>{t.team_request_hint()}</button>
+                                        <button type="button" class={classes!(button_css_class.clone(), unavailable.then(|| hidden_css_class.clone()), full.then(|| disabled_css_class.clone()))} onclick={move |_| on_request_join_team(team_id)} title={full.then(|| t.team_accept_full_hint())}>{t.team_request_hint()}</button>
                                     </td>
                                 </tr>
                             }
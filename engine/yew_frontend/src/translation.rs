@@ -56,6 +56,7 @@ pub trait Translation: Sized {
     s!(chat_send_message_placeholder);
     s!(chat_report_label);
     s!(chat_mute_label);
+    s!(chat_rate_limited_warning);
 
     // Live-board/leaderboard.
     sd!(liveboard_label, "Header for live leaderboard.");
@@ -106,10 +107,30 @@ pub trait Translation: Sized {
     s!(invitation_hint);
     s!(invitation_label);
     s!(invitation_copied_label);
+    s!(invitation_join_prompt);
+    s!(invitation_join_label);
+    s!(invitation_invalid_message);
 
     // Connection lost.
     s!(connection_losing_message);
     s!(connection_lost_message);
+    s!(outdated_client_message);
+
+    // Server full.
+    s!(server_full_message);
+    s!(queue_position_label);
+    fn server_full(self, queue_position: Option<u32>) -> String {
+        let message = self.server_full_message();
+        if let Some(queue_position) = queue_position {
+            let label = self.queue_position_label();
+            format!("{message} {label}: {queue_position}")
+        } else {
+            message.to_owned()
+        }
+    }
+
+    // Graphics lost.
+    s!(graphics_restoring_message);
 
     // Alert
     s!(alert_dismiss);
@@ -263,6 +284,23 @@ impl Translation for LanguageId {
         }
     }
 
+    fn chat_rate_limited_warning(self) -> &'static str {
+        match self {
+            Bork => "Bork slower",
+            German => "Du sendest zu viele Nachrichten",
+            English => "You are sending messages too quickly",
+            Spanish => "Estás enviando mensajes demasiado rápido",
+            French => "Vous envoyez des messages trop rapidement",
+            Italian => "Stai inviando messaggi troppo velocemente",
+            Arabic => "أنت ترسل الرسائل بسرعة كبيرة",
+            Japanese => "メッセージの送信が速すぎます",
+            Russian => "Вы отправляете сообщения слишком быстро",
+            Vietnamese => "Bạn đang gửi tin nhắn quá nhanh",
+            SimplifiedChinese => "您发送消息的速度太快了",
+            Hindi => "आप बहुत जल्दी संदेश भेज रहे हैं",
+        }
+    }
+
     fn chat_mute_label(self) -> &'static str {
         match self {
             German => "Stummschalten",
@@ -739,6 +777,57 @@ impl Translation for LanguageId {
         }
     }
 
+    fn invitation_join_prompt(self) -> &'static str {
+        match self {
+            Bork => "Bork with friend?",
+            German => "Dem Spiel deines Freundes beitreten?",
+            English => "Join your friend's game?",
+            Spanish => "¿Unirse a la partida de tu amigo?",
+            French => "Rejoindre la partie de votre ami ?",
+            Italian => "Unirsi alla partita del tuo amico?",
+            Arabic => "هل تريد الانضمام إلى لعبة صديقك؟",
+            Japanese => "友達のゲームに参加しますか？",
+            Russian => "Присоединиться к игре друга?",
+            Vietnamese => "Tham gia trò chơi của bạn bè?",
+            SimplifiedChinese => "加入好友的游戏？",
+            Hindi => "अपने मित्र के खेल में शामिल हों?",
+        }
+    }
+
+    fn invitation_join_label(self) -> &'static str {
+        match self {
+            Bork => "Bork",
+            German => "Beitreten",
+            English => "Join",
+            Spanish => "Unirse",
+            French => "Rejoindre",
+            Italian => "Unisciti",
+            Arabic => "انضم",
+            Japanese => "参加",
+            Russian => "Присоединиться",
+            Vietnamese => "Tham gia",
+            SimplifiedChinese => "加入",
+            Hindi => "शामिल हों",
+        }
+    }
+
+    fn invitation_invalid_message(self) -> &'static str {
+        match self {
+            Bork => "That invite is borked.",
+            German => "Dieser Einladungslink ist ungültig.",
+            English => "That invitation link is invalid.",
+            Spanish => "Ese enlace de invitación no es válido.",
+            French => "Ce lien d'invitation n'est pas valide.",
+            Italian => "Quel link di invito non è valido.",
+            Arabic => "رابط الدعوة هذا غير صالح.",
+            Japanese => "その招待リンクは無効です。",
+            Russian => "Эта ссылка-приглашение недействительна.",
+            Vietnamese => "Liên kết mời đó không hợp lệ.",
+            SimplifiedChinese => "该邀请链接无效。",
+            Hindi => "वह आमंत्रण लिंक अमान्य है।",
+        }
+    }
+
     fn connection_lost_message(self) -> &'static str {
         match self {
             Bork => "Your connection was borked. Try again later!",
@@ -756,6 +845,23 @@ impl Translation for LanguageId {
         }
     }
 
+    fn outdated_client_message(self) -> &'static str {
+        match self {
+            Bork => "Your game is old and borked. Refresh to bork again!",
+            German => "Das Spiel wurde aktualisiert. Bitte lade die Seite neu!",
+            English => "The game was updated. Please refresh!",
+            Spanish => "El juego se actualizó. ¡Por favor, actualiza la página!",
+            French => "Le jeu a été mis à jour. Veuillez actualiser !",
+            Italian => "Il gioco è stato aggiornato. Ricarica la pagina!",
+            Arabic => "تم تحديث اللعبة. يرجى تحديث الصفحة!",
+            Japanese => "ゲームが更新されました。ページを再読み込みしてください！",
+            Russian => "Игра обновилась. Пожалуйста, обновите страницу!",
+            Vietnamese => "Trò chơi đã được cập nhật. Vui lòng tải lại trang!",
+            SimplifiedChinese => "游戏已更新。请刷新页面！",
+            Hindi => "गेम अपडेट हो गया है। कृपया पेज रीफ़्रेश करें!",
+        }
+    }
+
     fn connection_losing_message(self) -> &'static str {
         match self {
             Bork => "Your connection was borked. Reborking now...",
@@ -773,6 +879,57 @@ impl Translation for LanguageId {
         }
     }
 
+    fn server_full_message(self) -> &'static str {
+        match self {
+            Bork => "Too many borks! Waiting for a spot...",
+            German => "Der Server ist voll. Warte auf einen freien Platz...",
+            English => "The server is full. Waiting for a spot...",
+            Spanish => "El servidor está lleno. Esperando un lugar...",
+            French => "Le serveur est plein. En attente d'une place...",
+            Italian => "Il server è pieno. In attesa di un posto...",
+            Arabic => "الخادم ممتلئ. في انتظار مكان...",
+            Japanese => "サーバーが満員です。空きを待っています...",
+            Russian => "Сервер заполнен. Ожидание свободного места...",
+            Vietnamese => "Máy chủ đã đầy. Đang chờ chỗ trống...",
+            SimplifiedChinese => "服务器已满。正在等待空位...",
+            Hindi => "सर्वर भरा हुआ है। जगह की प्रतीक्षा कर रहे हैं...",
+        }
+    }
+
+    fn queue_position_label(self) -> &'static str {
+        match self {
+            Bork => "Bork in line",
+            German => "Position in der Warteschlange",
+            English => "Position in line",
+            Spanish => "Posición en la cola",
+            French => "Position dans la file",
+            Italian => "Posizione in coda",
+            Arabic => "الترتيب في قائمة الانتظار",
+            Japanese => "待機順位",
+            Russian => "Место в очереди",
+            Vietnamese => "Vị trí trong hàng chờ",
+            SimplifiedChinese => "排队位置",
+            Hindi => "कतार में स्थान",
+        }
+    }
+
+    fn graphics_restoring_message(self) -> &'static str {
+        match self {
+            Bork => "Your graphics were borked. Reborking now...",
+            German => "Grafik verloren, Wiederherstellung wird versucht...",
+            English => "Graphics lost, attempting to restore...",
+            Spanish => "Gráficos perdidos, intentando restaurar...",
+            French => "Graphismes perdus, tentative de restauration...",
+            Italian => "Grafica persa, tentativo di ripristino in corso...",
+            Arabic => "فقدت الرسومات، جارٍ محاولة الاستعادة...",
+            Japanese => "グラフィックが失われました。復元を試みています...",
+            Russian => "Графика потеряна, попытка восстановления...",
+            Vietnamese => "Mất đồ họa, đang cố khôi phục...",
+            SimplifiedChinese => "图形丢失，正在尝试恢复...",
+            Hindi => "ग्राफ़िक्स खो गए, पुनर्स्थापित करने का प्रयास...",
+        }
+    }
+
     /*
     fn connection_lost_message(self) -> &'static str {
         match self {
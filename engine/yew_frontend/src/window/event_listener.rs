@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use gloo_events::{EventListener, EventListenerOptions};
+use gloo_events::{EventListener, EventListenerOptions, EventListenerPhase};
 use std::marker::PhantomData;
 use wasm_bindgen::JsCast;
 use web_sys::{window, EventTarget};
@@ -18,11 +18,11 @@ impl<E: JsCast> WindowEventListener<E> {
         name: &'static str,
         mut callback: impl FnMut(&E) + 'static,
         allow_prevent_default: bool,
+        phase: EventListenerPhase,
     ) -> Self {
-        let options = if allow_prevent_default {
-            EventListenerOptions::enable_prevent_default()
-        } else {
-            EventListenerOptions::default()
+        let options = EventListenerOptions {
+            phase,
+            passive: !allow_prevent_default,
         };
 
         Self {
@@ -41,7 +41,29 @@ impl<E: JsCast> WindowEventListener<E> {
         callback: impl FnMut(&E) + 'static,
         allow_prevent_default: bool,
     ) -> Self {
-        Self::new_inner(&window().unwrap(), name, callback, allow_prevent_default)
+        Self::new_inner(
+            &window().unwrap(),
+            name,
+            callback,
+            allow_prevent_default,
+            EventListenerPhase::Bubble,
+        )
+    }
+
+    /// Listens in the capture phase, so events that don't bubble (e.g. from the canvas) are
+    /// still received.
+    pub fn new_capture(
+        name: &'static str,
+        callback: impl FnMut(&E) + 'static,
+        allow_prevent_default: bool,
+    ) -> Self {
+        Self::new_inner(
+            &window().unwrap(),
+            name,
+            callback,
+            allow_prevent_default,
+            EventListenerPhase::Capture,
+        )
     }
 
     /// Goes on the body instead of the window.
@@ -55,6 +77,7 @@ impl<E: JsCast> WindowEventListener<E> {
             name,
             callback,
             allow_prevent_default,
+            EventListenerPhase::Bubble,
         )
     }
 }
@@ -14,6 +14,7 @@ pub fn derive_tower_type_data(input: TokenStream) -> TokenStream {
         let mut tower_capacities = Vec::<proc_macro2::TokenStream>::new();
         let mut tower_generations = Vec::<proc_macro2::TokenStream>::new();
         let mut score_weights = Vec::<proc_macro2::TokenStream>::new();
+        let mut build_costs = Vec::<proc_macro2::TokenStream>::new();
         let mut spawnables = Vec::<proc_macro2::TokenStream>::new();
         let mut sensor_radii = Vec::<proc_macro2::TokenStream>::new();
         let mut downgrades = Vec::<proc_macro2::TokenStream>::new();
@@ -32,6 +33,7 @@ pub fn derive_tower_type_data(input: TokenStream) -> TokenStream {
             let mut unit_generations = HashMap::<_, proc_macro2::TokenStream>::new();
             let mut sensor_radius = None;
             let mut score_weight = None;
+            let mut build_cost = None;
 
             for attribute in attrs.iter().chain(&variant_attrs) {
                 let meta = attribute.parse_meta().expect("couldn't parse as meta");
@@ -51,6 +53,10 @@ pub fn derive_tower_type_data(input: TokenStream) -> TokenStream {
                                         score_weight = Some(quote! {
                                             Self::#variant => #value
                                         });
+                                    } else if name.is_ident("cost") {
+                                        build_cost = Some(quote! {
+                                            Self::#variant => #value
+                                        });
                                     } else {
                                         panic!("unrecognized {:?}", name.to_token_stream());
                                     }
@@ -178,6 +184,10 @@ pub fn derive_tower_type_data(input: TokenStream) -> TokenStream {
             if let Some(score_weight) = score_weight {
                 score_weights.push(score_weight);
             }
+
+            if let Some(build_cost) = build_cost {
+                build_costs.push(build_cost);
+            }
         }
 
         let tower_delays = tower_delays.into_values();
@@ -234,6 +244,14 @@ pub fn derive_tower_type_data(input: TokenStream) -> TokenStream {
                     }
                 }
 
+                /// Abstract cost of building this tower, for display only.
+                pub fn build_cost(self) -> u32 {
+                    match self {
+                        #(#build_costs,)*
+                        _ => 0
+                    }
+                }
+
                 pub fn is_spawnable(self) -> bool {
                     match self {
                         #(#spawnables,)*
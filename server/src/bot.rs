@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2023 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::rng::{new_rng, ServerRng};
 use crate::service::TowerService;
 use common::field::Field;
 use common::force::Path;
@@ -13,8 +14,8 @@ use core_protocol::id::PlayerId;
 use core_protocol::name::PlayerAlias;
 use game_server::game_service::{Bot, BotAction, GameArenaService};
 use game_server::player::{PlayerRepo, PlayerTuple};
-use rand::prelude::{IteratorRandom, ThreadRng};
-use rand::{thread_rng, Rng};
+use rand::prelude::IteratorRandom;
+use rand::Rng;
 use std::cmp::Ordering;
 use std::sync::Arc;
 
@@ -25,6 +26,7 @@ pub struct TowerBot {
     before_quit: Ticks,
     /// War against player, and time remaining.
     war: Option<War>,
+    rng: ServerRng,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -34,7 +36,7 @@ struct War {
 }
 
 impl TowerBot {
-    fn random_before_quit(rng: &mut ThreadRng) -> Ticks {
+    fn random_before_quit(rng: &mut ServerRng) -> Ticks {
         Ticks::from_whole_secs(if false {
             rng.gen_range(0..=5)
         } else if cfg!(debug_assertions) && rng.gen_bool(0.1) {
@@ -47,11 +49,12 @@ impl TowerBot {
 
 impl Default for TowerBot {
     fn default() -> Self {
-        let mut rng = thread_rng();
+        let mut rng = new_rng();
         Self {
             territorial_ambition: rng.gen_range(8..=12),
             before_quit: Self::random_before_quit(&mut rng),
             war: None,
+            rng,
         }
     }
 }
@@ -93,12 +96,12 @@ impl Bot<TowerService> for TowerBot {
             None => return BotAction::Quit,
         };
 
-        let mut rng = thread_rng();
+        let rng = &mut self.rng;
 
         if !player.alive {
             self.war = None;
-            self.before_quit = Self::random_before_quit(&mut rng);
-            return BotAction::Some(Command::Spawn);
+            self.before_quit = Self::random_before_quit(rng);
+            return BotAction::Some(Command::Spawn(None));
         }
 
         let Some((random_tower_id, random_tower))
@@ -113,7 +116,7 @@ impl Bot<TowerService> for TowerBot {
                         .filter(|tower| !tower.force_units().is_empty())
                         .map(|tower| (tower_id, tower))
                 )
-                .choose(&mut rng) else {
+                .choose(rng) else {
             // Don't crash if ruler is on the run and enemy is hot on it's tail.
             return BotAction::None("no towers");
         };
@@ -154,7 +157,7 @@ impl Bot<TowerService> for TowerBot {
                 .filter(|u| {
                     u.has_prerequisites(&player.tower_counts) && !matches!(u, TowerType::Helipad)
                 })
-                .choose(&mut rng)
+                .choose(rng)
             {
                 return BotAction::Some(Command::Upgrade {
                     tower_id: random_tower_id,
@@ -325,7 +328,7 @@ impl Bot<TowerService> for TowerBot {
                             )
                     }
                 })
-                .choose(&mut rng);
+                .choose(rng);
 
             if let Some((destination, _)) = destination {
                 let max_edge_distance = strength.max_edge_distance();
@@ -348,6 +351,7 @@ impl Bot<TowerService> for TowerBot {
                             Command::SetSupplyLine {
                                 tower_id: path[0],
                                 path: Some(Path::new(path)),
+                                pull: false,
                             }
                         },
                     );
@@ -7,13 +7,26 @@
 #![feature(type_alias_impl_trait)]
 
 use service::TowerService;
+use simulation::SimulationOptions;
+use structopt::StructOpt;
 
 mod bot;
+mod options;
 mod regulator;
+mod rng;
 mod service;
+mod simulation;
+mod spawn_queue;
 mod world;
 
 fn main() {
+    // Headless alternative to running a server, e.g. `simulate --bots 10 --ticks 2400 --seed 0`.
+    if std::env::args().nth(1).as_deref() == Some("simulate") {
+        let options = SimulationOptions::from_iter(std::env::args().skip(1));
+        println!("{}", simulation::simulate(options));
+        return;
+    }
+
     let cdn = minicdn::release_include_mini_cdn!("../../client/dist/");
     game_server::entry_point::entry_point::<TowerService>(cdn, true);
 }
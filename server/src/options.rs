@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::service::{DeadTowers, GenerationMultipliers};
+use crate::world::Obstacles;
+use common::singleton::Singleton;
+use common::ticks::Ticks;
+use common_util::ticks::TicksRepr;
+use std::num::NonZeroU8;
+use structopt::StructOpt;
+
+/// Server options specific to this game (see [`game_server::options::Options::game`]).
+#[derive(Debug, Default, StructOpt)]
+pub struct TowerOptions {
+    /// What happens to the towers of players who are killed (including by leaving): `zombie`
+    /// (default), `neutral`, or `decay:<seconds>` (see [`DeadTowers`]).
+    #[structopt(long)]
+    pub dead_towers: Option<DeadTowers>,
+    /// Shorthand for `--dead-towers neutral`.
+    #[structopt(long, conflicts_with = "dead-towers")]
+    pub no_zombies: bool,
+    /// Don't let mutual allies see around each other's towers.
+    #[structopt(long)]
+    pub no_ally_vision: bool,
+    /// Maximum mutual allies per player (unlimited by default).
+    #[structopt(long)]
+    pub max_alliances: Option<usize>,
+    /// How many seconds a new player's ruler can't be attacked (10 by default).
+    #[structopt(long)]
+    pub spawn_protection_secs: Option<TicksRepr>,
+    /// Impassable regions, as `x1,y1,x2,y2` rectangles separated by `;` (see [`Obstacles`]).
+    #[structopt(long)]
+    pub obstacles: Option<Obstacles>,
+    /// The most chunks a (non-admin) viewport can span in each dimension (24 by default).
+    #[structopt(long)]
+    pub max_viewport_chunks: Option<NonZeroU8>,
+    /// How fast each kind of tower generates units, e.g. `Barracks=2,Mine=0.5` (see
+    /// [`GenerationMultipliers`]). Also adjustable while running, by admins.
+    #[structopt(long)]
+    pub generation_multipliers: Option<GenerationMultipliers>,
+    /// The most roads a path may take, from 2 to 64 (16 by default).
+    #[structopt(long, parse(try_from_str = parse_max_path_roads))]
+    pub max_path_roads: Option<u8>,
+}
+
+impl TowerOptions {
+    /// Resolves [`Self::dead_towers`] and [`Self::no_zombies`].
+    pub fn dead_towers(&self) -> DeadTowers {
+        if self.no_zombies {
+            DeadTowers::Decay(Ticks::ZERO)
+        } else {
+            self.dead_towers.unwrap_or_default()
+        }
+    }
+}
+
+/// Rejects values outside [`Singleton::MAX_PATH_ROADS_RANGE`] instead of clamping them.
+fn parse_max_path_roads(s: &str) -> Result<u8, &'static str> {
+    s.parse()
+        .ok()
+        .filter(|n| Singleton::MAX_PATH_ROADS_RANGE.contains(n))
+        .ok_or("max path roads must be from 2 to 64")
+}
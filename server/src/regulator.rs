@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use core_protocol::PlayerId;
-use std::collections::{hash_map::Entry, HashMap};
+use fxhash::FxHashMap;
+use std::collections::hash_map::Entry;
 
 const LOG: bool = false;
 
@@ -38,7 +39,8 @@ struct State {
 
 #[derive(Default)]
 pub struct Regulator {
-    states: HashMap<PlayerId, State>,
+    /// Deterministic iteration order keeps seeded simulations reproducible.
+    states: FxHashMap<PlayerId, State>,
 }
 
 impl Regulator {
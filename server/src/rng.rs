@@ -0,0 +1,23 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+
+/// Random number generator used for all server game logic.
+pub type ServerRng = StdRng;
+
+thread_local! {
+    static RNG: RefCell<ServerRng> = RefCell::new(ServerRng::from_entropy());
+}
+
+/// Makes subsequent [`new_rng`] calls on this thread reproducible.
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = ServerRng::seed_from_u64(seed));
+}
+
+/// Returns a new independent generator, which is deterministic if [`seed`] was called.
+pub fn new_rng() -> ServerRng {
+    RNG.with(|rng| ServerRng::seed_from_u64(rng.borrow_mut().gen()))
+}
@@ -2,35 +2,76 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::bot::TowerBot;
+use crate::options::TowerOptions;
 use crate::regulator::Regulator;
+use crate::rng::{self, new_rng, ServerRng};
+use crate::spawn_queue::SpawnQueue;
 use atomic_refcell::AtomicRef;
 use common::alerts::{AlertFlag, Alerts};
-use common::chunk::{ChunkId, ChunkRectangle};
+use common::chunk::{Chunk, ChunkId, ChunkInput, ChunkRectangle};
 use common::death_reason::DeathReason;
+use common::emote::EmoteEvent;
 use common::info::{GainedTowerReason, Info, InfoEvent, LostRulerReason};
-use common::player::Player;
-use common::protocol::{Command, Diff, NonActor, Update};
-use common::singleton::SingletonId;
+use common::ping::PingEvent;
+use common::player::{Player, PlayerInput};
+use common::protocol::{Command, Diff, NonActor, SpawnPreference, Update};
+use common::singleton::{SingletonId, SingletonInput, WorldEvent};
 use common::ticks::Ticks;
-use common::tower::{TowerArray, TowerId, TowerRectangle};
+use common::tower::{Tower, TowerArray, TowerId, TowerRectangle, TowerType};
 use common::unit::Unit;
 use common::world::{Knowledge, Visibility, World, WorldChunks};
 use common_util::actor2::WorldTick;
 use common_util::storage::Map;
+use common_util::ticks::TicksRepr;
 use core_protocol::id::{GameId, PlayerId};
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
+use game_server::afk::AfkAction;
 use game_server::context::Context;
 use game_server::game_service::GameArenaService;
 use game_server::player::{PlayerRepo, PlayerTuple};
-use log::warn;
-use std::cmp::Ordering;
+use log::{info, warn};
+use std::cmp::{Ordering, Reverse};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::num::NonZeroU8;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 pub struct TowerService {
+    /// Emotes sent this tick, cleared in post update.
+    pub(crate) emotes: Vec<EmoteEvent>,
+    /// Pings sent this tick, cleared in post update.
+    pub(crate) pings: Vec<PingEvent>,
     maybe_dead: FxHashSet<PlayerId>,
+    /// Clients that watch without playing, so have no [`World::player`] (or [`Regulator`] state).
+    spectators: FxHashSet<PlayerId>,
     pub regulator: Regulator,
+    pub(crate) rng: ServerRng,
     pub world: World,
+    /// See [`NonActor::max_view_radius`].
+    max_view_radius: Option<u16>,
+    /// See [`NonActor::ally_vision`] and [`TowerOptions::no_ally_vision`].
+    pub(crate) ally_vision: bool,
+    /// Maximum mutual allies per player (see [`TowerOptions::max_alliances`]).
+    pub(crate) max_alliances: usize,
+    /// How long a new player's ruler can't be attacked (see
+    /// [`TowerOptions::spawn_protection_secs`]).
+    pub(crate) spawn_protection: Ticks,
+    /// What happens to the towers of players who are killed (see [`TowerOptions::dead_towers`]).
+    pub(crate) dead_towers: DeadTowers,
+    /// The most chunks a (non-admin) viewport can span in each dimension (see
+    /// [`TowerOptions::max_viewport_chunks`]).
+    pub(crate) max_viewport_chunks: u8,
+    /// Former towers of players who left, who left, and how long until their units are cleared.
+    decaying_towers: FxHashMap<TowerId, (PlayerId, Ticks)>,
+    /// Players waiting for room in their preferred spawn region.
+    pub(crate) spawn_queue: SpawnQueue,
+    /// Generate a point-symmetric world (see [`TowerId::mirrored_tower_type`]) for balance
+    /// testing. Debug only, enabled by the `MIRROR_MAP` environment variable.
+    pub(crate) mirror: bool,
+    /// Whether the simulation is paused (see [`GameArenaService::set_paused`]).
+    pub(crate) paused: bool,
 }
 
 #[derive(Debug, Default)]
@@ -44,6 +85,9 @@ pub struct ClientData {
 pub struct PlayerData {
     pub alive: bool,
     pub towers: FxHashSet<TowerId>,
+    /// How many of [`Self::towers`] are at each distance from [`World::CENTER`], in towers, so
+    /// the occupied radius doesn't require iterating every tower.
+    pub(crate) tower_distances: BTreeMap<u16, u32>,
     /// Saturating counter of how long the player has lived.
     pub lifetime: Ticks,
     /// Clamped to 255.
@@ -52,6 +96,70 @@ pub struct PlayerData {
     pub death_reason: Option<DeathReason>,
     /// Cached alerts (some of which are used as persistent storage).
     pub(crate) alerts: Alerts,
+    /// Whose limit on mutual allies (possibly this player's) rejected an alliance, so
+    /// [`AlertFlag::AllianceLimitReached`] is cleared once they're under it.
+    pub(crate) alliance_limited_by: Option<PlayerId>,
+    /// Time until the player can send another emote.
+    pub(crate) emote_cooldown: Ticks,
+    /// Time until the player can send another ping.
+    pub(crate) ping_cooldown: Ticks,
+}
+
+/// What happens to the towers of a player who is killed (including by leaving the game).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DeadTowers {
+    /// They become zombie towers, keeping their units.
+    #[default]
+    Zombie,
+    /// They become zombie towers, whose units are cleared gradually over a period, starting with
+    /// those farthest from the ruler.
+    Decay(Ticks),
+}
+
+impl FromStr for DeadTowers {
+    type Err = &'static str;
+
+    /// Parses `zombie`, `neutral` (decay immediately), or `decay:<seconds>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zombie" => Ok(Self::Zombie),
+            "neutral" => Ok(Self::Decay(Ticks::ZERO)),
+            _ => s
+                .strip_prefix("decay:")
+                .and_then(|secs| secs.parse().ok())
+                .map(|secs| Self::Decay(Ticks::from_whole_secs(secs)))
+                .ok_or("invalid dead towers"),
+        }
+    }
+}
+
+/// Speeds up (or slows down) unit generation of tower types (see
+/// [`Singleton::generation_multipliers`]), configured as comma separated `TowerType=factor`s,
+/// e.g. `Barracks=2,Mine=0.5`.
+///
+/// [`Singleton::generation_multipliers`]: common::singleton::Singleton::generation_multipliers
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GenerationMultipliers(pub Vec<(TowerType, u16)>);
+
+impl FromStr for GenerationMultipliers {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| {
+                let (tower_type, factor) = s.split_once('=').ok_or("expected TowerType=factor")?;
+                let tower_type =
+                    TowerType::from_str(tower_type.trim()).map_err(|_| "invalid tower type")?;
+                let factor: f32 = factor.trim().parse().map_err(|_| "invalid factor")?;
+                if !(0.01..=100.0).contains(&factor) {
+                    return Err("factor out of range");
+                }
+                Ok((tower_type, (factor * 100.0).round() as u16))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
 }
 
 impl GameArenaService for TowerService {
@@ -70,25 +178,72 @@ impl GameArenaService for TowerService {
     type GameRequest = Command;
     type PlayerData = PlayerData;
     type PlayerExtension = ();
+    type Options = TowerOptions;
 
     fn new(_: usize) -> Self {
         print!("Generating world...");
-        let world = World::new(); // TODO Default?
+        let world = World::new();
         println!("done!");
 
         Self {
+            emotes: Default::default(),
+            pings: Default::default(),
             maybe_dead: Default::default(),
+            spectators: Default::default(),
             regulator: Default::default(),
+            rng: new_rng(),
             world,
+            max_view_radius: None,
+            ally_vision: true,
+            max_alliances: usize::MAX,
+            spawn_protection: Self::DEFAULT_SPAWN_PROTECTION,
+            dead_towers: Default::default(),
+            max_viewport_chunks: Self::DEFAULT_MAX_VIEWPORT_CHUNKS,
+            decaying_towers: Default::default(),
+            spawn_queue: Default::default(),
+            mirror: cfg!(debug_assertions) && std::env::var_os("MIRROR_MAP").is_some(),
+            paused: false,
         }
     }
 
+    fn set_options(&mut self, options: Self::Options) {
+        // Towers (including obstacles) are generated lazily, so none have been yet.
+        if let Some(obstacles) = options.obstacles {
+            self.world = World::with_obstacles(obstacles.0);
+        }
+        if let Some(generation_multipliers) = options.generation_multipliers {
+            self.world.dispatch_singleton_input(
+                SingletonInput::SetGenerationMultipliers(generation_multipliers.0),
+                |_| {},
+            );
+        }
+        if let Some(max_path_roads) = options.max_path_roads {
+            self.world
+                .dispatch_singleton_input(SingletonInput::SetMaxPathRoads(max_path_roads), |_| {});
+        }
+        self.dead_towers = options.dead_towers();
+        self.max_alliances = options.max_alliances.unwrap_or(usize::MAX);
+        self.spawn_protection = options
+            .spawn_protection_secs
+            .map_or(Self::DEFAULT_SPAWN_PROTECTION, Ticks::from_whole_secs);
+        self.max_viewport_chunks = options
+            .max_viewport_chunks
+            .map_or(Self::DEFAULT_MAX_VIEWPORT_CHUNKS, NonZeroU8::get);
+        self.ally_vision = !options.no_ally_vision;
+    }
+
     fn player_joined(
         &mut self,
         player_tuple: &Arc<PlayerTuple<Self>>,
         _players: &PlayerRepo<Self>,
     ) {
-        let player_id = player_tuple.borrow_player().player_id;
+        let player = player_tuple.borrow_player();
+        let player_id = player.player_id;
+        if player.is_spectator() {
+            self.spectators.insert(player_id);
+            return;
+        }
+        drop(player);
         if self.regulator.join(player_id) {
             self.world
                 .player
@@ -103,9 +258,31 @@ impl GameArenaService for TowerService {
         players: &PlayerRepo<Self>,
     ) -> Option<Self::GameUpdate> {
         let player_id = player_tuple.borrow_player().player_id;
-        if !self.regulator.active(player_id) {
+        if self.spectators.contains(&player_id) && !player_tuple.borrow_player().is_spectator() {
+            // Reconnected to play instead.
+            self.spectators.remove(&player_id);
+            self.player_joined(player_tuple, players);
+        }
+        if self.spectators.contains(&player_id) {
+            if !Self::spectator_command(&command) {
+                return None;
+            }
+        } else if !self.regulator.active(player_id) {
             return None;
         }
+        // Spectating (e.g. panning around) is still allowed.
+        if self.paused && !Self::spectator_command(&command) {
+            return None;
+        }
+
+        // Any command means the player is no longer AFK.
+        {
+            let mut player = player_tuple.borrow_player_mut();
+            let flags = player.alerts.flags();
+            if flags.contains(AlertFlag::Afk) {
+                player.alerts.set_flags(flags - AlertFlag::Afk);
+            }
+        }
 
         fn wrap(path: &str) -> impl Fn(&str) -> String + '_ {
             move |e| format!("{path} resulted in {e}")
@@ -118,12 +295,37 @@ impl GameArenaService for TowerService {
             } => self
                 .alliance(player_id, with, break_alliance, players)
                 .map_err(wrap("Alliance")),
-            Command::DeployForce { tower_id, path } => self
-                .deploy_force(player_id, tower_id, path, players)
+            Command::DeployForce {
+                tower_id,
+                path,
+                percent,
+            } => self
+                .deploy_force(player_id, tower_id, path, percent, players)
                 .map_err(wrap("DeployForce")),
-            Command::SetSupplyLine { tower_id, path } => {
+            Command::Emote(emote_id) => self
+                .emote(player_id, emote_id, players)
+                .map_err(wrap("Emote")),
+            Command::GiftTower { tower_id, to } => self
+                .gift_tower(player_id, tower_id, to, players)
+                .map_err(wrap("GiftTower")),
+            Command::Ping(tower_id) => self
+                .ping(player_id, tower_id, players)
+                .map_err(wrap("Ping")),
+            Command::RedirectForce {
+                tower_id,
+                source,
+                path,
+            } => self
+                .redirect_force(player_id, tower_id, source, path)
+                .map_err(wrap("RedirectForce")),
+            Command::SetSupplyLine {
+                tower_id,
+                path,
+                pull,
+            } => {
                 if let Some(path) = path
                     .as_ref()
+                    .filter(|_| !pull)
                     .filter(|_| {
                         self.world.chunk.get(tower_id).map_or(false, |t| {
                             let mut mobile = false;
@@ -145,16 +347,33 @@ impl GameArenaService for TowerService {
                     })
                     .cloned()
                 {
-                    self.deploy_force(player_id, tower_id, path, players)
-                        .map_err(wrap("SetSupplyLine/DeployForce"))?;
+                    self.deploy_force(
+                        player_id,
+                        tower_id,
+                        path,
+                        Tower::FULL_DEPLOY_PERCENT,
+                        players,
+                    )
+                    .map_err(wrap("SetSupplyLine/DeployForce"))?;
+                }
+                self.set_supply_line(player_id, tower_id, path, pull, players)
+                    .map_err(wrap("SetSupplyLine"))?;
+                if pull {
+                    // Don't wait for the next pull, but it's fine if there's nothing to pull.
+                    let _ = self.pull_supply_line(tower_id, players);
                 }
-                self.set_supply_line(player_id, tower_id, path, players)
-                    .map_err(wrap("SetSupplyLine"))
+                Ok(())
             }
             Command::SetViewport(viewport) => {
                 let mut player = player_tuple.borrow_player_mut();
                 if let Some(client) = player.client_mut() {
-                    client.data_mut().viewport = viewport;
+                    let (clamped, oversized) = self.clamp_viewport(viewport);
+                    if oversized && !client.admin {
+                        warn!("{player_id:?} requested oversized viewport {viewport:?}");
+                        client.data_mut().viewport = clamped;
+                    } else {
+                        client.data_mut().viewport = viewport;
+                    }
                     Ok(())
                 } else {
                     debug_assert!(false);
@@ -162,7 +381,14 @@ impl GameArenaService for TowerService {
                 }
                 .map_err(wrap("SetViewport"))
             }
-            Command::Spawn => self.spawn_player(player_id, players).map_err(wrap("Spawn")),
+            Command::Spawn(preference) => {
+                self.spawn_queue.remove(player_id);
+                self.spawn_player(player_id, preference, true, players)
+                    .map_err(wrap("Spawn"))
+            }
+            Command::QueueSpawn(preference) => self
+                .queue_spawn(player_id, preference, players)
+                .map_err(wrap("QueueSpawn")),
             Command::Upgrade {
                 tower_id,
                 tower_type,
@@ -177,22 +403,48 @@ impl GameArenaService for TowerService {
         None
     }
 
+    fn spectator_command(command: &Self::GameRequest) -> bool {
+        matches!(command, Command::SetViewport(_))
+    }
+
     fn player_left(&mut self, player_tuple: &Arc<PlayerTuple<Self>>, _: &PlayerRepo<Self>) {
-        let player_id = player_tuple.borrow_player().player_id;
+        let player = player_tuple.borrow_player();
+        let player_id = player.player_id;
+        if self.spectators.remove(&player_id) {
+            return;
+        }
         self.regulator.leave(player_id);
+        self.spawn_queue.remove(player_id);
+        drop(player);
 
         // Can't kill since we are in the ChunkInput phase and kill is ChunkMaintenance.
         self.maybe_dead.insert(player_id);
     }
 
+    fn player_afk(&mut self, player_tuple: &Arc<PlayerTuple<Self>>, action: AfkAction) {
+        let mut player = player_tuple.borrow_player_mut();
+        let player_id = player.player_id;
+        let flags = player.alerts.flags();
+        match action {
+            AfkAction::Warn => player.alerts.set_flags(flags | AlertFlag::Afk),
+            AfkAction::Kick => {
+                player.alerts.set_flags(flags - AlertFlag::Afk);
+                player.death_reason = Some(DeathReason::Afk);
+                // Same phase issue as player_left.
+                self.maybe_dead.insert(player_id);
+            }
+        }
+    }
+
     fn get_game_update(
         &self,
         player_tuple: &Arc<PlayerTuple<Self>>,
         client_data: &mut Self::ClientData,
-        _players: &PlayerRepo<Self>,
+        players: &PlayerRepo<Self>,
     ) -> Option<Self::GameUpdate> {
         let player = player_tuple.borrow_player();
-        if !self.regulator.active(player.player_id) {
+        let spectator = self.spectators.contains(&player.player_id);
+        if !spectator && !self.regulator.active(player.player_id) {
             return None;
         }
         let admin = if let Some(client) = player.client() {
@@ -205,28 +457,13 @@ impl GameArenaService for TowerService {
         let player_id = player.player_id;
         let player = AtomicRef::map(player, |player| &player.data);
 
-        let bounding_rectangle = if player.towers.is_empty() {
-            let middle: ChunkId = World::CENTER.into();
-            ChunkRectangle {
-                bottom_left: middle,
-                top_right: middle,
-            }
-            .into()
-        } else {
-            let margin = player
-                .tower_counts
-                .iter()
-                .filter(|(_, c)| **c > 0)
-                .map(|(t, _)| t.sensor_radius() / TowerId::CONVERSION)
-                .max()
-                .unwrap_or(0)
-                .clamp(3, 12);
-            TowerRectangle::bounding(player.towers.iter().copied()).add_margin(margin)
-        };
+        let bounding_rectangle = self.bounding_rectangle(player_id, &player, players);
 
         debug_assert!(bounding_rectangle.is_valid());
 
-        let effective_viewport = if admin {
+        // Spectators have no towers to be bound to, but their viewport size is still limited (see
+        // `Self::clamp_viewport`).
+        let effective_viewport = if admin || spectator {
             client_data.viewport
         } else {
             // Viewport clamped to bounds.
@@ -240,7 +477,8 @@ impl GameArenaService for TowerService {
             ),
         ));
 
-        let actor_update = self.world.get_update(
+        // Checksums are expensive and only useful for debugging desyncs.
+        let actor_update = self.world.get_update_with_checksum(
             &mut client_data.knowledge,
             Visibility {
                 chunk: |k: &Knowledge| {
@@ -269,10 +507,12 @@ impl GameArenaService for TowerService {
                                 .unwrap()
                                 .iter_player_ids()
                         })
-                        .chain(Some(player_id)) // You always need your own player to know who's an ally.
+                        // You always need your own player to know who's an ally (unless spectating).
+                        .chain((!spectator).then_some(player_id))
                 },
                 singleton: |_: &_| Some(SingletonId),
             },
+            admin.then_some(0),
         );
 
         let non_actor = NonActor {
@@ -281,14 +521,48 @@ impl GameArenaService for TowerService {
             death_reason: player.death_reason.into(),
             alerts: player.alerts,
             bounding_rectangle,
+            max_view_radius: self.max_view_radius,
+            ally_vision: self.ally_vision,
+            paused: self.paused,
+            spawn_queue: self
+                .spawn_queue
+                .position(player_id)
+                .map(|position| position.min(u16::MAX as usize) as u16),
+            tick_period_secs: Self::TICK_PERIOD_SECS,
         };
         let non_actor_diff = client_data.non_actor.diff(&non_actor);
         client_data.non_actor = non_actor;
 
+        // Only relay emotes of players whose ruler is visible (everything is visible to the dead
+        // and spectators, like on the client).
+        let emotes = self
+            .emotes
+            .iter()
+            .filter(|e| {
+                if player.alive && !spectator {
+                    e.player_id == player_id
+                        || self.can_see(player_id, &player, players, e.tower_id)
+                } else {
+                    Map::contains(&client_data.knowledge.chunk, e.tower_id.into())
+                }
+            })
+            .copied()
+            .collect();
+
+        // Pings are invisible to non-allies.
+        let pings = self
+            .pings
+            .iter()
+            .filter(|p| p.player_id == player_id || self.is_mutual_ally(player_id, p.player_id))
+            .copied()
+            .collect();
+
         // Always send even if there are no events, for accurate time-keeping.
         Some(Update {
             actor_update,
             non_actor_diff,
+            emotes,
+            pings,
         })
     }
 
@@ -297,16 +571,28 @@ impl GameArenaService for TowerService {
     }
 
     fn tick(&mut self, context: &mut Context<Self>) {
+        if self.paused {
+            return;
+        }
+        let mut pulls = Vec::new();
+        let mut spawn_protections = Vec::new();
         for mut player_ref in context.players.iter_borrow_mut() {
             let player = &mut *player_ref;
             if player.data.alive {
                 player.lifetime = player.lifetime.saturating_add(Ticks::ONE);
+                let spawn_protected = player.lifetime < self.spawn_protection;
+                if Map::get(&self.world.player, player.player_id)
+                    .is_some_and(|p| p.actor.spawn_protected != spawn_protected)
+                {
+                    spawn_protections.push((player.player_id, spawn_protected));
+                }
+                player.emote_cooldown = player.emote_cooldown.saturating_sub(Ticks::ONE);
+                player.ping_cooldown = player.ping_cooldown.saturating_sub(Ticks::ONE);
 
                 #[cfg(debug_assertions)]
                 if self.counter().every(Ticks::from_whole_secs(20))
                     && matches!(player.alias().as_str(), "chonk" | "squonk")
                 {
-                    use common::chunk::ChunkInput;
                     use common::force::{Force, Path};
 
                     let alias = player.alias();
@@ -343,6 +629,15 @@ impl GameArenaService for TowerService {
                     }
                 }
 
+                if self.counter().every(Self::PULL_PERIOD) {
+                    pulls.extend(player.data.towers.iter().copied().filter(|&tower_id| {
+                        self.world
+                            .chunk
+                            .get(tower_id)
+                            .is_some_and(|t| t.supply_pull && t.supply_line.is_some())
+                    }));
+                }
+
                 if self.counter().every(Ticks::from_whole_secs(1)) {
                     player.score = 0;
                     let mut tower_counts: TowerArray<u8> = TowerArray::default();
@@ -351,6 +646,13 @@ impl GameArenaService for TowerService {
                     alerts.reset_ephemeral();
                     let mut flags = alerts.flags();
 
+                    if let Some(limited_by) = player.data.alliance_limited_by {
+                        if self.mutual_allies(limited_by) < self.max_alliances {
+                            player.data.alliance_limited_by = None;
+                            flags -= AlertFlag::AllianceLimitReached;
+                        }
+                    }
+
                     // Assume ruler is not safe until proven otherwise.
                     flags |= AlertFlag::RulerNotSafe;
                     for &tower_id in &player.data.towers {
@@ -380,6 +682,7 @@ impl GameArenaService for TowerService {
                                     flags |= AlertFlag::RulerUnderAttack;
                                 }
                             } else if tower.active() {
+                                let (mut overflowing, mut full) = (false, false);
                                 for unit in Unit::iter() {
                                     if !unit.is_mobile(Some(tower.tower_type))
                                         || !tower.units.contains(unit)
@@ -387,7 +690,7 @@ impl GameArenaService for TowerService {
                                         continue;
                                     }
                                     let generates = tower.unit_generation(unit).is_some();
-                                    if generates && tower.supply_line.is_some() {
+                                    if generates && tower.push_supply_line().is_some() {
                                         // Problem will go away.
                                         continue;
                                     }
@@ -397,13 +700,20 @@ impl GameArenaService for TowerService {
                                         .available(unit)
                                         .cmp(&tower.units.capacity(unit, Some(tower.tower_type)))
                                     {
-                                        Ordering::Greater => alerts.overflowing = Some(tower_id),
+                                        Ordering::Greater => {
+                                            alerts.overflowing = Some(tower_id);
+                                            overflowing = true;
+                                        }
                                         Ordering::Equal if generates => {
-                                            alerts.full = Some(tower_id)
+                                            alerts.full = Some(tower_id);
+                                            full = true;
                                         }
                                         _ => {}
                                     }
                                 }
+                                alerts.overflowing_count =
+                                    alerts.overflowing_count.saturating_add(overflowing as u8);
+                                alerts.full_count = alerts.full_count.saturating_add(full as u8);
                             }
 
                             if tower.inbound_forces.iter().any(|f| f.player_id.is_none()) {
@@ -431,6 +741,29 @@ impl GameArenaService for TowerService {
             }
         }
 
+        for tower_id in pulls {
+            // Nothing to pull is common.
+            let _ = self.pull_supply_line(tower_id, &context.players);
+        }
+
+        self.decay_towers();
+
+        let retry = self.counter().every(Ticks::from_whole_secs(1));
+        let mut spawn_queue = std::mem::take(&mut self.spawn_queue);
+        spawn_queue.tick(retry, |player_id, preference| {
+            self.spawn_player(player_id, preference, false, &context.players)
+                == Err(Self::SPAWN_REGION_FULL)
+        });
+        self.spawn_queue = spawn_queue;
+
+        for (player_id, spawn_protected) in spawn_protections {
+            self.world.dispatch_player_input(
+                player_id,
+                PlayerInput::SpawnProtected(spawn_protected),
+                Self::on_info_event(&context.players, |_| unreachable!()),
+            );
+        }
+
         self.world
             .tick_after_inputs(&mut Self::on_info_event(&context.players, |_| {
                 unreachable!("tick_after_inputs killed player")
@@ -449,12 +782,18 @@ impl GameArenaService for TowerService {
 
     fn post_update(&mut self, context: &mut Context<Self>) {
         self.world.post_update();
+        self.emotes.clear();
+        self.pings.clear();
+        if self.paused {
+            return;
+        }
 
         // Boundary between old tick and new tick.
 
         // Take to avoid borrowing issue.
         let mut maybe_dead = std::mem::take(&mut self.maybe_dead);
         for player_id in maybe_dead.drain() {
+            self.decay_towers_of(player_id, &context.players);
             // Makes `ChunkMaintenance`s which have to run before tick_before_inputs.
             self.kill_player(player_id, &context.players);
         }
@@ -487,6 +826,59 @@ impl GameArenaService for TowerService {
             .sum::<usize>() as f32
     }
 
+    fn seed(seed: u64) {
+        info!("world seed: {seed}");
+        rng::seed(seed);
+    }
+
+    fn set_max_view(&mut self, max_view: f32) {
+        let radius = max_view.clamp(0.0, 1.0) * WorldChunks::SIZE as f32 * 0.5;
+        self.max_view_radius = Some(radius as u16);
+    }
+
+    fn start_world_event(&mut self, event: &str, duration: Duration) -> Result<(), &'static str> {
+        let event = WorldEvent::from_str(event).map_err(|_| "unknown world event")?;
+        let duration = Ticks::from_secs(duration.as_secs_f32());
+        self.world
+            .dispatch_singleton_input(SingletonInput::StartWorldEvent { event, duration }, |_| {});
+        Ok(())
+    }
+
+    fn set_generation_multipliers(&mut self, multipliers: &str) -> Result<(), &'static str> {
+        let multipliers = GenerationMultipliers::from_str(multipliers)?;
+        self.world.dispatch_singleton_input(
+            SingletonInput::SetGenerationMultipliers(multipliers.0),
+            |_| {},
+        );
+        Ok(())
+    }
+
+    fn set_paused(&mut self, paused: bool) -> Result<(), &'static str> {
+        self.paused = paused;
+        Ok(())
+    }
+
+    fn replay_frame(&self) -> Option<Self::GameUpdate> {
+        // Snapshots of the whole world are large, so take one per second.
+        if !self.counter().every(Ticks::from_whole_secs(1)) {
+            return None;
+        }
+        let actor_update = self.world.get_update(
+            &mut Knowledge::default(),
+            Visibility {
+                chunk: |_: &Knowledge| Map::keys(&self.world.chunk),
+                player: |_: &Knowledge| Map::keys(&self.world.player),
+                singleton: |_: &_| Some(SingletonId),
+            },
+        );
+        Some(Update {
+            actor_update,
+            non_actor_diff: NonActor::default().diff(&NonActor::default()),
+            emotes: self.emotes.clone(),
+            pings: self.pings.clone(),
+        })
+    }
+
     fn entities(&self) -> usize {
         self.world
             .chunk
@@ -504,10 +896,225 @@ impl GameArenaService for TowerService {
 }
 
 impl TowerService {
+    const DEFAULT_SPAWN_PROTECTION: Ticks = Ticks::from_whole_secs(10);
+    /// Enough to zoom out over a large territory, but not to see the whole world.
+    const DEFAULT_MAX_VIEWPORT_CHUNKS: u8 = 24;
+
     fn counter(&self) -> Ticks {
         self.world.singleton().tick
     }
 
+    /// Like [`Self::spawn_player`], but if `preference`'s region is full, waits in line for room
+    /// instead of spawning elsewhere.
+    fn queue_spawn(
+        &mut self,
+        player_id: PlayerId,
+        preference: SpawnPreference,
+        players: &PlayerRepo<Self>,
+    ) -> Result<(), &'static str> {
+        match self.spawn_player(player_id, Some(preference), false, players) {
+            Err(Self::SPAWN_REGION_FULL) => {
+                self.spawn_queue.push(player_id, preference);
+                Ok(())
+            }
+            result => {
+                self.spawn_queue.remove(player_id);
+                result
+            }
+        }
+    }
+
+    /// Schedules clearing the units of the towers of `player_id`, who is about to be killed, if
+    /// [`Self::dead_towers`] says so. Over the decay period, starts with those farthest from the
+    /// ruler.
+    fn decay_towers_of(&mut self, player_id: PlayerId, players: &PlayerRepo<Self>) {
+        let DeadTowers::Decay(period) = self.dead_towers else {
+            return;
+        };
+        let Some(player) = players.borrow_player(player_id) else {
+            return;
+        };
+        let ruler = player.data.alerts.ruler_position.unwrap_or(World::CENTER);
+        let mut towers: Vec<_> = player.data.towers.iter().copied().collect();
+        towers.sort_by_key(|&tower_id| Reverse(tower_id.distance_squared(ruler)));
+        let count = towers.len() as u32;
+        for (i, tower_id) in towers.into_iter().enumerate() {
+            let delay = (period.0 as u32 * (i as u32 + 1) / count) as TicksRepr;
+            let decay = (player_id, Ticks::from_repr(delay));
+            // Keep any earlier schedule, unless it was for a previous owner.
+            self.decaying_towers
+                .entry(tower_id)
+                .and_modify(|existing| {
+                    if existing.0 != player_id {
+                        *existing = decay;
+                    }
+                })
+                .or_insert(decay);
+        }
+    }
+
+    /// Clears the units of [`Self::decaying_towers`] that are due, unless they were captured.
+    fn decay_towers(&mut self) {
+        let mut due = Vec::new();
+        self.decaying_towers
+            .retain(|&tower_id, (player_id, delay)| {
+                let Some(tower) = self.world.chunk.get(tower_id) else {
+                    return false;
+                };
+                if tower.player_id.is_some_and(|p| p != *player_id) {
+                    // Captured by someone else.
+                    return false;
+                }
+                *delay = delay.saturating_sub(Ticks::ONE);
+                // Wait for the player to be killed.
+                if *delay == Ticks::ZERO && tower.player_id.is_none() {
+                    due.push(tower_id);
+                    return false;
+                }
+                true
+            });
+
+        for tower_id in due {
+            let (chunk_id, tower_id) = tower_id.split();
+            self.world.dispatch_chunk_input(
+                chunk_id,
+                ChunkInput::ClearZombies { tower_id },
+                |_| unreachable!(),
+            );
+        }
+    }
+
+    /// Shrinks `viewport` around its center to at most [`Self::max_viewport_chunks`] in each
+    /// dimension (fewer if [`Self::max_view_radius`] is smaller), also returning whether it was
+    /// oversized.
+    pub(crate) fn clamp_viewport(&self, viewport: ChunkRectangle) -> (ChunkRectangle, bool) {
+        if !viewport.is_valid() {
+            // Clients send invalid viewports to stop receiving updates.
+            return (viewport, false);
+        }
+        let max = (self.max_viewport_chunks as u16).min(self.max_view_chunks());
+        let mut oversized = false;
+        let mut shrink = |bottom_left: &mut u8, top_right: &mut u8| {
+            let size = (*top_right - *bottom_left) as u16 + 1;
+            if size > max {
+                oversized = true;
+                *bottom_left += ((size - max) / 2) as u8;
+                *top_right = *bottom_left + (max - 1) as u8;
+            }
+        };
+        let ChunkRectangle {
+            mut bottom_left,
+            mut top_right,
+        } = viewport;
+        shrink(&mut bottom_left.x, &mut top_right.x);
+        shrink(&mut bottom_left.y, &mut top_right.y);
+        (ChunkRectangle::new(bottom_left, top_right), oversized)
+    }
+
+    /// The most chunks a view of [`Self::max_view_radius`] can span in each dimension.
+    fn max_view_chunks(&self) -> u16 {
+        // A diameter can straddle one more chunk than it fills.
+        self.max_view_radius.map_or(u16::MAX, |radius| {
+            (radius * 2).div_ceil(Chunk::SIZE as u16) + 1
+        })
+    }
+
+    /// Approximates the towers a player can see: their own and those of [`Self::vision_allies`].
+    pub(crate) fn bounding_rectangle(
+        &self,
+        player_id: PlayerId,
+        player: &PlayerData,
+        players: &PlayerRepo<Self>,
+    ) -> TowerRectangle {
+        if player.towers.is_empty() {
+            let middle: ChunkId = World::CENTER.into();
+            ChunkRectangle {
+                bottom_left: middle,
+                top_right: middle,
+            }
+            .into()
+        } else {
+            let mut towers: Vec<TowerId> = player.towers.iter().copied().collect();
+            for ally in self.vision_allies(player_id) {
+                if let Some(ally) = players.borrow_player(ally) {
+                    towers.extend(ally.data.towers.iter().copied());
+                }
+            }
+
+            let margin = player
+                .tower_counts
+                .iter()
+                .filter(|(_, c)| **c > 0)
+                .map(|(t, _)| t.sensor_radius() / TowerId::CONVERSION)
+                .max()
+                .unwrap_or(0)
+                .clamp(3, 12);
+            TowerRectangle::bounding(towers).add_margin(margin)
+        }
+    }
+
+    /// Mutual allies around whose towers `player_id` can also see (none unless
+    /// [`Self::ally_vision`]).
+    pub(crate) fn vision_allies(&self, player_id: PlayerId) -> impl Iterator<Item = PlayerId> + '_ {
+        let allies = self
+            .ally_vision
+            .then(|| self.world.player(player_id).allies.iter().copied());
+        allies.into_iter().flatten().filter(move |&ally| {
+            Map::get(&self.world.player, ally)
+                .map_or(false, |ally| ally.actor.allies.contains(&player_id))
+        })
+    }
+
+    /// Whether `tower_id` is within sensor range of a tower of `player_id` or one of their
+    /// [`Self::vision_allies`] (like the client's `Visible`).
+    pub(crate) fn can_see(
+        &self,
+        player_id: PlayerId,
+        player: &PlayerData,
+        players: &PlayerRepo<Self>,
+        tower_id: TowerId,
+    ) -> bool {
+        let sees = |towers: &FxHashSet<TowerId>| {
+            towers.iter().any(|&id| {
+                self.world.chunk.get(id).map_or(false, |tower| {
+                    // Inactive towers have the default sensor radius.
+                    let typ = if tower.active() {
+                        tower.tower_type
+                    } else {
+                        TowerType::Mine
+                    };
+                    id.distance_squared(tower_id) <= (typ.sensor_radius() as u64).pow(2)
+                })
+            })
+        };
+        sees(&player.towers)
+            || self.vision_allies(player_id).any(|ally| {
+                players
+                    .borrow_player(ally)
+                    .map_or(false, |ally| sees(&ally.data.towers))
+            })
+    }
+
+    /// Whether `a` and `b` are allied with each other.
+    pub(crate) fn is_mutual_ally(&self, a: PlayerId, b: PlayerId) -> bool {
+        let allies = |a: PlayerId, b: PlayerId| {
+            Map::get(&self.world.player, a).map_or(false, |a| a.actor.allies.contains(&b))
+        };
+        allies(a, b) && allies(b, a)
+    }
+
+    /// How many players are allied with `player_id` and vice versa.
+    pub(crate) fn mutual_allies(&self, player_id: PlayerId) -> usize {
+        Map::get(&self.world.player, player_id).map_or(0, |player| {
+            player
+                .actor
+                .allies
+                .iter()
+                .filter(|&&ally| self.is_mutual_ally(player_id, ally))
+                .count()
+        })
+    }
+
     pub(crate) fn on_info_event<'a>(
         players: &'a PlayerRepo<Self>,
         mut maybe_dead: impl FnMut(PlayerId) + 'a,
@@ -530,6 +1137,12 @@ impl TowerService {
                         "tower {:?} was already in set of {:?} but now inserted due to {:?}",
                         tower_id, player_id, reason
                     );
+                    if inserted {
+                        *new_player
+                            .tower_distances
+                            .entry(Self::distance_from_center(tower_id))
+                            .or_default() += 1;
+                    }
                 } else {
                     debug_assert!(false);
                 }
@@ -558,6 +1171,17 @@ impl TowerService {
                 if let Some(mut old_player) = players.borrow_player_mut(player_id) {
                     let removed = old_player.towers.remove(&tower_id);
                     debug_assert!(removed);
+                    if removed {
+                        let distance = Self::distance_from_center(tower_id);
+                        if let Entry::Occupied(mut entry) =
+                            old_player.tower_distances.entry(distance)
+                        {
+                            *entry.get_mut() -= 1;
+                            if *entry.get() == 0 {
+                                entry.remove();
+                            }
+                        }
+                    }
                 } else {
                     debug_assert!(false);
                 }
@@ -566,3 +1190,753 @@ impl TowerService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::options::TowerOptions;
+    use crate::rng;
+    use crate::service::{DeadTowers, GenerationMultipliers, TowerService};
+    use crate::simulation::{test_game, test_game_unallied};
+    use common::alerts::AlertFlag;
+    use common::chunk::{ChunkId, ChunkInput, ChunkRectangle};
+    use common::force::{Force, Path};
+    use common::singleton::Singleton;
+    use common::ticks::Ticks;
+    use common::tower::{TowerId, TowerRectangle, TowerType};
+    use common::unit::Unit;
+    use common::units::Units;
+    use core_protocol::id::PlayerId;
+    use fxhash::FxHashMap;
+    use game_server::context::Context;
+    use game_server::context_service::ContextService;
+    use game_server::game_service::GameArenaService;
+    use game_server::metric::MetricRepo;
+    use game_server::player::PlayerRepo;
+    use std::num::NonZeroU8;
+    use std::time::Duration;
+    use structopt::StructOpt;
+
+    #[test]
+    fn world_event() {
+        let (mut context_service, mut metrics) = test_game(20, TowerOptions::default());
+
+        let service = &mut context_service.service;
+        let duration = Duration::from_secs(60);
+        assert!(service
+            .start_world_event("TripleGeneration", duration)
+            .is_err());
+        service
+            .start_world_event("DoubleGeneration", duration)
+            .unwrap();
+
+        let period = Ticks::from_whole_secs(6);
+        let doubled = Ticks::from_whole_secs(3);
+        for _ in 0..Ticks::from_whole_secs(59).0 {
+            let singleton = context_service.service.world.singleton();
+            assert_eq!(singleton.unit_generation(period), doubled);
+            context_service.simulate(&mut metrics);
+        }
+        for _ in 0..Ticks::from_whole_secs(2).0 {
+            context_service.simulate(&mut metrics);
+        }
+        let singleton = context_service.service.world.singleton();
+        assert_eq!(singleton.world_event, None);
+        assert_eq!(singleton.unit_generation(period), period);
+    }
+
+    #[test]
+    fn paused() {
+        let (mut context_service, mut metrics) = test_game(20, TowerOptions::default());
+
+        let snapshot = |service: &TowerService| {
+            let towers: Vec<_> = service
+                .world
+                .chunk
+                .iter_towers()
+                .map(|(tower_id, tower)| (tower_id, tower.clone()))
+                .collect();
+            (service.world.singleton().tick, towers)
+        };
+        context_service.service.set_paused(true).unwrap();
+        let before = snapshot(&context_service.service);
+        for _ in 0..Ticks::from_whole_secs(10).0 {
+            context_service.simulate(&mut metrics);
+        }
+        assert_eq!(snapshot(&context_service.service), before);
+
+        context_service.service.set_paused(false).unwrap();
+        context_service.simulate(&mut metrics);
+        assert_ne!(context_service.service.world.singleton().tick, before.0);
+    }
+
+    #[test]
+    fn max_bots() {
+        rng::seed(0);
+        let mut context_service =
+            ContextService::<TowerService>::new(Some(20), Some(5), Some(1000), None);
+        let mut metrics = MetricRepo::new();
+        let mut bots = 0;
+
+        for _ in 0..100 {
+            context_service.simulate(&mut metrics);
+            bots = context_service
+                .context
+                .players
+                .iter_borrow()
+                .filter(|p| p.player_id.is_bot())
+                .count();
+            assert!(bots <= 5, "{bots}");
+        }
+        // Bots still spawn, up to the maximum.
+        assert_eq!(bots, 5);
+    }
+
+    #[test]
+    fn ping() {
+        // Let the bots spawn.
+        let (mut context_service, _) = test_game(20, TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let player = players.iter_borrow().find(|p| p.data.alive).unwrap();
+        let (player_id, tower_id) = (player.player_id, *player.data.towers.iter().next().unwrap());
+        drop(player);
+
+        service.ping(player_id, tower_id, players).unwrap();
+        assert_eq!(service.pings.len(), 1);
+        assert!(service.ping(player_id, tower_id, players).is_err());
+        assert_eq!(service.pings.len(), 1);
+    }
+
+    #[test]
+    fn gift_tower() {
+        // Let the bots spawn and explore.
+        let (mut context_service, _) = test_game(40, TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let mut alive = players
+            .iter_borrow()
+            .filter(|p| p.data.alive)
+            .map(|p| p.player_id)
+            .collect::<Vec<_>>();
+        alive.sort();
+        let (a, b) = (alive[0], alive[1]);
+
+        let towers = players.borrow_player(a).unwrap().towers.clone();
+        let ruler = *towers
+            .iter()
+            .find(|&&id| service.world.chunk.get(id).unwrap().units.has_ruler())
+            .unwrap();
+        let gift = *towers
+            .iter()
+            .find(|&&id| !service.world.chunk.get(id).unwrap().units.has_ruler())
+            .unwrap();
+
+        assert!(service.gift_tower(a, gift, b, players).is_err());
+
+        service.alliance(a, b, false, players).unwrap();
+        service.alliance(b, a, false, players).unwrap();
+        assert!(service.gift_tower(a, ruler, b, players).is_err());
+        assert!(service.gift_tower(b, gift, a, players).is_err());
+        assert!(service.gift_tower(a, gift, a, players).is_err());
+
+        service.gift_tower(a, gift, b, players).unwrap();
+        assert_eq!(service.world.chunk.get(gift).unwrap().player_id, Some(b));
+        assert!(!players.borrow_player(a).unwrap().towers.contains(&gift));
+        assert!(players.borrow_player(b).unwrap().towers.contains(&gift));
+    }
+
+    #[test]
+    fn partial_deploy() {
+        let (mut context_service, _) = test_game(100, TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let (player_id, tower_id, neighbor) = service
+            .world
+            .chunk
+            .iter_towers()
+            .find_map(|(tower_id, tower)| {
+                let player_id = tower.player_id?;
+                if tower.force_units().len() < 3 || tower.units.has_ruler() {
+                    return None;
+                }
+                let neighbor = tower_id
+                    .neighbors()
+                    .find(|&n| service.world.chunk.get(n).is_some())?;
+                Some((player_id, tower_id, neighbor))
+            })
+            .unwrap();
+
+        let before = service.world.chunk.get(tower_id).unwrap().force_units();
+        let path = Path::new(vec![tower_id, neighbor]);
+        assert!(service
+            .deploy_force(player_id, tower_id, path.clone(), 0, players)
+            .is_err());
+        service
+            .deploy_force(player_id, tower_id, path, 50, players)
+            .unwrap();
+
+        let after = service.world.chunk.get(tower_id).unwrap().force_units();
+        for (unit, count) in before.iter() {
+            assert_eq!(after.available(unit), count / 2, "{unit:?} {count}");
+        }
+    }
+
+    #[test]
+    fn owner_history() {
+        let (mut context_service, mut metrics) = test_game(0, TowerOptions::default());
+        let mut owners = FxHashMap::<TowerId, PlayerId>::default();
+
+        for _ in 0..4000 {
+            context_service.simulate(&mut metrics);
+
+            let world = &context_service.service.world;
+            let tick = world.singleton().tick;
+            for (tower_id, tower) in world.chunk.iter_towers() {
+                let previous = if let Some(player_id) = tower.player_id {
+                    owners.insert(tower_id, player_id)
+                } else {
+                    owners.remove(&tower_id)
+                };
+                // Captured, as opposed to explored or abandoned.
+                if let Some(previous) = previous
+                    && tower.player_id.is_some_and(|p| p != previous)
+                {
+                    assert_eq!(tower.history.iter().next(), Some((previous, tick)));
+                    return;
+                }
+            }
+        }
+        panic!("no towers were captured");
+    }
+
+    #[test]
+    fn seed() {
+        let generate = |seed: u64| -> Vec<(TowerId, TowerType)> {
+            TowerService::seed(seed);
+            let mut context_service =
+                ContextService::<TowerService>::new(Some(8), Some(8), None, None);
+            let mut metrics = MetricRepo::new();
+            // Let the bots spawn, which generates chunks around them.
+            for _ in 0..20 {
+                context_service.simulate(&mut metrics);
+            }
+            let mut towers: Vec<_> = context_service
+                .service
+                .world
+                .chunk
+                .iter_towers()
+                .map(|(tower_id, tower)| (tower_id, tower.tower_type))
+                .collect();
+            towers.sort_unstable_by_key(|&(tower_id, _)| (tower_id.x, tower_id.y));
+            towers
+        };
+
+        let a = generate(5);
+        assert!(!a.is_empty());
+        assert_eq!(a, generate(5));
+        assert_ne!(a, generate(6));
+    }
+
+    #[test]
+    fn ally_vision() {
+        let (mut context_service, alive) = test_game_unallied(TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let [a, b, ..] = alive[..] else {
+            panic!("not enough bots spawned");
+        };
+
+        let rectangle = |service: &TowerService, player_id: PlayerId| {
+            let player = players.borrow_player(player_id).unwrap();
+            service.bounding_rectangle(player_id, &player.data, players)
+        };
+        let sees_all_of = |rectangle: TowerRectangle, of: PlayerId| {
+            let of = players.borrow_player(of).unwrap();
+            of.data.towers.iter().all(|&t| rectangle.contains(t))
+        };
+
+        let before = [rectangle(&*service, a), rectangle(&*service, b)];
+        assert!(!service.vision_allies(a).any(|p| p == b));
+
+        service.alliance(a, b, false, players).unwrap();
+        assert!(
+            !service.vision_allies(a).any(|p| p == b),
+            "alliance isn't mutual yet"
+        );
+        service.alliance(b, a, false, players).unwrap();
+        assert!(service.vision_allies(a).any(|p| p == b));
+        assert!(service.vision_allies(b).any(|p| p == a));
+        assert!(sees_all_of(rectangle(&*service, a), b));
+        assert!(sees_all_of(rectangle(&*service, b), a));
+
+        service.alliance(a, b, true, players).unwrap();
+        assert!(!service.vision_allies(a).any(|p| p == b));
+        assert_eq!([rectangle(&*service, a), rectangle(&*service, b)], before);
+    }
+
+    #[test]
+    fn can_see() {
+        let (mut context_service, alive) = test_game_unallied(TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let a = alive[0];
+        let ruler = players
+            .borrow_player(a)
+            .unwrap()
+            .data
+            .alerts
+            .ruler_position
+            .unwrap();
+        let can_see = |service: &TowerService, player_id: PlayerId| {
+            let player = players.borrow_player(player_id).unwrap();
+            service.can_see(player_id, &player.data, players, ruler)
+        };
+        assert!(can_see(&*service, a));
+
+        // Bots spawn apart, so some can't see the ruler.
+        let b = *alive[1..]
+            .iter()
+            .find(|&&b| !can_see(&*service, b))
+            .expect("every bot sees the ruler");
+
+        // Until they're mutual allies.
+        service.alliance(a, b, false, players).unwrap();
+        service.alliance(b, a, false, players).unwrap();
+        assert!(can_see(&*service, b));
+    }
+
+    #[test]
+    fn no_ally_vision() {
+        let options = TowerOptions::from_iter_safe(["server", "--no-ally-vision"]).unwrap();
+        let (mut context_service, alive) = test_game_unallied(options);
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let [a, b, ..] = alive[..] else {
+            panic!("not enough bots spawned");
+        };
+
+        service.alliance(a, b, false, players).unwrap();
+        service.alliance(b, a, false, players).unwrap();
+        assert!(service.is_mutual_ally(a, b));
+        assert_eq!(service.vision_allies(a).count(), 0);
+    }
+
+    #[test]
+    fn max_alliances() {
+        let (mut context_service, alive) = test_game_unallied(TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let [a, b, c, d, ..] = alive[..] else {
+            panic!("not enough bots spawned");
+        };
+
+        service.set_options(TowerOptions {
+            max_alliances: Some(2),
+            ..Default::default()
+        });
+        let ally = |service: &mut TowerService,
+                    players: &PlayerRepo<TowerService>,
+                    x: PlayerId,
+                    y: PlayerId| {
+            service.alliance(x, y, false, players)?;
+            service.alliance(y, x, false, players)
+        };
+        ally(service, players, a, b).unwrap();
+        ally(service, players, a, c).unwrap();
+        assert_eq!(service.mutual_allies(a), 2);
+
+        // Rejected because of the limit of `a`, which `d` (who completed the alliance) is under.
+        assert_eq!(ally(service, players, a, d), Err("alliance limit reached"));
+        assert!(!service.is_mutual_ally(a, d));
+        let limit_reached = |context: &Context<TowerService>| {
+            let player = context.players.borrow_player(d).unwrap();
+            player
+                .alerts
+                .flags()
+                .contains(AlertFlag::AllianceLimitReached)
+        };
+        assert!(limit_reached(context));
+
+        let tick = |service: &mut TowerService, context: &mut Context<TowerService>| {
+            for _ in 0..=Ticks::from_whole_secs(1).0 {
+                service.tick(context);
+                service.post_update(context);
+            }
+        };
+        tick(service, context);
+        assert!(limit_reached(context));
+
+        // Breaking an alliance frees a slot.
+        let players = &context.players;
+        service.alliance(a, b, true, players).unwrap();
+        tick(service, context);
+        assert!(!limit_reached(context));
+
+        let players = &context.players;
+        ally(service, players, a, d).unwrap();
+        assert!(service.is_mutual_ally(a, d));
+        assert_eq!(service.mutual_allies(a), 2);
+    }
+
+    #[test]
+    fn pull_supply_line() {
+        let (mut context_service, mut metrics) = test_game(100, TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let chunk = &service.world.chunk;
+        let (player_id, source_id, puller_id) = chunk
+            .iter_towers()
+            .find_map(|(source_id, source)| {
+                let player_id = source.player_id?;
+                if source.units.has_ruler() || !source.units.contains(Unit::Soldier) {
+                    return None;
+                }
+                let puller_id = source_id.neighbors().find(|&n| {
+                    chunk.get(n).is_some_and(|puller| {
+                        puller.player_id == Some(player_id)
+                            && puller.inbound_forces.is_empty()
+                            && puller.units.available(Unit::Soldier)
+                                < puller
+                                    .units
+                                    .capacity(Unit::Soldier, Some(puller.tower_type))
+                    })
+                })?;
+                Some((player_id, source_id, puller_id))
+            })
+            .unwrap();
+
+        let path = Path::new(vec![puller_id, source_id]);
+        assert!(service.pull_supply_line(puller_id, players).is_err());
+        service
+            .set_supply_line(player_id, puller_id, Some(path), true, players)
+            .unwrap();
+        let before = service.world.chunk.get(source_id).unwrap().force_units();
+        service.pull_supply_line(puller_id, players).unwrap();
+
+        // Units leave the source, towards the puller.
+        let source = service.world.chunk.get(source_id).unwrap();
+        assert!(source.force_units().available(Unit::Soldier) < before.available(Unit::Soldier));
+        context_service.simulate(&mut metrics);
+        let puller = context_service.service.world.chunk.get(puller_id).unwrap();
+        assert!(puller
+            .inbound_forces
+            .iter()
+            .any(|f| f.player_id == Some(player_id) && f.current_source() == source_id));
+        // Pulling doesn't push units arriving at the puller back to the source.
+        assert_eq!(puller.push_supply_line(), None);
+    }
+
+    fn ruler_tower(service: &TowerService, player_id: PlayerId) -> Option<TowerId> {
+        service
+            .world
+            .chunk
+            .iter_towers()
+            .find(|(_, t)| t.player_id == Some(player_id) && t.units.has_ruler())
+            .map(|(tower_id, _)| tower_id)
+    }
+
+    /// Sends an overwhelming force from a neighbor of `tower_id`.
+    fn attack(service: &mut TowerService, attacker: PlayerId, tower_id: TowerId) {
+        let mut units = Units::default();
+        for unit in [Unit::Bomber, Unit::Tank, Unit::Soldier] {
+            units.add(unit, 50);
+        }
+        let src = tower_id.neighbors().next().unwrap();
+        let force = Force::new(attacker, units, Path::new(vec![src, tower_id]));
+        let (chunk_id, tower_id) = tower_id.split();
+        service.world.dispatch_chunk_input(
+            chunk_id,
+            ChunkInput::AddInboundForce { tower_id, force },
+            |_| unreachable!(),
+        );
+    }
+
+    #[test]
+    fn spawn_protection() {
+        let (mut context_service, mut metrics) = test_game(
+            4,
+            TowerOptions {
+                spawn_protection_secs: Some(30),
+                ..Default::default()
+            },
+        );
+
+        let service = &mut context_service.service;
+        let (ruler_id, victim) = service
+            .world
+            .chunk
+            .iter_towers()
+            .find_map(|(tower_id, t)| t.units.has_ruler().then_some((tower_id, t.player_id?)))
+            .unwrap();
+        let attacker = service
+            .world
+            .chunk
+            .iter_towers()
+            .find_map(|(_, t)| t.player_id.filter(|&p| p != victim))
+            .unwrap();
+        assert!(service.world.player(victim).spawn_protected);
+
+        // Not captured within the window.
+        attack(service, attacker, ruler_id);
+        for _ in 0..40 {
+            context_service.simulate(&mut metrics);
+        }
+        let service = &context_service.service;
+        assert!(service.world.player(victim).spawn_protected);
+        assert_eq!(ruler_tower(service, victim), Some(ruler_id));
+
+        // Captured after it elapses.
+        while context_service.service.world.player(victim).spawn_protected {
+            context_service.simulate(&mut metrics);
+        }
+        let service = &mut context_service.service;
+        let ruler_id = ruler_tower(service, victim).unwrap();
+        attack(service, attacker, ruler_id);
+        for _ in 0..40 {
+            context_service.simulate(&mut metrics);
+        }
+        let tower = context_service.service.world.chunk.get(ruler_id).unwrap();
+        assert_ne!(tower.player_id, Some(victim));
+    }
+
+    #[test]
+    fn generation_multipliers() {
+        assert_eq!(
+            "Barracks=2, Mine=0.5".parse(),
+            Ok(GenerationMultipliers(vec![
+                (TowerType::Barracks, 200),
+                (TowerType::Mine, 50)
+            ]))
+        );
+        assert_eq!("".parse(), Ok(GenerationMultipliers::default()));
+        assert!("Barracks".parse::<GenerationMultipliers>().is_err());
+        assert!("Barracks=0".parse::<GenerationMultipliers>().is_err());
+        assert!("Castle=2".parse::<GenerationMultipliers>().is_err());
+
+        let options =
+            TowerOptions::from_iter_safe(["server", "--generation-multipliers", "Barracks=2"])
+                .unwrap();
+        let mut service = TowerService::new(0);
+        service.set_options(options);
+        let multipliers =
+            |service: &TowerService| service.world.singleton().generation_multipliers.clone();
+        assert_eq!(multipliers(&service), vec![(TowerType::Barracks, 200)]);
+        assert!(
+            TowerOptions::from_iter_safe(["server", "--generation-multipliers", "Barracks"])
+                .is_err()
+        );
+
+        // Live balancing, which leaves the multipliers alone if invalid.
+        service.set_generation_multipliers("Mine=0.5").unwrap();
+        assert_eq!(multipliers(&service), vec![(TowerType::Mine, 50)]);
+        assert!(service.set_generation_multipliers("Mine=0").is_err());
+        assert_eq!(multipliers(&service), vec![(TowerType::Mine, 50)]);
+    }
+
+    #[test]
+    fn max_path_roads() {
+        let parse = |n: &str| TowerOptions::from_iter_safe(["server", "--max-path-roads", n]);
+        assert!(parse("1").is_err());
+        assert!(parse("65").is_err());
+        assert!(parse("many").is_err());
+
+        let mut service = TowerService::new(0);
+        service.set_options(parse("32").unwrap());
+        assert_eq!(service.world.max_path_roads(), 32);
+        let mut service = TowerService::new(0);
+        service.set_options(TowerOptions::default());
+        assert_eq!(
+            service.world.max_path_roads(),
+            Singleton::DEFAULT_MAX_PATH_ROADS
+        );
+    }
+
+    #[test]
+    fn dead_towers() {
+        assert_eq!("zombie".parse(), Ok(DeadTowers::Zombie));
+        assert_eq!("neutral".parse(), Ok(DeadTowers::Decay(Ticks::ZERO)));
+        assert_eq!(
+            "decay:5".parse(),
+            Ok(DeadTowers::Decay(Ticks::from_whole_secs(5)))
+        );
+        assert!("decay".parse::<DeadTowers>().is_err());
+
+        let parse = |args: &[&str]| {
+            TowerOptions::from_iter_safe(std::iter::once("server").chain(args.iter().copied()))
+                .map(|options| options.dead_towers())
+        };
+        assert_eq!(parse(&[]).unwrap(), DeadTowers::Zombie);
+        assert_eq!(
+            parse(&["--no-zombies"]).unwrap(),
+            DeadTowers::Decay(Ticks::ZERO)
+        );
+        assert_eq!(
+            parse(&["--dead-towers", "decay:5"]).unwrap(),
+            DeadTowers::Decay(Ticks::from_whole_secs(5))
+        );
+        assert!(parse(&["--dead-towers", "decay:abc"]).is_err());
+        assert!(parse(&["--no-zombies", "--dead-towers", "zombie"]).is_err());
+
+        let period = Ticks::from_whole_secs(5);
+        let (mut context_service, mut metrics) = test_game(
+            100,
+            TowerOptions {
+                dead_towers: Some(DeadTowers::Decay(period)),
+                ..Default::default()
+            },
+        );
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let (player_id, towers) = context
+            .players
+            .iter_borrow()
+            .filter(|p| p.alive)
+            .map(|p| (p.player_id, p.towers.iter().copied().collect::<Vec<_>>()))
+            .max_by_key(|(_, towers)| towers.len())
+            .unwrap();
+        assert!(towers.len() > 1);
+        let player_tuple = context.players.get(player_id).unwrap().clone();
+        service.player_left(&player_tuple, &context.players);
+
+        // Killed, leaving zombie towers that haven't all decayed yet.
+        context_service.simulate(&mut metrics);
+        let chunk = &context_service.service.world.chunk;
+        assert!(towers
+            .iter()
+            .all(|&id| chunk.get(id).unwrap().player_id != Some(player_id)));
+        assert!(towers
+            .iter()
+            .any(|&id| !chunk.get(id).unwrap().units.is_empty()));
+
+        // Decayed unless captured.
+        for _ in 0..period.0 + 1 {
+            context_service.simulate(&mut metrics);
+        }
+        let chunk = &context_service.service.world.chunk;
+        for &id in &towers {
+            let tower = chunk.get(id).unwrap();
+            assert!(tower.player_id.is_some() || tower.units.is_empty());
+        }
+        assert!(context_service.service.decaying_towers.is_empty());
+    }
+
+    #[test]
+    fn no_zombies() {
+        let (mut context_service, mut metrics) = test_game(
+            100,
+            TowerOptions {
+                no_zombies: true,
+                ..Default::default()
+            },
+        );
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let (player_id, towers) = context
+            .players
+            .iter_borrow()
+            .filter(|p| p.alive)
+            .map(|p| (p.player_id, p.towers.iter().copied().collect::<Vec<_>>()))
+            .max_by_key(|(_, towers)| towers.len())
+            .unwrap();
+        assert!(towers.len() > 1);
+        let player_tuple = context.players.get(player_id).unwrap().clone();
+        service.player_left(&player_tuple, &context.players);
+
+        // Killed, and then neutral unless captured.
+        for _ in 0..3 {
+            context_service.simulate(&mut metrics);
+        }
+        let chunk = &context_service.service.world.chunk;
+        for &id in &towers {
+            let tower = chunk.get(id).unwrap();
+            assert_ne!(tower.player_id, Some(player_id));
+            assert!(tower.player_id.is_some() || tower.units.is_empty());
+        }
+
+        // Neither do other players that die leave zombies behind.
+        for _ in 0..500 {
+            context_service.simulate(&mut metrics);
+            let service = &context_service.service;
+            for (tower_id, tower) in service.world.chunk.iter_towers() {
+                assert!(tower.inbound_forces.iter().all(|f| f.player_id.is_some()));
+                assert!(
+                    tower.player_id.is_some()
+                        || tower.units.is_empty()
+                        || service.decaying_towers.contains_key(&tower_id),
+                    "zombie {tower_id:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn clamp_viewport() {
+        assert!(TowerOptions::from_iter_safe(["server", "--max-viewport-chunks", "0"]).is_err());
+        let mut context_service = ContextService::<TowerService>::new(None, None, None, None);
+        context_service.service.set_options(TowerOptions {
+            max_viewport_chunks: NonZeroU8::new(12),
+            ..Default::default()
+        });
+        let service = &context_service.service;
+        let max = service.max_viewport_chunks;
+        assert_eq!(max, 12);
+        let viewport = |bottom_left: (u8, u8), top_right: (u8, u8)| {
+            ChunkRectangle::new(
+                ChunkId::new(bottom_left.0, bottom_left.1),
+                ChunkId::new(top_right.0, top_right.1),
+            )
+        };
+
+        let normal = viewport((2, 3), (2 + max - 1, 10));
+        assert_eq!(service.clamp_viewport(normal), (normal, false));
+        let hidden = ChunkRectangle::invalid();
+        assert_eq!(service.clamp_viewport(hidden), (hidden, false));
+
+        // Shrunk around its center.
+        let (clamped, oversized) = service.clamp_viewport(viewport((0, 5), (u8::MAX, 6)));
+        assert!(oversized);
+        assert_eq!(clamped.dimensions().x, max);
+        assert_eq!(clamped.dimensions().y, 2);
+        assert_eq!(
+            clamped.bottom_left.x as u16 + clamped.top_right.x as u16,
+            u8::MAX as u16
+        );
+
+        // Also limited by the maximum view.
+        context_service.service.set_max_view(0.1);
+        let service = &context_service.service;
+        let max = service.max_view_chunks();
+        assert!(max < service.max_viewport_chunks as u16);
+        let (clamped, oversized) = service.clamp_viewport(normal);
+        assert!(oversized);
+        assert_eq!(clamped.dimensions().x as u16, max);
+    }
+}
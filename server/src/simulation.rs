@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::rng;
+use crate::service::TowerService;
+use common::death_reason::DeathReason;
+use common::ticks::Ticks;
+use common::tower::TowerId;
+use core_protocol::id::PlayerId;
+use fxhash::FxHashMap;
+use game_server::context_service::ContextService;
+use game_server::metric::MetricRepo;
+use std::fmt::{self, Display, Formatter};
+use structopt::StructOpt;
+
+/// Parameters of a headless, bot-only game (the `simulate` subcommand).
+#[derive(Copy, Clone, Debug, StructOpt)]
+#[structopt(name = "simulate")]
+pub struct SimulationOptions {
+    /// How many bots play.
+    #[structopt(long, default_value = "10")]
+    pub bots: usize,
+    /// How long to simulate.
+    #[structopt(long, default_value = "2400")]
+    pub ticks: usize,
+    /// Seed of the world and bots.
+    #[structopt(long, default_value = "0")]
+    pub seed: u64,
+}
+
+/// Aggregate results of a simulation.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SimulationStats {
+    /// Ticks simulated.
+    pub ticks: usize,
+    /// Times a tower changed from one player to another.
+    pub towers_captured: usize,
+    /// Times a player died because their ruler was killed.
+    pub rulers_killed: usize,
+    /// Longest any player stayed alive.
+    pub longest_lifetime: Ticks,
+    /// Towers owned by any player at the end.
+    pub towers_owned: usize,
+}
+
+impl Display for SimulationStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ticks: {}, towers captured: {}, rulers killed: {}, longest lifetime: {:.1}s, towers owned: {}",
+            self.ticks,
+            self.towers_captured,
+            self.rulers_killed,
+            self.longest_lifetime.to_secs(),
+            self.towers_owned
+        )
+    }
+}
+
+/// Runs a game with only bots and no networking. Deterministic for a given
+/// [`SimulationOptions::seed`].
+pub fn simulate(options: SimulationOptions) -> SimulationStats {
+    rng::seed(options.seed);
+
+    let mut context_service =
+        ContextService::<TowerService>::new(Some(options.bots), Some(options.bots), None, None);
+    let mut metrics = MetricRepo::new();
+
+    let mut stats = SimulationStats::default();
+    let mut owners = FxHashMap::<TowerId, PlayerId>::default();
+    let mut alive = FxHashMap::<PlayerId, bool>::default();
+
+    for _ in 0..options.ticks {
+        context_service.simulate(&mut metrics);
+        stats.ticks += 1;
+
+        let ContextService {
+            context, service, ..
+        } = &context_service;
+
+        for (tower_id, tower) in service.world.chunk.iter_towers() {
+            let Some(player_id) = tower.player_id else {
+                continue;
+            };
+            if let Some(previous) = owners.insert(tower_id, player_id)
+                && previous != player_id
+            {
+                stats.towers_captured += 1;
+            }
+        }
+
+        for player in context.players.iter_borrow() {
+            let was_alive = alive.insert(player.player_id, player.data.alive);
+            if was_alive == Some(true)
+                && !player.data.alive
+                && matches!(player.death_reason, Some(DeathReason::RulerKilled { .. }))
+            {
+                stats.rulers_killed += 1;
+            }
+            stats.longest_lifetime = stats.longest_lifetime.max(player.lifetime);
+        }
+    }
+
+    stats.towers_owned = context_service
+        .service
+        .world
+        .chunk
+        .iter_towers()
+        .filter(|(_, tower)| tower.player_id.is_some())
+        .count();
+    stats
+}
+
+/// A game with 8 bots, seeded for reproducibility, after `ticks`. Also returns the metrics, to keep
+/// simulating it.
+#[cfg(test)]
+pub(crate) fn test_game(
+    ticks: usize,
+    options: crate::options::TowerOptions,
+) -> (ContextService<TowerService>, MetricRepo<TowerService>) {
+    use game_server::game_service::GameArenaService;
+
+    rng::seed(0);
+    let mut context_service = ContextService::<TowerService>::new(Some(8), Some(8), None, None);
+    context_service.service.set_options(options);
+    let mut metrics = MetricRepo::new();
+    for _ in 0..ticks {
+        context_service.simulate(&mut metrics);
+    }
+    (context_service, metrics)
+}
+
+/// A [`test_game`] once the bots have spawned, with those that are alive, none of whom are allied.
+#[cfg(test)]
+pub(crate) fn test_game_unallied(
+    options: crate::options::TowerOptions,
+) -> (ContextService<TowerService>, Vec<PlayerId>) {
+    let (mut context_service, _) = test_game(20, options);
+
+    let ContextService {
+        context, service, ..
+    } = &mut context_service;
+    let players = &context.players;
+    let alive: Vec<PlayerId> = players
+        .iter_borrow()
+        .filter(|p| p.data.alive)
+        .map(|p| p.player_id)
+        .collect();
+
+    // Bots may have allied on their own.
+    for &p in &alive {
+        for &q in &alive {
+            if p != q {
+                service.alliance(p, q, true, players).unwrap();
+            }
+        }
+    }
+    (context_service, alive)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::{simulate, SimulationOptions};
+    use structopt::StructOpt;
+
+    #[test]
+    fn simulation_deterministic() {
+        let options = SimulationOptions {
+            bots: 4,
+            ticks: 400,
+            seed: 42,
+        };
+        let a = simulate(options);
+        let b = simulate(options);
+        assert_eq!(a, b);
+        assert_eq!(a.ticks, options.ticks);
+        assert!(a.towers_owned > 0, "{a}");
+    }
+
+    #[test]
+    fn simulation_options() {
+        let parse = |args: &[&str]| {
+            SimulationOptions::from_iter_safe(
+                std::iter::once("simulate").chain(args.iter().copied()),
+            )
+        };
+        let options = parse(&["--bots", "10", "--ticks", "2400", "--seed", "7"]).unwrap();
+        assert_eq!((options.bots, options.ticks, options.seed), (10, 2400, 7));
+        let options = parse(&["--ticks", "100"]).unwrap();
+        assert_eq!((options.bots, options.ticks, options.seed), (10, 100, 0));
+        assert!(parse(&["--ticks", "x"]).is_err());
+        assert!(parse(&["10,2400,7"]).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2023 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::protocol::SpawnPreference;
+use common::ticks::Ticks;
+use core_protocol::PlayerId;
+use fxhash::FxHashMap;
+use std::collections::VecDeque;
+
+/// Players waiting for room in their preferred spawn region (see
+/// [`Command::QueueSpawn`][`common::protocol::Command::QueueSpawn`]).
+#[derive(Debug, Default)]
+pub struct SpawnQueue {
+    /// Per region, in order of arrival, with how long until they give up and spawn anywhere.
+    regions: FxHashMap<SpawnPreference, VecDeque<(PlayerId, Ticks)>>,
+}
+
+impl SpawnQueue {
+    /// How long to wait for room before spawning anywhere.
+    pub const TIMEOUT: Ticks = Ticks::from_whole_secs(30);
+
+    /// Queues `player_id` for room in `preference`'s region, losing any previous place.
+    pub fn push(&mut self, player_id: PlayerId, preference: SpawnPreference) {
+        self.remove(player_id);
+        self.regions
+            .entry(preference)
+            .or_default()
+            .push_back((player_id, Self::TIMEOUT));
+    }
+
+    /// Removes `player_id` from line (e.g. if they left), returning whether they were waiting.
+    pub fn remove(&mut self, player_id: PlayerId) -> bool {
+        let mut removed = false;
+        for waiting in self.regions.values_mut() {
+            waiting.retain(|&(p, _)| {
+                let keep = p != player_id;
+                removed |= !keep;
+                keep
+            });
+        }
+        removed
+    }
+
+    /// Position in line (starting at 1) of `player_id`, if waiting.
+    pub fn position(&self, player_id: PlayerId) -> Option<usize> {
+        self.regions
+            .values()
+            .find_map(|waiting| waiting.iter().position(|&(p, _)| p == player_id))
+            .map(|i| i + 1)
+    }
+
+    /// Call every tick. Players who timed out are spawned anywhere by `spawn(player_id, None)`.
+    /// If `retry`, the first in line of each region are spawned, while there is room, by
+    /// `spawn(player_id, Some(preference))`, which returns whether the region was full.
+    pub fn tick(
+        &mut self,
+        retry: bool,
+        mut spawn: impl FnMut(PlayerId, Option<SpawnPreference>) -> bool,
+    ) {
+        for (&preference, waiting) in &mut self.regions {
+            waiting.retain_mut(|(player_id, remaining)| {
+                *remaining = remaining.saturating_sub(Ticks::ONE);
+                if *remaining == Ticks::ZERO {
+                    spawn(*player_id, None);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            if retry {
+                while let Some(&(player_id, _)) = waiting.front() {
+                    if spawn(player_id, Some(preference)) {
+                        break;
+                    }
+                    waiting.pop_front();
+                }
+            }
+        }
+        self.regions.retain(|_, waiting| !waiting.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spawn_queue::SpawnQueue;
+    use common::protocol::SpawnPreference;
+    use core_protocol::PlayerId;
+
+    #[test]
+    fn spawn_queue() {
+        let bot = |n: usize| PlayerId::nth_bot(n).unwrap();
+        let mut queue = SpawnQueue::default();
+        let mut spawned = Vec::new();
+
+        // Fill the quiet region.
+        let mut room = 0;
+        queue.push(bot(0), SpawnPreference::Quiet);
+        queue.push(bot(1), SpawnPreference::Quiet);
+        queue.push(bot(2), SpawnPreference::Contested);
+        let tick = |queue: &mut SpawnQueue, room: &mut usize, spawned: &mut Vec<_>| {
+            queue.tick(true, |player_id, preference| {
+                if preference == Some(SpawnPreference::Quiet) && *room == 0 {
+                    return true;
+                }
+                *room = room.saturating_sub(1);
+                spawned.push((player_id, preference));
+                false
+            })
+        };
+        tick(&mut queue, &mut room, &mut spawned);
+        assert_eq!(spawned, vec![(bot(2), Some(SpawnPreference::Contested))]);
+        assert_eq!(queue.position(bot(0)), Some(1));
+        assert_eq!(queue.position(bot(1)), Some(2));
+        assert_eq!(queue.position(bot(2)), None);
+
+        // Free space for one, in order of arrival.
+        spawned.clear();
+        room = 1;
+        tick(&mut queue, &mut room, &mut spawned);
+        assert_eq!(spawned, vec![(bot(0), Some(SpawnPreference::Quiet))]);
+        assert_eq!(queue.position(bot(1)), Some(1));
+
+        // Leaving.
+        queue.push(bot(3), SpawnPreference::Quiet);
+        assert!(queue.remove(bot(1)));
+        assert!(!queue.remove(bot(1)));
+        assert_eq!(queue.position(bot(3)), Some(1));
+
+        // Timing out spawns anywhere.
+        spawned.clear();
+        for _ in 0..SpawnQueue::TIMEOUT.0 {
+            tick(&mut queue, &mut room, &mut spawned);
+        }
+        assert_eq!(spawned, vec![(bot(3), None)]);
+        assert_eq!(queue.position(bot(3)), None);
+    }
+}
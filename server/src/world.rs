@@ -4,30 +4,45 @@
 use crate::TowerService;
 use common::alerts::{AlertFlag, Alerts};
 use common::chunk::{ChunkId, ChunkInput, ChunkMaintenance, RelativeTowerId};
+use common::emote::{EmoteEvent, EmoteId};
 use common::force::Path;
 use common::info::InfoEvent;
+use common::ping::PingEvent;
 use common::player::{PlayerInput, PlayerMaintainance};
+use common::protocol::SpawnPreference;
 use common::ticks::Ticks;
-use common::tower::{TowerId, TowerSet, TowerType};
+use common::tower::{Tower, TowerId, TowerRectangle, TowerSet, TowerType};
 use common::world::{World, WorldChunks};
 use common_util::x_vec2::U16Vec2;
 use core_protocol::id::PlayerId;
 use fxhash::{FxHashMap, FxHashSet};
 use game_server::player::PlayerRepo;
-use glam::IVec2;
-use rand::thread_rng;
+use glam::{IVec2, Vec2};
 use std::collections::VecDeque;
+use std::str::FromStr;
 use std::time::Instant;
 
 impl TowerService {
+    /// How far beyond other players [`SpawnPreference::Quiet`] spawns, in towers.
+    pub(crate) const QUIET_SPAWN_MARGIN: u16 = 12;
+    /// Error of [`Self::spawn_player`] if the preferred region is full and it can't fall back.
+    pub(crate) const SPAWN_REGION_FULL: &'static str = "spawn region is full";
+    /// How often pulling supply lines (see [`Tower::supply_pull`]) pull.
+    pub(crate) const PULL_PERIOD: Ticks = Ticks::from_whole_secs(5);
+    /// The most a pulling supply line takes from its source at once, leaving the rest to
+    /// defend it.
+    const MAX_PULL_PERCENT: u8 = 50;
+
+    /// Spawns `player_id`, honoring `preference` unless its region is full, in which case it
+    /// spawns anywhere if `fallback`.
     pub fn spawn_player(
         &mut self,
         player_id: PlayerId,
+        preference: Option<SpawnPreference>,
+        fallback: bool,
         players: &PlayerRepo<Self>,
     ) -> Result<(), &'static str> {
-        const MAX_TRIES: u32 = 100_000;
-
-        let mut player = match players.borrow_player_mut(player_id) {
+        let player = match players.borrow_player(player_id) {
             Some(player) => player,
             None => return Err("player not in game"),
         };
@@ -36,65 +51,19 @@ impl TowerService {
             return Err("already alive");
         }
 
-        let mut governor = MAX_TRIES;
-        let start = Instant::now();
-
-        // In towers^2.
-        let search_area = 100u32;
-
-        // In towers.
-        let mut search_radius = (search_area as f32 * (1.0 / std::f32::consts::PI)).sqrt() as u16;
-
-        let mut rng = thread_rng();
-        let result = loop {
-            if governor == 0 {
-                println!(
-                    "ran out of spawning attempts after {:?} (sr = {})",
-                    start.elapsed(),
-                    search_radius,
-                );
-                break Err("couldn't find spawnable tower");
-            }
-            governor -= 1;
-
-            let tower_id = TowerId(
-                U16Vec2::try_from(
-                    (common_util::range::gen_radius(&mut rng, search_radius as f32)
-                        + World::CENTER.0.as_vec2()
-                        + 0.5)
-                        .floor()
-                        .as_ivec2()
-                        .clamp(IVec2::ZERO, IVec2::splat(WorldChunks::SIZE as i32 - 1))
-                        .as_uvec2(),
-                )
-                .unwrap(),
-            );
-
-            if self.is_spawnable(tower_id) {
-                println!(
-                    "took {} tries (sr = {:.2}) over {:?} to spawn {}",
-                    MAX_TRIES - governor,
-                    search_radius as f32 * TowerId::CONVERSION as f32,
-                    start.elapsed(),
-                    if player.is_bot() { "bot" } else { "player" }
-                );
-
-                player.lifetime = Ticks::ZERO;
-                player.death_reason = None;
-                player.score = 0;
-                player.alerts = Alerts::default();
-
-                break Ok(tower_id);
-            }
-
-            // TODO increase slower once very big.
-            if governor % 8 == 0 {
-                search_radius += 1;
-            }
-        };
-
+        let is_bot = player.is_bot();
         drop(player);
 
+        let result = self.find_spawn(preference, fallback, is_bot, players);
+        if result.is_ok() {
+            let mut player = players.borrow_player_mut(player_id).unwrap();
+            player.lifetime = Ticks::ZERO;
+            player.death_reason = None;
+            player.score = 0;
+            player.alerts = Alerts::default();
+            player.alliance_limited_by = None;
+        }
+
         let mut on_info_event = Self::on_info_event(players, |player_id| {
             debug_assert!(
                 false,
@@ -160,6 +129,19 @@ impl TowerService {
             && self.world.player(with).allies.contains(&player_id);
 
         if new_alliance {
+            let limited_by = [player_id, with]
+                .into_iter()
+                .find(|&p| self.mutual_allies(p) >= self.max_alliances);
+            if let Some(limited_by) = limited_by {
+                if let Some(mut player) = players.borrow_player_mut(player_id) {
+                    let flags = player.alerts.flags();
+                    player
+                        .alerts
+                        .set_flags(flags | AlertFlag::AllianceLimitReached);
+                    player.alliance_limited_by = Some(limited_by);
+                }
+                return Err("alliance limit reached");
+            }
             for (a, b) in [(player_id, with), (with, player_id)] {
                 self.world.dispatch_player_input(
                     a,
@@ -195,21 +177,25 @@ impl TowerService {
         player_id: PlayerId,
         tower_id: TowerId,
         path: Path,
+        percent: u8,
         players: &PlayerRepo<Self>,
     ) -> Result<(), &'static str> {
+        if !(1..=Tower::FULL_DEPLOY_PERCENT).contains(&percent) {
+            return Err("invalid percent");
+        }
         let tower = self.world.chunk.get(tower_id).ok_or("no tower")?;
         if tower.player_id != Some(player_id) {
             return Err("source not under player's control");
         }
 
-        let strength = tower.force_units();
+        let strength = tower.force_units_percent(percent);
         if strength.is_empty() {
             return Err("empty force");
         }
 
         // Always some since strength isn't empty.
         let max_edge_distance = strength.max_edge_distance();
-        let path = path.validate(&self.world.chunk, tower_id, max_edge_distance)?;
+        let path = path.validate(&self.world, tower_id, max_edge_distance)?;
 
         if !player_id.is_bot() {
             let mut player = players.borrow_player_mut(player_id).ok_or_else(|| {
@@ -223,7 +209,11 @@ impl TowerService {
         let (chunk_id, tower_id) = tower_id.split();
         self.world.dispatch_chunk_input(
             chunk_id,
-            ChunkInput::DeployForce { tower_id, path },
+            ChunkInput::DeployForce {
+                tower_id,
+                path,
+                percent,
+            },
             Self::on_info_event(players, |player_id| {
                 debug_assert!(
                     false,
@@ -236,11 +226,56 @@ impl TowerService {
         Ok(())
     }
 
+    pub fn redirect_force(
+        &mut self,
+        player_id: PlayerId,
+        tower_id: TowerId,
+        source: TowerId,
+        path: Option<Path>,
+    ) -> Result<(), &'static str> {
+        let tower = self.world.chunk.get(tower_id).ok_or("no tower")?;
+        let mut forces = tower
+            .inbound_forces
+            .iter()
+            .filter(|f| f.player_id == Some(player_id) && f.current_source() == source)
+            .peekable();
+        if forces.peek().is_none() {
+            return Err("no such force");
+        }
+
+        // All matching forces are redirected, and they may differ in range (e.g. shells vs.
+        // soldiers), so the path must suit each of them.
+        if let Some(path) = &path {
+            for force in forces {
+                let max_edge_distance = force.units.max_edge_distance();
+                path.clone()
+                    .validate(&self.world, tower_id, max_edge_distance)?;
+            }
+        }
+
+        let (chunk_id, tower_id) = tower_id.split();
+        self.world.dispatch_chunk_input(
+            chunk_id,
+            ChunkInput::RedirectForce {
+                tower_id,
+                source,
+                player_id,
+                path,
+            },
+            |info| {
+                debug_assert!(false, "expected no info: {info:?}");
+            },
+        );
+
+        Ok(())
+    }
+
     pub fn set_supply_line(
         &mut self,
         player_id: PlayerId,
         tower_id: TowerId,
         path: Option<Path>,
+        pull: bool,
         players: &PlayerRepo<Self>,
     ) -> Result<(), &'static str> {
         let tower = self.world.chunk.get(tower_id).ok_or("no tower")?;
@@ -248,15 +283,36 @@ impl TowerService {
             return Err("source not under player's control");
         }
 
-        if !tower.generates_mobile_units() {
-            return Err("invalid supply line");
-        }
+        let max_edge_distance = if pull {
+            // Only units that follow roads can be pulled.
+            if tower.tower_type.ranged_distance().is_some() {
+                return Err("invalid supply line");
+            }
+            if let Some(path) = &path {
+                let source = self
+                    .world
+                    .chunk
+                    .get(path.destination())
+                    .ok_or("no source")?;
+                if source.player_id != Some(player_id)
+                    || source.tower_type.ranged_distance().is_some()
+                {
+                    return Err("invalid supply line");
+                }
+            }
+            None
+        } else {
+            if !tower.generates_mobile_units() {
+                return Err("invalid supply line");
+            }
+            tower.tower_type.ranged_distance()
+        };
 
-        let max_edge_distance = tower.tower_type.ranged_distance();
         let path = path
-            .map(|p| p.validate(&self.world.chunk, tower_id, max_edge_distance))
+            .map(|p| p.validate(&self.world, tower_id, max_edge_distance))
             .transpose()?
-            .filter(|p| Some(p) != tower.supply_line.as_ref());
+            // Setting the same supply line again clears it, but changing direction doesn't.
+            .filter(|p| Some(p) != tower.supply_line.as_ref() || pull != tower.supply_pull);
 
         if !player_id.is_bot() {
             let mut player = players.borrow_player_mut(player_id).ok_or_else(|| {
@@ -278,7 +334,11 @@ impl TowerService {
         let (chunk_id, tower_id) = tower_id.split();
         self.world.dispatch_chunk_input(
             chunk_id,
-            ChunkInput::SetSupplyLine { tower_id, path },
+            ChunkInput::SetSupplyLine {
+                tower_id,
+                path,
+                pull,
+            },
             |info| {
                 debug_assert!(false, "expected no info: {info:?}");
             },
@@ -287,6 +347,179 @@ impl TowerService {
         Ok(())
     }
 
+    /// Sends units from the source of `tower_id`'s pulling supply line to `tower_id`, up to
+    /// however many `tower_id` has room for.
+    pub fn pull_supply_line(
+        &mut self,
+        tower_id: TowerId,
+        players: &PlayerRepo<Self>,
+    ) -> Result<(), &'static str> {
+        let tower = self.world.chunk.get(tower_id).ok_or("no tower")?;
+        let player_id = tower.player_id.ok_or("no owner")?;
+        let path = tower
+            .supply_line
+            .as_ref()
+            .filter(|_| tower.supply_pull)
+            .ok_or("no pulling supply line")?;
+        let source_id = path.destination();
+        let source = self.world.chunk.get(source_id).ok_or("no source")?;
+        if source.player_id != Some(player_id) {
+            return Err("source not under player's control");
+        }
+        if source.units.has_ruler()
+            || source
+                .inbound_forces
+                .iter()
+                .any(|f| f.player_id != Some(player_id))
+        {
+            return Err("source needs its units");
+        }
+
+        let force_units = source.force_units();
+        let room: usize = force_units
+            .iter()
+            .map(|(unit, _)| {
+                let inbound: usize = tower
+                    .inbound_forces
+                    .iter()
+                    .filter(|f| f.player_id == Some(player_id))
+                    .map(|f| f.units.available(unit))
+                    .sum();
+                tower
+                    .units
+                    .capacity(unit, Some(tower.tower_type))
+                    .saturating_sub(tower.units.available(unit) + inbound)
+            })
+            .sum();
+        let available: usize = force_units.iter().map(|(_, count)| count).sum();
+        if room == 0 || available == 0 {
+            return Err("nothing to pull");
+        }
+
+        let percent = (room * 100)
+            .div_ceil(available)
+            .min(Self::MAX_PULL_PERCENT as usize) as u8;
+        let path = path.reversed();
+        self.deploy_force(player_id, source_id, path, percent, players)
+    }
+
+    /// Relays an emote to players that can see the sender's ruler.
+    pub fn emote(
+        &mut self,
+        player_id: PlayerId,
+        emote_id: EmoteId,
+        players: &PlayerRepo<Self>,
+    ) -> Result<(), &'static str> {
+        let Some(mut player) = players.borrow_player_mut(player_id) else {
+            return Err("nonexistent player");
+        };
+
+        if !player.alive {
+            return Err("cannot emote while dead");
+        }
+
+        if player.emote_cooldown != Ticks::ZERO {
+            return Err("emote rate limited");
+        }
+
+        let tower_id = player.alerts.ruler_position.ok_or("no ruler")?;
+        player.emote_cooldown = EmoteId::COOLDOWN;
+        drop(player);
+
+        self.emotes.push(EmoteEvent {
+            player_id,
+            tower_id,
+            emote_id,
+        });
+
+        Ok(())
+    }
+
+    /// Relays a ping of `tower_id` to the player's mutual allies.
+    pub fn ping(
+        &mut self,
+        player_id: PlayerId,
+        tower_id: TowerId,
+        players: &PlayerRepo<Self>,
+    ) -> Result<(), &'static str> {
+        let Some(mut player) = players.borrow_player_mut(player_id) else {
+            return Err("nonexistent player");
+        };
+
+        if !player.alive {
+            return Err("cannot ping while dead");
+        }
+
+        if player.ping_cooldown != Ticks::ZERO {
+            return Err("ping rate limited");
+        }
+
+        if !self.world.chunk.contains(tower_id) {
+            return Err("nonexistent tower");
+        }
+
+        player.ping_cooldown = PingEvent::COOLDOWN;
+        drop(player);
+
+        self.pings.push(PingEvent {
+            player_id,
+            tower_id,
+        });
+
+        Ok(())
+    }
+
+    /// Gives a tower to a mutual ally.
+    pub fn gift_tower(
+        &mut self,
+        player_id: PlayerId,
+        tower_id: TowerId,
+        to: PlayerId,
+        players: &PlayerRepo<Self>,
+    ) -> Result<(), &'static str> {
+        let Some(tower) = self.world.chunk.get(tower_id) else {
+            return Err("cannot gift nonexistent tower");
+        };
+
+        if tower.player_id != Some(player_id) {
+            return Err("cannot gift tower not owned");
+        }
+
+        if tower.units.has_ruler() {
+            return Err("cannot gift ruler");
+        }
+
+        if to == player_id {
+            return Err("cannot gift to self");
+        }
+
+        if !self.is_mutual_ally(player_id, to) {
+            return Err("cannot gift to non-ally");
+        }
+
+        if !players.borrow_player(to).map_or(false, |p| p.alive) {
+            return Err("cannot gift to dead player");
+        }
+
+        let (chunk_id, tower_id) = tower_id.split();
+        self.world.dispatch_chunk_input(
+            chunk_id,
+            ChunkInput::GiftTower {
+                tower_id,
+                player_id: to,
+            },
+            Self::on_info_event(players, |player_id| {
+                debug_assert!(
+                    false,
+                    "gifting tower should not have killed player {:?}",
+                    player_id
+                );
+            }),
+        );
+
+        Ok(())
+    }
+
     /// Upgrade or downgrade tower.
     pub fn upgrade_tower(
         &mut self,
@@ -444,8 +677,119 @@ impl TowerService {
         )
     }
 
+    /// Finds a spawnable tower, honoring `preference` unless its region is full, in which case
+    /// it falls back to anywhere if `fallback`, otherwise fails with [`Self::SPAWN_REGION_FULL`].
+    fn find_spawn(
+        &mut self,
+        mut preference: Option<SpawnPreference>,
+        fallback: bool,
+        is_bot: bool,
+        players: &PlayerRepo<Self>,
+    ) -> Result<TowerId, &'static str> {
+        const MAX_TRIES: u32 = 100_000;
+        // Tries before giving up on a preference.
+        const PREFERENCE_TRIES: u32 = 10_000;
+
+        let mut governor = MAX_TRIES;
+        let start = Instant::now();
+
+        // In towers^2.
+        let search_area = 100u32;
+
+        // In towers.
+        let default_radius = (search_area as f32 * (1.0 / std::f32::consts::PI)).sqrt() as u16;
+        let occupied_radius = preference.map_or(0, |_| Self::occupied_radius(players));
+
+        // Searches between inner_radius and inner_radius + search_radius.
+        let (mut inner_radius, mut search_radius) = match preference {
+            None => (0, default_radius),
+            Some(SpawnPreference::Contested) => (0, occupied_radius.max(default_radius)),
+            Some(SpawnPreference::Quiet) => {
+                (occupied_radius + Self::QUIET_SPAWN_MARGIN, default_radius)
+            }
+        };
+
+        loop {
+            if governor == 0 {
+                println!(
+                    "ran out of spawning attempts after {:?} (sr = {})",
+                    start.elapsed(),
+                    search_radius,
+                );
+                return Err("couldn't find spawnable tower");
+            }
+            governor -= 1;
+
+            if preference.is_some() && MAX_TRIES - governor > PREFERENCE_TRIES {
+                if !fallback {
+                    return Err(Self::SPAWN_REGION_FULL);
+                }
+                println!("falling back from {preference:?} spawn");
+                preference = None;
+                inner_radius = 0;
+                search_radius = default_radius;
+            }
+
+            let offset = common_util::range::gen_radius(&mut self.rng, search_radius as f32);
+            // Without a direction, it can't be pushed out past the inner radius.
+            if inner_radius != 0 && offset == Vec2::ZERO {
+                continue;
+            }
+            let tower_id = TowerId(
+                U16Vec2::try_from(
+                    (offset
+                        + offset.normalize_or_zero() * inner_radius as f32
+                        + World::CENTER.0.as_vec2()
+                        + 0.5)
+                        .floor()
+                        .as_ivec2()
+                        .clamp(IVec2::ZERO, IVec2::splat(WorldChunks::SIZE as i32 - 1))
+                        .as_uvec2(),
+                )
+                .unwrap(),
+            );
+
+            if self.is_spawnable(tower_id) {
+                println!(
+                    "took {} tries (sr = {:.2}) over {:?} to spawn {}",
+                    MAX_TRIES - governor,
+                    search_radius as f32 * TowerId::CONVERSION as f32,
+                    start.elapsed(),
+                    if is_bot { "bot" } else { "player" }
+                );
+                return Ok(tower_id);
+            }
+
+            // TODO increase slower once very big.
+            // Contested spawns don't grow, so they can fall back if it's full.
+            if governor % 8 == 0 && preference != Some(SpawnPreference::Contested) {
+                search_radius += 1;
+            }
+        }
+    }
+
+    /// Distance of the furthest owned tower from [`World::CENTER`], in towers (see
+    /// [`PlayerData::tower_distances`](crate::service::PlayerData::tower_distances)).
+    fn occupied_radius(players: &PlayerRepo<Self>) -> u16 {
+        players
+            .iter_borrow()
+            .filter_map(|player| player.data.tower_distances.keys().next_back().copied())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// In towers.
+    pub(crate) fn distance_from_center(tower_id: TowerId) -> u16 {
+        tower_id
+            .0
+            .as_vec2()
+            .distance(World::CENTER.0.as_vec2())
+            .round() as u16
+    }
+
     pub fn is_spawnable(&self, tower_id: TowerId) -> bool {
         tower_id.connectivity().is_some()
+            && !self.world.is_obstacle(tower_id)
             && self.is_good_spawn(tower_id)
             && self.is_safe_spawn(tower_id)
     }
@@ -457,6 +801,8 @@ impl TowerService {
                     return Err(());
                 }
                 tower.tower_type
+            } else if self.mirror {
+                tower_id.mirrored_tower_type()
             } else {
                 tower_id.tower_type()
             })
@@ -551,19 +897,70 @@ impl TowerService {
         }
     }
 
-    /// Generates all the `tower_ids`.
+    /// Generates all the `tower_ids`, those in [`Singleton::obstacles`] as cliffs.
+    ///
+    /// [`Singleton::obstacles`]: common::singleton::Singleton::obstacles
     fn generate(
         &mut self,
         tower_ids: impl IntoIterator<Item = TowerId>,
         c: &mut impl FnMut(InfoEvent),
     ) {
-        for (chunk_id, tower_ids) in group(tower_ids) {
-            let input = ChunkInput::Generate { tower_ids };
-            self.world.dispatch_chunk_input(chunk_id, input, &mut *c);
+        let (obstacles, tower_ids): (Vec<_>, Vec<_>) = tower_ids
+            .into_iter()
+            .partition(|&tower_id| self.world.is_obstacle(tower_id));
+        for (obstacle, tower_ids) in [(true, obstacles), (false, tower_ids)] {
+            for (chunk_id, tower_ids) in group(tower_ids) {
+                let input = ChunkInput::Generate {
+                    tower_ids,
+                    mirror: self.mirror,
+                    obstacle,
+                };
+                self.world.dispatch_chunk_input(chunk_id, input, &mut *c);
+            }
         }
     }
 }
 
+/// Impassable regions of the world (see [`Singleton::obstacles`]), configured as
+/// `x1,y1,x2,y2` rectangles (bottom left and top right towers) separated by `;`.
+///
+/// [`Singleton::obstacles`]: common::singleton::Singleton::obstacles
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Obstacles(pub Vec<TowerRectangle>);
+
+impl FromStr for Obstacles {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(';')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| {
+                let mut coordinates = s.split(',').map(|c| c.trim().parse::<u16>());
+                let mut next = || {
+                    coordinates
+                        .next()
+                        .and_then(|c| c.ok())
+                        .ok_or("invalid coordinate")
+                };
+                let bottom_left = TowerId::new(next()?, next()?);
+                let top_right = TowerId::new(next()?, next()?);
+                if coordinates.next().is_some() {
+                    return Err("too many coordinates");
+                }
+                let rectangle = TowerRectangle::new(bottom_left, top_right);
+                if !rectangle.is_valid() {
+                    return Err("invalid obstacle");
+                }
+                if !WorldChunks::RECTANGLE.contains(top_right) {
+                    return Err("obstacle outside world");
+                }
+                Ok(rectangle)
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
 /// Calls `f` for ever tower in `player_id`'s spawn bubble around `f`.
 fn spawn_bubble(tower_id: TowerId, player_id: PlayerId, f: impl FnMut(TowerId)) {
     let radius = if player_id.is_bot() && !cfg!(debug_assertions) {
@@ -608,3 +1005,270 @@ fn group(tower_ids: impl IntoIterator<Item = TowerId>) -> FxHashMap<ChunkId, Vec
     }
     chunk_map
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::options::TowerOptions;
+    use crate::rng;
+    use crate::service::TowerService;
+    use crate::simulation::{test_game, test_game_unallied};
+    use crate::world::{spawn_bubble, Obstacles};
+    use common::chunk::ChunkInput;
+    use common::force::{Force, Path};
+    use common::protocol::{Command, SpawnPreference};
+    use common::ticks::Ticks;
+    use common::tower::{TowerId, TowerRectangle, TowerType};
+    use common::unit::Unit;
+    use common::units::Units;
+    use common::world::World;
+    use core_protocol::id::PlayerId;
+    use fxhash::FxHashSet;
+    use game_server::context_service::ContextService;
+    use game_server::game_service::GameArenaService;
+    use std::str::FromStr;
+    use structopt::StructOpt;
+
+    #[test]
+    fn redirect_mixed_forces() {
+        let (mut context_service, _) = test_game(20, TowerOptions::default());
+        let service = &mut context_service.service;
+
+        // A road path tower -> a -> b.
+        let chunk = &service.world.chunk;
+        let exists = |tower_id: TowerId| chunk.get(tower_id).is_some();
+        let (tower_id, player_id, a, b) = chunk
+            .iter_towers()
+            .find_map(|(tower_id, tower)| {
+                let player_id = tower.player_id?;
+                tower_id.neighbors().filter(|&a| exists(a)).find_map(|a| {
+                    let b = a.neighbors().find(|&b| b != tower_id && exists(b))?;
+                    Some((tower_id, player_id, a, b))
+                })
+            })
+            .unwrap();
+
+        // Forces from the same source, only one of which must follow roads.
+        for unit in [Unit::Soldier, Unit::Shell] {
+            let mut units = Units::default();
+            units.add(unit, 5);
+            let force = Force::new(player_id, units, Path::new(vec![a, tower_id]));
+            let (chunk_id, tower_id) = tower_id.split();
+            service.world.dispatch_chunk_input(
+                chunk_id,
+                ChunkInput::AddInboundForce { tower_id, force },
+                |_| unreachable!(),
+            );
+        }
+
+        let road = Path::new(vec![tower_id, a, b]);
+        assert_eq!(
+            service.redirect_force(player_id, tower_id, a, Some(road)),
+            Err("path too long")
+        );
+        let direct = Path::new(vec![tower_id, a]);
+        service
+            .redirect_force(player_id, tower_id, a, Some(direct))
+            .unwrap();
+    }
+
+    #[test]
+    fn spawn_preference() {
+        let (mut context_service, _) = test_game_unallied(TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let players = &context.players;
+        let occupied_radius = TowerService::occupied_radius(players);
+        assert!(occupied_radius > 0);
+        assert_eq!(
+            occupied_radius,
+            service
+                .world
+                .chunk
+                .iter_towers()
+                .filter(|(_, tower)| tower.player_id.is_some())
+                .map(|(tower_id, _)| TowerService::distance_from_center(tower_id))
+                .max()
+                .unwrap()
+        );
+
+        for _ in 0..10 {
+            let tower_id = service.find_spawn(None, true, false, players).unwrap();
+            assert!(service.is_spawnable(tower_id));
+
+            let tower_id = service
+                .find_spawn(Some(SpawnPreference::Contested), true, false, players)
+                .unwrap();
+            let distance = TowerService::distance_from_center(tower_id);
+            assert!(
+                distance <= occupied_radius + 1,
+                "{distance} {occupied_radius}"
+            );
+
+            let tower_id = service
+                .find_spawn(Some(SpawnPreference::Quiet), true, false, players)
+                .unwrap();
+            let distance = TowerService::distance_from_center(tower_id);
+            assert!(
+                distance + 1 >= occupied_radius + TowerService::QUIET_SPAWN_MARGIN,
+                "{distance} {occupied_radius}"
+            );
+        }
+    }
+
+    #[test]
+    fn queue_spawn() {
+        let (mut context_service, alive) = test_game_unallied(TowerOptions::default());
+
+        let ContextService {
+            context, service, ..
+        } = &mut context_service;
+        let player_id = alive[0];
+        let player_tuple = context.players.get(player_id).unwrap().clone();
+        let is_quiet = |distance: u16, occupied_radius: u16| {
+            distance + 1 >= occupied_radius + TowerService::QUIET_SPAWN_MARGIN
+        };
+        let ruler_distance = |service: &TowerService| {
+            let (tower_id, _) = service
+                .world
+                .chunk
+                .iter_towers()
+                .find(|(_, t)| t.player_id == Some(player_id) && t.units.has_ruler())
+                .unwrap();
+            TowerService::distance_from_center(tower_id)
+        };
+
+        // There is room in the preferred region, so no need to fall back or wait.
+        service.kill_player(player_id, &context.players);
+        let occupied_radius = TowerService::occupied_radius(&context.players);
+        let tower_id = service
+            .find_spawn(Some(SpawnPreference::Quiet), false, false, &context.players)
+            .unwrap();
+        let distance = TowerService::distance_from_center(tower_id);
+        assert!(is_quiet(distance, occupied_radius), "{distance}");
+
+        service.player_command(
+            Command::QueueSpawn(SpawnPreference::Quiet),
+            &player_tuple,
+            &context.players,
+        );
+        assert!(context.players.borrow_player(player_id).unwrap().data.alive);
+        assert_eq!(service.spawn_queue.position(player_id), None);
+        let distance = ruler_distance(service);
+        assert!(is_quiet(distance, occupied_radius), "{distance}");
+
+        // Waiting in line (as if the region was full) spawns there once retried.
+        service.kill_player(player_id, &context.players);
+        let occupied_radius = TowerService::occupied_radius(&context.players);
+        service.spawn_queue.push(player_id, SpawnPreference::Quiet);
+        assert_eq!(service.spawn_queue.position(player_id), Some(1));
+        for _ in 0..=Ticks::from_whole_secs(1).0 {
+            // Without bots, which would spawn anywhere.
+            service.tick(context);
+            service.post_update(context);
+        }
+        assert_eq!(service.spawn_queue.position(player_id), None);
+        assert!(context.players.borrow_player(player_id).unwrap().data.alive);
+        let distance = ruler_distance(service);
+        assert!(is_quiet(distance, occupied_radius), "{distance}");
+    }
+
+    #[test]
+    fn mirror() {
+        rng::seed(0);
+        let mut context_service = ContextService::<TowerService>::new(None, None, None, None);
+        let service = &mut context_service.service;
+        service.mirror = true;
+
+        // Generate a region and its reflection.
+        let tower_id = TowerId::new(World::CENTER.x - 40, World::CENTER.y + 25);
+        let player_id = PlayerId::nth_bot(0).unwrap();
+        let mut tower_ids = FxHashSet::default();
+        for origin in [tower_id, tower_id.mirrored()] {
+            spawn_bubble(origin, player_id, |tower_id| {
+                service.traverse(&mut tower_ids, tower_id)
+            });
+        }
+        service.generate(tower_ids, &mut |_| {});
+
+        let mut compared = 0;
+        for (tower_id, tower) in service.world.chunk.iter_towers() {
+            if let Some(mirrored) = service.world.chunk.get(tower_id.mirrored()) {
+                assert_eq!(tower.tower_type, mirrored.tower_type, "{tower_id:?}");
+                compared += 1;
+            }
+        }
+        assert!(compared > 100, "{compared}");
+    }
+
+    #[test]
+    fn obstacles() {
+        assert_eq!(Obstacles::from_str(""), Ok(Obstacles::default()));
+        assert!(Obstacles::from_str("1,2,3").is_err());
+        assert!(Obstacles::from_str("5,5,4,4").is_err());
+        assert!(Obstacles::from_str("1,2,3,4,5").is_err());
+
+        let center = World::CENTER;
+        let wall = TowerRectangle::new(
+            TowerId::new(center.x - 1, center.y - 4),
+            TowerId::new(center.x + 1, center.y + 4),
+        );
+        let TowerRectangle {
+            bottom_left: bl,
+            top_right: tr,
+        } = wall;
+        let obstacles = format!("{},{},{},{};", bl.x, bl.y, tr.x, tr.y);
+        let options = TowerOptions::from_iter_safe(["server", "--obstacles", &obstacles]).unwrap();
+        assert_eq!(options.obstacles, Some(Obstacles(vec![wall])));
+        assert!(TowerOptions::from_iter_safe(["server", "--obstacles", "1,2,3"]).is_err());
+
+        rng::seed(0);
+        let mut context_service = ContextService::<TowerService>::new(None, None, None, None);
+        let service = &mut context_service.service;
+        service.set_options(options);
+
+        let player_id = PlayerId::nth_bot(0).unwrap();
+        let mut tower_ids = FxHashSet::default();
+        spawn_bubble(center, player_id, |tower_id| {
+            service.traverse(&mut tower_ids, tower_id)
+        });
+        service.generate(tower_ids, &mut |_| {});
+        let world = &service.world;
+
+        let mut cliffs = 0;
+        for (tower_id, tower) in world.chunk.iter_towers() {
+            if wall.contains(tower_id) {
+                assert_eq!(tower.tower_type, TowerType::Cliff, "{tower_id:?}");
+                assert!(!service.is_spawnable(tower_id));
+                cliffs += 1;
+            }
+        }
+        assert!(cliffs > 0);
+
+        // Closest towers on either side of the wall.
+        let beside = |x: u16| {
+            world
+                .chunk
+                .iter_towers()
+                .map(|(tower_id, _)| tower_id)
+                .filter(|tower_id| tower_id.x == x)
+                .min_by_key(|tower_id| tower_id.y.abs_diff(center.y))
+                .unwrap()
+        };
+        let (src, dst) = (beside(center.x - 3), beside(center.x + 3));
+
+        let path = world
+            .find_best_path(src, dst, None, player_id, |_| true)
+            .expect("no path around obstacle");
+        assert!(path.iter().all(|&tower_id| !wall.contains(tower_id)));
+        assert!(Path::new(path).validate(world, src, None).is_ok());
+
+        // Ranged units can't land on it either.
+        let cliff = wall.into_iter().find(|&t| world.chunk.contains(t)).unwrap();
+        assert_eq!(
+            world.find_best_path(src, cliff, Some(10), player_id, |_| true),
+            None
+        );
+    }
+}